@@ -0,0 +1,26 @@
+use std::path::Path;
+
+use miden_client::{account::AccountId, asset::FungibleAsset};
+use midenname_contracts::{notes::create_naming_register_name_note_from_name, pricing::quote_registration, queries::get_domain_owner, testing::{NamingFixture, TEST_PRICES, add_note_to_builder, execute_notes_and_build_chain}};
+
+// The registry reads its MASM sources relative to the working directory
+pub fn enter_registry_root() -> anyhow::Result<()> {
+    std::env::set_current_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("../.."))?;
+    Ok(())
+}
+
+// Initializes the registry of the fixture, registers `name` for `registrant` for one year and returns the owner
+pub async fn register_in_mock_chain(mut fixture: NamingFixture, registrant: AccountId, name: &str) -> anyhow::Result<Option<AccountId>> {
+    let token = fixture.fungible_asset.faucet_id();
+    let mut note_ids = vec![fixture.initialize_note.id(), fixture.set_prices_note.id()];
+
+    // The chain is built after the note, so the price comes from the fixture instead of the account
+    let amount = TEST_PRICES[name.len().clamp(1, TEST_PRICES.len()) - 1];
+    let note = create_naming_register_name_note_from_name(registrant, fixture.naming.id(), name, FungibleAsset::new(token, amount)?, 1).await?;
+    add_note_to_builder(&mut fixture.builder, note.clone())?;
+    note_ids.push(note.id());
+
+    execute_notes_and_build_chain(fixture.builder, &note_ids, &mut fixture.naming).await?;
+    assert_eq!(quote_registration(&fixture.naming, name, token, 1)?, amount);
+    Ok(get_domain_owner(&fixture.naming, name)?)
+}
@@ -0,0 +1,12 @@
+use midenname_contracts::testing::init_naming;
+use midenname_registration_example::{enter_registry_root, register_in_mock_chain};
+
+#[tokio::test]
+async fn test_register_with_published_fixtures() -> anyhow::Result<()> {
+    enter_registry_root()?;
+    let fixture = init_naming().await?;
+    let registrant = fixture.registrar_1.id();
+
+    assert_eq!(register_in_mock_chain(fixture, registrant, "alice").await?, Some(registrant));
+    Ok(())
+}
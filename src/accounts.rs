@@ -1,50 +1,133 @@
 
 use miden_client::{
-    Client, account::{Account, AccountBuilder, AccountStorageMode, AccountType}, auth::{AuthSecretKey, NoAuth}, keystore::FilesystemKeyStore};
-use miden_lib::{account::auth::{ AuthRpoFalcon512}, account::wallets::BasicWallet, transaction::TransactionKernel};
+    Client, ClientError, account::{Account, AccountBuilder, AccountId, AccountStorageMode, AccountType}, auth::{AuthSecretKey, NoAuth}, keystore::FilesystemKeyStore, note::Note};
+use miden_lib::{account::auth::{ AuthRpoFalcon512}, account::wallets::BasicWallet};
+use miden_crypto::Word;
 use miden_objects::account::AccountComponent;
-use rand::{RngCore, rngs::StdRng};
+use bip39::Mnemonic;
+use hkdf::Hkdf;
+use rand::{RngCore, SeedableRng, rngs::StdRng};
+use rand_chacha::ChaCha20Rng;
+use sha2::Sha256;
 use std::{fs, path::Path, sync::Arc};
 
-use crate::storage::naming_storage;
+use crate::{compile::CompileOptions, errors::{RegistryError, Result}, notes::{SignedOwnerNote, sign_owner_note}, storage::{WALLET_PUBKEY_SLOT, naming_storage}};
 
-pub async fn create_deployer_account(client: &mut Client<FilesystemKeyStore<StdRng>>, keystore: &mut Arc<FilesystemKeyStore<StdRng>>) -> anyhow::Result<Account> {
+// With a mnemonic the deployer is its owner wallet at index 0, recoverable with import-account
+pub async fn create_deployer_account(client: &mut Client<FilesystemKeyStore<StdRng>>, keystore: &mut Arc<FilesystemKeyStore<StdRng>>, mnemonic: Option<&str>) -> Result<Account> {
+    let (deployer_account, _) = match mnemonic {
+        Some(mnemonic) => create_account_from_mnemonic(client, keystore, mnemonic, 0).await?,
+        None => create_basic_wallet(client, keystore).await?,
+    };
+
+    println!(
+        "Deployer account ID: {:?}",
+        deployer_account.id().to_string()
+    );
+    Ok(deployer_account) 
+}
+
+// Same limit as miden_objects TokenSymbol, which encodes the symbol into a single felt
+pub const MAX_TOKEN_SYMBOL_LENGTH: usize = 6;
+
+// Checked before building a faucet, TokenSymbol only reports a generic error for these
+pub fn validate_token_symbol(symbol: &str) -> std::result::Result<(), RegistryError> {
+    let invalid = |reason| Err(RegistryError::InvalidTokenSymbol { symbol: symbol.to_string(), reason });
+    if symbol.is_empty() {
+        return invalid("symbol is empty");
+    }
+    if !symbol.chars().all(|c| c.is_ascii_uppercase()) {
+        return invalid("only uppercase A-Z allowed");
+    }
+    if symbol.len() > MAX_TOKEN_SYMBOL_LENGTH {
+        return invalid("at most 6 characters allowed");
+    }
+    Ok(())
+}
+
+pub fn naming_account_component(options: CompileOptions) -> Result<AccountComponent> {
+    let account_code = fs::read_to_string(Path::new("./masm/accounts/naming.masm"))?;
+
+    let account_component = AccountComponent::compile(
+            &account_code,
+            options.assembler(),
+            naming_storage()
+        )?
+        .with_supports_all_types();
+
+    Ok(account_component)
+}
+
+// Public RpoFalcon512 wallet tracked by the client. The key is stored in the keystore under its public key
+// commitment (the auth slot of the wallet) and returned so owner notes can be signed with it later.
+pub async fn create_basic_wallet(client: &mut Client<FilesystemKeyStore<StdRng>>, keystore: &Arc<FilesystemKeyStore<StdRng>>) -> Result<(Account, AuthSecretKey)> {
     let mut init_seed = [0_u8; 32];
     client.rng().fill_bytes(&mut init_seed);
 
     let key_pair = AuthSecretKey::new_rpo_falcon512();
+    let wallet = build_basic_wallet(init_seed, &key_pair)?;
+
+    add_contract_account(client, &wallet, None).await?;
+    keystore.add_key(&key_pair)?;
+
+    Ok((wallet, key_pair))
+}
 
-    // Build the account
-    let deployer_account = AccountBuilder::new(init_seed)
+fn build_basic_wallet(init_seed: [u8; 32], key_pair: &AuthSecretKey) -> Result<Account> {
+    Ok(AccountBuilder::new(init_seed)
         .account_type(AccountType::RegularAccountUpdatableCode)
         .storage_mode(AccountStorageMode::Public)
         .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
         .with_component(BasicWallet)
-        .build()
-        .unwrap();
+        .build()?)
+}
 
-    // Add the account to the client
-    client.add_account(&deployer_account, false).await?;
+// 24 words from 256 bits of entropy
+pub fn generate_mnemonic() -> String {
+    let mut entropy = [0_u8; 32];
+    rand::rng().fill_bytes(&mut entropy);
+    Mnemonic::from_entropy(&entropy).expect("32 bytes is a valid BIP39 entropy length").to_string()
+}
 
-    // Add the key pair to the keystore
-    keystore.add_key(&key_pair).unwrap();
+// Key and account seed of the owner wallet at `index`, both expanded with HKDF-SHA256 from the BIP39
+// seed of the mnemonic (no passphrase). Same mnemonic and index, same key and account ID.
+pub fn derive_owner_wallet(mnemonic: &str, index: u32) -> Result<(Account, AuthSecretKey)> {
+    let mnemonic = Mnemonic::parse_normalized(mnemonic).map_err(|err| RegistryError::InvalidMnemonic(err.to_string()))?;
+    let hkdf = Hkdf::<Sha256>::new(Some(b"midenname owner"), &mnemonic.to_seed(""));
+    let expand = |label: &str| {
+        let mut okm = [0_u8; 32];
+        hkdf.expand(format!("{label}/{index}").as_bytes(), &mut okm).expect("32 bytes is a valid HKDF-SHA256 output length");
+        okm
+    };
 
-    println!(
-        "Deployer account ID: {:?}",
-        deployer_account.id().to_string()
-    );
-    Ok(deployer_account) 
+    let key_pair = AuthSecretKey::new_rpo_falcon512_with_rng(&mut ChaCha20Rng::from_seed(expand("falcon512")));
+    let wallet = build_basic_wallet(expand("account"), &key_pair)?;
+    Ok((wallet, key_pair))
 }
 
-pub async fn create_naming_account(client: &mut Client<FilesystemKeyStore<StdRng>>) -> anyhow::Result<Account> {
-    let account_code = fs::read_to_string(Path::new("./masm/accounts/naming.masm")).unwrap();
+// Creates the wallet of derive_owner_wallet, or recovers it when it is already on chain, and stores its key
+pub async fn create_account_from_mnemonic(client: &mut Client<FilesystemKeyStore<StdRng>>, keystore: &Arc<FilesystemKeyStore<StdRng>>, mnemonic: &str, index: u32) -> Result<(Account, AuthSecretKey)> {
+    let (wallet, key_pair) = derive_owner_wallet(mnemonic, index)?;
 
-    let account_component = AccountComponent::compile(
-            &account_code,
-            TransactionKernel::assembler(),
-            naming_storage()
-        )?
-        .with_supports_all_types();
+    add_contract_account(client, &wallet, None).await?;
+    keystore.add_key(&key_pair)?;
+
+    Ok((wallet, key_pair))
+}
+
+// Signs an owner note with the key of a wallet created by create_basic_wallet
+pub async fn sign_as(client: &mut Client<FilesystemKeyStore<StdRng>>, keystore: &FilesystemKeyStore<StdRng>, account_id: AccountId, note: Note) -> Result<SignedOwnerNote> {
+    let record = client.get_account(account_id).await?
+        .ok_or_else(|| RegistryError::AccountNotTracked(account_id.to_hex()))?;
+    let pub_key = record.account().storage().get_item(WALLET_PUBKEY_SLOT)?;
+    let key = keystore.get_key(pub_key.into())?
+        .ok_or_else(|| RegistryError::KeyNotFound(account_id.to_hex()))?;
+
+    Ok(sign_owner_note(note, &key))
+}
+
+pub async fn create_naming_account(client: &mut Client<FilesystemKeyStore<StdRng>>, options: CompileOptions) -> Result<Account> {
+    let account_component = naming_account_component(options)?;
 
     let mut seed = [0_u8; 32];
     client.rng().fill_bytes(&mut seed);
@@ -56,7 +139,7 @@ pub async fn create_naming_account(client: &mut Client<FilesystemKeyStore<StdRng
         .with_auth_component(NoAuth)
         .build()?;
 
-    client.add_account(&account, false).await?;
+    add_contract_account(client, &account, None).await?;
 
     println!(
         "Naming account ID: {:?}",
@@ -64,3 +147,22 @@ pub async fn create_naming_account(client: &mut Client<FilesystemKeyStore<StdRng
     );
     Ok(account)
 }
+
+// Accounts already on chain are imported by id, new accounts are added with their seed so the first
+// transaction deploys them. `seed` is only needed when the account was built without one.
+pub async fn add_contract_account(client: &mut Client<FilesystemKeyStore<StdRng>>, account: &Account, seed: Option<Word>) -> Result<()> {
+    if client.get_account(account.id()).await?.is_some() {
+        return Ok(());
+    }
+    match client.import_account_by_id(account.id()).await {
+        Ok(()) => return Ok(()),
+        // Not deployed yet, added below
+        Err(ClientError::AccountNotFoundOnChain(_)) => {}
+        Err(err) => return Err(err.into()),
+    }
+
+    let seed = seed.or(account.seed()).ok_or_else(|| RegistryError::MissingAccountSeed(account.id().to_hex()))?;
+    let account = Account::new(account.id(), account.vault().clone(), account.storage().clone(), account.code().clone(), account.nonce(), Some(seed))?;
+    client.add_account(&account, false).await?;
+    Ok(())
+}
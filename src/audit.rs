@@ -0,0 +1,207 @@
+use miden_client::{account::{Account, AccountId, StorageSlot}, note::Note};
+use miden_crypto::Word;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, fmt};
+
+use crate::{compile::CompileOptions, domain::try_decode_domain, errors::{RegistryError, Result}, notes::{create_naming_activate_domain_note, create_naming_clear_expired_domain_note, create_naming_transfer_domain_note}, queries::{get_total_registrations, iter_registered_domains, map_len, read_map_item, word_to_account_id}, serde_hex::word_to_hex, storage::{ACCOUNT_ID_TO_DOMAIN_SLOT, CLAIMED_REVENUE_SLOT, DOMAIN_TO_ACCOUNT_ID_SLOT, DOMAIN_TO_OWNER_SLOT, DONATIONS_SLOT, TOTAL_REVENUE_SLOT}, vault::{reconcile_vault, vault_record}};
+
+// Consistency audit of a naming account. Reads whole storage maps, so it needs the full account
+// (tracked by the client or in MockChain tests).
+
+// Domain map key with the name it encodes, None for hashed registrations
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DomainKey {
+    #[serde(with = "crate::serde_hex::word")]
+    pub key: Word,
+    pub name: Option<String>,
+}
+
+impl From<Word> for DomainKey {
+    fn from(key: Word) -> Self {
+        Self { key, name: try_decode_domain(key) }
+    }
+}
+
+impl fmt::Display for DomainKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{name}"),
+            None => write!(f, "hashed {}", word_to_hex(self.key)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Violation {
+    // Domain resolves to an account that doesn't own it, owner None when the domain has no owner entry
+    ForwardOwnerMismatch {
+        domain: DomainKey,
+        #[serde(with = "crate::serde_hex::account_id")]
+        account: AccountId,
+        #[serde(with = "crate::serde_hex::option_account_id")]
+        owner: Option<AccountId>,
+    },
+    // Account reverse resolves to a domain that doesn't resolve back to it
+    OrphanedReverse {
+        #[serde(with = "crate::serde_hex::account_id")]
+        account: AccountId,
+        domain: DomainKey,
+        #[serde(with = "crate::serde_hex::option_account_id")]
+        resolves_to: Option<AccountId>,
+    },
+    ClaimedExceedsRevenue {
+        #[serde(with = "crate::serde_hex::account_id")]
+        token: AccountId,
+        total_revenue: u64,
+        claimed_revenue: u64,
+    },
+    // The counters owe more than the vault holds
+    VaultShortfall {
+        #[serde(with = "crate::serde_hex::account_id")]
+        token: AccountId,
+        vault_balance: u64,
+        shortfall: u64,
+    },
+    // More domains have an owner than were ever registered
+    RegisteredExceedsCount {
+        registered_names: usize,
+        total_registrations: u64,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::ForwardOwnerMismatch { domain, account, owner: Some(owner) } =>
+                write!(f, "{} resolves to {} but is owned by {}", domain, account.to_hex(), owner.to_hex()),
+            Violation::ForwardOwnerMismatch { domain, account, owner: None } =>
+                write!(f, "{} resolves to {} but has no owner", domain, account.to_hex()),
+            Violation::OrphanedReverse { account, domain, resolves_to: Some(resolves_to) } =>
+                write!(f, "{} reverse resolves to {} which resolves to {}", account.to_hex(), domain, resolves_to.to_hex()),
+            Violation::OrphanedReverse { account, domain, resolves_to: None } =>
+                write!(f, "{} reverse resolves to {} which does not resolve", account.to_hex(), domain),
+            Violation::ClaimedExceedsRevenue { token, total_revenue, claimed_revenue } =>
+                write!(f, "claimed revenue {} of {} exceeds total revenue {}", claimed_revenue, token.to_hex(), total_revenue),
+            Violation::VaultShortfall { token, vault_balance, shortfall } =>
+                write!(f, "vault holds {} of {}, {} less than the counters owe", vault_balance, token.to_hex(), shortfall),
+            Violation::RegisteredExceedsCount { registered_names, total_registrations } =>
+                write!(f, "{} names have an owner but the domain count only records {} registrations", registered_names, total_registrations),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditReport {
+    pub violations: Vec<Violation>,
+}
+
+impl AuditReport {
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+// Owner notes that repair the violations they can, each has to be submitted by its note's sender.
+// Revenue and count violations and reverse entries of accounts that own no domain can't be repaired by a note.
+#[derive(Debug, Clone, Default)]
+pub struct RepairPlan {
+    pub repairs: Vec<(Violation, Note)>,
+    pub manual: Vec<Violation>,
+}
+
+// Every forward entry resolves to the domain's owner, every reverse entry resolves back, the domain count
+// covers every owned domain, and no token's revenue counters owe more than the vault holds
+pub fn audit_registry(naming: &Account) -> Result<AuditReport> {
+    let mut violations = Vec::new();
+
+    for (key, value) in map_entries(naming, DOMAIN_TO_ACCOUNT_ID_SLOT)? {
+        let Some(account) = word_to_account_id(value)? else { continue };
+        let owner = read_account(naming, DOMAIN_TO_OWNER_SLOT, key)?;
+        if owner != Some(account) {
+            violations.push(Violation::ForwardOwnerMismatch { domain: key.into(), account, owner });
+        }
+    }
+
+    for (key, domain) in map_entries(naming, ACCOUNT_ID_TO_DOMAIN_SLOT)? {
+        let Some(account) = word_to_account_id(key)? else { continue };
+        let resolves_to = read_account(naming, DOMAIN_TO_ACCOUNT_ID_SLOT, domain)?;
+        if resolves_to != Some(account) {
+            violations.push(Violation::OrphanedReverse { account, domain: domain.into(), resolves_to });
+        }
+    }
+
+    let registered_names = map_len(naming, DOMAIN_TO_OWNER_SLOT)?;
+    let total_registrations = get_total_registrations(naming)?;
+    if registered_names as u64 > total_registrations {
+        violations.push(Violation::RegisteredExceedsCount { registered_names, total_registrations });
+    }
+
+    let mut tokens = BTreeSet::new();
+    for slot in [TOTAL_REVENUE_SLOT, CLAIMED_REVENUE_SLOT, DONATIONS_SLOT] {
+        for (key, _) in map_entries(naming, slot)? {
+            tokens.extend(word_to_account_id(key)?);
+        }
+    }
+    for token in tokens {
+        let record = vault_record(naming, token)?;
+        if record.claimed_revenue > record.total_revenue {
+            violations.push(Violation::ClaimedExceedsRevenue { token, total_revenue: record.total_revenue, claimed_revenue: record.claimed_revenue });
+        }
+        let reconciliation = reconcile_vault(&record);
+        if reconciliation.shortfall > 0 {
+            violations.push(Violation::VaultShortfall { token, vault_balance: record.vault_balance, shortfall: reconciliation.shortfall });
+        }
+    }
+
+    Ok(AuditReport { violations })
+}
+
+// `sender` submits the permissionless clear notes, domain notes are sent by the domain owner
+pub async fn plan_repairs(naming: &Account, report: &AuditReport, sender: AccountId, options: CompileOptions) -> Result<RepairPlan> {
+    let naming_id = naming.id();
+    let mut plan = RepairPlan::default();
+
+    for violation in &report.violations {
+        let note = match violation {
+            // Transferring the domain to its own owner clears the forward entry and links it to the owner
+            Violation::ForwardOwnerMismatch { domain, owner: Some(owner), .. } =>
+                Some(create_naming_transfer_domain_note(*owner, naming_id, domain.key, *owner, options).await?),
+            // Without an owner entry there is no expiry either, so the domain counts as expired
+            Violation::ForwardOwnerMismatch { domain, owner: None, .. } =>
+                Some(create_naming_clear_expired_domain_note(sender, naming_id, domain.key, options).await?),
+            // Activating a domain the account owns replaces its reverse entry, its own domain first
+            Violation::OrphanedReverse { account, domain, .. } => {
+                let owned = iter_registered_domains(naming)
+                    .filter(|(_, owner)| owner == account)
+                    .map(|(key, _)| key)
+                    .min_by_key(|key| *key != domain.key);
+                match owned {
+                    Some(key) => Some(create_naming_activate_domain_note(*account, naming_id, key, options).await?),
+                    None => None,
+                }
+            }
+            Violation::ClaimedExceedsRevenue { .. } | Violation::VaultShortfall { .. } | Violation::RegisteredExceedsCount { .. } => None,
+        };
+        match note {
+            Some(note) => plan.repairs.push((violation.clone(), note)),
+            None => plan.manual.push(violation.clone()),
+        }
+    }
+    Ok(plan)
+}
+
+fn map_entries(naming: &Account, slot: u8) -> Result<Vec<(Word, Word)>> {
+    match naming.storage().slots().get(slot as usize) {
+        Some(StorageSlot::Map(map)) => Ok(map.entries().map(|(key, value)| (*key, *value)).collect()),
+        Some(_) => Err(RegistryError::StorageSlotNotMap { slot }.into()),
+        None => Err(RegistryError::StorageSlotMissing { slot }.into()),
+    }
+}
+
+fn read_account(naming: &Account, slot: u8, key: Word) -> Result<Option<AccountId>> {
+    match read_map_item(naming, slot, key)? {
+        Some(value) => word_to_account_id(value),
+        None => Ok(None),
+    }
+}
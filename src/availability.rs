@@ -0,0 +1,81 @@
+use miden_client::account::{Account, AccountId};
+use serde::{Deserialize, Serialize};
+
+use crate::{domain::{try_encode_domain, validate_domain}, errors::Result, pricing::get_yearly_price, queries::{read_map_item, word_to_account_id}, storage::{DOMAIN_EXPIRY_DATES_SLOT, DOMAIN_TO_OWNER_SLOT, RESERVED_NAMES_SLOT}};
+
+const SUGGESTION_SUFFIXES: [&str; 12] = ["1", "2", "3", "4", "5", "6", "7", "8", "9", "x", "z", "0"];
+// Look-alike characters tried in place, in both directions
+const SUBSTITUTIONS: [(char, char); 5] = [('o', '0'), ('i', '1'), ('l', '1'), ('e', '3'), ('s', '5')];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Availability {
+    // Current owner if the domain is registered and not expired
+    #[serde(with = "crate::serde_hex::option_account_id")]
+    pub taken_by: Option<AccountId>,
+    pub reserved: bool,
+    // One year price for the domain length in the given payment token, 0 if no price is set
+    pub price: u64,
+}
+
+impl Availability {
+    pub fn is_available(&self) -> bool {
+        self.taken_by.is_none() && !self.reserved
+    }
+}
+
+// Mirrors register checks: a domain is taken while its expiry is not passed and it has an owner.
+// `now` is the block timestamp the registration would execute at.
+pub fn check_availability(naming: &Account, name: &str, payment_token: AccountId, now: u64) -> Result<Availability> {
+    validate_domain(name)?;
+    let domain = try_encode_domain(name)?;
+
+    let expiry = read_map_item(naming, DOMAIN_EXPIRY_DATES_SLOT, domain)?.map(|value| value[0].as_int()).unwrap_or(0);
+    let taken_by = if expiry >= now {
+        match read_map_item(naming, DOMAIN_TO_OWNER_SLOT, domain)? {
+            Some(value) => word_to_account_id(value)?,
+            None => None,
+        }
+    } else {
+        None
+    };
+
+    let reserved = read_map_item(naming, RESERVED_NAMES_SLOT, domain)?.is_some_and(|value| value[0].as_int() == 1);
+
+    let price = get_yearly_price(naming, domain, payment_token)?;
+
+    Ok(Availability { taken_by, reserved, price })
+}
+
+// Available variants of the name: digit/letter suffixes first, then look-alike substitutions
+pub fn suggest_alternatives(naming: &Account, name: &str, payment_token: AccountId, now: u64, count: usize) -> Result<Vec<String>> {
+    try_encode_domain(name)?;
+
+    let suffixed = SUGGESTION_SUFFIXES.iter().map(|suffix| format!("{}{}", name, suffix));
+    let substituted = name.char_indices().flat_map(|(position, character)| {
+        SUBSTITUTIONS.iter().filter_map(move |(a, b)| match character {
+            c if c == *a => Some(*b),
+            c if c == *b => Some(*a),
+            _ => None,
+        })
+        .map(move |replacement| {
+            let mut variant = name.to_string();
+            variant.replace_range(position..position + 1, &replacement.to_string());
+            variant
+        })
+    });
+
+    let mut suggestions: Vec<String> = Vec::new();
+    for candidate in suffixed.chain(substituted) {
+        if suggestions.len() == count {
+            break;
+        }
+        // Too long variants are skipped
+        if validate_domain(&candidate).is_err() || suggestions.contains(&candidate) {
+            continue;
+        }
+        if check_availability(naming, &candidate, payment_token, now)?.is_available() {
+            suggestions.push(candidate);
+        }
+    }
+    Ok(suggestions)
+}
@@ -0,0 +1,220 @@
+use miden_client::{account::{Account, AccountBuilder, AccountId, AccountStorageMode}, asset::FungibleAsset, auth::NoAuth, note::{Note, NoteAssets, NoteInputs}, testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1, transaction::{OutputNote, TransactionRequestBuilder}};
+use miden_crypto::Felt;
+use miden_testing::{Auth, MockChain};
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Display, fs, future::Future, path::Path, time::{Duration, Instant}};
+use thiserror::Error;
+
+use crate::{accounts::naming_account_component, compile::CompileOptions, domain::{try_encode_domain, validate_domain}, notes::{create_naming_free_register_name_note, create_naming_register_name_note_from_name, create_naming_set_price_notes, create_note_for_naming}, pricing::quote_registration, queries::get_domain_owner, registry::RegistryClient, transaction::{consume_notes, wait_for_tx}};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    Setup,
+    NoteConstruction,
+    Submission,
+    // Note committed and consumed by the naming account
+    Commitment,
+    // Sync and read of the registered name
+    Lookup,
+    // Read-only resolution of an existing name
+    Resolve,
+}
+
+#[derive(Debug, Error)]
+#[error("bench failed at {stage:?}: {error}")]
+pub struct BenchFailure {
+    pub stage: Stage,
+    pub error: String,
+}
+
+fn fail(stage: Stage) -> impl Fn(&dyn Display) -> BenchFailure {
+    move |err| BenchFailure { stage, error: err.to_string() }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StageReport {
+    pub stage: Stage,
+    pub samples: usize,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct LatencyRecorder {
+    samples: BTreeMap<Stage, Vec<Duration>>,
+}
+
+impl LatencyRecorder {
+    pub fn record(&mut self, stage: Stage, elapsed: Duration) {
+        self.samples.entry(stage).or_default().push(elapsed);
+    }
+
+    // Times the future, failed attempts are not recorded
+    pub async fn time<T, E: Display>(&mut self, stage: Stage, future: impl Future<Output = Result<T, E>>) -> Result<T, BenchFailure> {
+        let start = Instant::now();
+        let output = future.await.map_err(|err| fail(stage)(&err))?;
+        self.record(stage, start.elapsed());
+        Ok(output)
+    }
+
+    pub fn report(&self) -> Vec<StageReport> {
+        self.samples.iter().map(|(stage, samples)| {
+            let mut sorted = samples.clone();
+            sorted.sort();
+            StageReport {
+                stage: *stage,
+                samples: sorted.len(),
+                p50_ms: as_ms(percentile(&sorted, 50)),
+                p95_ms: as_ms(percentile(&sorted, 95)),
+                max_ms: as_ms(sorted.last().copied().unwrap_or_default()),
+            }
+        }).collect()
+    }
+}
+
+// Nearest-rank percentile of sorted samples
+pub fn percentile(sorted: &[Duration], percent: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (percent * sorted.len()).div_ceil(100).max(1);
+    sorted[rank.min(sorted.len()) - 1]
+}
+
+fn as_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+// JSON for a .json path, CSV otherwise
+pub fn write_report(path: &Path, reports: &[StageReport]) -> Result<(), BenchFailure> {
+    let content = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::to_string_pretty(reports).map_err(|err| fail(Stage::Setup)(&err))?
+    } else {
+        let mut csv = String::from("stage,samples,p50_ms,p95_ms,max_ms\n");
+        for report in reports {
+            let stage = serde_json::to_value(report.stage).map_err(|err| fail(Stage::Setup)(&err))?;
+            csv.push_str(&format!("{},{},{:.3},{:.3},{:.3}\n", stage.as_str().unwrap_or_default(), report.samples, report.p50_ms, report.p95_ms, report.max_ms));
+        }
+        csv
+    };
+    fs::write(path, content).map_err(|err| fail(Stage::Setup)(&err))
+}
+
+// Disposable names `{prefix}{i}`, the prefix has to leave room for the counter
+pub fn bench_names(prefix: &str, iterations: usize) -> Result<Vec<String>, BenchFailure> {
+    let names: Vec<String> = (0..iterations).map(|i| format!("{prefix}{i}")).collect();
+    for name in &names {
+        validate_domain(name).map_err(|err| fail(Stage::Setup)(&err))?;
+    }
+    Ok(names)
+}
+
+// Registers every name from `sender` on the network and resolves `resolve_name` (read-only) as many times
+pub async fn run_network(registry: &mut RegistryClient, sender: AccountId, payment_token: AccountId, names: &[String], resolve_name: Option<&str>, recorder: &mut LatencyRecorder) -> Result<(), BenchFailure> {
+    let naming_id = registry.naming_id;
+
+    for name in names {
+        let naming = registry.naming_account().await.map_err(|err| fail(Stage::NoteConstruction)(&err))?;
+        let note = recorder.time(Stage::NoteConstruction, register_note(&naming, sender, payment_token, name, registry.compile)).await?;
+
+        let request = TransactionRequestBuilder::new()
+            .own_output_notes(vec![OutputNote::Full(note)])
+            .build()
+            .map_err(|err| fail(Stage::Submission)(&err))?;
+        let tx_id = recorder.time(Stage::Submission, registry.client.submit_new_transaction(sender, request)).await?;
+
+        recorder.time(Stage::Commitment, async {
+            wait_for_tx(&mut registry.client, tx_id).await?;
+            consume_notes(&mut registry.client, naming_id, None).await
+        }).await?;
+
+        let owner = recorder.time(Stage::Lookup, async {
+            registry.sync().await?;
+            registry.domain_owner(name).await
+        }).await?;
+        if owner != Some(sender) {
+            return Err(BenchFailure { stage: Stage::Lookup, error: format!("{name} is not owned by the sender after registration") });
+        }
+    }
+
+    if let Some(resolve_name) = resolve_name {
+        for _ in names {
+            recorder.time(Stage::Resolve, async {
+                registry.sync().await?;
+                registry.resolve(resolve_name).await
+            }).await?;
+        }
+    }
+    Ok(())
+}
+
+// Same pipeline on a local MockChain: submission is transaction execution, commitment is block proving
+pub async fn run_mock(names: &[String], recorder: &mut LatencyRecorder) -> Result<(), BenchFailure> {
+    let setup = fail(Stage::Setup);
+    // Compiled like a deployment, debug info would skew the timings
+    let options = CompileOptions::RELEASE;
+    let payment_token = AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1).map_err(|err| setup(&err))?;
+
+    let mut builder = MockChain::builder();
+    let owner = builder.add_existing_wallet(Auth::BasicAuth).map_err(|err| setup(&err))?;
+    let registrant = builder.add_existing_wallet(Auth::BasicAuth).map_err(|err| setup(&err))?;
+    let mut naming = AccountBuilder::new(rand::random())
+        .with_auth_component(NoAuth)
+        .with_component(naming_account_component(options).map_err(|err| setup(&err))?)
+        .storage_mode(AccountStorageMode::Public)
+        .build_existing()
+        .map_err(|err| setup(&err))?;
+    builder.add_account(naming.clone()).map_err(|err| setup(&err))?;
+    let mut chain = builder.build().map_err(|err| setup(&err))?;
+
+    let init_inputs = NoteInputs::new(vec![owner.id().suffix(), owner.id().prefix().as_felt(), Felt::new(0), Felt::new(0), Felt::new(500), Felt::new(0), Felt::new(0), Felt::new(0)])
+        .map_err(|err| setup(&err))?;
+    let mut setup_notes = vec![create_note_for_naming("initialize_naming".to_string(), init_inputs, owner.id(), naming.id(), NoteAssets::new(vec![]).map_err(|err| setup(&err))?, options).await.map_err(|err| setup(&err))?];
+    let prices: BTreeMap<u8, u64> = (1..=20).map(|length| (length, 100)).collect();
+    setup_notes.extend(create_naming_set_price_notes(owner.id(), naming.id(), payment_token, &prices, options).await.map_err(|err| setup(&err))?);
+    for note in &setup_notes {
+        execute_mock(&mut chain, &mut naming, note, None).await?;
+    }
+
+    for name in names {
+        let note = recorder.time(Stage::NoteConstruction, register_note(&naming, registrant.id(), payment_token, name, options)).await?;
+        execute_mock(&mut chain, &mut naming, &note, Some(recorder)).await?;
+
+        let owner = recorder.time(Stage::Lookup, async { get_domain_owner(&naming, name) }).await?;
+        if owner != Some(registrant.id()) {
+            return Err(BenchFailure { stage: Stage::Lookup, error: format!("{name} is not owned by the registrant after registration") });
+        }
+    }
+    if let Some(name) = names.first() {
+        for _ in names {
+            recorder.time(Stage::Resolve, async { get_domain_owner(&naming, name) }).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn register_note(naming: &Account, registrant: AccountId, payment_token: AccountId, name: &str, options: CompileOptions) -> crate::errors::Result<Note> {
+    let amount = quote_registration(naming, name, payment_token, 1)?;
+    if amount == 0 {
+        return create_naming_free_register_name_note(registrant, naming.id(), payment_token, try_encode_domain(name)?, 1, options).await;
+    }
+    create_naming_register_name_note_from_name(registrant, naming.id(), name, FungibleAsset::new(payment_token, amount)?, 1, options).await
+}
+
+// Notes are consumed as unauthenticated input notes, so they don't have to be in the genesis block
+async fn execute_mock(chain: &mut MockChain, naming: &mut Account, note: &Note, recorder: Option<&mut LatencyRecorder>) -> Result<(), BenchFailure> {
+    let mut scratch = LatencyRecorder::default();
+    let recorder = recorder.unwrap_or(&mut scratch);
+
+    let executed_tx = recorder.time(Stage::Submission, async {
+        chain.build_tx_context(naming.id(), &[], &[note.clone()])?.build()?.execute().await.map_err(anyhow::Error::from)
+    }).await?;
+    naming.apply_delta(executed_tx.account_delta()).map_err(|err| fail(Stage::Submission)(&err))?;
+
+    recorder.time(Stage::Commitment, async {
+        chain.add_pending_executed_transaction(&executed_tx)?;
+        chain.prove_next_block().map(|_| ())
+    }).await
+}
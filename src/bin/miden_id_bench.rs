@@ -0,0 +1,73 @@
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use midenname_contracts::{bench::{LatencyRecorder, bench_names, run_mock, run_network, write_report}, compile::CompileOptions, config::{CliConfig, DEFAULT_CONFIG_PATH}, context::Context, registry::RegistryClient};
+
+/// Measures registration and resolution latency against the network of ./midenname.toml (testnet without one), or
+/// against a local MockChain with --mock
+#[derive(Parser)]
+#[command(name = "miden-id-bench")]
+struct Args {
+    /// Run the pipeline on a local MockChain instead of the network
+    #[arg(long)]
+    mock: bool,
+
+    /// Number of names to register (and resolutions to run)
+    #[arg(long, default_value_t = 10)]
+    iterations: usize,
+
+    /// Prefix of the disposable names, `{prefix}{i}` is registered
+    #[arg(long)]
+    prefix: Option<String>,
+
+    /// Naming account ID (network mode)
+    #[arg(long)]
+    naming: Option<String>,
+
+    /// Wallet paying for the registrations (network mode)
+    #[arg(long)]
+    sender: Option<String>,
+
+    /// Payment token faucet ID (network mode)
+    #[arg(long)]
+    token: Option<String>,
+
+    /// Existing name to resolve read-only (network mode)
+    #[arg(long)]
+    resolve: Option<String>,
+
+    /// Report path, JSON for .json and CSV otherwise
+    #[arg(long, default_value = "bench.json")]
+    output: PathBuf,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    // Random prefix so reruns don't hit names registered by an earlier run
+    let prefix = args.prefix.unwrap_or_else(|| format!("b{}x", rand::random::<u16>()));
+    let names = bench_names(&prefix, args.iterations)?;
+    let mut recorder = LatencyRecorder::default();
+
+    if args.mock {
+        run_mock(&names, &mut recorder).await?;
+    } else {
+        let required = |value: Option<String>, flag: &str| value.ok_or_else(|| anyhow::anyhow!("--{flag} is required without --mock"));
+        let config = CliConfig::load_or_default(Path::new(DEFAULT_CONFIG_PATH))?;
+        let naming_id = config.account_id(&required(args.naming, "naming")?)?;
+        let sender = config.account_id(&required(args.sender, "sender")?)?;
+        let token = config.account_id(&required(args.token, "token")?)?;
+
+        let ctx = Context::connect(config, CompileOptions::RELEASE).await?;
+        let mut registry = RegistryClient::connect(ctx.client, naming_id).await?.with_compile_options(ctx.compile);
+        run_network(&mut registry, sender, token, &names, args.resolve.as_deref(), &mut recorder).await?;
+    }
+
+    let reports = recorder.report();
+    for report in &reports {
+        println!("{:?}: n={} p50={:.1}ms p95={:.1}ms max={:.1}ms", report.stage, report.samples, report.p50_ms, report.p95_ms, report.max_ms);
+    }
+    write_report(&args.output, &reports)?;
+    println!("Report written to {}", args.output.display());
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use std::{path::PathBuf, process::ExitCode};
+
+use clap::Parser;
+use midenname_contracts::{compile::CompileOptions, config::{CliConfig, DEFAULT_CONFIG_PATH}, context::Context, registry::RegistryClient, smoke::{SmokeConfig, StepStatus, run_smoke_test}};
+
+/// Runs the registration critical path against the network of the CLI config and reports each step
+#[derive(Parser)]
+#[command(name = "miden-id-smoke")]
+struct Args {
+    /// CLI config with the network, keystore and naming account ID
+    #[arg(long, default_value = DEFAULT_CONFIG_PATH)]
+    config: PathBuf,
+
+    /// Naming account ID, overrides the config
+    #[arg(long)]
+    naming: Option<String>,
+
+    /// Funded wallet that registers the throwaway name
+    #[arg(long)]
+    account: String,
+
+    /// Wallet the name is transferred to and released from
+    #[arg(long)]
+    second: String,
+
+    /// Payment token faucet ID
+    #[arg(long)]
+    token: String,
+
+    /// Existing name that must resolve
+    #[arg(long)]
+    resolve: Option<String>,
+
+    /// Name to register, random if omitted
+    #[arg(long)]
+    name: Option<String>,
+
+    /// JSON report path
+    #[arg(long, default_value = "smoke.json")]
+    output: PathBuf,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<ExitCode> {
+    let args = Args::parse();
+    let config = CliConfig::load(&args.config)?;
+    let naming_id = match args.naming.as_deref() {
+        Some(naming) => config.account_id(naming)?,
+        None => config.naming_account_id()?.ok_or_else(|| anyhow::anyhow!("no naming account in {}, pass --naming", args.config.display()))?,
+    };
+    let smoke_config = SmokeConfig {
+        primary: config.account_id(&args.account)?,
+        secondary: config.account_id(&args.second)?,
+        payment_token: config.account_id(&args.token)?,
+        known_name: args.resolve,
+    };
+    // Random name so reruns don't collide with names left by a failed run
+    let name = args.name.unwrap_or_else(|| format!("smoke{}", rand::random::<u32>()));
+
+    let ctx = Context::connect(config, CompileOptions::RELEASE).await?;
+    let mut registry = RegistryClient::connect(ctx.client, naming_id).await?.with_compile_options(ctx.compile);
+
+    let report = run_smoke_test(&mut registry, &smoke_config, &name).await;
+    for result in &report.steps {
+        let status = match result.status {
+            StepStatus::Passed => "PASS",
+            StepStatus::Failed => "FAIL",
+            StepStatus::Skipped => "SKIP",
+        };
+        println!("{status} {:?} ({:.1}ms){}", result.step, result.elapsed_ms, result.error.as_deref().map(|err| format!(": {err}")).unwrap_or_default());
+    }
+    report.write(&args.output)?;
+    println!("Report written to {}", args.output.display());
+
+    Ok(if report.passed() { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
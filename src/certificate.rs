@@ -0,0 +1,82 @@
+use miden_client::account::AccountId;
+use miden_crypto::Word;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use crate::{errors::{RegistryError, Result}, queries::{Lookup, word_to_account_id}, stateless::{AccountStateSource, StorageMapWitness, prove_resolution, verify_storage_map_witness}, storage::DOMAIN_TO_ACCOUNT_ID_SLOT};
+
+// Proof that a name resolved to an account at a block: the domain map entry with the openings up to the
+// block's account root. Anyone holding a trusted header of that block can check it offline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Certificate {
+    pub name: String,
+    #[serde(with = "crate::serde_hex::account_id")]
+    pub naming_id: AccountId,
+    #[serde(with = "crate::serde_hex::account_id")]
+    pub account_id: AccountId,
+    // Key the name is registered under
+    pub lookup: Lookup,
+    pub witness: StorageMapWitness,
+}
+
+impl Certificate {
+    pub fn block_num(&self) -> u32 {
+        self.witness.block_num
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content).map_err(io::Error::from)?)
+    }
+
+    // Compact JSON, the proofs make up most of it
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string(self).map_err(io::Error::from)?)?;
+        Ok(())
+    }
+}
+
+// Fails with NameNotFound when the name doesn't resolve at the node's latest block
+pub async fn issue_certificate(source: &mut impl AccountStateSource, naming_id: AccountId, name: &str) -> Result<Certificate> {
+    let (lookup, account_id, witness) = prove_resolution(source, naming_id, name).await?
+        .ok_or_else(|| RegistryError::NameNotFound(name.to_string()))?;
+    Ok(Certificate { name: name.to_string(), naming_id, account_id, lookup, witness })
+}
+
+// `trusted_account_root` is the account root of the certificate's block, taken from a header the verifier trusts
+pub fn verify_certificate(certificate: &Certificate, trusted_account_root: Word) -> Result<()> {
+    let invalid = |reason| RegistryError::InvalidCertificate { name: certificate.name.clone(), reason };
+
+    if certificate.witness.account_root != trusted_account_root {
+        return Err(invalid("issued against another account root").into());
+    }
+    let key = certificate.lookup.key(&certificate.name)?;
+    let value = verify_storage_map_witness(certificate.naming_id, &certificate.witness, DOMAIN_TO_ACCOUNT_ID_SLOT, key)?;
+    match word_to_account_id(value)? {
+        Some(account_id) if account_id == certificate.account_id => Ok(()),
+        Some(_) => Err(invalid("name resolves to another account").into()),
+        None => Err(invalid("name does not resolve at that block").into()),
+    }
+}
+
+// Certificates of one name at several blocks, each checked against the trusted account root of its block.
+// Returns the account the name resolved to at each block, in block order.
+pub fn verify_historical(certificates: &[Certificate], trusted_roots: &BTreeMap<u32, Word>) -> Result<Vec<(u32, AccountId)>> {
+    let mut history = Vec::with_capacity(certificates.len());
+    for certificate in certificates {
+        let invalid = |reason| RegistryError::InvalidCertificate { name: certificate.name.clone(), reason };
+        if let Some(first) = certificates.first() {
+            if certificate.name != first.name {
+                return Err(invalid("certificates are for different names").into());
+            }
+            if certificate.naming_id != first.naming_id {
+                return Err(invalid("certificates are for different naming accounts").into());
+            }
+        }
+        let root = trusted_roots.get(&certificate.block_num()).ok_or_else(|| invalid("no trusted account root for its block"))?;
+        verify_certificate(certificate, *root)?;
+        history.push((certificate.block_num(), certificate.account_id));
+    }
+    history.sort_by_key(|(block_num, _)| *block_num);
+    Ok(history)
+}
@@ -1,34 +1,139 @@
-use std::sync::Arc;
+use std::{fs, io, path::{Path, PathBuf}, sync::Arc, thread, time::Duration};
 
 use miden_client::{builder::ClientBuilder, keystore::FilesystemKeyStore, rpc::{Endpoint, GrpcClient}, Client};
 use miden_client_sqlite_store::ClientBuilderSqliteExt;
 use rand::rngs::StdRng;
 
-const TIMEOUT: u64 = 10_000;
+use crate::{compile::CompileOptions, config::{CliConfig, ConfigError, DEFAULT_KEYSTORE_PATH, DEFAULT_STORE_PATH}, errors::Result};
 
-pub async fn initiate_client(keystore: Arc<FilesystemKeyStore<StdRng>>) -> anyhow::Result<Client<FilesystemKeyStore<StdRng>>> {
-    let endpoint = Endpoint::testnet();
+pub const DEFAULT_RPC_TIMEOUT_MS: u64 = 10_000;
+pub const RPC_TIMEOUT_ENV: &str = "MIDEN_RPC_TIMEOUT_MS";
 
-    let rpc_client = Arc::new(GrpcClient::new(&endpoint, TIMEOUT));
-
-    let store_path = std::path::PathBuf::from("./store.sqlite3");
+// Every RPC call, including the initial sync, fails after timeout_ms
+pub async fn initiate_client_with_timeout(keystore: Arc<FilesystemKeyStore<StdRng>>, endpoint: &Endpoint, store_path: PathBuf, timeout_ms: u64, options: CompileOptions) -> Result<Client<FilesystemKeyStore<StdRng>>> {
+    let rpc_client = Arc::new(GrpcClient::new(endpoint, timeout_ms));
 
     let mut client= ClientBuilder::new()
         .rpc(rpc_client)
         .sqlite_store(store_path)
         .authenticator(keystore.clone())
-        .in_debug_mode(true.into())
+        .in_debug_mode(options.debug.into())
         .build()
         .await?;
 
-    let sync_summary = client.sync_state().await.unwrap();
+    let sync_summary = client.sync_state().await?;
     println!("Latest block: {}", sync_summary.block_num);
     Ok(client)
 }
 
-pub fn create_keystore() -> anyhow::Result<Arc<FilesystemKeyStore<StdRng>>> {
-    let keystore_path = std::path::PathBuf::from("./keystore");
-    let keystore: Arc<FilesystemKeyStore<StdRng>> = Arc::new(FilesystemKeyStore::<StdRng>::new(keystore_path)?);
-    
-    Ok(keystore)
-}
\ No newline at end of file
+// Value of MIDEN_RPC_TIMEOUT_MS, unset falls back to the default
+pub fn parse_rpc_timeout(value: Option<&str>) -> std::result::Result<u64, ConfigError> {
+    match value {
+        None => Ok(DEFAULT_RPC_TIMEOUT_MS),
+        Some(value) => value.trim().parse::<u64>()
+            .ok()
+            .filter(|timeout_ms| *timeout_ms > 0)
+            .ok_or_else(|| ConfigError::InvalidRpcTimeout(value.to_string())),
+    }
+}
+
+pub fn rpc_timeout_from_env() -> std::result::Result<u64, ConfigError> {
+    parse_rpc_timeout(std::env::var(RPC_TIMEOUT_ENV).ok().as_deref())
+}
+
+pub fn create_keystore(path: PathBuf) -> Result<Arc<FilesystemKeyStore<StdRng>>> {
+    Ok(Arc::new(FilesystemKeyStore::<StdRng>::new(path)?))
+}
+
+// Local client state removed by clean_environment. The store may be a single SQLite file (with -wal and
+// -shm siblings while a client has it open) or a directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanPaths {
+    pub store: PathBuf,
+    pub keystore: PathBuf,
+}
+
+impl Default for CleanPaths {
+    fn default() -> Self {
+        Self { store: PathBuf::from(DEFAULT_STORE_PATH), keystore: PathBuf::from(DEFAULT_KEYSTORE_PATH) }
+    }
+}
+
+impl From<&CliConfig> for CleanPaths {
+    fn from(config: &CliConfig) -> Self {
+        Self { store: config.store_path.clone(), keystore: config.keystore_path.clone() }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CleanReport {
+    pub removed: Vec<PathBuf>,
+    // Nothing to remove
+    pub missing: Vec<PathBuf>,
+    // Still there after the retries, with the last error
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl CleanReport {
+    pub fn is_clean(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+const CLEAN_RETRIES: u32 = 5;
+const CLEAN_BACKOFF: Duration = Duration::from_millis(50);
+
+// Drops the client first so its store connections are closed before the files are removed
+pub fn clean_environment_after(client: Client<FilesystemKeyStore<StdRng>>, paths: &CleanPaths) -> CleanReport {
+    drop(client);
+    clean_environment(paths)
+}
+
+// Removes the store, its WAL/SHM siblings and the keystore. Files another process still holds (sharing
+// violations on Windows) are retried with backoff and reported as failed if they never free up.
+pub fn clean_environment(paths: &CleanPaths) -> CleanReport {
+    let mut report = CleanReport::default();
+    for path in [paths.store.clone(), sqlite_sibling(&paths.store, "-wal"), sqlite_sibling(&paths.store, "-shm"), paths.keystore.clone()] {
+        match remove_with_retry(&path) {
+            Ok(true) => report.removed.push(path),
+            Ok(false) => report.missing.push(path),
+            Err(err) => report.failed.push((path, err.to_string())),
+        }
+    }
+    report
+}
+
+fn sqlite_sibling(store: &Path, suffix: &str) -> PathBuf {
+    let mut name = store.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+// Ok(false) when the path doesn't exist
+fn remove_with_retry(path: &Path) -> io::Result<bool> {
+    let mut backoff = CLEAN_BACKOFF;
+    let mut attempt = 0;
+    loop {
+        let result = match fs::symlink_metadata(path) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => Err(err),
+            Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path),
+            Ok(_) => fs::remove_file(path),
+        };
+        match result {
+            Ok(()) => return Ok(true),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(true),
+            Err(err) if attempt < CLEAN_RETRIES && is_sharing_violation(&err) => {
+                thread::sleep(backoff);
+                backoff *= 2;
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+// ERROR_SHARING_VIOLATION (32) and ERROR_LOCK_VIOLATION (33) on Windows, unix removes open files
+fn is_sharing_violation(err: &io::Error) -> bool {
+    cfg!(windows) && matches!(err.raw_os_error(), Some(32) | Some(33))
+}
@@ -0,0 +1,36 @@
+use miden_assembly::Assembler;
+use miden_client::{ScriptBuilder, transaction::TransactionKernel};
+
+// How masm is assembled. Debug mode keeps source locations for error reports and debug instructions, at the
+// cost of larger compiled code and slower proving. Debug info is not part of procedure roots, so note
+// template roots and deployed account code are the same in both modes. Passed explicitly to everything that
+// compiles masm, the CLI threads its options through the Context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompileOptions {
+    pub debug: bool,
+}
+
+impl CompileOptions {
+    pub const DEBUG: Self = Self { debug: true };
+    pub const RELEASE: Self = Self { debug: false };
+
+    // Release unless --debug-masm is given
+    pub fn cli(debug_masm: bool) -> Self {
+        Self { debug: debug_masm }
+    }
+
+    pub fn assembler(self) -> Assembler {
+        TransactionKernel::assembler().with_debug_mode(self.debug)
+    }
+
+    pub fn script_builder(self) -> ScriptBuilder {
+        ScriptBuilder::new(self.debug)
+    }
+}
+
+// Debug in tests (testing feature), release otherwise
+impl Default for CompileOptions {
+    fn default() -> Self {
+        Self { debug: cfg!(feature = "testing") }
+    }
+}
@@ -0,0 +1,353 @@
+use std::{collections::BTreeMap, fmt, fs, path::{Path, PathBuf}, str::FromStr, sync::Arc};
+
+use miden_client::{account::AccountId, rpc::{Endpoint, GrpcClient, NodeRpcClient}, transaction::TransactionId};
+use miden_objects::address::NetworkId;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{domain::{DEVNET_HRP, MAINNET_HRP, TESTNET_HRP}, errors::MidenIdError, queries::{parse_account_id, resolve_account_id}, pricing::{PriceTable, testnet_prices, validate_letter_count, validate_payment_token, validate_price}};
+
+pub const DEFAULT_CONFIG_PATH: &str = "./midenname.toml";
+pub const DEFAULT_KEYSTORE_PATH: &str = "./keystore";
+pub const DEFAULT_STORE_PATH: &str = "./store.sqlite3";
+// Payment token deploy sets prices for when the config doesn't name one
+pub const TESTNET_PAYMENT_TOKEN: &str = "0x54bf4e12ef20082070758b022456c7";
+
+const TESTNET_EXPLORER_URL: &str = "https://testnet.midenscan.com";
+
+const HEALTH_CHECK_TIMEOUT: u64 = 10_000;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ConfigError {
+    #[error("unsupported network '{0}', expected testnet, devnet, mainnet, localhost or an rpc url")]
+    UnsupportedNetwork(String),
+    #[error("invalid rpc url '{0}'")]
+    InvalidRpcUrl(String),
+    #[error("network {0} has no default rpc endpoint, pass the rpc url explicitly")]
+    RpcUrlRequired(String),
+    #[error("keystore path must not be empty")]
+    EmptyKeystorePath,
+    #[error("invalid account id '{0}'")]
+    InvalidAccountId(String),
+    #[error("invalid rpc timeout '{0}', expected a positive number of milliseconds")]
+    InvalidRpcTimeout(String),
+    #[error("rpc endpoint {0} is not reachable: {1}")]
+    EndpointUnreachable(String, String),
+    #[error("account {0} does not exist on the network")]
+    AccountNotFound(String),
+    #[error("invalid alias '{0}', aliases must not be empty or valid account ids themselves")]
+    InvalidAlias(String),
+    #[error("invalid price config: {0}")]
+    InvalidPriceConfig(String),
+    #[error("config io error: {0}")]
+    Io(String),
+}
+
+// Written to the config as its name, or as the endpoint URL for custom networks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Network {
+    Devnet,
+    Testnet,
+    Mainnet,
+    Localhost,
+    Custom(Endpoint),
+}
+
+impl Network {
+    // None for mainnet, which has no published endpoint yet, its rpc url has to be given explicitly
+    pub fn endpoint(&self) -> Option<Endpoint> {
+        match self {
+            Network::Devnet => Some(Endpoint::devnet()),
+            Network::Testnet => Some(Endpoint::testnet()),
+            Network::Mainnet => None,
+            Network::Localhost => Some(Endpoint::localhost()),
+            Network::Custom(endpoint) => Some(endpoint.clone()),
+        }
+    }
+
+    pub fn network_id(&self) -> NetworkId {
+        match self {
+            Network::Devnet => NetworkId::Devnet,
+            Network::Mainnet => NetworkId::Mainnet,
+            // Local and custom nodes run with the testnet genesis unless configured otherwise
+            Network::Testnet | Network::Localhost | Network::Custom(_) => NetworkId::Testnet,
+        }
+    }
+
+    // Human readable part of the network's bech32 addresses
+    pub fn bech32_prefix(&self) -> &'static str {
+        match self {
+            Network::Mainnet => MAINNET_HRP,
+            Network::Devnet => DEVNET_HRP,
+            Network::Testnet | Network::Localhost | Network::Custom(_) => TESTNET_HRP,
+        }
+    }
+
+    pub fn to_bech32(&self, account_id: AccountId) -> String {
+        account_id.to_bech32(self.network_id())
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Network::Devnet => write!(f, "devnet"),
+            Network::Testnet => write!(f, "testnet"),
+            Network::Mainnet => write!(f, "mainnet"),
+            Network::Localhost => write!(f, "localhost"),
+            Network::Custom(endpoint) => write!(f, "{}", endpoint),
+        }
+    }
+}
+
+// Network names are case insensitive, anything else has to be an RPC URL
+impl FromStr for Network {
+    type Err = ConfigError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "devnet" => Ok(Network::Devnet),
+            "testnet" => Ok(Network::Testnet),
+            "mainnet" => Ok(Network::Mainnet),
+            "localhost" => Ok(Network::Localhost),
+            _ if value.contains("://") => Endpoint::try_from(value.trim())
+                .map(Network::Custom)
+                .map_err(|_| ConfigError::UnsupportedNetwork(value.to_string())),
+            _ => Err(ConfigError::UnsupportedNetwork(value.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for Network {
+    type Error = ConfigError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Network> for String {
+    fn from(network: Network) -> Self {
+        network.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CliConfig {
+    pub network: Network,
+    pub rpc_url: String,
+    pub keystore_path: PathBuf,
+    // Config files written before the store path was configurable use the default
+    #[serde(default = "default_store_path")]
+    pub store_path: PathBuf,
+    pub naming_id: Option<String>,
+    // Faucet deploy sets the initial prices for, TESTNET_PAYMENT_TOKEN if unset
+    pub payment_token: Option<String>,
+    // Price config file (see load_price_config) deploy sets, the testnet prices if unset
+    pub prices: Option<PathBuf>,
+    // Aliases usable wherever the CLI takes an account ID, e.g. testnet-naming = "0x..." under [contracts]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub contracts: BTreeMap<String, String>,
+}
+
+// What the CLI used before it had a config file: testnet with the keystore and store in the working directory
+impl Default for CliConfig {
+    fn default() -> Self {
+        Self {
+            network: Network::Testnet,
+            rpc_url: Endpoint::testnet().to_string(),
+            keystore_path: PathBuf::from(DEFAULT_KEYSTORE_PATH),
+            store_path: default_store_path(),
+            naming_id: None,
+            payment_token: None,
+            prices: None,
+            contracts: BTreeMap::new(),
+        }
+    }
+}
+
+fn default_store_path() -> PathBuf {
+    PathBuf::from(DEFAULT_STORE_PATH)
+}
+
+impl CliConfig {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let content = fs::read_to_string(path).map_err(|err| ConfigError::Io(err.to_string()))?;
+        toml::from_str(&content).map_err(|err| ConfigError::Io(err.to_string()))
+    }
+
+    // The defaults when there is no file at `path`
+    pub fn load_or_default(path: &Path) -> Result<Self, ConfigError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::load(path)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ConfigError> {
+        let content = toml::to_string_pretty(self).map_err(|err| ConfigError::Io(err.to_string()))?;
+        fs::write(path, content).map_err(|err| ConfigError::Io(err.to_string()))
+    }
+
+    pub fn endpoint(&self) -> Result<Endpoint, ConfigError> {
+        Endpoint::try_from(self.rpc_url.as_str()).map_err(|_| ConfigError::InvalidRpcUrl(self.rpc_url.clone()))
+    }
+
+    pub fn naming_account_id(&self) -> Result<Option<AccountId>, ConfigError> {
+        self.naming_id
+            .as_ref()
+            .map(|id| self.account_id(id).map_err(|_| ConfigError::InvalidAccountId(id.clone())))
+            .transpose()
+    }
+
+    // Alias first, then bech32 and hex. validate() rejects aliases that parse as IDs, so an alias never
+    // changes what a real ID means.
+    pub fn account_id(&self, value: &str) -> Result<AccountId, MidenIdError> {
+        resolve_account_id(value, &self.contracts)
+    }
+
+    // Bech32 address with the prefix of the configured network
+    pub fn address(&self, account_id: AccountId) -> String {
+        self.network.to_bech32(account_id)
+    }
+
+    // "alias (0x...)" for IDs with an alias, the hex ID otherwise
+    pub fn account_label(&self, account_id: AccountId) -> String {
+        let alias = self.contracts.iter().find(|(_, address)| parse_account_id(address).ok() == Some(account_id));
+        match alias {
+            Some((alias, _)) => format!("{} ({})", alias, account_id.to_hex()),
+            None => account_id.to_hex(),
+        }
+    }
+
+    pub fn payment_token_id(&self) -> Result<AccountId, ConfigError> {
+        let id = self.payment_token.as_deref().unwrap_or(TESTNET_PAYMENT_TOKEN);
+        self.account_id(id).map_err(|_| ConfigError::InvalidAccountId(id.to_string()))
+    }
+
+    // Initial prices set by deploy
+    pub fn deploy_prices(&self) -> Result<BTreeMap<u8, u64>, MidenIdError> {
+        match &self.prices {
+            Some(path) => load_price_config(path),
+            None => Ok(testnet_prices()),
+        }
+    }
+
+    // Only testnet has an explorer
+    pub fn explorer_tx_url(&self, tx_id: TransactionId) -> Option<String> {
+        (self.network == Network::Testnet).then(|| format!("{}/tx/{:?}", TESTNET_EXPLORER_URL, tx_id))
+    }
+
+    // Offline checks, no network access
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.endpoint()?;
+        if self.keystore_path.as_os_str().is_empty() {
+            return Err(ConfigError::EmptyKeystorePath);
+        }
+        self.naming_account_id()?;
+        self.payment_token_id()?;
+        for (alias, address) in &self.contracts {
+            if alias.trim().is_empty() || parse_account_id(alias).is_ok() {
+                return Err(ConfigError::InvalidAlias(alias.clone()));
+            }
+            parse_account_id(address).map_err(|_| ConfigError::InvalidAccountId(address.clone()))?;
+        }
+        Ok(())
+    }
+
+    // Checks that the endpoint responds and the configured contracts exist
+    pub async fn validate_online(&self) -> Result<(), ConfigError> {
+        self.validate()?;
+
+        let rpc_client = Arc::new(GrpcClient::new(&self.endpoint()?, HEALTH_CHECK_TIMEOUT));
+        rpc_client
+            .get_block_header_by_number(None, false)
+            .await
+            .map_err(|err| ConfigError::EndpointUnreachable(self.rpc_url.clone(), err.to_string()))?;
+
+        if let Some(naming_id) = self.naming_account_id()? {
+            rpc_client
+                .get_account_details(naming_id)
+                .await
+                .map_err(|_| ConfigError::AccountNotFound(naming_id.to_hex()))?;
+        }
+        Ok(())
+    }
+}
+
+// Builds config from (possibly partial) values, missing rpc url is the network endpoint
+pub fn build_config(network: Network, rpc_url: Option<String>, keystore_path: PathBuf, naming_id: Option<String>) -> Result<CliConfig, ConfigError> {
+    let rpc_url = match rpc_url.or_else(|| network.endpoint().map(|endpoint| endpoint.to_string())) {
+        Some(rpc_url) => rpc_url,
+        None => return Err(ConfigError::RpcUrlRequired(network.to_string())),
+    };
+
+    let config = CliConfig {
+        network,
+        rpc_url,
+        keystore_path,
+        naming_id,
+        ..CliConfig::default()
+    };
+    config.validate()?;
+    Ok(config)
+}
+
+// One entry of a price config file, `length` is the letter count
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceTier {
+    pub length: u8,
+    pub price: u64,
+}
+
+// TOML has no top level arrays, tiers go under [[tiers]]
+#[derive(Deserialize)]
+struct PriceConfigFile {
+    tiers: Vec<PriceTier>,
+}
+
+// .json files hold [{"length": 1, "price": 100}, ...], anything else is read as TOML
+pub fn load_price_config(path: &Path) -> Result<BTreeMap<u8, u64>, MidenIdError> {
+    let content = fs::read_to_string(path).map_err(|err| ConfigError::Io(err.to_string()))?;
+    let tiers: Vec<PriceTier> = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str(&content).map_err(|err| ConfigError::InvalidPriceConfig(err.to_string()))?
+    } else {
+        toml::from_str::<PriceConfigFile>(&content).map_err(|err| ConfigError::InvalidPriceConfig(err.to_string()))?.tiers
+    };
+    price_table(&tiers)
+}
+
+// Price table written by export-prices, checked like a price config before it is imported
+pub fn load_price_table(path: &Path) -> Result<PriceTable, MidenIdError> {
+    let content = fs::read_to_string(path).map_err(|err| ConfigError::Io(err.to_string()))?;
+    let table: PriceTable = serde_json::from_str(&content).map_err(|err| ConfigError::InvalidPriceConfig(err.to_string()))?;
+    validate_payment_token(table.payment_token)?;
+    for (&letter_count, &price) in &table.prices {
+        validate_letter_count(letter_count)?;
+        validate_price(price, false)?;
+    }
+    Ok(table)
+}
+
+pub fn save_price_table(path: &Path, table: &PriceTable) -> Result<(), MidenIdError> {
+    let content = serde_json::to_string_pretty(table).map_err(|err| ConfigError::InvalidPriceConfig(err.to_string()))?;
+    fs::write(path, content).map_err(|err| ConfigError::Io(err.to_string()))?;
+    Ok(())
+}
+
+// Same checks as set-price without --allow-free, a length listed twice is rejected instead of overwritten
+pub fn price_table(tiers: &[PriceTier]) -> Result<BTreeMap<u8, u64>, MidenIdError> {
+    if tiers.is_empty() {
+        return Err(ConfigError::InvalidPriceConfig("no price tiers".to_string()).into());
+    }
+    let mut prices = BTreeMap::new();
+    for tier in tiers {
+        validate_letter_count(tier.length)?;
+        validate_price(tier.price, false)?;
+        if prices.insert(tier.length, tier.price).is_some() {
+            return Err(ConfigError::InvalidPriceConfig(format!("length {} is listed more than once", tier.length)).into());
+        }
+    }
+    Ok(prices)
+}
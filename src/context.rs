@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use miden_client::{Client, keystore::FilesystemKeyStore};
+use rand::rngs::StdRng;
+
+use crate::{client::{create_keystore, initiate_client_with_timeout, rpc_timeout_from_env}, compile::CompileOptions, config::CliConfig, errors::Result};
+
+// What every CLI command runs with, built once from the config instead of in each command
+pub struct Context {
+    pub config: CliConfig,
+    pub client: Client<FilesystemKeyStore<StdRng>>,
+    pub keystore: Arc<FilesystemKeyStore<StdRng>>,
+    // How notes, scripts and the naming component are compiled
+    pub compile: CompileOptions,
+}
+
+impl Context {
+    // Tests pass a client served by a mock chain
+    pub fn new(config: CliConfig, client: Client<FilesystemKeyStore<StdRng>>, keystore: Arc<FilesystemKeyStore<StdRng>>, compile: CompileOptions) -> Self {
+        Self { config, client, keystore, compile }
+    }
+
+    // Client on the configured endpoint, store and keystore, timeout from MIDEN_RPC_TIMEOUT_MS
+    pub async fn connect(config: CliConfig, compile: CompileOptions) -> Result<Self> {
+        config.validate()?;
+        let keystore = create_keystore(config.keystore_path.clone())?;
+        let client = initiate_client_with_timeout(keystore.clone(), &config.endpoint()?, config.store_path.clone(), rpc_timeout_from_env()?, compile).await?;
+        Ok(Self { config, client, keystore, compile })
+    }
+}
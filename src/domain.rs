@@ -1,4 +1,29 @@
-use miden_crypto::{Felt, Word};
+use std::collections::BTreeSet;
+
+use miden_crypto::{Felt, StarkField, Word, hash::rpo::Rpo256};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::errors::RegistryError;
+
+// Names longer than this are rejected by the Rust side, the contract allows 21
+pub const MAX_DOMAIN_LENGTH: usize = 20;
+
+// Highest code encode_char returns
+pub const MAX_CHAR_CODE: u8 = 36;
+const CHARS_PER_FELT: usize = 7;
+const BITS_PER_CHAR: usize = 8;
+
+// The highest character code in the top byte of a packed felt must stay below the field modulus, otherwise
+// Felt::new would silently reduce it and two names could share an encoding
+const _: () = assert!((MAX_CHAR_CODE as u64) << ((CHARS_PER_FELT - 1) * BITS_PER_CHAR) < Felt::MODULUS);
+
+// Felt::new reduces values modulo the field, this refuses them instead
+pub fn checked_felt(value: u64) -> Result<Felt, RegistryError> {
+    if value >= Felt::MODULUS {
+        return Err(RegistryError::FeltOverflow { value });
+    }
+    Ok(Felt::new(value))
+}
 
 // Helper function to encode a single character to its numeric representation
 pub fn encode_char(chr: char) -> Option<u8> {
@@ -41,48 +66,82 @@ pub fn decode_char(encoded: u8) -> Option<char> {
 // So we have to reverse here
 // [P4, P3, P2, P1] -> on MASM [P1, P2, P3, P4]
 pub fn encode_domain(domain: String) -> Word {
-    // Validate length: must be > 0 and <= 20
-    let len = domain.len();
-    assert!(len > 0, "Domain name must have at least 1 character");
-    assert!(len <= 20, "Domain name must be at most 20 characters");
+    try_encode_domain(&domain).unwrap_or_else(|err| panic!("{}", err))
+}
 
-    // Encode each character and store in a vector
-    let mut encoded_chars: Vec<u8> = Vec::new();
-    for c in domain.chars() {
-        let char_code = encode_char(c)
-            .expect(&format!("Invalid character '{}' in domain name", c));
-        encoded_chars.push(char_code);
-    }
+// Same as encode_domain but returns validation errors instead of panicking. Only the format is checked so
+// names registered before the address rule can still be looked up and managed; registration paths call
+// validate_domain.
+pub fn try_encode_domain(domain: &str) -> Result<Word, RegistryError> {
+    validate_domain_format(domain)?;
 
-    // Pack characters into Felts (7 characters per Felt, 8 bits each)
-    // First 7 characters go into felt3, next 7 into felt2, next 6 into felt1
-    let mut felt1: u64 = 0;
-    let mut felt2: u64 = 0;
-    let mut felt3: u64 = 0;
+    let encoded_chars: Vec<u8> = domain.chars().filter_map(encode_char).collect();
+    pack_domain(&encoded_chars, domain.len())
+}
 
-    for (i, &char_code) in encoded_chars.iter().enumerate() {
-        let bit_shift = (i % 7) * 8;
+// Rules for new registrations: the format rules plus no names that read as an account address
+pub fn validate_domain(domain: &str) -> Result<(), RegistryError> {
+    validate_domain_format(domain)?;
+    if looks_like_address(domain) {
+        return Err(RegistryError::LooksLikeAddress(domain.to_string()));
+    }
+    Ok(())
+}
 
-        if i < 7 {
-            // First 7 characters go into felt3
-            felt3 |= (char_code as u64) << bit_shift;
-        } else if i < 14 {
-            // Next 7 characters go into felt2
-            felt2 |= (char_code as u64) << bit_shift;
-        } else {
-            // Remaining characters go into felt1
-            felt1 |= (char_code as u64) << bit_shift;
+// Length must be > 0 and <= MAX_DOMAIN_LENGTH, only a-z and 0-9 allowed
+fn validate_domain_format(domain: &str) -> Result<(), RegistryError> {
+    if domain.is_empty() {
+        return Err(RegistryError::EmptyDomain);
+    }
+    for (position, character) in domain.chars().enumerate() {
+        if encode_char(character).is_none() {
+            return Err(RegistryError::InvalidCharacter { character, position });
         }
     }
+    if domain.len() > MAX_DOMAIN_LENGTH {
+        return Err(RegistryError::DomainTooLong { length: domain.len() });
+    }
+    Ok(())
+}
 
-    // Format: [felt1, felt2, felt3, length]
-    // This is reversed for MASM storage (becomes [length, felt3, felt2, felt1] on stack)
-    Word::new([
-        Felt::new(felt1),
-        Felt::new(felt2),
-        Felt::new(felt3),
-        Felt::new(len as u64),
-    ])
+// Human readable parts of Miden bech32 addresses
+pub const MAINNET_HRP: &str = "mm";
+pub const TESTNET_HRP: &str = "mtst";
+pub const DEVNET_HRP: &str = "mdev";
+pub const ADDRESS_HRPS: [&str; 3] = [MAINNET_HRP, TESTNET_HRP, DEVNET_HRP];
+
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+// Full addresses don't fit in MAX_DOMAIN_LENGTH, but wallets show them shortened, so a name that is the
+// start of a hex ID ("0x" and hex digits) or of a bech32 address (hrp, "1", bech32 characters) could pass
+// for someone else's account
+pub fn looks_like_address(domain: &str) -> bool {
+    if let Some(digits) = domain.strip_prefix("0x") {
+        return digits.chars().all(|c| c.is_ascii_hexdigit());
+    }
+    ADDRESS_HRPS.iter().any(|hrp| {
+        domain.strip_prefix(hrp)
+            .and_then(|rest| rest.strip_prefix('1'))
+            .is_some_and(|data| !data.is_empty() && data.chars().all(|c| BECH32_CHARSET.contains(c)))
+    })
+}
+
+// Distinct valid names for load tests, the same seed always gives the same names in the same order
+pub fn generate_unique_names(count: usize, seed: u64) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut seen = BTreeSet::new();
+    let mut names = Vec::with_capacity(count);
+
+    while names.len() < count {
+        let length = rng.random_range(1..=MAX_DOMAIN_LENGTH);
+        let name: String = (0..length)
+            .map(|_| decode_char(rng.random_range(1..=MAX_CHAR_CODE)).expect("1..=MAX_CHAR_CODE are valid character codes"))
+            .collect();
+        if !looks_like_address(&name) && seen.insert(name.clone()) {
+            names.push(name);
+        }
+    }
+    names
 }
 
 pub fn encode_domain_as_felts(domain: String) -> [Felt;4] {
@@ -91,10 +150,8 @@ pub fn encode_domain_as_felts(domain: String) -> [Felt;4] {
     [encoded_domain[0], encoded_domain[1], encoded_domain[2], encoded_domain[3]]
 }
 
+// Skips length validation, used to build invalid domains for contract tests
 pub fn unsafe_encode_domain(domain: String) -> Word {
-    // Validate length: must be > 0 and <= 20
-    let len = domain.len();
-
     // Encode each character and store in a vector
     let mut encoded_chars: Vec<u8> = Vec::new();
     for c in domain.chars() {
@@ -103,6 +160,16 @@ pub fn unsafe_encode_domain(domain: String) -> Word {
         encoded_chars.push(char_code);
     }
 
+    pack_domain(&encoded_chars, domain.len()).expect("character codes fit in a felt")
+}
+
+// Key of a name registered with register_hashed, hmerge(DOMAIN, ZERO) in naming.masm. It can't be decoded
+// back, looking it up requires knowing the name.
+pub fn hash_domain(domain: Word) -> Word {
+    Rpo256::merge(&[domain, Word::default()])
+}
+
+fn pack_domain(encoded_chars: &[u8], len: usize) -> Result<Word, RegistryError> {
     // Pack characters into Felts (7 characters per Felt, 8 bits each)
     // First 7 characters go into felt3, next 7 into felt2, next 6 into felt1
     let mut felt1: u64 = 0;
@@ -110,7 +177,7 @@ pub fn unsafe_encode_domain(domain: String) -> Word {
     let mut felt3: u64 = 0;
 
     for (i, &char_code) in encoded_chars.iter().enumerate() {
-        let bit_shift = (i % 7) * 8;
+        let bit_shift = (i % CHARS_PER_FELT) * BITS_PER_CHAR;
 
         if i < 7 {
             // First 7 characters go into felt3
@@ -126,12 +193,27 @@ pub fn unsafe_encode_domain(domain: String) -> Word {
 
     // Format: [felt1, felt2, felt3, length]
     // This is reversed for MASM storage (becomes [length, felt3, felt2, felt1] on stack)
-    Word::new([
-        Felt::new(felt1),
-        Felt::new(felt2),
-        Felt::new(felt3),
-        Felt::new(len as u64),
-    ])
+    Ok(Word::new([
+        checked_felt(felt1)?,
+        checked_felt(felt2)?,
+        checked_felt(felt3)?,
+        checked_felt(len as u64)?,
+    ]))
+}
+
+// Decodes only canonical encodings (what try_encode_domain produces), None for crafted or corrupt words
+pub fn try_decode_domain(encoded_domain: Word) -> Option<String> {
+    let length = encoded_domain[3].as_int() as usize;
+    if length == 0 || length > MAX_DOMAIN_LENGTH {
+        return None;
+    }
+
+    let felts = [encoded_domain[2].as_int(), encoded_domain[1].as_int(), encoded_domain[0].as_int()];
+    let name: Option<String> = (0..length)
+        .map(|i| decode_char(((felts[i / 7] >> ((i % 7) * 8)) & 0xFF) as u8))
+        .collect();
+
+    name.filter(|name| try_encode_domain(name).is_ok_and(|encoded| encoded == encoded_domain))
 }
 
 pub fn decode_domain(encoded_domain: Word) -> String {
@@ -0,0 +1,120 @@
+use miden_client::account::{Account, AccountId, StorageSlot};
+use miden_crypto::Word;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+
+use crate::{domain::try_decode_domain, queries::word_to_account_id, storage::*};
+
+// How the words of a slot are laid out, so the dump can decode them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WordKind {
+    Raw,
+    Flag,
+    Amount,
+    Account,
+    Domain,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum DecodedWord {
+    Empty,
+    Flag(bool),
+    Amount(u64),
+    Account(#[serde(with = "crate::serde_hex::account_id")] AccountId),
+    Domain(String),
+    // Layout unknown or the word doesn't fit it
+    Raw(#[serde(with = "crate::serde_hex::word")] Word),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlotContents {
+    Value(DecodedWord),
+    Map(Vec<(DecodedWord, DecodedWord)>),
+}
+
+// Serialize only, name and description point into NAMING_STORAGE_LAYOUT
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StorageSlotDump {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub contents: SlotContents,
+}
+
+// Every slot of the account labeled from NAMING_STORAGE_LAYOUT. Slots past the layout are dumped raw.
+pub fn dump_storage(account: &Account) -> BTreeMap<u8, StorageSlotDump> {
+    account.storage().slots().iter().enumerate().map(|(index, slot)| {
+        let index = index as u8;
+        let (name, description) = NAMING_STORAGE_LAYOUT.get(index as usize)
+            .map(|spec| (spec.name, spec.description))
+            .unwrap_or(("UNKNOWN", "not in the naming layout"));
+        let (key_kind, value_kind) = word_kinds(index);
+
+        let contents = match slot {
+            StorageSlot::Value(value) => SlotContents::Value(decode_word(*value, value_kind)),
+            StorageSlot::Map(map) => SlotContents::Map(map.entries()
+                .map(|(key, value)| (decode_word(*key, key_kind), decode_word(*value, value_kind)))
+                .collect()),
+        };
+        (index, StorageSlotDump { name, description, contents })
+    }).collect()
+}
+
+// (map key, value) layout per slot, key is ignored for value slots
+fn word_kinds(index: u8) -> (WordKind, WordKind) {
+    match index {
+        INIT_FLAG_SLOT => (WordKind::Raw, WordKind::Flag),
+        OWNER_SLOT => (WordKind::Raw, WordKind::Account),
+        PRICES_SLOT => (WordKind::Raw, WordKind::Amount),
+        ACCOUNT_ID_TO_DOMAIN_SLOT => (WordKind::Account, WordKind::Domain),
+        DOMAIN_TO_ACCOUNT_ID_SLOT | DOMAIN_TO_OWNER_SLOT => (WordKind::Domain, WordKind::Account),
+        REF_RATE_SLOT | REF_TOTAL_REVENUE_SLOT | REF_CLAIMED_REVENUE_SLOT => (WordKind::Account, WordKind::Amount),
+        TOTAL_REVENUE_SLOT | CLAIMED_REVENUE_SLOT | DONATIONS_SLOT | BASE_FEE_SLOT => (WordKind::Account, WordKind::Amount),
+        DOMAIN_EXPIRY_DATES_SLOT => (WordKind::Domain, WordKind::Amount),
+        RESERVED_NAMES_SLOT => (WordKind::Domain, WordKind::Flag),
+        OWNER_DOMAIN_COUNT_SLOT => (WordKind::Account, WordKind::Amount),
+        REVENUE_TOKENS_SLOT => (WordKind::Raw, WordKind::Account),
+        CLASS_MULTIPLIERS_SLOT => (WordKind::Raw, WordKind::Amount),
+        SPONSOR_APPROVALS_SLOT => (WordKind::Account, WordKind::Account),
+        DOMAIN_SPONSOR_SLOT => (WordKind::Domain, WordKind::Account),
+        PAYMENT_RECORDS_SLOT => (WordKind::Domain, WordKind::Raw),
+        MULTISIG_OWNERS_SLOT => (WordKind::Account, WordKind::Flag),
+        MULTISIG_OWNER_LIST_SLOT => (WordKind::Raw, WordKind::Account),
+        ACTION_APPROVALS_SLOT | REGISTRATION_AUTHORIZATIONS_SLOT => (WordKind::Raw, WordKind::Flag),
+        COMMITMENTS_SLOT | FRONTEND_FEES_SLOT => (WordKind::Raw, WordKind::Amount),
+        DOMAIN_COUNT_SLOT | ONE_YEAR_TIMESTAMP_SLOT | COMMIT_REVEAL_DELAY_SLOT | FRONTEND_FEE_MAX_BPS_SLOT | PRICING_MODE_SLOT | MAX_DOMAINS_PER_ACCOUNT_SLOT | REVENUE_TOKEN_COUNT_SLOT | MULTISIG_OWNER_COUNT_SLOT | MULTISIG_THRESHOLD_SLOT | GC_GRACE_PERIOD_SLOT => (WordKind::Raw, WordKind::Amount),
+        _ => (WordKind::Raw, WordKind::Raw),
+    }
+}
+
+fn decode_word(word: Word, kind: WordKind) -> DecodedWord {
+    if word == Word::default() {
+        return DecodedWord::Empty;
+    }
+    let single_felt = word[1].as_int() == 0 && word[2].as_int() == 0 && word[3].as_int() == 0;
+
+    match kind {
+        WordKind::Flag if single_felt && word[0].as_int() <= 1 => DecodedWord::Flag(word[0].as_int() == 1),
+        WordKind::Amount if single_felt => DecodedWord::Amount(word[0].as_int()),
+        WordKind::Account => match word_to_account_id(word) {
+            Ok(Some(account_id)) => DecodedWord::Account(account_id),
+            _ => DecodedWord::Raw(word),
+        },
+        WordKind::Domain => try_decode_domain(word).map(DecodedWord::Domain).unwrap_or(DecodedWord::Raw(word)),
+        _ => DecodedWord::Raw(word),
+    }
+}
+
+impl fmt::Display for DecodedWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodedWord::Empty => write!(f, "empty"),
+            DecodedWord::Flag(flag) => write!(f, "{flag}"),
+            DecodedWord::Amount(amount) => write!(f, "{amount}"),
+            DecodedWord::Account(account_id) => write!(f, "{}", account_id.to_hex()),
+            DecodedWord::Domain(name) => write!(f, "\"{name}\""),
+            DecodedWord::Raw(word) => write!(f, "[{}, {}, {}, {}]", word[0].as_int(), word[1].as_int(), word[2].as_int(), word[3].as_int()),
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use miden_client::{ClientError, ScriptBuilderError, keystore::KeyStoreError, transaction::TransactionRequestError};
+use miden_objects::{AccountError, AccountIdError, AssetError, NoteError};
+use thiserror::Error;
+
+use crate::{config::ConfigError, transcript::TranscriptError};
+#[cfg(feature = "testing")]
+use crate::simulation::SimulationFailure;
+
+// Registry level validation errors
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RegistryError {
+    #[error("price is zero, registrations would be free (pass --allow-free to allow it)")]
+    ZeroPrice,
+    #[error("price {price} exceeds the field modulus and would wrap")]
+    PriceExceedsFieldModulus { price: u64 },
+    #[error("price {price} does not fit in u32, the contract rejects it")]
+    PriceNotU32 { price: u64 },
+    #[error("price of {years} years at {yearly_price} per year overflows the contract's u32 price arithmetic")]
+    PriceOverflow { yearly_price: u64, years: u32 },
+    #[error("letter count must be between 1 and 20, got {letter_count}")]
+    InvalidLetterCount { letter_count: u8 },
+    #[error("storage slot {slot} does not exist on the account")]
+    StorageSlotMissing { slot: u8 },
+    #[error("storage slot {slot} is not a map")]
+    StorageSlotNotMap { slot: u8 },
+    #[error("domain name must have at least 1 character")]
+    EmptyDomain,
+    #[error("domain name must be at most 20 characters, got {length}")]
+    DomainTooLong { length: usize },
+    #[error("invalid character '{character}' at position {position} in domain name")]
+    InvalidCharacter { character: char, position: usize },
+    #[error("expected {expected} note inputs, got {actual}")]
+    InvalidNoteInputs { expected: usize, actual: usize },
+    #[error("note has {count} inputs, at most {max} allowed")]
+    TooManyNoteInputs { count: usize, max: usize },
+    #[error("invalid '{field}' input of {template} note: {reason}")]
+    InvalidNoteField { template: &'static str, field: &'static str, reason: &'static str },
+    #[error("no schema for note template '{0}'")]
+    UnknownNoteTemplate(String),
+    #[error("registry owner is not set, naming account is not initialized")]
+    OwnerNotSet,
+    #[error("account {0} is not tracked by the client")]
+    AccountNotTracked(String),
+    #[error("name '{0}' is not registered or not activated")]
+    NameNotFound(String),
+    #[error("invalid account address '{0}', expected hex or bech32")]
+    InvalidAddress(String),
+    #[error("registration term must be between 1 and 10 years, got {years}")]
+    InvalidRegistrationYears { years: u32 },
+    #[error("registration note must carry {expected} asset(s), got {actual}")]
+    PaymentAssetCount { expected: usize, actual: usize },
+    #[error("registration payment must be a fungible asset of {expected}, got {actual}")]
+    WrongPaymentAsset { expected: String, actual: String },
+    #[error("no key for account {0} in the keystore")]
+    KeyNotFound(String),
+    #[error("account {0} is not on chain and has no seed to deploy it")]
+    MissingAccountSeed(String),
+    #[error("invalid mnemonic: {0}")]
+    InvalidMnemonic(String),
+    #[error("invalid token symbol '{symbol}': {reason}")]
+    InvalidTokenSymbol { symbol: String, reason: &'static str },
+    #[error("name '{name}' appears more than once in the batch")]
+    DuplicateInBatch { name: String },
+    #[error("a sweep clears between 1 and {max} domains, got {count}")]
+    InvalidSweepBatch { count: usize, max: usize },
+    #[error("health check reported version {version}, features {features:#b} and {slot_count} slots, which this build does not expect")]
+    UnexpectedHealthReport { version: u64, features: u64, slot_count: u64 },
+    #[error("local store is synced to block {local_height} and was not synced recently, sync before querying")]
+    StaleState { local_height: u32 },
+    #[error("class multiplier must be at most 100000 bps, got {bps}")]
+    ClassMultiplierTooHigh { bps: u32 },
+    #[error("price {price} scaled by {bps} bps overflows the contract's u32 price arithmetic")]
+    ClassPriceOverflow { price: u64, bps: u32 },
+    #[error("sponsored registration by {sender} names the sender as beneficiary, use a plain register note")]
+    SenderMismatch { sender: String },
+    #[error("proposal file '{0}' does not hold a serialized note")]
+    InvalidProposal(String),
+    #[error("transaction {tx_id} was rejected: {reason}")]
+    TransactionRejected { tx_id: String, reason: String },
+    #[error("transaction {tx_id} was not committed within {waited_secs}s")]
+    TransactionTimeout { tx_id: String, waited_secs: u64 },
+    #[error("timed out after {waited_ms}ms waiting for {what}")]
+    PollTimeout { what: String, waited_ms: u64 },
+    #[error("invalid amount '{amount}': {reason}")]
+    InvalidAmount { amount: String, reason: &'static str },
+    #[error("account {0} is not a basic fungible faucet")]
+    NotAFungibleFaucet(String),
+    #[error("'{0}' looks like an account address and can't be used as a name")]
+    LooksLikeAddress(String),
+    #[error("storage proof of account {account_id} is invalid: {reason}")]
+    InvalidStorageProof { account_id: String, reason: &'static str },
+    #[error("domain maps of '{name}' are inconsistent: {reason}")]
+    InconsistentDomainState { name: String, reason: &'static str },
+    #[error("certificate for '{name}' does not verify: {reason}")]
+    InvalidCertificate { name: String, reason: &'static str },
+    #[error("note was not built from a {template} template for this registry: {reason}")]
+    NoteParamsMismatch { template: String, reason: &'static str },
+    #[error("{value} is not below the field modulus and would wrap")]
+    FeltOverflow { value: u64 },
+    #[error("name '{0}' has no recorded payment to refund, it was registered before payments were recorded")]
+    NoPaymentRecord(String),
+    #[error("{beneficiary} has not authorized a relayed registration of '{name}'")]
+    RegistrationNotAuthorized { name: String, beneficiary: String },
+}
+
+impl RegistryError {
+    // Stable machine readable code, frontends localize on this instead of the message.
+    // Codes are part of the public API, never rename one.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RegistryError::ZeroPrice => "ZERO_PRICE",
+            RegistryError::PriceExceedsFieldModulus { .. } => "PRICE_TOO_HIGH",
+            RegistryError::PriceNotU32 { .. } => "PRICE_NOT_U32",
+            RegistryError::PriceOverflow { .. } => "PRICE_OVERFLOW",
+            RegistryError::InvalidLetterCount { .. } => "INVALID_LETTER_COUNT",
+            RegistryError::StorageSlotMissing { .. } => "STORAGE_SLOT_MISSING",
+            RegistryError::StorageSlotNotMap { .. } => "STORAGE_SLOT_NOT_MAP",
+            RegistryError::EmptyDomain => "EMPTY_NAME",
+            RegistryError::DomainTooLong { .. } => "NAME_TOO_LONG",
+            RegistryError::InvalidCharacter { .. } => "INVALID_CHAR",
+            RegistryError::InvalidNoteInputs { .. } => "INVALID_NOTE_INPUTS",
+            RegistryError::TooManyNoteInputs { .. } => "TOO_MANY_NOTE_INPUTS",
+            RegistryError::InvalidNoteField { .. } => "INVALID_NOTE_FIELD",
+            RegistryError::UnknownNoteTemplate(_) => "UNKNOWN_NOTE_TEMPLATE",
+            RegistryError::OwnerNotSet => "REGISTRY_NOT_INITIALIZED",
+            RegistryError::AccountNotTracked(_) => "ACCOUNT_NOT_TRACKED",
+            RegistryError::NameNotFound(_) => "NAME_NOT_FOUND",
+            RegistryError::InvalidAddress(_) => "INVALID_ADDRESS",
+            RegistryError::InvalidRegistrationYears { .. } => "INVALID_REGISTRATION_YEARS",
+            RegistryError::PaymentAssetCount { .. } => "INVALID_PAYMENT_ASSETS",
+            RegistryError::WrongPaymentAsset { .. } => "WRONG_PAYMENT_ASSET",
+            RegistryError::KeyNotFound(_) => "KEY_NOT_FOUND",
+            RegistryError::MissingAccountSeed(_) => "MISSING_ACCOUNT_SEED",
+            RegistryError::InvalidMnemonic(_) => "INVALID_MNEMONIC",
+            RegistryError::InvalidTokenSymbol { .. } => "INVALID_TOKEN_SYMBOL",
+            RegistryError::DuplicateInBatch { .. } => "DUPLICATE_IN_BATCH",
+            RegistryError::InvalidSweepBatch { .. } => "INVALID_SWEEP_BATCH",
+            RegistryError::UnexpectedHealthReport { .. } => "UNEXPECTED_HEALTH_REPORT",
+            RegistryError::StaleState { .. } => "STALE_STATE",
+            RegistryError::ClassMultiplierTooHigh { .. } => "CLASS_MULTIPLIER_TOO_HIGH",
+            RegistryError::ClassPriceOverflow { .. } => "CLASS_PRICE_OVERFLOW",
+            RegistryError::SenderMismatch { .. } => "SENDER_MISMATCH",
+            RegistryError::InvalidProposal(_) => "INVALID_PROPOSAL",
+            RegistryError::TransactionRejected { .. } => "TRANSACTION_REJECTED",
+            RegistryError::TransactionTimeout { .. } => "TRANSACTION_TIMEOUT",
+            RegistryError::PollTimeout { .. } => "POLL_TIMEOUT",
+            RegistryError::InvalidAmount { .. } => "INVALID_AMOUNT",
+            RegistryError::NotAFungibleFaucet(_) => "NOT_A_FUNGIBLE_FAUCET",
+            RegistryError::LooksLikeAddress(_) => "LOOKS_LIKE_ADDRESS",
+            RegistryError::InvalidStorageProof { .. } => "INVALID_STORAGE_PROOF",
+            RegistryError::InconsistentDomainState { .. } => "INCONSISTENT_DOMAIN_STATE",
+            RegistryError::InvalidCertificate { .. } => "INVALID_CERTIFICATE",
+            RegistryError::NoteParamsMismatch { .. } => "NOTE_PARAMS_MISMATCH",
+            RegistryError::FeltOverflow { .. } => "FELT_OVERFLOW",
+            RegistryError::NoPaymentRecord(_) => "NO_PAYMENT_RECORD",
+            RegistryError::RegistrationNotAuthorized { .. } => "REGISTRATION_NOT_AUTHORIZED",
+        }
+    }
+}
+
+// MASM assert messages (naming account and note scripts) and the code each one maps to.
+// Contract failures only reach Rust as executor error strings, so they are matched on the message.
+pub const CONTRACT_ERROR_CODES: &[(&str, &str)] = &[
+    ("Only owner", "NOT_OWNER"),
+    ("Only domain owner", "NOT_DOMAIN_OWNER"),
+    ("Contract already initialized", "ALREADY_INITIALIZED"),
+    ("This payment token not allowed", "PAYMENT_TOKEN_NOT_ALLOWED"),
+    ("Price zero for this length", "ZERO_PRICE"),
+    ("Validating payment sub overflow", "INSUFFICIENT_PAYMENT"),
+    ("Paid amount less than price", "INSUFFICIENT_PAYMENT"),
+    ("Payment required", "INSUFFICIENT_PAYMENT"),
+    ("Domain is already taken", "NAME_TAKEN"),
+    ("21 characters allowed", "NAME_TOO_LONG"),
+    ("Ref rate higher or equal to 10000", "REF_RATE_TOO_HIGH"),
+    ("Max 2500 ref rate", "REF_RATE_TOO_HIGH"),
+    ("Ref rate zero", "REF_RATE_ZERO"),
+    ("Referrer rate is zero", "REFERRER_NOT_FOUND"),
+    ("Domain length field does not match actual character count", "INVALID_NAME_ENCODING"),
+    ("Domain characters are not packed in order", "INVALID_NAME_ENCODING"),
+    ("Domain byte is not a valid character code", "INVALID_NAME_ENCODING"),
+    ("Domain length zero", "EMPTY_NAME"),
+    ("Overflow at discount calc", "ARITHMETIC_OVERFLOW"),
+    ("Underflow at discount calc", "ARITHMETIC_OVERFLOW"),
+    ("Timestamp len overflow", "ARITHMETIC_OVERFLOW"),
+    ("U32 Overflow", "ARITHMETIC_OVERFLOW"),
+    ("Fee calculation underflow", "ARITHMETIC_OVERFLOW"),
+    ("Fee calculation overflow", "ARITHMETIC_OVERFLOW"),
+    ("Max 10 years", "INVALID_REGISTRATION_YEARS"),
+    ("Min 1 year", "INVALID_REGISTRATION_YEARS"),
+    ("Domain not expired", "NAME_NOT_EXPIRED"),
+    ("Domain expired", "NAME_EXPIRED"),
+    ("Price must fit in u32", "PRICE_TOO_HIGH"),
+    ("Domain is reserved", "NAME_RESERVED"),
+    ("Reserved flag must be 0 or 1", "INVALID_NOTE_INPUTS"),
+    ("Set price padding must be zero", "INVALID_NOTE_INPUTS"),
+    ("Letter count must be between 1 and 21", "INVALID_LETTER_COUNT"),
+    ("Registration requires commit and reveal", "COMMIT_REVEAL_REQUIRED"),
+    ("Commitment already exists", "COMMITMENT_EXISTS"),
+    ("Commitment not found", "COMMITMENT_NOT_FOUND"),
+    ("Commitment reveal delay not passed", "COMMITMENT_NOT_MATURED"),
+    ("Commitment reveal window passed", "COMMITMENT_EXPIRED"),
+    ("Frontend fee higher than allowed maximum", "FRONTEND_FEE_TOO_HIGH"),
+    ("No frontend fees to claim", "NO_FRONTEND_FEES"),
+    ("Donation note has no assets in payment token", "DONATION_REQUIRED"),
+    ("Pricing mode must be 0 or 1", "INVALID_PRICING_MODE"),
+    ("Account owns the maximum number of domains", "QUOTA_EXCEEDED"),
+    ("Name class must be 0, 1 or 2", "INVALID_NAME_CLASS"),
+    ("Class multiplier higher than 100000 bps", "CLASS_MULTIPLIER_TOO_HIGH"),
+    ("Class multiplier overflows the price", "CLASS_PRICE_OVERFLOW"),
+    ("Sender is not the approved sponsor of the beneficiary", "SENDER_MISMATCH"),
+    ("Sender is not a multisig owner", "NOT_MULTISIG_OWNER"),
+    ("Account is already a multisig owner", "MULTISIG_OWNER_EXISTS"),
+    ("Threshold higher than the number of multisig owners", "INVALID_THRESHOLD"),
+    ("Owner action has fewer approvals than the threshold", "ACTION_NOT_APPROVED"),
+    ("P2N note expects exactly 4 note inputs", "INVALID_NOTE_INPUTS"),
+    ("Target name is not registered on registry", "NAME_NOT_FOUND"),
+    ("Payment note carries more than one asset", "INVALID_PAYMENT_ASSETS"),
+    ("Payment asset is not a fungible asset of the payment token", "WRONG_PAYMENT_ASSET"),
+    ("Domain is not registered", "NAME_NOT_FOUND"),
+    ("Domain grace period not passed", "NAME_IN_GRACE_PERIOD"),
+    ("Sweep note inputs must be note details, recipient and the domain batch", "INVALID_NOTE_INPUTS"),
+    ("Refund flag must be 0 or 1", "INVALID_NOTE_INPUTS"),
+    ("Domain has no recorded payment to refund", "NO_PAYMENT_RECORD"),
+    ("Refund higher than unclaimed protocol revenue", "REFUND_EXCEEDS_REVENUE"),
+    ("Beneficiary has not authorized a registration of this domain", "REGISTRATION_NOT_AUTHORIZED"),
+    ("P2N's target name address and resolved address do not match", "NAME_TARGET_MISMATCH"),
+];
+
+// Code of the first contract assert message found in an executor error
+pub fn contract_error_code(message: &str) -> Option<&'static str> {
+    CONTRACT_ERROR_CODES.iter()
+        .find(|(assert_message, _)| message.contains(assert_message))
+        .map(|(_, code)| *code)
+}
+
+// Crate wide error returned by the public library functions
+#[derive(Debug, Error)]
+pub enum MidenIdError {
+    #[error(transparent)]
+    Registry(#[from] RegistryError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("note construction failed: {0}")]
+    Note(#[from] NoteError),
+    #[error("account or storage error: {0}")]
+    Account(#[from] AccountError),
+    #[error("invalid account id: {0}")]
+    AccountId(#[from] AccountIdError),
+    #[error("invalid asset: {0}")]
+    Asset(#[from] AssetError),
+    #[error("client error: {0}")]
+    Client(#[from] ClientError),
+    #[error("transaction request error: {0}")]
+    TransactionRequest(#[from] TransactionRequestError),
+    #[error("keystore error: {0}")]
+    KeyStore(#[from] KeyStoreError),
+    #[error("script compilation failed: {0}")]
+    Script(#[from] ScriptBuilderError),
+    #[error("assembly failed: {0}")]
+    Assembly(String),
+    #[cfg(feature = "testing")]
+    #[error(transparent)]
+    Simulation(#[from] SimulationFailure),
+    #[error(transparent)]
+    Transcript(#[from] TranscriptError),
+    #[error("roots.lock is not valid TOML: {0}")]
+    RootsLockParse(#[from] toml::de::Error),
+    #[error("roots could not be serialized for roots.lock: {0}")]
+    RootsLockSerialize(#[from] toml::ser::Error),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl MidenIdError {
+    // Registry errors keep their own code, executor failures are mapped through the contract assert messages
+    pub fn code(&self) -> &'static str {
+        match self {
+            MidenIdError::Registry(err) => err.code(),
+            MidenIdError::Config(_) => "CONFIG_ERROR",
+            MidenIdError::Note(_) => "NOTE_ERROR",
+            MidenIdError::Account(_) => "ACCOUNT_ERROR",
+            MidenIdError::AccountId(_) => "INVALID_ACCOUNT_ID",
+            MidenIdError::Asset(_) => "INVALID_ASSET",
+            MidenIdError::Client(err) => contract_error_code(&err.to_string()).unwrap_or("CLIENT_ERROR"),
+            MidenIdError::TransactionRequest(_) => "TRANSACTION_REQUEST_ERROR",
+            MidenIdError::KeyStore(_) => "KEYSTORE_ERROR",
+            MidenIdError::Script(_) => "SCRIPT_ERROR",
+            MidenIdError::Assembly(_) => "ASSEMBLY_ERROR",
+            #[cfg(feature = "testing")]
+            MidenIdError::Simulation(failure) => contract_error_code(&failure.error).unwrap_or("SIMULATION_FAILED"),
+            MidenIdError::Transcript(_) => "TRANSCRIPT_ERROR",
+            MidenIdError::RootsLockParse(_) | MidenIdError::RootsLockSerialize(_) => "ROOTS_LOCK_ERROR",
+            MidenIdError::Io(_) => "IO_ERROR",
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, MidenIdError>;
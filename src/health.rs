@@ -0,0 +1,98 @@
+use std::{collections::BTreeSet, fs, path::Path};
+
+use miden_client::{Client, account::AccountId, keystore::FilesystemKeyStore};
+use miden_crypto::Felt;
+use miden_objects::vm::AdviceInputs;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::{compile::CompileOptions, errors::Result, notes::create_library, storage::NAMING_STORAGE_LAYOUT, transaction::create_tx_script};
+
+// Version of the health_check output layout, must match HEALTH_CHECK_VERSION in naming.masm
+pub const HEALTH_CHECK_VERSION: u64 = 1;
+
+// Feature bits of HEALTH_FEATURES in naming.masm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NamingFeature {
+    Referrals = 1 << 0,
+    ReservedNames = 1 << 1,
+    OwnerSignatures = 1 << 2,
+    CommitReveal = 1 << 3,
+    FrontendFees = 1 << 4,
+    Donations = 1 << 5,
+    BaseFee = 1 << 6,
+    PricingModes = 1 << 7,
+    DomainQuota = 1 << 8,
+    ClassPricing = 1 << 9,
+    SponsoredRegistration = 1 << 10,
+    HashedNames = 1 << 11,
+    Multisig = 1 << 12,
+    GarbageCollection = 1 << 13,
+    Revocation = 1 << 14,
+    RelayedRegistration = 1 << 15,
+}
+
+impl NamingFeature {
+    pub const ALL: [NamingFeature; 16] = [
+        NamingFeature::Referrals,
+        NamingFeature::ReservedNames,
+        NamingFeature::OwnerSignatures,
+        NamingFeature::CommitReveal,
+        NamingFeature::FrontendFees,
+        NamingFeature::Donations,
+        NamingFeature::BaseFee,
+        NamingFeature::PricingModes,
+        NamingFeature::DomainQuota,
+        NamingFeature::ClassPricing,
+        NamingFeature::SponsoredRegistration,
+        NamingFeature::HashedNames,
+        NamingFeature::Multisig,
+        NamingFeature::GarbageCollection,
+        NamingFeature::Revocation,
+        NamingFeature::RelayedRegistration,
+    ];
+
+    pub fn bit(self) -> u64 {
+        self as u64
+    }
+}
+
+// Features of the naming contract this crate compiles
+pub fn naming_features() -> u64 {
+    NamingFeature::ALL.iter().fold(0, |bits, feature| bits | feature.bit())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub version: u64,
+    pub features: u64,
+    pub slot_count: u64,
+}
+
+impl HealthReport {
+    // Output stack of health_check: [version, features, slot_count, 0, ...]
+    pub fn from_stack(stack: &[Felt; 16]) -> Self {
+        Self { version: stack[0].as_int(), features: stack[1].as_int(), slot_count: stack[2].as_int() }
+    }
+
+    pub fn has(&self, feature: NamingFeature) -> bool {
+        self.features & feature.bit() != 0
+    }
+
+    // Deployed contract reports the layout, features and slots this crate was built against
+    pub fn is_expected(&self) -> bool {
+        self.version == HEALTH_CHECK_VERSION && self.features == naming_features() && self.slot_count == NAMING_STORAGE_LAYOUT.len() as u64
+    }
+}
+
+// Calls health_check on the contract from a transaction script executed locally, nothing is submitted.
+// The contract must be tracked by the client.
+pub async fn run_health_check(client: &mut Client<FilesystemKeyStore<StdRng>>, contract_id: AccountId, options: CompileOptions) -> Result<HealthReport> {
+    let naming_code = fs::read_to_string(Path::new("./masm/accounts/naming.masm"))?;
+    let script_code = fs::read_to_string(Path::new("./masm/scripts/health_check.masm"))?;
+    let script = create_tx_script(script_code, Some(create_library(naming_code, "miden_name::naming", options)?), options)?;
+
+    let stack = client.execute_program(contract_id, script, AdviceInputs::default(), BTreeSet::new()).await?;
+    Ok(HealthReport::from_stack(&stack))
+}
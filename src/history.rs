@@ -0,0 +1,171 @@
+use miden_client::{Client, ClientError, account::AccountId, keystore::FilesystemKeyStore, note::{Note, NoteTag}, store::{InputNoteState, NoteFilter}};
+use miden_crypto::Word;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::{compile::CompileOptions, domain::hash_domain, errors::Result, notes::compile_naming_note_script, queries::{Lookup, account_id_from_felts}, schema::note_schema};
+
+// Ownership history of names, replayed from the notes the naming account consumed. Only notes the store has
+// synced are known, track the naming account from its deployment for the full history. Expiry is not an
+// event: an expired name keeps its last owner until it is cleared or registered again, like on chain.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "owner", rename_all = "snake_case")]
+pub enum OwnershipChange {
+    // Registration or renewal
+    Registered(#[serde(with = "crate::serde_hex::account_id")] AccountId),
+    Transferred(#[serde(with = "crate::serde_hex::account_id")] AccountId),
+    // Deregistered by the owner, revoked by the registry owner, cleared or swept after expiry
+    Released,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnershipEvent {
+    // Block the note was consumed in
+    pub block_num: u32,
+    // Key of the name in the domain maps, hash_domain of it for hashed registrations
+    #[serde(with = "crate::serde_hex::word")]
+    pub domain: Word,
+    pub change: OwnershipChange,
+}
+
+// Owner from `from_block` up to, not including, `until_block`. No owner while the name is released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnershipPeriod {
+    #[serde(with = "crate::serde_hex::option_account_id")]
+    pub owner: Option<AccountId>,
+    pub from_block: u32,
+    pub until_block: Option<u32>,
+}
+
+// Ownership events of every name in block order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnershipLog {
+    pub events: Vec<OwnershipEvent>,
+}
+
+// Note templates that change who owns a name. register_hashed has no template of its own, it is a
+// register_name note with the hashed input set.
+const OWNERSHIP_TEMPLATES: [&str; 11] = [
+    "register_name",
+    "register_with_referrer",
+    "register_with_frontend_fee",
+    "register_sponsored",
+    "register_authorized",
+    "reveal_registration",
+    "transfer_domain",
+    "deregister_domain",
+    "clear_expired_domain",
+    "gc_expired_domains",
+    "admin_revoke",
+];
+
+impl OwnershipLog {
+    // (block consumed in, note) pairs, notes of other templates are skipped
+    pub fn from_notes(notes: impl IntoIterator<Item = (u32, Note)>) -> Result<Self> {
+        let mut templates = Vec::with_capacity(OWNERSHIP_TEMPLATES.len());
+        // Roots are the same in both compile modes
+        for template in OWNERSHIP_TEMPLATES {
+            templates.push((compile_naming_note_script(template, CompileOptions::RELEASE)?.root(), template));
+        }
+
+        let mut events = Vec::new();
+        for (block_num, note) in notes {
+            let root = note.script().root();
+            if let Some((_, template)) = templates.iter().find(|(template_root, _)| *template_root == root) {
+                events.extend(ownership_events(template, &note, block_num)?);
+            }
+        }
+        // Stable, notes of one block keep their order
+        events.sort_by_key(|event| event.block_num);
+        Ok(Self { events })
+    }
+}
+
+// A sweep releases every name of its batch, the other templates change one name
+fn ownership_events(template: &str, note: &Note, block_num: u32) -> Result<Vec<OwnershipEvent>> {
+    let schema = note_schema(template)?;
+    let inputs = note.recipient().inputs().values();
+    // Accounts are stored as [suffix, prefix]
+    let account = |name| -> Result<Option<AccountId>> {
+        schema.field(inputs, name).map(|felts| account_id_from_felts(felts[1], felts[0])).transpose()
+    };
+    if template == "gc_expired_domains" {
+        // Empty batch entries are skipped by the sweep
+        let domains = schema.field(inputs, "domains").unwrap_or_default();
+        return Ok(domains
+            .chunks_exact(4)
+            .map(|felts| Word::new([felts[0], felts[1], felts[2], felts[3]]))
+            .filter(|domain| *domain != Word::default())
+            .map(|domain| OwnershipEvent { block_num, domain, change: OwnershipChange::Released })
+            .collect());
+    }
+    let Some(domain) = schema.field(inputs, "domain").map(|felts| Word::new([felts[0], felts[1], felts[2], felts[3]])) else {
+        return Ok(Vec::new());
+    };
+
+    // Any non zero flag selects register_hashed, as in register_name.masm
+    let hashed = schema.field(inputs, "hashed").is_some_and(|felts| felts[0].as_int() != 0);
+    let domain = if hashed { hash_domain(domain) } else { domain };
+    let change = match template {
+        // Submitted by a sponsor or relayer on behalf of the beneficiary
+        "register_sponsored" | "register_authorized" => match account("beneficiary")? {
+            Some(beneficiary) => OwnershipChange::Registered(beneficiary),
+            None => return Ok(Vec::new()),
+        },
+        "transfer_domain" => match account("new_owner")? {
+            Some(new_owner) => OwnershipChange::Transferred(new_owner),
+            None => return Ok(Vec::new()),
+        },
+        "deregister_domain" | "clear_expired_domain" | "admin_revoke" => OwnershipChange::Released,
+        _ => OwnershipChange::Registered(note.metadata().sender()),
+    };
+    Ok(vec![OwnershipEvent { block_num, domain, change }])
+}
+
+// Notes tagged for the naming account that the store saw consumed, with the block of their nullifier
+pub async fn ownership_log(client: &mut Client<FilesystemKeyStore<StdRng>>, naming_id: AccountId) -> Result<OwnershipLog> {
+    let tag = NoteTag::from_account_id(naming_id);
+    let mut notes = Vec::new();
+    for record in client.get_input_notes(NoteFilter::Consumed).await? {
+        let block_num = match record.state() {
+            InputNoteState::ConsumedExternal(state) => state.nullifier_block_height,
+            InputNoteState::ConsumedAuthenticatedLocal(state) => state.nullifier_block_height,
+            InputNoteState::ConsumedUnauthenticatedLocal(state) => state.nullifier_block_height,
+            _ => continue,
+        };
+        let note = Note::try_from(record).map_err(ClientError::from)?;
+        if note.metadata().tag() == tag {
+            notes.push((block_num.as_u32(), note));
+        }
+    }
+    OwnershipLog::from_notes(notes)
+}
+
+// Owners of the name in block order, renewals don't start a new period. Plain and hashed registrations
+// of the name are both followed.
+pub fn ownership_timeline(log: &OwnershipLog, name: &str) -> Result<Vec<OwnershipPeriod>> {
+    let keys = [Lookup::Plain.key(name)?, Lookup::Hashed.key(name)?];
+    let mut periods: Vec<OwnershipPeriod> = Vec::new();
+    for event in log.events.iter().filter(|event| keys.contains(&event.domain)) {
+        let owner = match event.change {
+            OwnershipChange::Registered(owner) | OwnershipChange::Transferred(owner) => Some(owner),
+            OwnershipChange::Released => None,
+        };
+        if periods.last().is_some_and(|period| period.owner == owner) {
+            continue;
+        }
+        if let Some(period) = periods.last_mut() {
+            period.until_block = Some(event.block_num);
+        }
+        periods.push(OwnershipPeriod { owner, from_block: event.block_num, until_block: None });
+    }
+    Ok(periods)
+}
+
+// None before the first registration and while the name is released
+pub fn owner_at_block(log: &OwnershipLog, name: &str, block_num: u32) -> Result<Option<AccountId>> {
+    Ok(ownership_timeline(log, name)?.into_iter()
+        .find(|period| period.from_block <= block_num && period.until_block.is_none_or(|until| block_num < until))
+        .and_then(|period| period.owner))
+}
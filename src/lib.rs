@@ -2,6 +2,34 @@ pub mod storage;
 pub mod domain;
 pub mod accounts;
 pub mod client;
+pub mod context;
 pub mod transaction;
 pub mod scripts;
-pub mod notes;
\ No newline at end of file
+pub mod notes;
+pub mod errors;
+pub mod pricing;
+#[cfg(feature = "testing")]
+pub mod simulation;
+pub mod queries;
+pub mod registry;
+pub mod config;
+pub mod transcript;
+pub mod availability;
+pub mod vault;
+pub mod dump;
+pub mod roots;
+pub mod schema;
+pub mod serde_hex;
+pub mod health;
+pub mod audit;
+pub mod token;
+pub mod stateless;
+pub mod certificate;
+pub mod history;
+pub mod compile;
+#[cfg(feature = "bench")]
+pub mod bench;
+#[cfg(feature = "smoke")]
+pub mod smoke;
+#[cfg(feature = "testing")]
+pub mod testing;
@@ -1,10 +1,25 @@
-use clap::{Parser, Subcommand};
-use midenname_contracts::{scripts::deploy};
+use std::{io, path::{Path, PathBuf}, process::ExitCode};
+
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use midenname_contracts::{accounts::generate_mnemonic as generate_mnemonic_words, client::{CleanPaths, clean_environment}, compile::CompileOptions, config::{CliConfig, DEFAULT_CONFIG_PATH, Network}, context::Context, errors::{MidenIdError, RegistryError, contract_error_code}, pricing::NameClass, schema::schema_report, token::CliAmount, scripts::{PriceUpdate, SpendReport, add_multisig_owner, approve_action, authorize_registration, audit, certificate, check, deploy, dump, execute_action, export_price_table, gc_expired, health, history, import_account, import_price_table, init_config, list_names, propose_set_price, register, renew, resolve, resolve_stateless, revoke, send, set_class_multiplier, set_multisig_threshold, set_price, set_prices, stats, status, transfer_domain}};
 
 #[derive(Parser)]
 #[command(name = "midenname-contracts")]
 #[command(about = "Miden Name Registry CLI", long_about = None)]
 struct Cli {
+    /// Print errors as JSON with a stable machine readable code, and spend reports of register, renew and transfer-domain
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// CLI config with the network, keystore and store paths, ./midenname.toml or the testnet defaults if omitted
+    #[arg(long, global = true)]
+    cli_config: Option<PathBuf>,
+
+    /// Compile contracts and notes with debug info (source locations in errors), larger code and slower proving
+    #[arg(long, global = true)]
+    debug_masm: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -12,7 +27,37 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Deploy the naming contract to the network
-    Deploy,
+    Deploy {
+        /// Record submitted notes and transactions to this JSON file
+        #[arg(long)]
+        transcript: Option<PathBuf>,
+
+        /// Derive the deployer (the registry owner) from this BIP39 mnemonic instead of a random key
+        #[arg(long, conflicts_with = "generate_mnemonic")]
+        mnemonic: Option<String>,
+
+        /// Generate a new mnemonic for the deployer and print it
+        #[arg(long)]
+        generate_mnemonic: bool,
+    },
+
+    /// Recover an owner wallet and its key from a mnemonic into the keystore and store
+    ImportAccount {
+        /// BIP39 mnemonic the wallet was created from
+        #[arg(long)]
+        mnemonic: String,
+
+        /// Wallet index under the mnemonic, 0 is the deployer
+        #[arg(long, default_value_t = 0)]
+        index: u32,
+    },
+
+    /// Replay a deployment transcript on a mock chain and verify the post state (testing feature)
+    #[cfg(feature = "testing")]
+    ReplayTranscript {
+        /// Transcript JSON file
+        path: PathBuf,
+    },
 
     /// Initialize the deployed registry with owner and payment token
     Init {
@@ -21,26 +66,565 @@ enum Commands {
         owner: Option<String>,
     },
 
-    /// Register a new name
+    /// Register a new name, prints what it cost the registering account
     Register {
         /// Name to register
         #[arg(long)]
         name: String,
 
-        /// Account ID to map the name to
+        /// Registering account ID, pays and owns the name
+        #[arg(long)]
+        account: String,
+
+        /// Account owning the name when --account only relays the payment, it must authorize the name first
+        /// with authorize-registration
+        #[arg(long)]
+        beneficiary: Option<String>,
+
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// Payment token faucet ID
+        #[arg(long)]
+        token: String,
+
+        /// Registration term in years
+        #[arg(long, default_value_t = 1)]
+        years: u32,
+    },
+
+    /// Let a relayer pay one registration of a name that the sending account will own
+    AuthorizeRegistration {
+        /// Name the relayer may register
+        #[arg(long)]
+        name: String,
+
+        /// Beneficiary account ID, owns the name once it is registered
+        #[arg(long)]
+        account: String,
+
+        /// Naming account ID
         #[arg(long)]
-        account: Option<String>,
+        naming: String,
+    },
+
+    /// Extend a name you own, prints what it cost the owner
+    Renew {
+        /// Name to renew
+        #[arg(long)]
+        name: String,
+
+        /// Owner account ID
+        #[arg(long)]
+        account: String,
+
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// Payment token faucet ID
+        #[arg(long)]
+        token: String,
+
+        /// Years to add
+        #[arg(long, default_value_t = 1)]
+        years: u32,
+    },
+
+    /// Transfer a name you own to another account
+    TransferDomain {
+        /// Name to transfer
+        #[arg(long)]
+        name: String,
+
+        /// Owner account ID
+        #[arg(long)]
+        account: String,
+
+        /// New owner, hex or bech32
+        #[arg(long)]
+        to: String,
+
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+    },
+
+    /// Check if a name is available, suggest alternatives if not
+    Check {
+        /// Name to check
+        name: String,
+
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// Payment token faucet ID used for the price
+        #[arg(long)]
+        token: String,
+
+        /// Number of alternatives to suggest
+        #[arg(long, default_value_t = 3)]
+        suggestions: usize,
+    },
+
+    /// Export a certificate proving the account a name resolves to at the latest block
+    Certificate {
+        /// Name to certify
+        name: String,
+
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// File to write, prints to stdout if omitted
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Print the account a name resolves to
+    Resolve {
+        /// Name to resolve
+        name: String,
+
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// Read the entry and its proofs from the node instead of importing and syncing the registry
+        #[arg(long)]
+        stateless: bool,
+    },
+
+    /// Send tokens to the account a name resolves to
+    Send {
+        /// Recipient name
+        name: String,
+
+        /// Amount in whole tokens (e.g. 1.5), base units with --raw
+        #[arg(long)]
+        amount: String,
+
+        /// Read --amount as token base units
+        #[arg(long)]
+        raw: bool,
+
+        /// Token faucet ID
+        #[arg(long)]
+        token: String,
+
+        /// Sending wallet account ID
+        #[arg(long)]
+        sender: String,
+
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+    },
+
+    /// List registered names with their owners
+    ListNames {
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// Number of names to skip
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+
+        /// Maximum number of names to print
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+
+    /// Print every storage slot of an account, labeled and decoded where the layout is known
+    DumpStorage {
+        /// Account ID
+        id: String,
+    },
+
+    /// Print the input schema of every note template as JSON
+    NoteSchemas {
+        /// Write the report to this file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Show registry revenue counters and flag vault balance they do not account for
+    Status {
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// Payment token faucet ID
+        #[arg(long)]
+        token: String,
+    },
+
+    /// Check that a deployed registry answers the read only health check with what this build expects
+    Health {
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+    },
+
+    /// Show who owned a name at which blocks
+    History {
+        /// Name to look up
+        name: String,
+
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+    },
+
+    /// Clear domains past their expiry and grace period, collecting the sweep bounty if one is set
+    GcExpired {
+        /// Account submitting the sweep notes and receiving the bounty
+        #[arg(long)]
+        sender: String,
+
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// Most sweep notes to submit, each clears up to 16 domains
+        #[arg(long, default_value_t = 10)]
+        max_batches: usize,
+    },
+
+    /// Release a registered name as the registry owner, optionally refunding the price paid for it
+    Revoke {
+        /// Name to revoke
+        #[arg(long)]
+        name: String,
+
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// Registry owner account ID, read from the naming account if omitted
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Account receiving the refund from protocol revenue, no refund if omitted
+        #[arg(long)]
+        refund_to: Option<String>,
+
+        /// Skip the confirmation prompt
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Show how many names are registered and resolving
+    Stats {
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+    },
+
+    /// Check the domain maps and revenue counters for invariant violations
+    Audit {
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// Directory to write the notes repairing the violations to
+        #[arg(long)]
+        fix_plan: Option<PathBuf>,
+
+        /// Sender of the permissionless repair notes, the registry owner if omitted
+        #[arg(long)]
+        sender: Option<String>,
+    },
+
+    /// Create the CLI config file (interactive unless --non-interactive)
+    InitConfig {
+        /// Config file path
+        #[arg(long, default_value = DEFAULT_CONFIG_PATH)]
+        path: PathBuf,
+
+        /// Network: testnet, devnet, mainnet, localhost or the RPC URL of a custom node
+        #[arg(long)]
+        network: Option<Network>,
+
+        /// RPC URL, defaults to the network endpoint
+        #[arg(long)]
+        rpc_url: Option<String>,
+
+        /// Keystore directory
+        #[arg(long)]
+        keystore: Option<PathBuf>,
+
+        /// Naming account ID
+        #[arg(long)]
+        naming: Option<String>,
+
+        /// Do not prompt, use flags and defaults
+        #[arg(long)]
+        non_interactive: bool,
+    },
+
+    /// Remove the local client store (with its WAL/SHM files) and keystore
+    Clean {
+        /// SQLite store file or directory, the config store path if omitted
+        #[arg(long)]
+        store: Option<PathBuf>,
+
+        /// Keystore directory, the config keystore path if omitted
+        #[arg(long)]
+        keystore: Option<PathBuf>,
+    },
+
+    /// Print shell completions
+    Completions {
+        /// Target shell
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+
+    /// Set registration price for a letter count
+    SetPrice {
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// Registry owner account ID, read from the naming account if omitted
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Payment token faucet ID
+        #[arg(long, required_unless_present = "class")]
+        token: Option<String>,
+
+        /// Letter count the price applies to
+        #[arg(long, required_unless_present = "class")]
+        length: Option<u8>,
+
+        /// New price in whole tokens (e.g. 1.5), base units with --raw
+        #[arg(long, required_unless_present = "class")]
+        price: Option<String>,
+
+        /// Read --price as token base units
+        #[arg(long)]
+        raw: bool,
+
+        /// Set the multiplier of a name class (alpha, numeric or mixed) instead of a length price
+        #[arg(long, requires = "multiplier_bps", conflicts_with_all = ["token", "length", "price"])]
+        class: Option<NameClass>,
+
+        /// Class multiplier in basis points, 10000 is 1x and 0 unsets it
+        #[arg(long, requires = "class")]
+        multiplier_bps: Option<u32>,
+
+        /// Allow setting a zero price (free registrations)
+        #[arg(long)]
+        allow_free: bool,
+
+        /// Skip confirmation on large price changes
+        #[arg(long)]
+        yes: bool,
+
+        /// Write the note to this file for multisig approval instead of submitting it, --owner is the proposer
+        #[arg(long, requires = "owner", conflicts_with = "class")]
+        propose: Option<PathBuf>,
+    },
+
+    /// Approve a proposed owner note as a multisig owner
+    ApproveAction {
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// Approving multisig owner account ID
+        #[arg(long)]
+        approver: String,
+
+        /// Proposal file written with --propose
+        #[arg(long)]
+        proposal: PathBuf,
+    },
+
+    /// Submit a proposed owner note once enough multisig owners approved it
+    ExecuteAction {
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// Proposal file written with --propose
+        #[arg(long)]
+        proposal: PathBuf,
+    },
+
+    /// Add an account that can approve owner notes once a multisig threshold is set
+    AddMultisigOwner {
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// Registry owner account ID, read from the naming account if omitted
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Account ID of the new multisig owner
+        #[arg(long)]
+        account: String,
+    },
+
+    /// Set the number of multisig approvals owner notes need, 0 goes back to the single registry owner
+    SetMultisigThreshold {
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// Registry owner account ID, read from the naming account if omitted
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Required approvals, at most the number of multisig owners
+        #[arg(long)]
+        threshold: u32,
+    },
+
+    /// Set registration prices of several letter counts from a config file
+    SetPrices {
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// Registry owner account ID, read from the naming account if omitted
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Payment token faucet ID
+        #[arg(long)]
+        token: String,
+
+        /// JSON ([{"length": 1, "price": 100}]) or TOML ([[tiers]]) price tiers, see prices.example.toml
+        #[arg(long, required_unless_present = "test", conflicts_with = "test")]
+        config: Option<PathBuf>,
+
+        /// Use the built in testnet prices instead of a config file
+        #[arg(long)]
+        test: bool,
+    },
+
+    /// Export the registration prices of a payment token as JSON
+    ExportPrices {
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// Payment token faucet ID
+        #[arg(long)]
+        token: String,
+
+        /// File to write, prints to stdout if omitted
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Set the prices of an exported price table, only changed entries are submitted
+    ImportPrices {
+        /// Naming account ID
+        #[arg(long)]
+        naming: String,
+
+        /// Registry owner account ID, read from the naming account if omitted
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Price table written by export-prices
+        #[arg(long)]
+        input: PathBuf,
     },
 }
 
+// Same codes as the library errors, anything unknown is reported as INTERNAL
+fn error_code(err: &anyhow::Error) -> &'static str {
+    if let Some(err) = err.downcast_ref::<MidenIdError>() {
+        return err.code();
+    }
+    if let Some(err) = err.downcast_ref::<RegistryError>() {
+        return err.code();
+    }
+    contract_error_code(&format!("{err:#}")).unwrap_or("INTERNAL")
+}
+
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() -> ExitCode {
     let cli = Cli::parse();
+    let json = cli.json;
+    // Progress of long running waits, stderr keeps stdout parseable
+    tracing_subscriber::fmt().with_writer(io::stderr).init();
+
+    match run(cli.command, cli.cli_config.as_deref(), json, CompileOptions::cli(cli.debug_masm)).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) if json => {
+            eprintln!("{}", serde_json::json!({ "error": { "code": error_code(&err), "message": format!("{err:#}") } }));
+            ExitCode::FAILURE
+        }
+        Err(err) => {
+            eprintln!("Error: {err:?}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// The scripts print the summary for people, --json adds it as one JSON line for scripts
+fn print_spend_report(report: &SpendReport, json: bool) -> anyhow::Result<()> {
+    if json {
+        println!("{}", serde_json::to_string(report)?);
+    }
+    Ok(())
+}
+
+// An explicit path must exist, otherwise the default path is used if present and the testnet defaults if not
+fn load_config(path: Option<&Path>) -> Result<CliConfig, MidenIdError> {
+    let config = match path {
+        Some(path) => CliConfig::load(path)?,
+        None => CliConfig::load_or_default(Path::new(DEFAULT_CONFIG_PATH))?,
+    };
+    config.validate()?;
+    Ok(config)
+}
 
-    match cli.command {
-        Commands::Deploy => {
+async fn run(command: Commands, config_path: Option<&Path>, json: bool, compile: CompileOptions) -> anyhow::Result<()> {
+    // Commands that only work on local files run without a config
+    let config = match &command {
+        Commands::InitConfig { .. } | Commands::Completions { .. } | Commands::NoteSchemas { .. } => CliConfig::default(),
+        #[cfg(feature = "testing")]
+        Commands::ReplayTranscript { .. } => CliConfig::default(),
+        _ => load_config(config_path)?,
+    };
+    // Account IDs can be given as config aliases
+    let account_id = |value: &str| config.account_id(value);
+    let connect = || Context::connect(config.clone(), compile);
+
+    match command {
+        Commands::Deploy { transcript, mnemonic, generate_mnemonic } => {
+            let mnemonic = match mnemonic {
+                Some(mnemonic) => Some(mnemonic),
+                None if generate_mnemonic => {
+                    let mnemonic = generate_mnemonic_words();
+                    println!("Deployer mnemonic, write it down to recover the owner key:\n{}\n", mnemonic);
+                    Some(mnemonic)
+                }
+                None => None,
+            };
             println!("Deploying Miden Name Registry contract...\n");
-            deploy().await?;
+            let deployment = deploy(connect().await?, transcript, mnemonic.as_deref()).await?;
+            println!("Network: {}", deployment.network);
+            println!("Deployer: {} ({})", deployment.deployer_id.to_hex(), deployment.network.to_bech32(deployment.deployer_id));
+            println!("Naming account: {} ({})", deployment.naming_id.to_hex(), deployment.network.to_bech32(deployment.naming_id));
+        }
+        Commands::ImportAccount { mnemonic, index } => {
+            import_account(connect().await?, &mnemonic, index).await?;
+        }
+        #[cfg(feature = "testing")]
+        Commands::ReplayTranscript { path } => {
+            midenname_contracts::scripts::replay(&path, compile).await?;
         }
         Commands::Init { owner } => {
             println!("Initializing registry...");
@@ -52,16 +636,151 @@ async fn main() -> anyhow::Result<()> {
                 println!("Error: --owner is required for initialization");
             }
         }
-        Commands::Register { name, account } => {
-            println!("Registering name: {}", name);
-            if let Some(account_id) = account {
-                println!("Account: {}", account_id);
-                // TODO: Implement registration logic
-                println!("Note: Registration logic not yet implemented");
+        Commands::Register { name, account, beneficiary, naming, token, years } => {
+            let beneficiary = beneficiary.as_deref().map(account_id).transpose()?;
+            let report = register(connect().await?, account_id(&naming)?, account_id(&account)?, beneficiary, &name, account_id(&token)?, years).await?;
+            print_spend_report(&report, json)?;
+        }
+        Commands::AuthorizeRegistration { name, account, naming } => {
+            authorize_registration(connect().await?, account_id(&naming)?, account_id(&account)?, &name).await?;
+        }
+        Commands::Renew { name, account, naming, token, years } => {
+            let report = renew(connect().await?, account_id(&naming)?, account_id(&account)?, &name, account_id(&token)?, years).await?;
+            print_spend_report(&report, json)?;
+        }
+        Commands::TransferDomain { name, account, to, naming } => {
+            let report = transfer_domain(connect().await?, account_id(&naming)?, account_id(&account)?, &name, &to).await?;
+            print_spend_report(&report, json)?;
+        }
+        Commands::Check { name, naming, token, suggestions } => {
+            check(connect().await?, account_id(&naming)?, account_id(&token)?, &name, suggestions).await?;
+        }
+        Commands::Certificate { name, naming, output } => {
+            certificate(&config, account_id(&naming)?, &name, output.as_deref()).await?;
+        }
+        Commands::Resolve { name, naming, stateless } => {
+            let naming = account_id(&naming)?;
+            if stateless {
+                resolve_stateless(&config, naming, &name).await?;
             } else {
-                println!("Error: --account is required for registration");
+                resolve(connect().await?, naming, &name).await?;
             }
         }
+        Commands::Send { name, amount, raw, token, sender, naming } => {
+            send(connect().await?, account_id(&naming)?, account_id(&sender)?, &name, account_id(&token)?, &CliAmount::parse(&amount, raw)?).await?;
+        }
+        Commands::ListNames { naming, offset, limit } => {
+            list_names(connect().await?, account_id(&naming)?, offset, limit).await?;
+        }
+        Commands::Status { naming, token } => {
+            status(connect().await?, account_id(&naming)?, account_id(&token)?).await?;
+        }
+        Commands::DumpStorage { id } => {
+            dump(connect().await?, account_id(&id)?).await?;
+        }
+        Commands::NoteSchemas { output } => {
+            let report = schema_report()?;
+            match output {
+                Some(path) => std::fs::write(path, report)?,
+                None => println!("{report}"),
+            }
+        }
+        Commands::Health { naming } => {
+            health(connect().await?, account_id(&naming)?).await?;
+        }
+        Commands::History { name, naming } => {
+            history(connect().await?, account_id(&naming)?, &name).await?;
+        }
+        Commands::GcExpired { sender, naming, max_batches } => {
+            gc_expired(connect().await?, account_id(&naming)?, account_id(&sender)?, max_batches).await?;
+        }
+        Commands::Revoke { name, naming, owner, refund_to, yes } => {
+            let owner = owner.as_deref().map(account_id).transpose()?;
+            let refund_to = refund_to.as_deref().map(account_id).transpose()?;
+            revoke(connect().await?, account_id(&naming)?, owner, &name, refund_to, yes).await?;
+        }
+        Commands::Stats { naming } => {
+            stats(connect().await?, account_id(&naming)?).await?;
+        }
+        Commands::Audit { naming, fix_plan, sender } => {
+            audit(connect().await?, account_id(&naming)?, sender.as_deref().map(account_id).transpose()?, fix_plan.as_deref()).await?;
+        }
+        Commands::InitConfig { path, network, rpc_url, keystore, naming, non_interactive } => {
+            init_config(&path, network, rpc_url, keystore, naming, non_interactive).await?;
+        }
+        Commands::Clean { store, keystore } => {
+            let defaults = CleanPaths::from(&config);
+            let report = clean_environment(&CleanPaths { store: store.unwrap_or(defaults.store), keystore: keystore.unwrap_or(defaults.keystore) });
+            for path in &report.removed {
+                println!("Removed {}", path.display());
+            }
+            for (path, err) in &report.failed {
+                eprintln!("Could not remove {}: {}", path.display(), err);
+            }
+            anyhow::ensure!(report.is_clean(), "{} path(s) could not be removed, close running clients and retry", report.failed.len());
+        }
+        Commands::Completions { shell } => {
+            clap_complete::generate(shell, &mut Cli::command(), "midenname-contracts", &mut io::stdout());
+        }
+        Commands::SetPrice { naming, owner, token, length, price, raw, class, multiplier_bps, allow_free, yes, propose } => {
+            let naming = account_id(&naming)?;
+            let owner = owner.as_deref().map(account_id).transpose()?;
+            match (class, multiplier_bps, token, length, price) {
+                (Some(class), Some(multiplier_bps), ..) => {
+                    set_class_multiplier(connect().await?, naming, owner, class, multiplier_bps).await?;
+                }
+                (_, _, Some(token), Some(length), Some(price)) => {
+                    let update = PriceUpdate { payment_token: account_id(&token)?, letter_count: length, price: CliAmount::parse(&price, raw)?, allow_free };
+                    match (propose, owner) {
+                        (Some(output), Some(proposer)) => {
+                            propose_set_price(connect().await?, naming, proposer, &update, &output).await?;
+                        }
+                        _ => {
+                            set_price(connect().await?, naming, owner, &update, yes).await?;
+                        }
+                    }
+                }
+                _ => unreachable!("clap requires either --class or --token, --length and --price"),
+            }
+        }
+        Commands::ApproveAction { naming, approver, proposal } => {
+            approve_action(connect().await?, account_id(&naming)?, account_id(&approver)?, &proposal).await?;
+        }
+        Commands::ExecuteAction { naming, proposal } => {
+            execute_action(connect().await?, account_id(&naming)?, &proposal).await?;
+        }
+        Commands::AddMultisigOwner { naming, owner, account } => {
+            add_multisig_owner(
+                connect().await?,
+                account_id(&naming)?,
+                owner.as_deref().map(account_id).transpose()?,
+                account_id(&account)?,
+            ).await?;
+        }
+        Commands::SetMultisigThreshold { naming, owner, threshold } => {
+            set_multisig_threshold(connect().await?, account_id(&naming)?, owner.as_deref().map(account_id).transpose()?, threshold).await?;
+        }
+        Commands::SetPrices { naming, owner, token, config, test } => {
+            set_prices(
+                connect().await?,
+                account_id(&naming)?,
+                owner.as_deref().map(account_id).transpose()?,
+                account_id(&token)?,
+                config.as_deref(),
+                test,
+            ).await?;
+        }
+        Commands::ExportPrices { naming, token, output } => {
+            export_price_table(connect().await?, account_id(&naming)?, account_id(&token)?, output.as_deref()).await?;
+        }
+        Commands::ImportPrices { naming, owner, input } => {
+            import_price_table(
+                connect().await?,
+                account_id(&naming)?,
+                owner.as_deref().map(account_id).transpose()?,
+                &input,
+            ).await?;
+        }
     }
 
     Ok(())
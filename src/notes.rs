@@ -1,36 +1,638 @@
 use miden_assembly::{DefaultSourceManager, Library, LibraryPath, ast::{Module, ModuleKind}};
-use miden_client::{ScriptBuilder, account::{AccountId}, note::{Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteTag, NoteType}, transaction::TransactionKernel
-};
-use miden_crypto::{Felt, Word};
-use std::{fs, path::Path, sync::Arc};
+use miden_client::{account::{Account, AccountId}, asset::{Asset, FungibleAsset}, auth::AuthSecretKey, note::{Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript, NoteTag, NoteType}};
+use miden_crypto::{Felt, Word, hash::rpo::Rpo256};
+use miden_lib::note::utils::build_p2id_recipient;
+use serde::{Deserialize, Serialize};
+use std::{collections::{BTreeMap, BTreeSet}, fs, path::Path, sync::Arc};
 
-pub async fn create_note_for_naming(name: String, inputs: NoteInputs, sender: AccountId, target_id: AccountId, assets: NoteAssets) -> anyhow::Result<Note> {
-    let note_code = fs::read_to_string(Path::new(&format!("./masm/notes/{}.masm", name)))?;
-    let naming_code = fs::read_to_string(Path::new("./masm/accounts/naming.masm")).unwrap();
-    let library = create_library(naming_code, "miden_name::naming")?;
+use crate::{compile::CompileOptions, domain::{try_decode_domain, try_encode_domain, validate_domain}, errors::{MidenIdError, RegistryError, Result}, pricing::{NameClass, PricingMode, validate_class_multiplier, validate_letter_count, validate_payment_token, validate_price, validate_registration_years}, queries::get_account_for_name, schema::note_schema, serde_hex::word_to_hex};
+
+// Random serial number, two notes with the same script, inputs and assets still get distinct ids
+pub async fn create_note_for_naming(name: String, inputs: NoteInputs, sender: AccountId, target_id: AccountId, assets: NoteAssets, options: CompileOptions) -> Result<Note> {
+    create_note_for_naming_with_serial_num(name, inputs, sender, target_id, assets, random_serial_num(), options).await
+}
 
-    let note_script = ScriptBuilder::new(true)
-        .with_dynamically_linked_library(&library)
-        .unwrap()
-        .compile_note_script(note_code)
-        .unwrap();
+pub async fn create_note_for_naming_with_serial_num(name: String, inputs: NoteInputs, sender: AccountId, target_id: AccountId, assets: NoteAssets, serial_num: Word, options: CompileOptions) -> Result<Note> {
+    let note_script = compile_naming_note_script(&name, options)?;
 
-    let recipient = NoteRecipient::new(Word::default(), note_script, inputs.clone());
+    let recipient = NoteRecipient::new(serial_num, note_script, inputs.clone());
     let tag = NoteTag::from_account_id(target_id);
     let metadata = NoteMetadata::new(sender, NoteType::Public, tag, NoteExecutionHint::none(), Felt::new(0))?;
     let note = Note::new(assets, metadata, recipient);
     Ok(note)
 }
 
-pub fn create_library(account_code: String, library_path: &str) -> anyhow::Result<Library> {
-    let assembler = TransactionKernel::assembler().with_debug_mode(true);
+// Script of a note template in masm/notes, linked against the naming account. Its root identifies the template
+// and is the same in both compile modes.
+pub fn compile_naming_note_script(name: &str, options: CompileOptions) -> Result<NoteScript> {
+    let note_code = fs::read_to_string(Path::new(&format!("./masm/notes/{}.masm", name)))?;
+    let naming_code = fs::read_to_string(Path::new("./masm/accounts/naming.masm"))?;
+    let library = create_library(naming_code, "miden_name::naming", options)?;
+
+    Ok(options.script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(note_code)?)
+}
+
+pub fn random_serial_num() -> Word {
+    Word::new(rand::random::<[u64; 4]>().map(Felt::new))
+}
+
+// Same note with `serial_num` instead of the random one, works with every create_naming_* constructor.
+// Notes with a known serial number can be recreated later, see NoteParams.
+pub fn with_serial_num(note: Note, serial_num: Word) -> Note {
+    let recipient = NoteRecipient::new(serial_num, note.script().clone(), note.inputs().clone());
+    Note::new(note.assets().clone(), *note.metadata(), recipient)
+}
+
+// Everything a naming note is built from. Stored when the note is created, it rebuilds the same note
+// (same id and nullifier) if the note is lost before it is consumed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteParams {
+    // Name of the template under masm/notes
+    pub template: String,
+    #[serde(with = "crate::serde_hex::account_id")]
+    pub sender: AccountId,
+    #[serde(with = "crate::serde_hex::account_id")]
+    pub naming_id: AccountId,
+    #[serde(with = "crate::serde_hex::word")]
+    pub serial_num: Word,
+    pub inputs: Vec<u64>,
+    #[serde(with = "crate::serde_hex::serializable")]
+    pub assets: NoteAssets,
+}
+
+impl NoteParams {
+    // Parameters of a note built by one of the constructors for `naming_id` from `template`
+    pub fn from_note(template: &str, naming_id: AccountId, note: &Note) -> Result<Self> {
+        let mismatch = |reason| RegistryError::NoteParamsMismatch { template: template.to_string(), reason };
+        if note.metadata().tag() != NoteTag::from_account_id(naming_id) {
+            return Err(mismatch("note is not tagged for the naming account").into());
+        }
+        if note.script().root() != compile_naming_note_script(template, CompileOptions::RELEASE)?.root() {
+            return Err(mismatch("note script root differs from the template").into());
+        }
+        Ok(Self {
+            template: template.to_string(),
+            sender: note.metadata().sender(),
+            naming_id,
+            serial_num: note.serial_num(),
+            inputs: note.inputs().values().iter().map(|felt| felt.as_int()).collect(),
+            assets: note.assets().clone(),
+        })
+    }
+}
+
+// Rebuilds the note the parameters were taken from. The template is compiled from the current masm
+// sources, so the id only matches while the template and the naming library are unchanged.
+pub async fn recreate_note(params: &NoteParams, options: CompileOptions) -> Result<Note> {
+    let inputs = NoteInputs::new(params.inputs.iter().map(|value| Felt::new(*value)).collect())?;
+    create_note_for_naming_with_serial_num(params.template.clone(), inputs, params.sender, params.naming_id, params.assets.clone(), params.serial_num, options).await
+}
+
+pub fn create_library(account_code: String, library_path: &str, options: CompileOptions) -> Result<Library> {
+    let assembler = options.assembler();
     let source_manager = Arc::new(DefaultSourceManager::default());
+    let library_path = LibraryPath::new(library_path)
+        .map_err(|err| MidenIdError::Assembly(err.to_string()))?;
     let module = Module::parser(ModuleKind::Library).parse_str(
-        LibraryPath::new(library_path)?,
+        library_path,
         account_code,
         &source_manager,
-    ).unwrap();
-    let library = assembler.clone().assemble_library([module]).unwrap();
+    ).map_err(|err| MidenIdError::Assembly(err.to_string()))?;
+    let library = assembler.clone().assemble_library([module])
+        .map_err(|err| MidenIdError::Assembly(err.to_string()))?;
 
     Ok(library)
-}
\ No newline at end of file
+}
+
+// Price of a letter count, named fields so length and price can't be swapped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetPriceInput {
+    pub letter_count: u8,
+    pub price: u64,
+}
+
+impl SetPriceInput {
+    // Inputs of set_price note: [KEY, PRICE]
+    pub fn to_note_inputs(&self, payment_token: AccountId) -> Result<NoteInputs> {
+        note_schema("set_price")?.encode(&[
+            &[payment_token.suffix(), payment_token.prefix().as_felt()],
+            &[Felt::new(self.letter_count.into())],
+            &[Felt::new(self.price)],
+        ])
+    }
+}
+
+// Owner only. Sets registration price of a letter count for the payment token.
+// Prices are validated before building the note, zero price is only allowed with allow_free.
+pub async fn create_naming_set_price_note(owner: AccountId, naming_id: AccountId, payment_token: AccountId, input: SetPriceInput, allow_free: bool, options: CompileOptions) -> Result<Note> {
+    validate_payment_token(payment_token)?;
+    validate_letter_count(input.letter_count)?;
+    validate_price(input.price, allow_free)?;
+
+    create_note_for_naming("set_price".to_string(), input.to_note_inputs(payment_token)?, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// One set_price note per letter count in the table. Every entry is validated before any note is built,
+// zero prices are rejected. An empty table gives no notes.
+pub async fn create_naming_set_price_notes(owner: AccountId, naming_id: AccountId, payment_token: AccountId, prices: &BTreeMap<u8, u64>, options: CompileOptions) -> Result<Vec<Note>> {
+    validate_payment_token(payment_token)?;
+    for (&letter_count, &price) in prices {
+        validate_letter_count(letter_count)?;
+        validate_price(price, false)?;
+    }
+
+    let mut notes = Vec::with_capacity(prices.len());
+    for (&letter_count, &price) in prices {
+        notes.push(create_naming_set_price_note(owner, naming_id, payment_token, SetPriceInput { letter_count, price }, false, options).await?);
+    }
+    Ok(notes)
+}
+
+// Domain owner only. Links the domain to the owner account, so the name resolves and the account reverse
+// resolves to it
+pub async fn create_naming_activate_domain_note(owner: AccountId, naming_id: AccountId, domain: Word, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("activate_domain")?.encode(&[&domain.to_vec()])?;
+    create_note_for_naming("activate_domain".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Domain owner only. Clears the domain mapping, the new owner has to activate it again
+pub async fn create_naming_transfer_domain_note(owner: AccountId, naming_id: AccountId, domain: Word, new_owner: AccountId, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("transfer_domain")?.encode(&[&[new_owner.suffix(), new_owner.prefix().as_felt()], &domain.to_vec()])?;
+
+    create_note_for_naming("transfer_domain".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Sets the registry owner and the length of a year in seconds. Only the first one executed takes effect,
+// the contract rejects it once initialized
+pub async fn create_naming_initialize_note(sender: AccountId, naming_id: AccountId, owner: AccountId, one_year: u32, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("initialize_naming")?.encode(&[&[owner.suffix(), owner.prefix().as_felt()], &[Felt::new(one_year.into())]])?;
+    create_note_for_naming("initialize_naming".to_string(), inputs, sender, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Domain owner only, releases the domain before it expires
+pub async fn create_naming_deregister_note(owner: AccountId, naming_id: AccountId, domain: Word, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("deregister_domain")?.encode(&[&domain.to_vec()])?;
+    create_note_for_naming("deregister_domain".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Owner only. Releases a registered domain, expired or not. With `refund_to` the price recorded at registration
+// is paid to it by a P2ID note from protocol revenue, domains registered before payments were recorded can't be refunded.
+pub async fn create_naming_admin_revoke_note(owner: AccountId, naming_id: AccountId, domain: Word, refund_to: Option<AccountId>, options: CompileOptions) -> Result<Note> {
+    let (refund, note_details, recipient) = match refund_to {
+        Some(target) => {
+            let recipient = build_p2id_recipient(target, random_serial_num())?;
+            let tag = NoteTag::from_account_id(target);
+            // NOTE_DETAILS: [execution_hint, note_type, aux, tag]
+            let note_details = [Felt::from(NoteExecutionHint::none()), Felt::from(NoteType::Public), Felt::new(0), Felt::from(tag)];
+            (Felt::new(1), note_details, recipient.digest())
+        }
+        None => (Felt::new(0), [Felt::new(0); 4], Word::default()),
+    };
+    let inputs = note_schema("admin_revoke")?.encode(&[&domain.to_vec(), &[refund], &note_details, &recipient.to_vec()])?;
+
+    create_note_for_naming("admin_revoke".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Anyone can send it. Clears the mappings of an expired domain, or of a domain nobody owns
+pub async fn create_naming_clear_expired_domain_note(sender: AccountId, naming_id: AccountId, domain: Word, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("clear_expired_domain")?.encode(&[&domain.to_vec()])?;
+    create_note_for_naming("clear_expired_domain".to_string(), inputs, sender, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Most domains one sweep note clears, the unused domain words of the note are zero
+pub const GC_BATCH_SIZE: usize = 16;
+
+// Permissionless. Clears every listed domain, all of them must be past expiry plus the grace period or the
+// note fails. With a sweep bounty set the sender is paid per domain by a P2ID note from the registry.
+pub async fn create_naming_gc_note(sender: AccountId, naming_id: AccountId, domains: &[Word], options: CompileOptions) -> Result<Note> {
+    if domains.is_empty() || domains.len() > GC_BATCH_SIZE {
+        return Err(RegistryError::InvalidSweepBatch { count: domains.len(), max: GC_BATCH_SIZE }.into());
+    }
+    let mut seen = BTreeSet::new();
+    let mut domain_inputs = Vec::with_capacity(4 * GC_BATCH_SIZE);
+    for domain in domains {
+        if !seen.insert(*domain) {
+            let name = try_decode_domain(*domain).unwrap_or_else(|| word_to_hex(*domain));
+            return Err(RegistryError::DuplicateInBatch { name }.into());
+        }
+        domain_inputs.extend(domain.to_vec());
+    }
+    domain_inputs.resize(4 * GC_BATCH_SIZE, Felt::new(0));
+
+    let recipient = build_p2id_recipient(sender, random_serial_num())?;
+    let tag = NoteTag::from_account_id(sender);
+    // NOTE_DETAILS: [execution_hint, note_type, aux, tag]
+    let note_details = [Felt::from(NoteExecutionHint::none()), Felt::from(NoteType::Public), Felt::new(0), Felt::from(tag)];
+    let inputs = note_schema("gc_expired_domains")?.encode(&[&note_details, &recipient.digest().to_vec(), &domain_inputs])?;
+
+    create_note_for_naming("gc_expired_domains".to_string(), inputs, sender, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Owner only. Seconds after expiry during which the sweep leaves a domain alone, clear_expired_domain ignores it
+pub async fn create_naming_set_gc_grace_period_note(owner: AccountId, naming_id: AccountId, grace_period_secs: u32, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("set_gc_grace_period")?.encode(&[&[Felt::new(grace_period_secs.into())]])?;
+
+    create_note_for_naming("set_gc_grace_period".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Owner only. Paid from protocol revenue per swept domain, None disables the bounty
+pub async fn create_naming_set_gc_bounty_note(owner: AccountId, naming_id: AccountId, bounty: Option<FungibleAsset>, options: CompileOptions) -> Result<Note> {
+    let (amount, token) = match bounty {
+        Some(bounty) => {
+            validate_payment_token(bounty.faucet_id())?;
+            (Felt::new(bounty.amount()), [bounty.faucet_id().suffix(), bounty.faucet_id().prefix().as_felt()])
+        }
+        None => (Felt::new(0), [Felt::new(0); 2]),
+    };
+    let inputs = note_schema("set_gc_bounty")?.encode(&[&[amount], &token])?;
+
+    create_note_for_naming("set_gc_bounty".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Owner only. Flat fee added to every registration price paid in `payment_token`, zero disables it
+pub async fn create_naming_set_base_fee_note(owner: AccountId, naming_id: AccountId, payment_token: AccountId, amount: u64, options: CompileOptions) -> Result<Note> {
+    validate_payment_token(payment_token)?;
+    validate_price(amount, true)?;
+    let inputs = note_schema("set_base_fee")?.encode(&[&[Felt::new(amount)], &[payment_token.suffix(), payment_token.prefix().as_felt()]])?;
+
+    create_note_for_naming("set_base_fee".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Owner only. Most domains one account may own after a registration, zero removes the cap
+pub async fn create_naming_set_max_domains_note(owner: AccountId, naming_id: AccountId, max_domains: u32, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("set_max_domains_per_account")?.encode(&[&[Felt::new(max_domains.into())]])?;
+
+    create_note_for_naming("set_max_domains_per_account".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Owner only. Scales the yearly price of every name in the class, 10000 bps keeps the length price
+// and 0 removes the multiplier.
+pub async fn create_naming_set_class_multiplier_note(owner: AccountId, naming_id: AccountId, class: NameClass, multiplier_bps: u32, options: CompileOptions) -> Result<Note> {
+    validate_class_multiplier(multiplier_bps)?;
+    let inputs = note_schema("set_class_multiplier")?.encode(&[&[class.as_felt()], &[Felt::new(multiplier_bps.into())]])?;
+
+    create_note_for_naming("set_class_multiplier".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Owner only. Switches how multi-year registrations are priced, the yearly prices stay as they are
+pub async fn create_naming_set_pricing_mode_note(owner: AccountId, naming_id: AccountId, mode: PricingMode, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("set_pricing_mode")?.encode(&[&[mode.as_felt()]])?;
+
+    create_note_for_naming("set_pricing_mode".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Owner only. Reserves (or releases) a domain, reserved domains can only be registered by the owner.
+pub async fn create_naming_set_reserved_name_note(owner: AccountId, naming_id: AccountId, domain: Word, reserved: bool, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("set_reserved_name")?.encode(&[&domain.to_vec(), &[Felt::new(reserved.into())]])?;
+
+    create_note_for_naming("set_reserved_name".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Inputs of register_name note: [TOKEN, DOMAIN, REG_LEN]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisterNoteInputs {
+    #[serde(with = "crate::serde_hex::account_id")]
+    pub payment_token: AccountId,
+    #[serde(with = "crate::serde_hex::word")]
+    pub domain: Word,
+    pub years: u32,
+}
+
+impl RegisterNoteInputs {
+    // Rejects terms the contract would reject, so every register note constructor fails before building
+    pub fn to_note_inputs(&self) -> Result<NoteInputs> {
+        validate_registration_years(self.years)?;
+
+        let [token, domain, years] = self.field_values();
+        note_schema("register_name")?.encode(&[&token, &domain, &years, &[Felt::new(0)]])
+    }
+
+    // Values of the register_name schema fields, shared with the notes that extend its layout
+    fn field_values(&self) -> [Vec<Felt>; 3] {
+        [
+            vec![self.payment_token.suffix(), self.payment_token.prefix().as_felt()],
+            self.domain.to_vec(),
+            vec![Felt::new(self.years.into())],
+        ]
+    }
+
+    pub fn from_note_inputs(inputs: &NoteInputs) -> Result<Self> {
+        let values = inputs.values();
+        let expected = note_schema("register_name")?.input_count();
+        if values.len() != expected {
+            return Err(RegistryError::InvalidNoteInputs { expected, actual: values.len() }.into());
+        }
+
+        Ok(Self {
+            payment_token: AccountId::try_from([values[1], values[0]])?,
+            domain: Word::new([values[4], values[5], values[6], values[7]]),
+            years: u32::try_from(values[8].as_int())
+                .map_err(|_| RegistryError::InvalidNoteField { template: "register_name", field: "years", reason: "does not fit in a u32" })?,
+        })
+    }
+}
+
+// Paid registrations carry exactly one fungible asset of the payment token, free registrations are built
+// without assets. Anything else would be added to the registry vault on top of the payment.
+pub fn validate_registration_assets(assets: &NoteAssets, payment_token: AccountId) -> std::result::Result<(), RegistryError> {
+    if assets.num_assets() != 1 {
+        return Err(RegistryError::PaymentAssetCount { expected: 1, actual: assets.num_assets() });
+    }
+
+    match assets.iter().next() {
+        Some(Asset::Fungible(asset)) if asset.faucet_id() != payment_token => Err(RegistryError::WrongPaymentAsset {
+            expected: payment_token.to_hex(),
+            actual: asset.faucet_id().to_hex(),
+        }),
+        Some(Asset::NonFungible(asset)) => Err(RegistryError::WrongPaymentAsset {
+            expected: payment_token.to_hex(),
+            actual: format!("non-fungible asset of {}", asset.faucet_id_prefix().to_hex()),
+        }),
+        _ => Ok(()),
+    }
+}
+
+pub async fn create_naming_register_name_note(registrant: AccountId, naming_id: AccountId, domain: Word, payment: FungibleAsset, years: u32, options: CompileOptions) -> Result<Note> {
+    create_naming_register_name_note_with_assets(registrant, naming_id, payment.faucet_id(), domain, years, NoteAssets::new(vec![payment.into()])?, options).await
+}
+
+// Paid registration with the note assets given as is, rejected before building unless they are a single
+// payment in `payment_token`
+pub async fn create_naming_register_name_note_with_assets(registrant: AccountId, naming_id: AccountId, payment_token: AccountId, domain: Word, years: u32, assets: NoteAssets, options: CompileOptions) -> Result<Note> {
+    validate_registration_assets(&assets, payment_token)?;
+    let inputs = RegisterNoteInputs { payment_token, domain, years };
+
+    create_note_for_naming("register_name".to_string(), inputs.to_note_inputs()?, registrant, naming_id, assets, options).await
+}
+
+// Registration keyed by hash_domain(domain), the name isn't stored on the registry. Activate and transfer
+// it with the hash as the domain word. Sent by the owner of the active hashed domain it renews it.
+pub async fn create_naming_register_hashed_note(registrant: AccountId, naming_id: AccountId, domain: Word, payment: FungibleAsset, years: u32, options: CompileOptions) -> Result<Note> {
+    validate_registration_years(years)?;
+    let [token, domain, years] = RegisterNoteInputs { payment_token: payment.faucet_id(), domain, years }.field_values();
+    let inputs = note_schema("register_name")?.encode(&[&token, &domain, &years, &[Felt::new(1)]])?;
+
+    create_note_for_naming("register_name".to_string(), inputs, registrant, naming_id, NoteAssets::new(vec![payment.into()])?, options).await
+}
+
+// Extends a register_hashed domain, `domain` is the plain name so the contract can price the extension
+pub async fn create_naming_extend_hashed_domain_note(owner: AccountId, naming_id: AccountId, domain: Word, payment: FungibleAsset, years: u32, options: CompileOptions) -> Result<Note> {
+    validate_registration_years(years)?;
+    let [token, domain, years] = RegisterNoteInputs { payment_token: payment.faucet_id(), domain, years }.field_values();
+    let inputs = note_schema("extend_domain")?.encode(&[&token, &domain, &years, &[Felt::new(1)]])?;
+
+    create_note_for_naming("extend_domain".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![payment.into()])?, options).await
+}
+
+// Encodes the name (including its length felt) instead of taking a hand built domain word
+pub async fn create_naming_register_name_note_from_name(registrant: AccountId, naming_id: AccountId, name: &str, payment: FungibleAsset, years: u32, options: CompileOptions) -> Result<Note> {
+    validate_domain(name)?;
+    create_naming_register_name_note(registrant, naming_id, try_encode_domain(name)?, payment, years, options).await
+}
+
+// Register notes for several names paid in one token, `registrations` pairs each name with its payment
+// amount (zero for a free registration). Every name is validated before any note is built and a repeated
+// name fails the whole batch: in one transaction the second note would renew the first and pay twice.
+pub async fn create_naming_register_name_notes(registrant: AccountId, naming_id: AccountId, payment_token: AccountId, registrations: &[(&str, u64)], years: u32, options: CompileOptions) -> Result<Vec<Note>> {
+    validate_registration_years(years)?;
+    let mut seen = BTreeSet::new();
+    let mut domains = Vec::with_capacity(registrations.len());
+    for (name, amount) in registrations {
+        validate_domain(name)?;
+        let domain = try_encode_domain(name)?;
+        if !seen.insert(*name) {
+            return Err(RegistryError::DuplicateInBatch { name: name.to_string() }.into());
+        }
+        domains.push((domain, *amount));
+    }
+
+    let mut notes = Vec::with_capacity(domains.len());
+    for (domain, amount) in domains {
+        let note = match amount {
+            0 => create_naming_free_register_name_note(registrant, naming_id, payment_token, domain, years, options).await?,
+            amount => create_naming_register_name_note(registrant, naming_id, domain, FungibleAsset::new(payment_token, amount)?, years, options).await?,
+        };
+        notes.push(note);
+    }
+    Ok(notes)
+}
+
+// Free registration path, only succeeds when the price for the domain length is zero
+pub async fn create_naming_free_register_name_note(registrant: AccountId, naming_id: AccountId, payment_token: AccountId, domain: Word, years: u32, options: CompileOptions) -> Result<Note> {
+    let inputs = RegisterNoteInputs { payment_token, domain, years };
+
+    create_note_for_naming("register_name".to_string(), inputs.to_note_inputs()?, registrant, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Register note sent by the current owner of an active domain, extends the expiry by `years` instead of
+// failing as a duplicate
+pub async fn create_naming_renew_via_register(owner: AccountId, naming_id: AccountId, domain: Word, payment: FungibleAsset, years: u32, options: CompileOptions) -> Result<Note> {
+    let inputs = RegisterNoteInputs { payment_token: payment.faucet_id(), domain, years };
+
+    create_note_for_naming("register_name".to_string(), inputs.to_note_inputs()?, owner, naming_id, NoteAssets::new(vec![payment.into()])?, options).await
+}
+
+// Blocks after the reveal delay in which a commitment can be revealed, mirrors COMMITMENT_REVEAL_WINDOW
+pub const COMMITMENT_REVEAL_WINDOW: u32 = 1000;
+
+// Commitment of commit-reveal registration: hash(hash(DOMAIN, SALT), REGISTRANT)
+pub fn registration_commitment(domain: Word, salt: Word, registrant: AccountId) -> Word {
+    let registrant = Word::new([registrant.suffix(), registrant.prefix().as_felt(), Felt::new(0), Felt::new(0)]);
+    Rpo256::merge(&[Rpo256::merge(&[domain, salt]), registrant])
+}
+
+// Records the commitment on chain, the name stays hidden until the reveal note is created
+pub async fn create_naming_commit_note(registrant: AccountId, naming_id: AccountId, commitment: Word, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("commit_registration")?.encode(&[&commitment.to_vec()])?;
+    create_note_for_naming("commit_registration".to_string(), inputs, registrant, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Must be sent by the same registrant as the commit note, after the configured delay
+pub async fn create_naming_reveal_note(registrant: AccountId, naming_id: AccountId, domain: Word, salt: Word, payment: FungibleAsset, years: u32, options: CompileOptions) -> Result<Note> {
+    validate_registration_years(years)?;
+    let [token, domain, years] = RegisterNoteInputs { payment_token: payment.faucet_id(), domain, years }.field_values();
+    let inputs = note_schema("reveal_registration")?.encode(&[&token, &domain, &years, &salt.to_vec()])?;
+
+    create_note_for_naming("reveal_registration".to_string(), inputs, registrant, naming_id, NoteAssets::new(vec![payment.into()])?, options).await
+}
+
+// Owner only. Zero blocks disables commit-reveal and allows direct registrations again
+pub async fn create_naming_set_commit_reveal_delay_note(owner: AccountId, naming_id: AccountId, blocks: u32, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("set_commit_reveal_delay")?.encode(&[&[Felt::new(blocks.into())]])?;
+
+    create_note_for_naming("set_commit_reveal_delay".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Frontend that submitted the registration, receives `bps` of the paid price
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrontendFee {
+    #[serde(with = "crate::serde_hex::account_id")]
+    pub recipient: AccountId,
+    pub bps: u16,
+}
+
+pub async fn create_naming_register_name_note_with_frontend_fee(registrant: AccountId, naming_id: AccountId, domain: Word, payment: FungibleAsset, years: u32, frontend_fee: FrontendFee, options: CompileOptions) -> Result<Note> {
+    validate_registration_years(years)?;
+    let [token, domain, years] = RegisterNoteInputs { payment_token: payment.faucet_id(), domain, years }.field_values();
+    let recipient = [frontend_fee.recipient.suffix(), frontend_fee.recipient.prefix().as_felt()];
+    let inputs = note_schema("register_with_frontend_fee")?.encode(&[&token, &domain, &years, &recipient, &[Felt::new(frontend_fee.bps.into())]])?;
+
+    create_note_for_naming("register_with_frontend_fee".to_string(), inputs, registrant, naming_id, NoteAssets::new(vec![payment.into()])?, options).await
+}
+
+// Sponsor pays, the beneficiary owns the domain. The contract rejects it with SENDER_MISMATCH unless the
+// beneficiary approved the sponsor with an approve_sponsor note first.
+pub async fn create_naming_register_sponsored_note(sponsor: AccountId, naming_id: AccountId, beneficiary: AccountId, domain: Word, payment: FungibleAsset, years: u32, options: CompileOptions) -> Result<Note> {
+    if beneficiary == sponsor {
+        return Err(RegistryError::SenderMismatch { sender: sponsor.to_hex() }.into());
+    }
+    validate_registration_years(years)?;
+    let [token, domain, years] = RegisterNoteInputs { payment_token: payment.faucet_id(), domain, years }.field_values();
+    let beneficiary = [beneficiary.suffix(), beneficiary.prefix().as_felt()];
+    let inputs = note_schema("register_sponsored")?.encode(&[&token, &domain, &years, &beneficiary])?;
+
+    create_note_for_naming("register_sponsored".to_string(), inputs, sponsor, naming_id, NoteAssets::new(vec![payment.into()])?, options).await
+}
+
+// Sent by the beneficiary. Lets any account pay one registration of the domain for the beneficiary, see
+// create_naming_register_authorized_note. Only the hash of the name (domain::hash_domain) goes on chain here.
+pub async fn create_naming_authorize_registration_note(beneficiary: AccountId, naming_id: AccountId, domain_hash: Word, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("authorize_registration")?.encode(&[&domain_hash.to_vec()])?;
+
+    create_note_for_naming("authorize_registration".to_string(), inputs, beneficiary, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Relayer pays, the beneficiary owns the domain. The contract rejects it with REGISTRATION_NOT_AUTHORIZED unless
+// the beneficiary authorized this domain with an authorize_registration note first, the authorization is used up.
+pub async fn create_naming_register_authorized_note(relayer: AccountId, naming_id: AccountId, beneficiary: AccountId, domain: Word, payment: FungibleAsset, years: u32, options: CompileOptions) -> Result<Note> {
+    if beneficiary == relayer {
+        return Err(RegistryError::SenderMismatch { sender: relayer.to_hex() }.into());
+    }
+    validate_registration_years(years)?;
+    let [token, domain, years] = RegisterNoteInputs { payment_token: payment.faucet_id(), domain, years }.field_values();
+    let beneficiary = [beneficiary.suffix(), beneficiary.prefix().as_felt()];
+    let inputs = note_schema("register_authorized")?.encode(&[&token, &domain, &years, &beneficiary])?;
+
+    create_note_for_naming("register_authorized".to_string(), inputs, relayer, naming_id, NoteAssets::new(vec![payment.into()])?, options).await
+}
+
+// Identifies an owner note for multisig approvals. The recipient covers the serial number, script and
+// inputs, so approvals are for this exact note and can't be replayed with another one.
+pub fn action_hash(note: &Note) -> Word {
+    note.recipient().digest()
+}
+
+// Multisig owner only. Approves the owner note with the given action hash, the approval is consumed
+// when that note executes.
+pub async fn create_naming_approve_action_note(approver: AccountId, naming_id: AccountId, action: Word, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("approve_action")?.encode(&[&action.to_vec()])?;
+
+    create_note_for_naming("approve_action".to_string(), inputs, approver, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Owner only. The new owner can approve owner notes once a multisig threshold is set
+pub async fn create_naming_add_multisig_owner_note(owner: AccountId, naming_id: AccountId, multisig_owner: AccountId, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("add_multisig_owner")?.encode(&[&[multisig_owner.suffix(), multisig_owner.prefix().as_felt()]])?;
+
+    create_note_for_naming("add_multisig_owner".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Owner only. Owner notes then need `threshold` approvals from multisig owners, zero goes back to the
+// single registry owner
+pub async fn create_naming_set_multisig_threshold_note(owner: AccountId, naming_id: AccountId, threshold: u32, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("set_multisig_threshold")?.encode(&[&[Felt::new(threshold.into())]])?;
+
+    create_note_for_naming("set_multisig_threshold".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Lets `sponsor` register domains owned by the beneficiary (the sender), None revokes the approval.
+// Only one sponsor is approved at a time.
+pub async fn create_naming_approve_sponsor_note(beneficiary: AccountId, naming_id: AccountId, sponsor: Option<AccountId>, options: CompileOptions) -> Result<Note> {
+    let sponsor = sponsor.map(|sponsor| [sponsor.suffix(), sponsor.prefix().as_felt()]).unwrap_or([Felt::new(0); 2]);
+    let inputs = note_schema("approve_sponsor")?.encode(&[&sponsor])?;
+
+    create_note_for_naming("approve_sponsor".to_string(), inputs, beneficiary, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Owner only. Zero disables frontend fees, registrations with a non zero fee fail
+pub async fn create_naming_set_frontend_fee_max_note(owner: AccountId, naming_id: AccountId, max_bps: u16, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("set_frontend_fee_max")?.encode(&[&[Felt::new(max_bps.into())]])?;
+
+    create_note_for_naming("set_frontend_fee_max".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Sends the whole frontend fee balance of `claimer` in `payment_token` back to it as a P2ID note
+pub async fn create_naming_claim_frontend_fees_note(claimer: AccountId, naming_id: AccountId, payment_token: AccountId, options: CompileOptions) -> Result<Note> {
+    let recipient = build_p2id_recipient(claimer, random_serial_num())?;
+    let tag = NoteTag::from_account_id(claimer);
+
+    // NOTE_DETAILS: [execution_hint, note_type, aux, tag]
+    let note_details = [Felt::from(NoteExecutionHint::none()), Felt::from(NoteType::Public), Felt::new(0), Felt::from(tag)];
+    let inputs = note_schema("claim_frontend_fees")?.encode(&[&[payment_token.suffix(), payment_token.prefix().as_felt()], &note_details, &recipient.digest().to_vec()])?;
+
+    create_note_for_naming("claim_frontend_fees".to_string(), inputs, claimer, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Intentional contribution, counted in the donations slot instead of showing up as unaccounted vault balance
+pub async fn create_naming_donate_note(donor: AccountId, naming_id: AccountId, donation: FungibleAsset, options: CompileOptions) -> Result<Note> {
+    let token = donation.faucet_id();
+    let inputs = note_schema("donate")?.encode(&[&[token.suffix(), token.prefix().as_felt()]])?;
+
+    create_note_for_naming("donate".to_string(), inputs, donor, naming_id, NoteAssets::new(vec![donation.into()])?, options).await
+}
+
+// Standard P2ID note to the account the name resolves to, fails before any note is built if it doesn't resolve
+// Address-like names are refused even when registered, a wallet could show them as the address itself
+pub fn create_p2id_note_to_name(naming: &Account, sender: AccountId, name: &str, assets: Vec<Asset>) -> Result<Note> {
+    validate_domain(name)?;
+    let target = get_account_for_name(naming, name)?.ok_or_else(|| RegistryError::NameNotFound(name.to_string()))?;
+
+    let recipient = build_p2id_recipient(target, random_serial_num())?;
+    let metadata = NoteMetadata::new(sender, NoteType::Public, NoteTag::from_account_id(target), NoteExecutionHint::always(), Felt::new(0))?;
+    Ok(Note::new(NoteAssets::new(assets)?, metadata, recipient))
+}
+
+// Owner note together with the signature the consuming transaction has to put in its advice map
+pub struct SignedOwnerNote {
+    pub note: Note,
+    // hash(PUB_KEY, RECIPIENT)
+    pub signature_key: Word,
+    pub signature: Vec<Felt>,
+}
+
+impl SignedOwnerNote {
+    pub fn advice_map_entry(&self) -> (Word, Vec<Felt>) {
+        (self.signature_key, self.signature.clone())
+    }
+}
+
+// Owner only. Enables signature mode for owner methods, `Word::default()` disables it
+pub async fn create_naming_set_owner_pubkey_note(owner: AccountId, naming_id: AccountId, pub_key: Word, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("set_owner_pubkey")?.encode(&[&pub_key.to_vec()])?;
+    create_note_for_naming("set_owner_pubkey".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+pub async fn create_naming_transfer_owner_note(owner: AccountId, naming_id: AccountId, new_owner: AccountId, options: CompileOptions) -> Result<Note> {
+    let inputs = note_schema("transfer_ownership")?.encode(&[&[new_owner.suffix(), new_owner.prefix().as_felt()]])?;
+
+    create_note_for_naming("transfer_ownership".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await
+}
+
+// Signs the note recipient with the owner key, required once the owner public key is set on the registry
+pub async fn create_naming_transfer_owner_note_signed(owner: AccountId, naming_id: AccountId, new_owner: AccountId, owner_key: &AuthSecretKey, options: CompileOptions) -> Result<SignedOwnerNote> {
+    let inputs = note_schema("transfer_ownership")?.encode(&[&[new_owner.suffix(), new_owner.prefix().as_felt()]])?;
+    let note = create_note_for_naming("transfer_ownership".to_string(), inputs, owner, naming_id, NoteAssets::new(vec![])?, options).await?;
+
+    Ok(sign_owner_note(note, owner_key))
+}
+
+// Signs the recipient of any owner note, the contract checks it against the owner public key
+pub fn sign_owner_note(note: Note, owner_key: &AuthSecretKey) -> SignedOwnerNote {
+    let message = note.recipient().digest();
+    let pub_key: Word = owner_key.public_key().to_commitment().into();
+    let signature = owner_key.sign(message).to_prepared_signature(message);
+
+    SignedOwnerNote { note, signature_key: Rpo256::merge(&[pub_key, message]), signature }
+}
@@ -0,0 +1,274 @@
+use miden_client::account::{Account, AccountId, AccountType};
+use miden_crypto::{Felt, StarkField, Word};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{domain::{MAX_DOMAIN_LENGTH, try_encode_domain}, errors::{RegistryError, Result}, queries::{account_id_to_word, read_map_item}, storage::{BASE_FEE_SLOT, CLASS_MULTIPLIERS_SLOT, PRICES_SLOT, PRICING_MODE_SLOT}};
+
+// Price updates changing the current price by more than this factor need confirmation
+pub const PRICE_CHANGE_CONFIRMATION_RATIO: u64 = 10;
+
+// Prices set at testnet deploy, [letter_count, price]
+pub const TESTNET_PRICES: [(u8, u64); 5] = [
+    (1, 375_000_000),
+    (2, 200_000_000),
+    (3, 120_000_000),
+    (4, 55_000_000),
+    (5, 20_000_000),
+];
+
+pub fn testnet_prices() -> BTreeMap<u8, u64> {
+    TESTNET_PRICES.into_iter().collect()
+}
+
+// Registration terms and discounts, must match naming.masm
+pub const MAX_REGISTRATION_YEARS: u32 = 10;
+pub const THREE_YEAR_DISCOUNT_BPS: u64 = 3000;
+pub const FIVE_YEAR_DISCOUNT_BPS: u64 = 5000;
+
+// How the yearly price turns into the price of a term, stored in PRICING_MODE_SLOT
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PricingMode {
+    // 30% off the yearly price from 3 years, 50% off from 5 years
+    #[default]
+    DiscountCurve,
+    // price_per_year * years
+    Linear,
+}
+
+impl PricingMode {
+    pub fn as_felt(&self) -> Felt {
+        match self {
+            PricingMode::DiscountCurve => Felt::new(0),
+            PricingMode::Linear => Felt::new(1),
+        }
+    }
+
+    // Unknown values can't be stored, set_pricing_mode rejects them
+    pub fn from_felt(value: Felt) -> Option<Self> {
+        match value.as_int() {
+            0 => Some(PricingMode::DiscountCurve),
+            1 => Some(PricingMode::Linear),
+            _ => None,
+        }
+    }
+}
+
+// Character class of a name, the key of CLASS_MULTIPLIERS_SLOT
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NameClass {
+    // Letters only
+    Alpha,
+    // Digits only
+    Numeric,
+    Mixed,
+}
+
+// 10x, the contract rejects higher multipliers
+pub const MAX_CLASS_MULTIPLIER_BPS: u32 = 100000;
+
+impl NameClass {
+    pub const ALL: [NameClass; 3] = [NameClass::Alpha, NameClass::Numeric, NameClass::Mixed];
+
+    pub fn as_felt(&self) -> Felt {
+        match self {
+            NameClass::Alpha => Felt::new(0),
+            NameClass::Numeric => Felt::new(1),
+            NameClass::Mixed => Felt::new(2),
+        }
+    }
+
+    // Same check as _get_name_class: character codes above 26 are digits
+    pub fn of_domain(domain: Word) -> Self {
+        let length = domain[3].as_int();
+        let digits: u64 = domain[..3].iter()
+            .flat_map(|felt| felt.as_int().to_le_bytes())
+            .filter(|&byte| byte > 26)
+            .count() as u64;
+
+        match digits {
+            0 => NameClass::Alpha,
+            digits if digits == length => NameClass::Numeric,
+            _ => NameClass::Mixed,
+        }
+    }
+}
+
+impl std::str::FromStr for NameClass {
+    type Err = String;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        match value {
+            "alpha" => Ok(NameClass::Alpha),
+            "numeric" => Ok(NameClass::Numeric),
+            "mixed" => Ok(NameClass::Mixed),
+            _ => Err(format!("unknown name class '{value}', expected alpha, numeric or mixed")),
+        }
+    }
+}
+
+pub fn validate_class_multiplier(bps: u32) -> std::result::Result<(), RegistryError> {
+    if bps > MAX_CLASS_MULTIPLIER_BPS {
+        return Err(RegistryError::ClassMultiplierTooHigh { bps });
+    }
+    Ok(())
+}
+
+// Multiplier of a name class in bps, 0 if not set (the length price is used as is)
+pub fn get_class_multiplier(naming: &Account, class: NameClass) -> Result<u32> {
+    let key = Word::new([class.as_felt(), Felt::new(0), Felt::new(0), Felt::new(0)]);
+    let Some(value) = read_map_item(naming, CLASS_MULTIPLIERS_SLOT, key)? else {
+        return Ok(0);
+    };
+    // The contract caps multipliers far below u32::MAX, a larger stored value is reported at the cap of the type
+    u32::try_from(value[0].as_int()).map_err(|_| RegistryError::ClassMultiplierTooHigh { bps: u32::MAX }.into())
+}
+
+// Rounds and fails like _apply_class_multiplier, which splits the price to stay in u32
+pub fn apply_class_multiplier(price: u64, bps: u32) -> std::result::Result<u64, RegistryError> {
+    if bps == 0 {
+        return Ok(price);
+    }
+    let overflow = || RegistryError::ClassPriceOverflow { price, bps };
+    let price = u32::try_from(price).map_err(|_| overflow())?;
+    let quot_part = (price / 10000).checked_mul(bps).ok_or_else(overflow)?;
+    let rem_part = (price % 10000).checked_mul(bps).ok_or_else(overflow)? / 10000;
+    Ok(u64::from(quot_part.checked_add(rem_part).ok_or_else(overflow)?))
+}
+
+// Prices are stored as a single felt. Felt::new reduces values modulo the field (2^64 - 2^32 + 1),
+// so a price near u64::MAX would be stored as a tiny price instead of failing. Reject those here, and
+// prices the contract rejects because they don't fit its u32 price arithmetic.
+pub fn validate_price(price: u64, allow_free: bool) -> std::result::Result<(), RegistryError> {
+    if price == 0 && !allow_free {
+        return Err(RegistryError::ZeroPrice);
+    }
+    if price >= Felt::MODULUS {
+        return Err(RegistryError::PriceExceedsFieldModulus { price });
+    }
+    if price > u64::from(u32::MAX) {
+        return Err(RegistryError::PriceNotU32 { price });
+    }
+    Ok(())
+}
+
+// Assets only come from fungible faucets, a price keyed by any other account ID can never be paid.
+// The type is part of the ID, so this catches most mistyped IDs without a network lookup.
+pub fn validate_payment_token(payment_token: AccountId) -> std::result::Result<(), RegistryError> {
+    if payment_token.account_type() != AccountType::FungibleFaucet {
+        return Err(RegistryError::NotAFungibleFaucet(payment_token.to_hex()));
+    }
+    Ok(())
+}
+
+pub fn validate_letter_count(letter_count: u8) -> std::result::Result<(), RegistryError> {
+    if letter_count == 0 || letter_count as usize > MAX_DOMAIN_LENGTH {
+        return Err(RegistryError::InvalidLetterCount { letter_count });
+    }
+    Ok(())
+}
+
+// Returns true when new price differs from the old one more than PRICE_CHANGE_CONFIRMATION_RATIO times.
+// Unset (zero) old prices never require confirmation.
+pub fn price_change_requires_confirmation(old_price: u64, new_price: u64) -> bool {
+    if old_price == 0 {
+        return false;
+    }
+    if new_price == 0 {
+        return true;
+    }
+
+    let (low, high) = if old_price < new_price { (old_price, new_price) } else { (new_price, old_price) };
+    high as u128 > low as u128 * PRICE_CHANGE_CONFIRMATION_RATIO as u128
+}
+
+// Mirrors _assert_registration_length: between 1 and MAX_REGISTRATION_YEARS
+pub fn validate_registration_years(years: u32) -> std::result::Result<(), RegistryError> {
+    if years == 0 || years > MAX_REGISTRATION_YEARS {
+        return Err(RegistryError::InvalidRegistrationYears { years });
+    }
+    Ok(())
+}
+
+// Same rounding as _calculate_domain_price: the discount is floored, the yearly price is multiplied by the
+// term and the base fee is added once, undiscounted
+pub fn calculate_registration_cost(base_fee: u64, yearly_price: u64, years: u32) -> std::result::Result<u64, RegistryError> {
+    calculate_registration_cost_for_mode(PricingMode::DiscountCurve, base_fee, yearly_price, years)
+}
+
+pub fn calculate_registration_cost_for_mode(mode: PricingMode, base_fee: u64, yearly_price: u64, years: u32) -> std::result::Result<u64, RegistryError> {
+    validate_registration_years(years)?;
+
+    let discount_bps = match (mode, years) {
+        (PricingMode::Linear, _) => 0,
+        (PricingMode::DiscountCurve, 5..) => FIVE_YEAR_DISCOUNT_BPS,
+        (PricingMode::DiscountCurve, 3..) => THREE_YEAR_DISCOUNT_BPS,
+        _ => 0,
+    };
+    // The contract computes the price in u32 and fails on overflow, so does this
+    let overflow = || RegistryError::PriceOverflow { yearly_price, years };
+    let discount = fits_u32(yearly_price.checked_mul(discount_bps)).ok_or_else(overflow)? / 10000;
+    let term_price = fits_u32((yearly_price - discount).checked_mul(years.into())).ok_or_else(overflow)?;
+    fits_u32(term_price.checked_add(base_fee)).ok_or_else(overflow)
+}
+
+fn fits_u32(value: Option<u64>) -> Option<u64> {
+    value.filter(|&value| value <= u64::from(u32::MAX))
+}
+
+// Discount curve unless the owner switched to linear pricing
+pub fn get_pricing_mode(naming: &Account) -> Result<PricingMode> {
+    Ok(PricingMode::from_felt(naming.storage().get_item(PRICING_MODE_SLOT)?[0]).unwrap_or_default())
+}
+
+// Flat fee in the payment token added to every registration, 0 if not set
+pub fn get_base_fee(naming: &Account, payment_token: AccountId) -> Result<u64> {
+    Ok(read_map_item(naming, BASE_FEE_SLOT, account_id_to_word(payment_token))?.map(|value| value[0].as_int()).unwrap_or(0))
+}
+
+fn price_key(payment_token: AccountId, letter_count: Felt) -> Word {
+    Word::new([payment_token.suffix(), payment_token.prefix().as_felt(), letter_count, Felt::new(0)])
+}
+
+// One year price of the name in the payment token: the length price scaled by the multiplier
+// of its class. 0 if no price is set.
+pub fn get_yearly_price(naming: &Account, domain: Word, payment_token: AccountId) -> Result<u64> {
+    let length_price = read_map_item(naming, PRICES_SLOT, price_key(payment_token, domain[3]))?.map(|value| value[0].as_int()).unwrap_or(0);
+    Ok(apply_class_multiplier(length_price, get_class_multiplier(naming, NameClass::of_domain(domain))?)?)
+}
+
+// Prices of one payment token by letter count, the JSON format price schedules are kept in.
+// Letter counts without a price are left out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceTable {
+    #[serde(with = "crate::serde_hex::account_id")]
+    pub payment_token: AccountId,
+    pub prices: BTreeMap<u8, u64>,
+}
+
+pub fn export_prices(naming: &Account, payment_token: AccountId) -> Result<PriceTable> {
+    let mut prices = BTreeMap::new();
+    for letter_count in 1..=MAX_DOMAIN_LENGTH as u8 {
+        if let Some(value) = read_map_item(naming, PRICES_SLOT, price_key(payment_token, Felt::from(letter_count)))? {
+            prices.insert(letter_count, value[0].as_int());
+        }
+    }
+    Ok(PriceTable { payment_token, prices })
+}
+
+// Entries of `target` that differ from `current`. Letter counts missing from `target` keep their price,
+// a table can't remove a price since zero prices are rejected.
+pub fn price_changes(current: &BTreeMap<u8, u64>, target: &BTreeMap<u8, u64>) -> BTreeMap<u8, u64> {
+    target.iter()
+        .filter(|(letter_count, price)| current.get(letter_count) != Some(price))
+        .map(|(&letter_count, &price)| (letter_count, price))
+        .collect()
+}
+
+// Exact amount a registration of `name` for `years` has to attach
+pub fn quote_registration(naming: &Account, name: &str, payment_token: AccountId, years: u32) -> Result<u64> {
+    let yearly_price = get_yearly_price(naming, try_encode_domain(name)?, payment_token)?;
+    Ok(calculate_registration_cost_for_mode(get_pricing_mode(naming)?, get_base_fee(naming, payment_token)?, yearly_price, years)?)
+}
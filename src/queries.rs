@@ -0,0 +1,339 @@
+use miden_client::{account::{Account, AccountId, StorageSlot}, asset::FungibleAsset};
+use miden_crypto::{Felt, Word, hash::rpo::Rpo256, merkle::SmtProof};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{domain::{hash_domain, try_decode_domain, try_encode_domain}, errors::{RegistryError, Result}, storage::{ACCOUNT_ID_TO_DOMAIN_SLOT, DOMAIN_COUNT_SLOT, DOMAIN_EXPIRY_DATES_SLOT, DOMAIN_SPONSOR_SLOT, GC_BOUNTY_SLOT, GC_GRACE_PERIOD_SLOT, DOMAIN_TO_ACCOUNT_ID_SLOT, DOMAIN_TO_OWNER_SLOT, FRONTEND_FEES_SLOT, MAX_DOMAINS_PER_ACCOUNT_SLOT, ACTION_APPROVALS_SLOT, MULTISIG_OWNER_COUNT_SLOT, MULTISIG_OWNER_LIST_SLOT, MULTISIG_THRESHOLD_SLOT, OWNER_DOMAIN_COUNT_SLOT, OWNER_SLOT, PAYMENT_RECORDS_SLOT, REGISTRATION_AUTHORIZATIONS_SLOT, SPONSOR_APPROVALS_SLOT}};
+
+// (prefix, suffix) of an account id, the only two felts it is made of
+pub fn account_id_to_felts(account_id: AccountId) -> (Felt, Felt) {
+    (account_id.prefix().as_felt(), account_id.suffix())
+}
+
+// Fails if the felts are not a valid id (e.g. suffix and prefix swapped)
+pub fn account_id_from_felts(prefix: Felt, suffix: Felt) -> Result<AccountId> {
+    Ok(AccountId::try_from([prefix, suffix])?)
+}
+
+// Account ids are stored as [suffix, prefix, 0, 0]
+pub fn account_id_to_word(account_id: AccountId) -> Word {
+    let (prefix, suffix) = account_id_to_felts(account_id);
+    Word::new([suffix, prefix, Felt::new(0), Felt::new(0)])
+}
+
+// Accepts hex (0x...) and bech32 (mtst1...) account ids, as users copy them from wallets and explorers
+pub fn parse_account_id(address: &str) -> Result<AccountId> {
+    let address = address.trim();
+    if address.starts_with("0x") {
+        return AccountId::from_hex(address).map_err(|_| RegistryError::InvalidAddress(address.to_string()).into());
+    }
+    AccountId::from_bech32(address)
+        .map(|(_, account_id)| account_id)
+        .map_err(|_| RegistryError::InvalidAddress(address.to_string()).into())
+}
+
+// Aliases from the CLI config are tried before the address formats
+pub fn resolve_account_id(value: &str, aliases: &BTreeMap<String, String>) -> Result<AccountId> {
+    match aliases.get(value.trim()) {
+        Some(address) => parse_account_id(address),
+        None => parse_account_id(value),
+    }
+}
+
+// Empty word means there is no account
+pub fn word_to_account_id(word: Word) -> Result<Option<AccountId>> {
+    if word == Word::default() {
+        return Ok(None);
+    }
+    Ok(Some(account_id_from_felts(word[1], word[0])?))
+}
+
+// Reads a map entry. Absent keys and empty maps are Ok(None), missing or non-map slots are typed errors.
+pub fn read_map_item(account: &Account, slot: u8, key: Word) -> std::result::Result<Option<Word>, RegistryError> {
+    match account.storage().slots().get(slot as usize) {
+        Some(StorageSlot::Map(map)) => {
+            let value = map.get(&key);
+            if value == Word::default() {
+                Ok(None)
+            } else {
+                Ok(Some(value))
+            }
+        }
+        Some(_) => Err(RegistryError::StorageSlotNotMap { slot }),
+        None => Err(RegistryError::StorageSlotMissing { slot }),
+    }
+}
+
+// Stored value (empty word if absent) and its Merkle proof against the map root of the slot.
+// The proof is keyed by the hashed map key, see StorageMap::hash_key.
+pub fn open_map_item(account: &Account, slot: u8, key: Word) -> std::result::Result<(Word, SmtProof), RegistryError> {
+    match account.storage().slots().get(slot as usize) {
+        Some(StorageSlot::Map(map)) => Ok((map.get(&key), map.open(&key).into())),
+        Some(_) => Err(RegistryError::StorageSlotNotMap { slot }),
+        None => Err(RegistryError::StorageSlotMissing { slot }),
+    }
+}
+
+// How a name is keyed in the domain maps: its encoded word, or hash_domain of it for names registered
+// with a hashed register note
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Lookup {
+    Plain,
+    Hashed,
+}
+
+impl Lookup {
+    pub fn key(self, name: &str) -> Result<Word> {
+        let domain = try_encode_domain(name)?;
+        Ok(match self {
+            Lookup::Plain => domain,
+            Lookup::Hashed => hash_domain(domain),
+        })
+    }
+}
+
+fn read_account_by_name(naming: &Account, slot: u8, name: &str, lookup: Lookup) -> Result<Option<AccountId>> {
+    match read_map_item(naming, slot, lookup.key(name)?)? {
+        Some(value) => word_to_account_id(value),
+        None => Ok(None),
+    }
+}
+
+// The contract keeps a name either plain or hashed, never both, so the first hit is the registration
+fn read_account_by_any_key(naming: &Account, slot: u8, name: &str) -> Result<Option<AccountId>> {
+    match read_account_by_name(naming, slot, name, Lookup::Plain)? {
+        Some(account_id) => Ok(Some(account_id)),
+        None => read_account_by_name(naming, slot, name, Lookup::Hashed),
+    }
+}
+
+// Domain -> Account, only set for activated domains. Finds plain and hashed registrations.
+pub fn get_account_for_name(naming: &Account, name: &str) -> Result<Option<AccountId>> {
+    read_account_by_any_key(naming, DOMAIN_TO_ACCOUNT_ID_SLOT, name)
+}
+
+pub fn get_account_for_name_with(naming: &Account, name: &str, lookup: Lookup) -> Result<Option<AccountId>> {
+    read_account_by_name(naming, DOMAIN_TO_ACCOUNT_ID_SLOT, name, lookup)
+}
+
+// Account -> Domain, only set for activated domains. None for hashed registrations, only the hash is stored.
+pub fn get_name_for_account(naming: &Account, account_id: AccountId) -> Result<Option<String>> {
+    let value = read_map_item(naming, ACCOUNT_ID_TO_DOMAIN_SLOT, account_id_to_word(account_id))?;
+    Ok(value.and_then(try_decode_domain))
+}
+
+// Post-state of a transfer: the name is owned by `new_owner` and resolves to nobody or to `new_owner`
+// (whose reverse entry then points back to it), and the previous owner no longer reverse resolves to it
+pub fn verify_transfer(naming: &Account, name: &str, previous_owner: AccountId, new_owner: AccountId) -> Result<()> {
+    let inconsistent = |reason| Err(RegistryError::InconsistentDomainState { name: name.to_string(), reason }.into());
+    // Hashed registrations are keyed by the name hash in every map
+    let mut key = Lookup::Plain.key(name)?;
+    if read_map_item(naming, DOMAIN_TO_OWNER_SLOT, key)?.is_none() {
+        key = Lookup::Hashed.key(name)?;
+    }
+
+    let owner = read_map_item(naming, DOMAIN_TO_OWNER_SLOT, key)?.map(word_to_account_id).transpose()?.flatten();
+    if owner != Some(new_owner) {
+        return inconsistent("owner is not the new owner");
+    }
+    match read_map_item(naming, DOMAIN_TO_ACCOUNT_ID_SLOT, key)?.map(word_to_account_id).transpose()?.flatten() {
+        None => {}
+        Some(account_id) if account_id == new_owner => {
+            if read_map_item(naming, ACCOUNT_ID_TO_DOMAIN_SLOT, account_id_to_word(new_owner))? != Some(key) {
+                return inconsistent("resolves to the new owner without its reverse entry");
+            }
+        }
+        Some(_) => return inconsistent("resolves to an account other than the new owner"),
+    }
+    if previous_owner != new_owner && read_map_item(naming, ACCOUNT_ID_TO_DOMAIN_SLOT, account_id_to_word(previous_owner))? == Some(key) {
+        return inconsistent("previous owner still reverse resolves to it");
+    }
+    Ok(())
+}
+
+// Registry owner, stored as [suffix, prefix, 0, 0] at init
+pub fn get_owner_id(naming: &Account) -> Result<AccountId> {
+    let value = naming.storage().get_item(OWNER_SLOT)?;
+    word_to_account_id(value)?.ok_or_else(|| RegistryError::OwnerNotSet.into())
+}
+
+// Finds plain and hashed registrations
+pub fn get_domain_owner(naming: &Account, name: &str) -> Result<Option<AccountId>> {
+    read_account_by_any_key(naming, DOMAIN_TO_OWNER_SLOT, name)
+}
+
+// Claimable frontend fees of `recipient` in `payment_token`
+pub fn get_frontend_fee_balance(naming: &Account, recipient: AccountId, payment_token: AccountId) -> Result<u64> {
+    let key = Word::new([recipient.suffix(), recipient.prefix().as_felt(), payment_token.suffix(), payment_token.prefix().as_felt()]);
+    Ok(read_map_item(naming, FRONTEND_FEES_SLOT, key)?.map(|value| value[0].as_int()).unwrap_or(0))
+}
+
+// Domains the account owns, expired domains count until they are cleared or registered by someone else
+pub fn get_domain_count(naming: &Account, owner: AccountId) -> Result<u64> {
+    Ok(read_map_item(naming, OWNER_DOMAIN_COUNT_SLOT, account_id_to_word(owner))?.map(|value| value[0].as_int()).unwrap_or(0))
+}
+
+// Account the beneficiary allows to register domains for it, None if no sponsor is approved
+pub fn get_approved_sponsor(naming: &Account, beneficiary: AccountId) -> Result<Option<AccountId>> {
+    match read_map_item(naming, SPONSOR_APPROVALS_SLOT, account_id_to_word(beneficiary))? {
+        Some(value) => word_to_account_id(value),
+        None => Ok(None),
+    }
+}
+
+// Account that paid the current registration of the name, None unless it was registered with register_sponsored
+// or register_authorized
+pub fn get_domain_sponsor(naming: &Account, name: &str) -> Result<Option<AccountId>> {
+    match read_map_item(naming, DOMAIN_SPONSOR_SLOT, try_encode_domain(name)?)? {
+        Some(value) => word_to_account_id(value),
+        None => Ok(None),
+    }
+}
+
+// Price charged for the current registration of a name, renewals don't change it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentRecord {
+    pub amount: u64,
+    #[serde(with = "crate::serde_hex::account_id")]
+    pub payment_token: AccountId,
+    pub years: u64,
+}
+
+// Kept on transfer and cleared when the name is released. None for unregistered names and for names registered
+// before payments were recorded. Finds plain and hashed registrations.
+pub fn get_payment_record(naming: &Account, name: &str) -> Result<Option<PaymentRecord>> {
+    for lookup in [Lookup::Plain, Lookup::Hashed] {
+        // Stored as [amount, token_suffix, token_prefix, years]
+        if let Some(record) = read_map_item(naming, PAYMENT_RECORDS_SLOT, lookup.key(name)?)? {
+            let payment_token = account_id_from_felts(record[2], record[1])?;
+            return Ok(Some(PaymentRecord { amount: record[0].as_int(), payment_token, years: record[3].as_int() }));
+        }
+    }
+    Ok(None)
+}
+
+// Approvals owner notes need, zero when the registry owner acts alone
+pub fn get_multisig_threshold(naming: &Account) -> Result<u64> {
+    Ok(naming.storage().get_item(MULTISIG_THRESHOLD_SLOT)?[0].as_int())
+}
+
+// Multisig owners in the order they were added
+pub fn get_multisig_owners(naming: &Account) -> Result<Vec<AccountId>> {
+    let count = naming.storage().get_item(MULTISIG_OWNER_COUNT_SLOT)?[0].as_int();
+    let mut owners = Vec::with_capacity(count as usize);
+    for index in 0..count {
+        let key = Word::new([Felt::new(index), Felt::new(0), Felt::new(0), Felt::new(0)]);
+        if let Some(word) = read_map_item(naming, MULTISIG_OWNER_LIST_SLOT, key)?
+            && let Some(owner) = word_to_account_id(word)?
+        {
+            owners.push(owner);
+        }
+    }
+    Ok(owners)
+}
+
+// Approval of `owner` for an action (see notes::action_hash) that has not executed yet
+pub fn has_approved_action(naming: &Account, action: Word, owner: AccountId) -> Result<bool> {
+    let key = Rpo256::merge(&[action, account_id_to_word(owner)]);
+    Ok(read_map_item(naming, ACTION_APPROVALS_SLOT, key)?.is_some())
+}
+
+// Authorization the beneficiary has not used yet, the key is hash(BENEFICIARY, NAME_HASH) like the contract's
+pub fn is_registration_authorized(naming: &Account, beneficiary: AccountId, name: &str) -> Result<bool> {
+    let key = Rpo256::merge(&[Lookup::Hashed.key(name)?, account_id_to_word(beneficiary)]);
+    Ok(read_map_item(naming, REGISTRATION_AUTHORIZATIONS_SLOT, key)?.is_some())
+}
+
+// Zero when there is no cap
+pub fn get_max_domains_per_account(naming: &Account) -> Result<u64> {
+    Ok(naming.storage().get_item(MAX_DOMAINS_PER_ACCOUNT_SLOT)?[0].as_int())
+}
+
+// Seconds after expiry before a sweep may clear a domain
+pub fn get_gc_grace_period(naming: &Account) -> Result<u64> {
+    Ok(naming.storage().get_item(GC_GRACE_PERIOD_SLOT)?[0].as_int())
+}
+
+// Asset paid per swept domain, None when no bounty is set
+pub fn get_gc_bounty(naming: &Account) -> Result<Option<FungibleAsset>> {
+    let bounty = naming.storage().get_item(GC_BOUNTY_SLOT)?;
+    if bounty[0].as_int() == 0 {
+        return Ok(None);
+    }
+    // Stored as the asset word [amount, 0, token_suffix, token_prefix]
+    let token = account_id_from_felts(bounty[3], bounty[2])?;
+    Ok(Some(FungibleAsset::new(token, bounty[0].as_int())?))
+}
+
+// Domains a sweep can clear at `now`, in expiry order. Reads the full expiry map like iter_registered_domains.
+pub fn sweepable_domains(naming: &Account, now: u64) -> Result<Vec<Word>> {
+    let grace_period = get_gc_grace_period(naming)?;
+    let mut expired: Vec<(u64, Word)> = match naming.storage().slots().get(DOMAIN_EXPIRY_DATES_SLOT as usize) {
+        Some(StorageSlot::Map(map)) => map.entries()
+            .map(|(domain, expiry)| (expiry[0].as_int(), *domain))
+            .filter(|(expiry, _)| *expiry != 0 && expiry + grace_period <= now)
+            .collect(),
+        Some(_) => return Err(RegistryError::StorageSlotNotMap { slot: DOMAIN_EXPIRY_DATES_SLOT }.into()),
+        None => return Err(RegistryError::StorageSlotMissing { slot: DOMAIN_EXPIRY_DATES_SLOT }.into()),
+    };
+    expired.sort();
+    Ok(expired.into_iter().map(|(_, domain)| domain).collect())
+}
+
+// Registrations counted on chain. Released and cleared names stay counted, and registering them again
+// counts again, so this is never below the number of registered names.
+pub fn get_total_registrations(naming: &Account) -> Result<u64> {
+    Ok(naming.storage().get_item(DOMAIN_COUNT_SLOT)?[0].as_int())
+}
+
+// Entries of a map slot with a non empty value. Like the iterators below it needs the full account.
+pub fn map_len(account: &Account, slot: u8) -> Result<usize> {
+    match account.storage().slots().get(slot as usize) {
+        Some(StorageSlot::Map(map)) => Ok(map.entries().filter(|(_, value)| **value != Word::default()).count()),
+        Some(_) => Err(RegistryError::StorageSlotNotMap { slot }.into()),
+        None => Err(RegistryError::StorageSlotMissing { slot }.into()),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegistryStats {
+    // Domains with an owner entry
+    pub registered_names: usize,
+    // Domains resolving to an account
+    pub resolving_names: usize,
+    // Accounts with a primary name
+    pub reverse_entries: usize,
+    pub total_registrations: u64,
+}
+
+// Map sizes for capacity monitoring without an indexer
+pub fn registry_stats(naming: &Account) -> Result<RegistryStats> {
+    Ok(RegistryStats {
+        registered_names: map_len(naming, DOMAIN_TO_OWNER_SLOT)?,
+        resolving_names: map_len(naming, DOMAIN_TO_ACCOUNT_ID_SLOT)?,
+        reverse_entries: map_len(naming, ACCOUNT_ID_TO_DOMAIN_SLOT)?,
+        total_registrations: get_total_registrations(naming)?,
+    })
+}
+
+// Every registered (not cleared) domain with its owner. Reads the full storage map, so only practical
+// for small registries held as full accounts (tracked by the client or in MockChain tests).
+pub fn iter_registered_domains(naming: &Account) -> impl Iterator<Item = (Word, AccountId)> + '_ {
+    let entries = match naming.storage().slots().get(DOMAIN_TO_OWNER_SLOT as usize) {
+        Some(StorageSlot::Map(map)) => Some(map.entries()),
+        _ => None,
+    };
+    entries.into_iter().flatten().filter_map(|(domain, owner)| {
+        word_to_account_id(*owner).ok().flatten().map(|owner| (*domain, owner))
+    })
+}
+
+// Registered names sorted by name, so pages are stable between calls. Hashed registrations are left out.
+pub fn list_registered_names(naming: &Account) -> Vec<(String, AccountId)> {
+    let mut names: Vec<(String, AccountId)> = iter_registered_domains(naming)
+        .filter_map(|(domain, owner)| try_decode_domain(domain).map(|name| (name, owner)))
+        .collect();
+    names.sort();
+    names
+}
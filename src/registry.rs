@@ -0,0 +1,315 @@
+use miden_client::{Client, account::{Account, AccountId}, asset::{Asset, FungibleAsset}, keystore::FilesystemKeyStore, transaction::{OutputNote, TransactionId, TransactionRequestBuilder}};
+use miden_crypto::{Word, merkle::SmtProof};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use std::{collections::{BTreeMap, HashMap}, time::{Duration, Instant, SystemTime, UNIX_EPOCH}};
+
+use crate::{availability::{Availability, check_availability, suggest_alternatives}, compile::CompileOptions, domain::{hash_domain, try_encode_domain}, errors::{MidenIdError, RegistryError, Result}, notes::{create_naming_activate_domain_note, create_naming_authorize_registration_note, create_naming_deregister_note, create_naming_register_authorized_note, create_naming_register_name_notes, create_naming_renew_via_register, create_naming_set_price_notes, create_naming_transfer_domain_note, create_p2id_note_to_name}, pricing::{PriceTable, export_prices, price_changes, quote_registration}, queries::{PaymentRecord, get_account_for_name, get_domain_owner, get_payment_record, get_name_for_account, is_registration_authorized, get_owner_id, list_registered_names, open_map_item, parse_account_id, verify_transfer}, storage::DOMAIN_TO_ACCOUNT_ID_SLOT, token::{FaucetMetadata, get_faucet_metadata}};
+
+// Resolution with the freshness info needed by caching layers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolveResult {
+    #[serde(with = "crate::serde_hex::option_account_id")]
+    pub account_id: Option<AccountId>,
+    // Block height the local store was synced to when the naming account was read
+    pub block_height: u32,
+    pub fetched_at: SystemTime,
+}
+
+// Lookups read the local store, a store not synced since a registration still reports the name as free.
+// The policy decides what a lookup does when this client hasn't synced within `max_age`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryPolicy {
+    // Read the store as is
+    Unchecked,
+    AutoSync { max_age: Duration },
+    // Fail with RegistryError::StaleState and leave syncing to the caller
+    RequireFresh { max_age: Duration },
+}
+
+impl Default for QueryPolicy {
+    fn default() -> Self {
+        QueryPolicy::AutoSync { max_age: Duration::from_secs(DEFAULT_MAX_SYNC_AGE_SECS) }
+    }
+}
+
+// About five blocks
+pub const DEFAULT_MAX_SYNC_AGE_SECS: u64 = 15;
+
+// Notes are submitted with own_output_notes, which builds the transaction script from the BasicWallet
+// interface, so senders have to be wallets. The contract itself only checks the note sender id; contract
+// accounts without that interface (e.g. multisigs) create the same notes from their own procedures.
+pub struct RegistryClient {
+    pub client: Client<FilesystemKeyStore<StdRng>>,
+    pub naming_id: AccountId,
+    pub policy: QueryPolicy,
+    // Notes built by the client are compiled with these
+    pub compile: CompileOptions,
+    // Block height and time of the last sync through `sync`, None until the first one
+    last_sync: Option<(u32, Instant)>,
+    // Faucet metadata never changes, None for tokens that aren't basic fungible faucets
+    faucet_metadata: HashMap<AccountId, Option<FaucetMetadata>>,
+}
+
+impl RegistryClient {
+    // Tracks the naming account if the local store doesn't know it yet (e.g. fresh machine)
+    pub async fn connect(mut client: Client<FilesystemKeyStore<StdRng>>, naming_id: AccountId) -> Result<Self> {
+        if client.get_account(naming_id).await?.is_none() {
+            track_registry(&mut client, naming_id).await?;
+        }
+        Ok(Self { client, naming_id, policy: QueryPolicy::default(), compile: CompileOptions::default(), last_sync: None, faucet_metadata: HashMap::new() })
+    }
+
+    pub fn with_policy(mut self, policy: QueryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_compile_options(mut self, compile: CompileOptions) -> Self {
+        self.compile = compile;
+        self
+    }
+
+    // Syncs the store and records the height for the staleness check, returns the synced height
+    pub async fn sync(&mut self) -> Result<u32> {
+        let height = self.client.sync_state().await?.block_num.as_u32();
+        self.last_sync = Some((height, Instant::now()));
+        Ok(height)
+    }
+
+    // Height of the last sync through this client
+    pub fn last_sync_height(&self) -> Option<u32> {
+        self.last_sync.map(|(height, _)| height)
+    }
+
+    async fn ensure_fresh(&mut self) -> Result<()> {
+        let max_age = match self.policy {
+            QueryPolicy::Unchecked => return Ok(()),
+            QueryPolicy::AutoSync { max_age } | QueryPolicy::RequireFresh { max_age } => max_age,
+        };
+        if self.last_sync.is_some_and(|(_, synced_at)| synced_at.elapsed() <= max_age) {
+            return Ok(());
+        }
+        match self.policy {
+            QueryPolicy::AutoSync { .. } => {
+                self.sync().await?;
+                Ok(())
+            }
+            _ => Err(RegistryError::StaleState { local_height: self.client.get_sync_height().await?.as_u32() }.into()),
+        }
+    }
+
+    // Symbol and decimals of a payment token, read once per client
+    pub async fn faucet_metadata(&mut self, faucet_id: AccountId) -> Result<Option<FaucetMetadata>> {
+        if let Some(metadata) = self.faucet_metadata.get(&faucet_id) {
+            return Ok(metadata.clone());
+        }
+        let metadata = match get_faucet_metadata(&mut self.client, faucet_id).await {
+            Ok(metadata) => Some(metadata),
+            Err(MidenIdError::Registry(RegistryError::NotAFungibleFaucet(_))) => None,
+            Err(err) => return Err(err),
+        };
+        self.faucet_metadata.insert(faucet_id, metadata.clone());
+        Ok(metadata)
+    }
+
+    // Checked against the query policy first
+    pub async fn naming_account(&mut self) -> Result<Account> {
+        self.ensure_fresh().await?;
+        let record = self.client.get_account(self.naming_id).await?
+            .ok_or_else(|| RegistryError::AccountNotTracked(self.naming_id.to_hex()))?;
+        Ok(record.account().clone())
+    }
+
+    pub async fn resolve(&mut self, name: &str) -> Result<Option<AccountId>> {
+        get_account_for_name(&self.naming_account().await?, name)
+    }
+
+    pub async fn resolve_with_metadata(&mut self, name: &str) -> Result<ResolveResult> {
+        // After resolve, which may sync first
+        let account_id = self.resolve(name).await?;
+        let block_height = self.client.get_sync_height().await?.as_u32();
+
+        Ok(ResolveResult { account_id, block_height, fetched_at: SystemTime::now() })
+    }
+
+    pub async fn reverse_resolve(&mut self, account_id: AccountId) -> Result<Option<String>> {
+        get_name_for_account(&self.naming_account().await?, account_id)
+    }
+
+    pub async fn name_proof(&mut self, name: &str) -> Result<(Word, SmtProof)> {
+        self.ensure_fresh().await?;
+        get_name_proof(&mut self.client, self.naming_id, name).await
+    }
+
+    pub async fn domain_owner(&mut self, name: &str) -> Result<Option<AccountId>> {
+        get_domain_owner(&self.naming_account().await?, name)
+    }
+
+    pub async fn payment_record(&mut self, name: &str) -> Result<Option<PaymentRecord>> {
+        get_payment_record(&self.naming_account().await?, name)
+    }
+
+    pub async fn owner(&mut self) -> Result<AccountId> {
+        get_owner_id(&self.naming_account().await?)
+    }
+
+    pub async fn list_names(&mut self) -> Result<Vec<(String, AccountId)>> {
+        Ok(list_registered_names(&self.naming_account().await?))
+    }
+
+    // Links the name to its owner account, submitted by the owner
+    pub async fn activate(&mut self, owner: AccountId, name: &str) -> Result<TransactionId> {
+        let note = create_naming_activate_domain_note(owner, self.naming_id, try_encode_domain(name)?, self.compile).await?;
+
+        let request = TransactionRequestBuilder::new()
+            .own_output_notes(vec![OutputNote::Full(note)])
+            .build()?;
+        Ok(self.client.submit_new_transaction(owner, request).await?)
+    }
+
+    // Submits the transfer note from the domain owner, `to` may be hex or bech32.
+    // The naming account applies it when it consumes its notes.
+    pub async fn transfer_domain_to_address(&mut self, owner: AccountId, name: &str, to: &str) -> Result<TransactionId> {
+        let new_owner = parse_account_id(to)?;
+        let note = create_naming_transfer_domain_note(owner, self.naming_id, try_encode_domain(name)?, new_owner, self.compile).await?;
+
+        let request = TransactionRequestBuilder::new()
+            .own_output_notes(vec![OutputNote::Full(note)])
+            .build()?;
+        Ok(self.client.submit_new_transaction(owner, request).await?)
+    }
+
+    // Checks the domain maps once the naming account has consumed a transfer note
+    pub async fn verify_transfer(&mut self, name: &str, previous_owner: AccountId, new_owner: AccountId) -> Result<()> {
+        verify_transfer(&self.naming_account().await?, name, previous_owner, new_owner)
+    }
+
+    // Releases the name before expiry, submitted by its owner
+    pub async fn deregister(&mut self, owner: AccountId, name: &str) -> Result<TransactionId> {
+        let note = create_naming_deregister_note(owner, self.naming_id, try_encode_domain(name)?, self.compile).await?;
+
+        let request = TransactionRequestBuilder::new()
+            .own_output_notes(vec![OutputNote::Full(note)])
+            .build()?;
+        Ok(self.client.submit_new_transaction(owner, request).await?)
+    }
+
+    // Resolves the name and sends the asset with a P2ID note from `sender`
+    pub async fn send_to_name(&mut self, sender: AccountId, name: &str, asset: Asset) -> Result<TransactionId> {
+        let note = create_p2id_note_to_name(&self.naming_account().await?, sender, name, vec![asset])?;
+
+        let request = TransactionRequestBuilder::new()
+            .own_output_notes(vec![OutputNote::Full(note)])
+            .build()?;
+        Ok(self.client.submit_new_transaction(sender, request).await?)
+    }
+
+    // Registers every name in one transaction from `registrant`, each paying its current quote.
+    // Duplicates are rejected before anything is submitted.
+    pub async fn register_names(&mut self, registrant: AccountId, names: &[&str], payment_token: AccountId, years: u32) -> Result<TransactionId> {
+        let naming = self.naming_account().await?;
+        let registrations = names.iter()
+            .map(|name| Ok((*name, quote_registration(&naming, name, payment_token, years)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let notes = create_naming_register_name_notes(registrant, self.naming_id, payment_token, &registrations, years, self.compile).await?;
+
+        let request = TransactionRequestBuilder::new()
+            .own_output_notes(notes.into_iter().map(OutputNote::Full).collect())
+            .build()?;
+        Ok(self.client.submit_new_transaction(registrant, request).await?)
+    }
+
+    // Sent by the beneficiary, lets any account pay one registration of the name for it with register_for
+    pub async fn authorize_registration(&mut self, beneficiary: AccountId, name: &str) -> Result<TransactionId> {
+        let note = create_naming_authorize_registration_note(beneficiary, self.naming_id, hash_domain(try_encode_domain(name)?), self.compile).await?;
+
+        let request = TransactionRequestBuilder::new()
+            .own_output_notes(vec![OutputNote::Full(note)])
+            .build()?;
+        Ok(self.client.submit_new_transaction(beneficiary, request).await?)
+    }
+
+    // `relayer` pays the current quote and `beneficiary` owns the name. Fails before anything is submitted
+    // unless the beneficiary's authorization is on chain.
+    pub async fn register_for(&mut self, relayer: AccountId, beneficiary: AccountId, name: &str, payment_token: AccountId, years: u32) -> Result<TransactionId> {
+        let naming = self.naming_account().await?;
+        if !is_registration_authorized(&naming, beneficiary, name)? {
+            return Err(RegistryError::RegistrationNotAuthorized { name: name.to_string(), beneficiary: beneficiary.to_hex() }.into());
+        }
+        let payment = FungibleAsset::new(payment_token, quote_registration(&naming, name, payment_token, years)?)?;
+        let note = create_naming_register_authorized_note(relayer, self.naming_id, beneficiary, try_encode_domain(name)?, payment, years, self.compile).await?;
+
+        let request = TransactionRequestBuilder::new()
+            .own_output_notes(vec![OutputNote::Full(note)])
+            .build()?;
+        Ok(self.client.submit_new_transaction(relayer, request).await?)
+    }
+
+    // Extends the name by `years` at its current quote, submitted by its owner
+    pub async fn renew(&mut self, owner: AccountId, name: &str, payment_token: AccountId, years: u32) -> Result<TransactionId> {
+        let price = quote_registration(&self.naming_account().await?, name, payment_token, years)?;
+        let note = create_naming_renew_via_register(owner, self.naming_id, try_encode_domain(name)?, FungibleAsset::new(payment_token, price)?, years, self.compile).await?;
+
+        let request = TransactionRequestBuilder::new()
+            .own_output_notes(vec![OutputNote::Full(note)])
+            .build()?;
+        Ok(self.client.submit_new_transaction(owner, request).await?)
+    }
+
+    pub async fn export_prices(&mut self, payment_token: AccountId) -> Result<PriceTable> {
+        export_prices(&self.naming_account().await?, payment_token)
+    }
+
+    // Submits a set_price note for each entry of the table that differs from the chain, all in one
+    // transaction from `setter`. Returns the changed entries, without a transaction if there were none.
+    pub async fn import_prices(&mut self, setter: AccountId, table: &PriceTable) -> Result<(BTreeMap<u8, u64>, Option<TransactionId>)> {
+        let current = self.export_prices(table.payment_token).await?;
+        let changes = price_changes(&current.prices, &table.prices);
+        if changes.is_empty() {
+            return Ok((changes, None));
+        }
+        let notes = create_naming_set_price_notes(setter, self.naming_id, table.payment_token, &changes, self.compile).await?;
+
+        let request = TransactionRequestBuilder::new()
+            .own_output_notes(notes.into_iter().map(OutputNote::Full).collect())
+            .build()?;
+        let tx_id = self.client.submit_new_transaction(setter, request).await?;
+        Ok((changes, Some(tx_id)))
+    }
+
+    pub async fn quote_registration(&mut self, name: &str, payment_token: AccountId, years: u32) -> Result<u64> {
+        quote_registration(&self.naming_account().await?, name, payment_token, years)
+    }
+
+    // Expiry is checked against local time, the registration itself uses the block timestamp
+    pub async fn check_availability(&mut self, name: &str, payment_token: AccountId) -> Result<Availability> {
+        check_availability(&self.naming_account().await?, name, payment_token, unix_now())
+    }
+
+    pub async fn suggest_alternatives(&mut self, name: &str, payment_token: AccountId, count: usize) -> Result<Vec<String>> {
+        suggest_alternatives(&self.naming_account().await?, name, payment_token, unix_now(), count)
+    }
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or(0)
+}
+
+// Imports the public naming account from the network into the local store and syncs it
+pub async fn track_registry(client: &mut Client<FilesystemKeyStore<StdRng>>, naming_id: AccountId) -> Result<()> {
+    println!("Importing naming account {} from network", naming_id);
+    client.import_account_by_id(naming_id).await?;
+    client.sync_state().await?;
+    Ok(())
+}
+
+// Resolved account word of the name and its proof against the domain -> account map root, so a light client
+// can check the resolution against the registry's storage commitment instead of trusting the RPC.
+// An unresolved name returns the empty word, the proof then shows non-membership.
+pub async fn get_name_proof(client: &mut Client<FilesystemKeyStore<StdRng>>, registry_id: AccountId, name: &str) -> Result<(Word, SmtProof)> {
+    let domain = try_encode_domain(name)?;
+    let record = client.get_account(registry_id).await?
+        .ok_or_else(|| RegistryError::AccountNotTracked(registry_id.to_hex()))?;
+    Ok(open_map_item(record.account(), DOMAIN_TO_ACCOUNT_ID_SLOT, domain)?)
+}
@@ -0,0 +1,108 @@
+use miden_client::account::AccountComponent;
+use miden_crypto::Word;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+use crate::{accounts::naming_account_component, compile::CompileOptions, errors::Result};
+
+// Checked in next to Cargo.toml, regenerate with `UPDATE_ROOTS=1 cargo test --test roots_tests`
+pub const ROOTS_LOCK_PATH: &str = "./roots.lock";
+
+// contract -> procedure name -> hex root
+pub type ProcedureRoots = BTreeMap<String, BTreeMap<String, String>>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum RootChange {
+    Added { contract: String, procedure: String, root: String },
+    Removed { contract: String, procedure: String, root: String },
+    Changed { contract: String, procedure: String, locked: String, current: String },
+}
+
+impl fmt::Display for RootChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RootChange::Added { contract, procedure, root } => write!(f, "+ {contract}::{procedure} {root}"),
+            RootChange::Removed { contract, procedure, root } => write!(f, "- {contract}::{procedure} {root}"),
+            RootChange::Changed { contract, procedure, locked, current } => write!(f, "~ {contract}::{procedure} {locked} -> {current}"),
+        }
+    }
+}
+
+// Compiles every contract from masm/accounts and collects the roots of its exported procedures, debug info
+// isn't part of them so release mode is used
+pub fn compute_procedure_roots() -> Result<ProcedureRoots> {
+    let mut roots = ProcedureRoots::new();
+    roots.insert("naming".to_string(), component_roots(&naming_account_component(CompileOptions::RELEASE)?));
+    Ok(roots)
+}
+
+// Root of an exported naming procedure, what FPI callers push before tx::execute_foreign_procedure
+pub fn naming_procedure_root(procedure: &str) -> Result<Option<Word>> {
+    let component = naming_account_component(CompileOptions::RELEASE)?;
+    let library = component.library();
+    Ok(library.exports()
+        .find(|name| name.name.to_string() == procedure)
+        .and_then(|name| library.get_procedure_root_by_name(name.clone())))
+}
+
+fn component_roots(component: &AccountComponent) -> BTreeMap<String, String> {
+    let library = component.library();
+    library.exports()
+        .filter_map(|name| {
+            library.get_procedure_root_by_name(name.clone())
+                .map(|root| (name.name.to_string(), root.to_hex()))
+        })
+        .collect()
+}
+
+// Missing lock file reads as empty, so every procedure shows up as added
+pub fn read_roots_lock(path: &Path) -> Result<ProcedureRoots> {
+    if !path.exists() {
+        return Ok(ProcedureRoots::new());
+    }
+    let content = fs::read_to_string(path)?;
+    Ok(toml::from_str(&content)?)
+}
+
+pub fn write_roots_lock(path: &Path, roots: &ProcedureRoots) -> Result<()> {
+    let content = toml::to_string_pretty(roots)?;
+    fs::write(path, content)?;
+    Ok(())
+}
+
+pub fn diff_procedure_roots(locked: &ProcedureRoots, current: &ProcedureRoots) -> Vec<RootChange> {
+    let empty = BTreeMap::new();
+    let mut changes = Vec::new();
+
+    let contracts: std::collections::BTreeSet<&String> = locked.keys().chain(current.keys()).collect();
+    for contract in contracts {
+        let locked_procs = locked.get(contract).unwrap_or(&empty);
+        let current_procs = current.get(contract).unwrap_or(&empty);
+
+        for (procedure, root) in locked_procs {
+            match current_procs.get(procedure) {
+                None => changes.push(RootChange::Removed { contract: contract.clone(), procedure: procedure.clone(), root: root.clone() }),
+                Some(current_root) if current_root != root => changes.push(RootChange::Changed {
+                    contract: contract.clone(),
+                    procedure: procedure.clone(),
+                    locked: root.clone(),
+                    current: current_root.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        for (procedure, root) in current_procs {
+            if !locked_procs.contains_key(procedure) {
+                changes.push(RootChange::Added { contract: contract.clone(), procedure: procedure.clone(), root: root.clone() });
+            }
+        }
+    }
+    changes
+}
+
+// Recompiles the contracts and diffs them against roots.lock, empty when nothing changed
+pub fn verify_procedure_roots() -> Result<Vec<RootChange>> {
+    let locked = read_roots_lock(Path::new(ROOTS_LOCK_PATH))?;
+    Ok(diff_procedure_roots(&locked, &compute_procedure_roots()?))
+}
@@ -0,0 +1,178 @@
+use miden_client::note::{Note, NoteInputs};
+use miden_crypto::Felt;
+use miden_objects::MAX_INPUTS_PER_NOTE;
+use serde::Serialize;
+
+use crate::{errors::{RegistryError, Result}, notes::GC_BATCH_SIZE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    // Must not be all zeros
+    Required,
+    // All zeros is a valid value (e.g. zero price, disabled setting)
+    Optional,
+    // Must be zero, keeps words aligned for mem_loadw
+    Padding,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct NoteField {
+    pub name: &'static str,
+    // Number of felts
+    pub width: usize,
+    pub kind: FieldKind,
+}
+
+const fn required(name: &'static str, width: usize) -> NoteField {
+    NoteField { name, width, kind: FieldKind::Required }
+}
+
+const fn optional(name: &'static str, width: usize) -> NoteField {
+    NoteField { name, width, kind: FieldKind::Optional }
+}
+
+const fn padding(width: usize) -> NoteField {
+    NoteField { name: "padding", width, kind: FieldKind::Padding }
+}
+
+// Input layout of a note template, `template` is the script name in masm/notes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct NoteSchema {
+    pub template: &'static str,
+    pub fields: &'static [NoteField],
+}
+
+const ACCOUNT: usize = 2;
+const WORD: usize = 4;
+
+pub static NOTE_SCHEMAS: [NoteSchema; 38] = [
+    NoteSchema { template: "P2N", fields: &[required("name", WORD)] },
+    NoteSchema { template: "activate_domain", fields: &[required("domain", WORD)] },
+    NoteSchema { template: "add_multisig_owner", fields: &[required("owner", ACCOUNT), padding(2)] },
+    NoteSchema { template: "admin_revoke", fields: &[required("domain", WORD), optional("refund", 1), padding(3), optional("note_details", WORD), optional("recipient", WORD)] },
+    NoteSchema { template: "approve_action", fields: &[required("action", WORD)] },
+    NoteSchema { template: "approve_sponsor", fields: &[optional("sponsor", ACCOUNT), padding(2)] },
+    NoteSchema { template: "authorize_registration", fields: &[required("name_hash", WORD)] },
+    NoteSchema { template: "claim_frontend_fees", fields: &[required("payment_token", ACCOUNT), padding(2), required("note_details", WORD), required("recipient", WORD)] },
+    NoteSchema { template: "claim_protocol_revenue", fields: &[required("recipient", WORD), required("note_details", WORD), required("payment_token", ACCOUNT), padding(2)] },
+    NoteSchema { template: "clear_expired_domain", fields: &[required("domain", WORD)] },
+    NoteSchema { template: "commit_registration", fields: &[required("commitment", WORD)] },
+    NoteSchema { template: "deregister_domain", fields: &[required("domain", WORD)] },
+    NoteSchema { template: "donate", fields: &[required("payment_token", ACCOUNT), padding(2)] },
+    NoteSchema { template: "extend_domain", fields: &[required("payment_token", ACCOUNT), padding(2), required("domain", WORD), required("years", 1), optional("hashed", 1), padding(2)] },
+    NoteSchema { template: "gc_expired_domains", fields: &[required("note_details", WORD), required("recipient", WORD), required("domains", WORD * GC_BATCH_SIZE)] },
+    NoteSchema { template: "initialize_naming", fields: &[required("owner", ACCOUNT), padding(2), required("one_year", 1), padding(3)] },
+    NoteSchema { template: "register_name", fields: &[required("payment_token", ACCOUNT), padding(2), required("domain", WORD), required("years", 1), optional("hashed", 1), padding(2)] },
+    NoteSchema { template: "register_authorized", fields: &[required("payment_token", ACCOUNT), padding(2), required("domain", WORD), required("years", 1), padding(3), required("beneficiary", ACCOUNT), padding(2)] },
+    NoteSchema { template: "register_sponsored", fields: &[required("payment_token", ACCOUNT), padding(2), required("domain", WORD), required("years", 1), padding(3), required("beneficiary", ACCOUNT), padding(2)] },
+    NoteSchema { template: "register_with_frontend_fee", fields: &[required("payment_token", ACCOUNT), padding(2), required("domain", WORD), required("years", 1), padding(3), required("frontend_fee_recipient", ACCOUNT), optional("frontend_fee_bps", 1), padding(1)] },
+    NoteSchema { template: "register_with_referrer", fields: &[required("referrer", ACCOUNT), padding(2), required("payment_token", ACCOUNT), padding(2), required("domain", WORD), required("years", 1), padding(3)] },
+    NoteSchema { template: "reveal_registration", fields: &[required("payment_token", ACCOUNT), padding(2), required("domain", WORD), required("years", 1), padding(3), optional("salt", WORD)] },
+    NoteSchema { template: "set_all_prices", fields: &[required("payment_token", ACCOUNT)] },
+    NoteSchema { template: "set_base_fee", fields: &[optional("base_fee", 1), padding(3), required("payment_token", ACCOUNT), padding(2)] },
+    NoteSchema { template: "set_class_multiplier", fields: &[optional("class", 1), padding(3), optional("multiplier_bps", 1), padding(3)] },
+    NoteSchema { template: "set_commit_reveal_delay", fields: &[optional("delay_blocks", 1), padding(3)] },
+    NoteSchema { template: "set_frontend_fee_max", fields: &[optional("max_bps", 1), padding(3)] },
+    NoteSchema { template: "set_gc_bounty", fields: &[optional("amount", 1), padding(1), optional("payment_token", ACCOUNT)] },
+    NoteSchema { template: "set_gc_grace_period", fields: &[optional("grace_period", 1), padding(3)] },
+    NoteSchema { template: "set_max_domains_per_account", fields: &[optional("max_domains", 1), padding(3)] },
+    NoteSchema { template: "set_multisig_threshold", fields: &[optional("threshold", 1), padding(3)] },
+    NoteSchema { template: "set_owner_pubkey", fields: &[optional("pub_key", WORD)] },
+    NoteSchema { template: "set_price", fields: &[required("payment_token", ACCOUNT), required("letter_count", 1), padding(1), optional("price", 1), padding(3)] },
+    NoteSchema { template: "set_pricing_mode", fields: &[optional("mode", 1), padding(3)] },
+    NoteSchema { template: "set_referrer_rate", fields: &[required("rate", 1), padding(3), required("referrer", ACCOUNT), padding(2)] },
+    NoteSchema { template: "set_reserved_name", fields: &[required("domain", WORD), optional("reserved", 1), padding(3)] },
+    NoteSchema { template: "transfer_domain", fields: &[required("new_owner", ACCOUNT), padding(2), required("domain", WORD)] },
+    NoteSchema { template: "transfer_ownership", fields: &[required("new_owner", ACCOUNT), padding(2)] },
+];
+
+impl NoteSchema {
+    pub fn input_count(&self) -> usize {
+        self.fields.iter().map(|field| field.width).sum()
+    }
+
+    // Values of the non padding fields in order, padding is filled with zeros.
+    // Fails instead of truncating when a value doesn't fit its field.
+    pub fn encode(&self, values: &[&[Felt]]) -> Result<NoteInputs> {
+        let input_count = self.input_count();
+        if input_count > MAX_INPUTS_PER_NOTE {
+            return Err(RegistryError::TooManyNoteInputs { count: input_count, max: MAX_INPUTS_PER_NOTE }.into());
+        }
+
+        let value_fields: Vec<&NoteField> = self.fields.iter().filter(|field| field.kind != FieldKind::Padding).collect();
+        if values.len() != value_fields.len() {
+            return Err(RegistryError::InvalidNoteInputs { expected: value_fields.len(), actual: values.len() }.into());
+        }
+
+        let mut values = values.iter();
+        let mut inputs = Vec::with_capacity(input_count);
+        for field in self.fields {
+            if field.kind == FieldKind::Padding {
+                inputs.extend(std::iter::repeat_n(Felt::new(0), field.width));
+                continue;
+            }
+            let value = values.next().expect("value count checked above");
+            if value.len() != field.width {
+                return Err(self.field_error(field, "value width does not match the field").into());
+            }
+            inputs.extend_from_slice(value);
+        }
+
+        let inputs = NoteInputs::new(inputs)?;
+        self.validate(&inputs)?;
+        Ok(inputs)
+    }
+
+    // Felts of a named field, None when the template has no such field or the inputs are too short
+    pub fn field<'a>(&self, inputs: &'a [Felt], name: &str) -> Option<&'a [Felt]> {
+        let mut offset = 0;
+        for field in self.fields {
+            if field.name == name {
+                return inputs.get(offset..offset + field.width);
+            }
+            offset += field.width;
+        }
+        None
+    }
+
+    // Input count, zero padding and non empty required fields
+    pub fn validate(&self, inputs: &NoteInputs) -> std::result::Result<(), RegistryError> {
+        let values = inputs.values();
+        if values.len() != self.input_count() {
+            return Err(RegistryError::InvalidNoteInputs { expected: self.input_count(), actual: values.len() });
+        }
+
+        let mut offset = 0;
+        for field in self.fields {
+            let all_zero = values[offset..offset + field.width].iter().all(|felt| felt.as_int() == 0);
+            match field.kind {
+                FieldKind::Padding if !all_zero => return Err(self.field_error(field, "padding must be zero")),
+                FieldKind::Required if all_zero => return Err(self.field_error(field, "required field is empty")),
+                _ => {}
+            }
+            offset += field.width;
+        }
+        Ok(())
+    }
+
+    fn field_error(&self, field: &NoteField, reason: &'static str) -> RegistryError {
+        RegistryError::InvalidNoteField { template: self.template, field: field.name, reason }
+    }
+}
+
+pub fn note_schema(template: &str) -> std::result::Result<&'static NoteSchema, RegistryError> {
+    NOTE_SCHEMAS.iter()
+        .find(|schema| schema.template == template)
+        .ok_or_else(|| RegistryError::UnknownNoteTemplate(template.to_string()))
+}
+
+// The note doesn't record which template built it, so the caller names it
+pub fn validate_note(note: &Note, template: &str) -> std::result::Result<(), RegistryError> {
+    note_schema(template)?.validate(note.recipient().inputs())
+}
+
+// Schemas of every note template as JSON, for frontends building notes themselves
+pub fn schema_report() -> serde_json::Result<String> {
+    serde_json::to_string_pretty(&NOTE_SCHEMAS)
+}
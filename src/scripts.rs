@@ -1,113 +1,779 @@
 
 use miden_client::{
-    account::{AccountId}, note::{NoteAssets, NoteInputs}, transaction::{OutputNote, TransactionRequestBuilder}
+    Client, account::{Account, AccountId}, asset::FungibleAsset, keystore::FilesystemKeyStore, note::Note, rpc::{GrpcClient, NodeRpcClient}, transaction::{OutputNote, TransactionId, TransactionRequest, TransactionRequestBuilder}, utils::{Deserializable, Serializable}
 };
-use miden_crypto::Felt;
-use tokio::time::{sleep, Duration};
+use miden_crypto::{Felt, Word};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io::{self, Write}, path::{Path, PathBuf}, sync::Arc};
 
 
-use crate::{accounts::{create_deployer_account, create_naming_account}, client::{create_keystore, initiate_client}, notes::create_note_for_naming, transaction::wait_for_tx};
+use crate::{accounts::{create_account_from_mnemonic, create_deployer_account, create_naming_account}, audit::{AuditReport, audit_registry, plan_repairs}, availability::Availability, certificate::{Certificate, issue_certificate}, client::rpc_timeout_from_env, compile::CompileOptions, config::{CliConfig, ConfigError, DEFAULT_KEYSTORE_PATH, Network, build_config, load_price_config, load_price_table, save_price_table}, context::Context, dump::{SlotContents, StorageSlotDump, dump_storage}, errors::{RegistryError, Result}, health::{HealthReport, NamingFeature, run_health_check}, history::{OwnershipPeriod, ownership_log, ownership_timeline}, notes::{GC_BATCH_SIZE, SetPriceInput, action_hash, create_naming_add_multisig_owner_note, create_naming_admin_revoke_note, create_naming_approve_action_note, create_naming_gc_note, create_naming_initialize_note, create_naming_set_multisig_threshold_note, create_naming_set_class_multiplier_note, create_naming_set_price_note, create_naming_set_price_notes}, pricing::{NameClass, PriceTable, get_class_multiplier, price_change_requires_confirmation, testnet_prices}, queries::{Lookup, RegistryStats, account_id_from_felts, get_gc_bounty, get_multisig_owners, get_multisig_threshold, get_owner_id, get_payment_record, read_map_item, registry_stats, sweepable_domains}, registry::{RegistryClient, unix_now}, roots::verify_procedure_roots, stateless::resolve_via_rpc, storage::{DOMAIN_TO_OWNER_SLOT, PRICES_SLOT}, token::{CliAmount, format_token_amount, get_faucet_metadata, token_label}, transaction::{DeferredSync, consume_notes, wait_for_tx, with_deferred_sync}, transcript::{Transcript, TranscriptRecorder}, vault::{SpendSummary, VaultRecord, reconcile_vault, spend_summary, vault_delta, vault_record}};
 
-pub async fn deploy() -> anyhow::Result<()> {
+// Accounts created by deploy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deployment {
+    pub network: Network,
+    pub deployer_id: AccountId,
+    pub naming_id: AccountId,
+}
+
+// Transaction of a state changing command and what it cost the sender per token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendReport {
+    pub tx_id: String,
+    pub spend: Vec<SpendSummary>,
+}
+
+// Alternatives are only suggested when the name is not available
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameCheck {
+    pub availability: Availability,
+    pub alternatives: Vec<String>,
+}
+
+// Price of one letter count as given on the command line, the amount is converted once the faucet is known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriceUpdate {
+    pub payment_token: AccountId,
+    pub letter_count: u8,
+    pub price: CliAmount,
+    pub allow_free: bool,
+}
+
+// When a transcript path is given every note and transaction request is recorded for audit.
+// Initial prices and their payment token come from the config. With a mnemonic the deployer, which
+// becomes the registry owner, can be recovered with import_account.
+pub async fn deploy(ctx: Context, transcript_path: Option<PathBuf>, mnemonic: Option<&str>) -> Result<Deployment> {
     println!("Starting Miden Name Registry deployment...");
-    let mut keystore = create_keystore()?;
-    let mut client = initiate_client(keystore.clone()).await?;
+    let root_changes = verify_procedure_roots()?;
+    if !root_changes.is_empty() {
+        println!("⚠️  Procedure roots differ from roots.lock, FPI callers pinned to the old roots will break:");
+        for change in &root_changes {
+            println!("    {}", change);
+        }
+    }
+    let payment_token_id = ctx.config.payment_token_id()?;
+    let prices = ctx.config.deploy_prices()?;
+    let mut transcript = transcript_path.map(TranscriptRecorder::new);
+    let Context { config, mut client, mut keystore, compile } = ctx;
 
-    let deployer_account = create_deployer_account(&mut client, &mut keystore).await?;
-    let naming_account = create_naming_account(&mut client).await?;
+    let deployer_account = create_deployer_account(&mut client, &mut keystore, mnemonic).await?;
+    let naming_account = create_naming_account(&mut client, compile).await?;
     client.sync_state().await?;
 
-    let initialize_inputs = NoteInputs::new([
-        Felt::new(deployer_account.id().suffix().into()),
-        Felt::new(deployer_account.id().prefix().into()),
-        Felt::new(0),
-        Felt::new(0),
-        Felt::new(5000),
-        Felt::new(0),
-        Felt::new(0),
-        Felt::new(0),
-    ].to_vec())?;
-    let init_note = create_note_for_naming("initialize_naming".to_string(), initialize_inputs, deployer_account.id(), naming_account.id(), NoteAssets::new(vec![]).unwrap()).await?;
+    let init_note = create_naming_initialize_note(deployer_account.id(), naming_account.id(), deployer_account.id(), 5000, compile).await?;
+    if let Some(transcript) = transcript.as_mut() {
+        transcript.record_note("initialize_naming", &init_note)?;
+    }
 
     let init_req = TransactionRequestBuilder::new()
         .own_output_notes(vec![OutputNote::Full(init_note)])
         .build()?;
+    if let Some(transcript) = transcript.as_mut() {
+        transcript.record_transaction(deployer_account.id(), &init_req);
+    }
 
-    let init_tx_id = client.submit_new_transaction(deployer_account.id(), init_req).await?;
+    let set_prices_notes = create_naming_set_price_notes(deployer_account.id(), naming_account.id(), payment_token_id, &prices, compile).await?;
+    if let Some(transcript) = transcript.as_mut() {
+        for note in &set_prices_notes {
+            transcript.record_note("set_price", note)?;
+        }
+    }
 
-    println!(
-        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
-        init_tx_id
-    );
-    client.sync_state().await?;
+    let set_price_req = TransactionRequestBuilder::new()
+        .own_output_notes(set_prices_notes.into_iter().map(OutputNote::Full).collect::<Vec<_>>())
+        .build()?;
+    if let Some(transcript) = transcript.as_mut() {
+        transcript.record_transaction(deployer_account.id(), &set_price_req);
+    }
 
-    println!("naming initialize note creation tx submitted, waiting for onchain commitment");
+    // Neither transaction needs the other committed, the client tracks the deployer's state between them
+    with_deferred_sync(&mut client, async |scope| {
+        for request in [init_req, set_price_req] {
+            let tx_id = scope.source().submit_new_transaction(deployer_account.id(), request).await?;
+            print_tx_link(&config, tx_id);
+            scope.defer(tx_id);
+        }
+        println!("initialize and set prices txs submitted, waiting for onchain commitment");
+        Ok(())
+    }).await?;
 
-    wait_for_tx(&mut client, init_tx_id).await?;
+    // Consume notes explicitly (required for NoAuth accounts)
+    println!("Consuming initialization notes...");
+    consume_notes(&mut client, naming_account.id(), transcript.as_mut()).await?;
+
+    if let Some(transcript) = transcript {
+        client.sync_state().await?;
+        let naming_record = client.get_account(naming_account.id()).await?
+            .ok_or_else(|| RegistryError::AccountNotTracked(naming_account.id().to_hex()))?;
+        transcript.finish(naming_record.account())?;
+        println!("Deployment transcript written");
+    }
 
-    sleep(Duration::from_secs(6)).await;
+    Ok(Deployment { network: config.network, deployer_id: deployer_account.id(), naming_id: naming_account.id() })
+}
 
-    client.sync_state().await?;
+// Every tier of the config file (or the testnet prices with use_test_prices) in one transaction.
+// Owner defaults to the registry owner stored on the naming account.
+pub async fn set_prices(mut ctx: Context, naming_id: AccountId, owner_id: Option<AccountId>, payment_token: AccountId, config_path: Option<&Path>, use_test_prices: bool) -> Result<TransactionId> {
+    let prices = match config_path {
+        Some(path) => load_price_config(path)?,
+        None if use_test_prices => testnet_prices(),
+        None => return Err(ConfigError::InvalidPriceConfig("pass --config <path>, or --test for the testnet prices".to_string()).into()),
+    };
 
-    println!("Setting prices");
+    let owner_id = owner_or_registry_owner(&mut ctx.client, naming_id, owner_id).await?;
 
-    let payment_token_id = AccountId::from_hex("0x54bf4e12ef20082070758b022456c7")?;
+    for (letter_count, price) in &prices {
+        println!("{} letter(s): {}", letter_count, price);
+    }
+    let set_prices_notes = create_naming_set_price_notes(owner_id, naming_id, payment_token, &prices, ctx.compile).await?;
+    let set_prices_req = TransactionRequestBuilder::new()
+        .own_output_notes(set_prices_notes.into_iter().map(OutputNote::Full).collect::<Vec<_>>())
+        .build()?;
 
-    let set_prices_note_inputs = NoteInputs::new([
-        Felt::new(payment_token_id.suffix().into()),
-        Felt::new(payment_token_id.prefix().into()),
-    ].to_vec())?;
+    submit_and_consume(&mut ctx, naming_id, owner_id, set_prices_req).await
+}
 
-    let set_prices_note = create_note_for_naming("set_all_prices_testnet".to_string(), set_prices_note_inputs, deployer_account.id(), naming_account.id(), NoteAssets::new(vec![]).unwrap()).await?;
+// Owner defaults to the registry owner stored on the naming account. None when the confirmation is declined.
+pub async fn set_price(mut ctx: Context, naming_id: AccountId, owner_id: Option<AccountId>, update: &PriceUpdate, skip_confirmation: bool) -> Result<Option<TransactionId>> {
+    let PriceUpdate { payment_token, letter_count, allow_free, .. } = *update;
+    let price = cli_amount(&mut ctx.client, payment_token, &update.price).await?;
+
+    let naming_record = ctx.client.get_account(naming_id).await?
+        .ok_or_else(|| RegistryError::AccountNotTracked(naming_id.to_hex()))?;
+    let owner_id = match owner_id {
+        Some(owner_id) => owner_id,
+        None => get_owner_id(naming_record.account())?,
+    };
+
+    let set_price_note = create_naming_set_price_note(owner_id, naming_id, payment_token, SetPriceInput { letter_count, price }, allow_free, ctx.compile).await?;
+    let price_key = Word::new([payment_token.suffix(), payment_token.prefix().as_felt(), Felt::new(letter_count.into()), Felt::new(0)]);
+    let old_price = read_map_item(naming_record.account(), PRICES_SLOT, price_key)?.map(|price| price[0].as_int()).unwrap_or(0);
+
+    println!("Current price for {} letter(s): {}, new price: {} (base units)", letter_count, old_price, price);
+    if price_change_requires_confirmation(old_price, price) && !skip_confirmation {
+        println!("Warning: new price differs from the current price by more than 10x");
+        if !confirm("Continue?")? {
+            println!("Aborted");
+            return Ok(None);
+        }
+    }
 
     let set_price_req = TransactionRequestBuilder::new()
-        .own_output_notes(vec![OutputNote::Full(set_prices_note)])
+        .own_output_notes(vec![OutputNote::Full(set_price_note)])
         .build()?;
 
-    let set_prices_tx_id = client.submit_new_transaction(deployer_account.id(), set_price_req).await?;
+    Ok(Some(submit_and_consume(&mut ctx, naming_id, owner_id, set_price_req).await?))
+}
 
-    println!(
-        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
-        set_prices_tx_id
-    );
-    client.sync_state().await?;
+// Owner defaults to the registry owner stored on the naming account
+pub async fn set_class_multiplier(mut ctx: Context, naming_id: AccountId, owner_id: Option<AccountId>, class: NameClass, multiplier_bps: u32) -> Result<TransactionId> {
+    let naming_record = ctx.client.get_account(naming_id).await?
+        .ok_or_else(|| RegistryError::AccountNotTracked(naming_id.to_hex()))?;
+    let owner_id = match owner_id {
+        Some(owner_id) => owner_id,
+        None => get_owner_id(naming_record.account())?,
+    };
 
-    println!("set prices tx submitted, waiting for onchain commitment");
+    let note = create_naming_set_class_multiplier_note(owner_id, naming_id, class, multiplier_bps, ctx.compile).await?;
+    let old_bps = get_class_multiplier(naming_record.account(), class)?;
+    println!("Current multiplier for {:?} names: {} bps, new multiplier: {} bps (0 is unset)", class, old_bps, multiplier_bps);
 
-    wait_for_tx(&mut client, set_prices_tx_id).await?;
+    submit_naming_note(&mut ctx, naming_id, owner_id, note).await
+}
 
-    sleep(Duration::from_secs(6)).await;
+// Writes the set_price note of `proposer` (a multisig owner) to a file instead of submitting it and returns
+// its action hash. Owners approve it with approve_action and once the threshold is met execute_action submits
+// it from the proposer, whose key must be in the keystore.
+pub async fn propose_set_price(mut ctx: Context, naming_id: AccountId, proposer: AccountId, update: &PriceUpdate, output: &Path) -> Result<Word> {
+    let price = cli_amount(&mut ctx.client, update.payment_token, &update.price).await?;
+
+    let note = create_naming_set_price_note(proposer, naming_id, update.payment_token, SetPriceInput { letter_count: update.letter_count, price }, update.allow_free, ctx.compile).await?;
+    fs::write(output, note.to_bytes())?;
+    let action = action_hash(&note);
+    println!("Proposal written to {}, action hash: {}", output.display(), action.to_hex());
+    Ok(action)
+}
 
-    client.sync_state().await?;
+pub async fn approve_action(mut ctx: Context, naming_id: AccountId, approver: AccountId, proposal: &Path) -> Result<TransactionId> {
+    let action = action_hash(&load_proposal(proposal)?);
+    println!("Approving action {}", action.to_hex());
+    let note = create_naming_approve_action_note(approver, naming_id, action, ctx.compile).await?;
+    submit_naming_note(&mut ctx, naming_id, approver, note).await
+}
 
-    // Consume notes explicitly (required for NoAuth accounts)
-    println!("Consuming initialization notes...");
-    let consumable_notes = client.get_consumable_notes(Some(naming_account.id())).await?;
+// Submits the proposed note from its proposer, fails on chain unless enough owners approved it
+pub async fn execute_action(mut ctx: Context, naming_id: AccountId, proposal: &Path) -> Result<TransactionId> {
+    let naming_record = ctx.client.get_account(naming_id).await?
+        .ok_or_else(|| RegistryError::AccountNotTracked(naming_id.to_hex()))?;
+    let threshold = get_multisig_threshold(naming_record.account())?;
+    println!("Executing with a threshold of {} of {} multisig owners", threshold, get_multisig_owners(naming_record.account())?.len());
+
+    let note = load_proposal(proposal)?;
+    let sender = note.metadata().sender();
+    submit_naming_note(&mut ctx, naming_id, sender, note).await
+}
 
-    if !consumable_notes.is_empty() {
-        println!("Found {} consumable note(s)", consumable_notes.len());
+// Owner defaults to the registry owner stored on the naming account
+pub async fn add_multisig_owner(mut ctx: Context, naming_id: AccountId, owner_id: Option<AccountId>, multisig_owner: AccountId) -> Result<TransactionId> {
+    let owner_id = owner_or_registry_owner(&mut ctx.client, naming_id, owner_id).await?;
 
-        let note_ids: Vec<_> = consumable_notes.iter().map(|(record, _)| (record.id(), None)).collect();
+    let note = create_naming_add_multisig_owner_note(owner_id, naming_id, multisig_owner, ctx.compile).await?;
+    submit_naming_note(&mut ctx, naming_id, owner_id, note).await
+}
 
-        let nop_script_code = std::fs::read_to_string(std::path::Path::new("./masm/scripts/nop.masm"))?;
-        use miden_client::ScriptBuilder;
-        let transaction_script = ScriptBuilder::new(false)
-            .compile_tx_script(nop_script_code)?;
+// Owner defaults to the registry owner stored on the naming account
+pub async fn set_multisig_threshold(mut ctx: Context, naming_id: AccountId, owner_id: Option<AccountId>, threshold: u32) -> Result<TransactionId> {
+    let naming_record = ctx.client.get_account(naming_id).await?
+        .ok_or_else(|| RegistryError::AccountNotTracked(naming_id.to_hex()))?;
+    let owner_id = match owner_id {
+        Some(owner_id) => owner_id,
+        None => get_owner_id(naming_record.account())?,
+    };
+    println!("Current threshold: {}, new threshold: {} (0 is single owner)", get_multisig_threshold(naming_record.account())?, threshold);
+
+    let note = create_naming_set_multisig_threshold_note(owner_id, naming_id, threshold, ctx.compile).await?;
+    submit_naming_note(&mut ctx, naming_id, owner_id, note).await
+}
 
-        let consume_request = TransactionRequestBuilder::new()
-            .authenticated_input_notes(note_ids)
-            .custom_script(transaction_script)
-            .build()?;
+async fn owner_or_registry_owner(client: &mut Client<FilesystemKeyStore<StdRng>>, naming_id: AccountId, owner_id: Option<AccountId>) -> Result<AccountId> {
+    if let Some(owner_id) = owner_id {
+        return Ok(owner_id);
+    }
+    let naming_record = client.get_account(naming_id).await?
+        .ok_or_else(|| RegistryError::AccountNotTracked(naming_id.to_hex()))?;
+    Ok(get_owner_id(naming_record.account())?)
+}
 
-        let consume_tx_id = client.submit_new_transaction(naming_account.id(), consume_request).await?;
-        println!("Consuming notes via transaction: {:?}", consume_tx_id);
+async fn cli_amount(client: &mut Client<FilesystemKeyStore<StdRng>>, payment_token: AccountId, amount: &CliAmount) -> Result<u64> {
+    let decimals = match amount {
+        CliAmount::Raw(_) => 0,
+        CliAmount::Display(_) => get_faucet_metadata(client, payment_token).await?.decimals,
+    };
+    Ok(amount.base_units(decimals)?)
+}
 
-        wait_for_tx(&mut client, consume_tx_id).await?;
-        println!("✅ Notes consumed successfully!");
-    } else {
-        println!("Warning: No consumable notes found");
+fn load_proposal(path: &Path) -> Result<Note> {
+    Note::read_from_bytes(&fs::read(path)?).map_err(|_| RegistryError::InvalidProposal(path.display().to_string()).into())
+}
+
+async fn submit_naming_note(ctx: &mut Context, naming_id: AccountId, sender: AccountId, note: Note) -> Result<TransactionId> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note)])
+        .build()?;
+    submit_and_consume(ctx, naming_id, sender, request).await
+}
+
+// Submits from `sender`, syncs once the transaction is committed and consumes the naming account's notes
+async fn submit_and_consume(ctx: &mut Context, naming_id: AccountId, sender: AccountId, request: TransactionRequest) -> Result<TransactionId> {
+    let config = &ctx.config;
+    let tx_id = with_deferred_sync(&mut ctx.client, async |scope| {
+        let tx_id = scope.source().submit_new_transaction(sender, request).await?;
+        print_tx_link(config, tx_id);
+        scope.defer(tx_id);
+        Ok(tx_id)
+    }).await?;
+
+    consume_notes(&mut ctx.client, naming_id, None).await?;
+    Ok(tx_id)
+}
+
+fn print_tx_link(config: &CliConfig, tx_id: TransactionId) {
+    match config.explorer_tx_url(tx_id) {
+        Some(url) => println!("View transaction on MidenScan: {}", url),
+        None => println!("Submitted transaction {:?}", tx_id),
+    }
+}
+
+async fn synced_registry(client: Client<FilesystemKeyStore<StdRng>>, naming_id: AccountId, compile: CompileOptions) -> Result<RegistryClient> {
+    let mut registry = RegistryClient::connect(client, naming_id).await?.with_compile_options(compile);
+    registry.sync().await?;
+    Ok(registry)
+}
+
+pub async fn check(ctx: Context, naming_id: AccountId, payment_token: AccountId, name: &str, suggestions: usize) -> Result<NameCheck> {
+    let mut registry = synced_registry(ctx.client, naming_id, ctx.compile).await?;
+
+    let availability = registry.check_availability(name, payment_token).await?;
+    if availability.is_available() {
+        let metadata = registry.faucet_metadata(payment_token).await?;
+        println!("✅ {} is available, price: {} per year", name, format_token_amount(metadata.as_ref(), availability.price));
+        return Ok(NameCheck { availability, alternatives: Vec::new() });
+    }
+
+    match availability.taken_by {
+        Some(owner) => println!("{} is taken by {}", name, owner),
+        None => println!("{} is reserved", name),
     }
+    let alternatives = registry.suggest_alternatives(name, payment_token, suggestions).await?;
+    if !alternatives.is_empty() {
+        println!("Try: {}", alternatives.join(", "));
+    }
+    Ok(NameCheck { availability, alternatives })
+}
+
+pub async fn resolve(ctx: Context, naming_id: AccountId, name: &str) -> Result<Option<AccountId>> {
+    let mut registry = RegistryClient::connect(ctx.client, naming_id).await?.with_compile_options(ctx.compile);
+    let account_id = registry.resolve(name).await?;
+    print_resolved(&ctx.config, name, account_id);
+    if let Some(record) = registry.payment_record(name).await? {
+        println!("Registered for {} year(s), paid {} (base units) of {}", record.years, record.amount, ctx.config.account_label(record.payment_token));
+    }
+    Ok(account_id)
+}
+
+// Needs no keystore or store, only the configured node
+pub async fn resolve_stateless(config: &CliConfig, naming_id: AccountId, name: &str) -> Result<Option<AccountId>> {
+    let mut rpc_client: Arc<dyn NodeRpcClient> = Arc::new(GrpcClient::new(&config.endpoint()?, rpc_timeout_from_env()?));
+    let account_id = resolve_via_rpc(&mut rpc_client, naming_id, name).await?;
+    print_resolved(config, name, account_id);
+    Ok(account_id)
+}
+
+// Proof of the name's current resolution from the node, written as compact JSON or printed
+pub async fn certificate(config: &CliConfig, naming_id: AccountId, name: &str, output: Option<&Path>) -> Result<Certificate> {
+    let mut rpc_client: Arc<dyn NodeRpcClient> = Arc::new(GrpcClient::new(&config.endpoint()?, rpc_timeout_from_env()?));
+    let certificate = issue_certificate(&mut rpc_client, naming_id, name).await?;
+    match output {
+        Some(path) => {
+            certificate.save(path)?;
+            println!("✅ Certificate for {} at block {} written to {}", name, certificate.block_num(), path.display());
+        }
+        None => println!("{}", serde_json::to_string(&certificate).map_err(io::Error::from)?),
+    }
+    Ok(certificate)
+}
+
+fn print_resolved(config: &CliConfig, name: &str, account_id: Option<AccountId>) {
+    match account_id {
+        Some(account_id) => println!("{} resolves to {} ({})", name, account_id.to_hex(), config.address(account_id)),
+        None => println!("{} does not resolve to an account", name),
+    }
+}
+
+pub async fn status(ctx: Context, naming_id: AccountId, payment_token: AccountId) -> Result<VaultRecord> {
+    let mut registry = synced_registry(ctx.client, naming_id, ctx.compile).await?;
+
+    let naming = registry.naming_account().await?;
+    let record = vault_record(&naming, payment_token)?;
+    let reconciliation = reconcile_vault(&record);
+
+    let metadata = registry.faucet_metadata(payment_token).await?;
+    let amount = |base_units| format_token_amount(metadata.as_ref(), base_units);
+
+    println!("Owner: {}", ctx.config.account_label(registry.owner().await?));
+    println!("Token: {}", token_label(metadata.as_ref(), payment_token));
+    println!("Vault balance: {}", amount(record.vault_balance));
+    println!("Revenue: {} (claimed {})", amount(record.total_revenue), amount(record.claimed_revenue));
+    println!("Donations: {}", amount(record.donations));
+    println!("Owed to referrers: {}, to frontends: {}", amount(record.referrer_owed), amount(record.frontend_owed));
+    if reconciliation.unaccounted > 0 {
+        println!("⚠️  Unaccounted vault balance: {}", amount(reconciliation.unaccounted));
+    }
+    if reconciliation.shortfall > 0 {
+        println!("⚠️  Vault is short of counters by {}", amount(reconciliation.shortfall));
+    }
+    if reconciliation.is_clean() {
+        println!("✅ Vault matches revenue and donation counters");
+    }
+    Ok(record)
+}
+
+pub async fn send(mut ctx: Context, naming_id: AccountId, sender: AccountId, name: &str, payment_token: AccountId, amount: &CliAmount) -> Result<TransactionId> {
+    let amount = cli_amount(&mut ctx.client, payment_token, amount).await?;
+    let mut registry = synced_registry(ctx.client, naming_id, ctx.compile).await?;
+
+    let asset = FungibleAsset::new(payment_token, amount)?;
+    let tx_id = registry.send_to_name(sender, name, asset.into()).await?;
+    print_tx_link(&ctx.config, tx_id);
+    wait_for_tx(&mut registry.client, tx_id).await?;
+    println!("✅ Sent {} to {}", amount, name);
+    Ok(tx_id)
+}
 
+// With a beneficiary the registrant only relays the payment, the beneficiary owns the name and must have
+// authorized it with authorize_registration
+pub async fn register(ctx: Context, naming_id: AccountId, registrant: AccountId, beneficiary: Option<AccountId>, name: &str, payment_token: AccountId, years: u32) -> Result<SpendReport> {
+    let mut registry = synced_registry(ctx.client, naming_id, ctx.compile).await?;
+    let before = local_account(&mut registry.client, registrant).await?;
+
+    let price = registry.quote_registration(name, payment_token, years).await?;
+    let tx_id = match beneficiary {
+        Some(beneficiary) => registry.register_for(registrant, beneficiary, name, payment_token, years).await?,
+        None => registry.register_names(registrant, &[name], payment_token, years).await?,
+    };
+    print_tx_link(&ctx.config, tx_id);
+    let report = spend_report(&mut registry, registrant, &before, tx_id, &[FungibleAsset::new(payment_token, price)?]).await?;
+    match beneficiary {
+        Some(beneficiary) => println!("✅ Registered {} for {} year(s), owned by {}", name, years, ctx.config.account_label(beneficiary)),
+        None => println!("✅ Registered {} for {} year(s)", name, years),
+    }
+    Ok(report)
+}
+
+// Sent by the beneficiary, a relayer can then pay one registration of the name with register --beneficiary
+pub async fn authorize_registration(ctx: Context, naming_id: AccountId, beneficiary: AccountId, name: &str) -> Result<TransactionId> {
+    let mut registry = synced_registry(ctx.client, naming_id, ctx.compile).await?;
+
+    let tx_id = registry.authorize_registration(beneficiary, name).await?;
+    print_tx_link(&ctx.config, tx_id);
+    wait_for_tx(&mut registry.client, tx_id).await?;
+    consume_notes(&mut registry.client, naming_id, None).await?;
+    println!("✅ Authorized a relayed registration of {}", name);
+    Ok(tx_id)
+}
+
+pub async fn renew(ctx: Context, naming_id: AccountId, owner: AccountId, name: &str, payment_token: AccountId, years: u32) -> Result<SpendReport> {
+    let mut registry = synced_registry(ctx.client, naming_id, ctx.compile).await?;
+    let before = local_account(&mut registry.client, owner).await?;
+
+    let price = registry.quote_registration(name, payment_token, years).await?;
+    let tx_id = registry.renew(owner, name, payment_token, years).await?;
+    print_tx_link(&ctx.config, tx_id);
+    let report = spend_report(&mut registry, owner, &before, tx_id, &[FungibleAsset::new(payment_token, price)?]).await?;
+    println!("✅ Renewed {} for {} year(s)", name, years);
+    Ok(report)
+}
+
+pub async fn transfer_domain(ctx: Context, naming_id: AccountId, owner: AccountId, name: &str, to: &str) -> Result<SpendReport> {
+    let mut registry = synced_registry(ctx.client, naming_id, ctx.compile).await?;
+    let before = local_account(&mut registry.client, owner).await?;
+
+    let tx_id = registry.transfer_domain_to_address(owner, name, to).await?;
+    print_tx_link(&ctx.config, tx_id);
+    let report = spend_report(&mut registry, owner, &before, tx_id, &[]).await?;
+    println!("✅ Transferred {} to {}", name, to);
+    Ok(report)
+}
+
+async fn local_account(client: &mut Client<FilesystemKeyStore<StdRng>>, account_id: AccountId) -> Result<Account> {
+    let record = client.get_account(account_id).await?
+        .ok_or_else(|| RegistryError::AccountNotTracked(account_id.to_hex()))?;
+    Ok(record.account().clone())
+}
+
+// Waits for the transaction, lets the naming account consume its notes and prints the sender's spend
+// against its vault from before the transaction
+async fn spend_report(registry: &mut RegistryClient, sender: AccountId, before: &Account, tx_id: TransactionId, sent: &[FungibleAsset]) -> Result<SpendReport> {
+    wait_for_tx(&mut registry.client, tx_id).await?;
+    consume_notes(&mut registry.client, registry.naming_id, None).await?;
+    let after = local_account(&mut registry.client, sender).await?;
+
+    let spend = spend_summary(&vault_delta(before, &after), sent);
+    for summary in &spend {
+        let metadata = registry.faucet_metadata(summary.faucet_id).await?;
+        let amount = |base_units| format_token_amount(metadata.as_ref(), base_units);
+        let net = match summary.net_cost {
+            cost if cost >= 0 => format!("net cost {}", amount(cost as u64)),
+            cost => format!("net received {}", amount(cost.unsigned_abs())),
+        };
+        println!("{}: sent {}, change {}, {}", token_label(metadata.as_ref(), summary.faucet_id), amount(summary.sent), amount(summary.change), net);
+    }
+    Ok(SpendReport { tx_id: tx_id.to_hex(), spend })
+}
+
+// Labeled dump of every storage slot, tracks the account first if needed
+pub async fn dump(ctx: Context, account_id: AccountId) -> Result<BTreeMap<u8, StorageSlotDump>> {
+    let mut registry = synced_registry(ctx.client, account_id, ctx.compile).await?;
+
+    let slots = dump_storage(&registry.naming_account().await?);
+    for (index, slot) in &slots {
+        match &slot.contents {
+            SlotContents::Value(value) => println!("[{}] {} ({}): {}", index, slot.name, slot.description, value),
+            SlotContents::Map(entries) => {
+                println!("[{}] {} ({}): {} entries", index, slot.name, slot.description, entries.len());
+                for (key, value) in entries {
+                    println!("    {} -> {}", key, value);
+                }
+            }
+        }
+    }
+    Ok(slots)
+}
+
+// Sweeps the domains past expiry and grace period, GC_BATCH_SIZE per note and at most `max_batches` notes.
+// Candidates are checked against local time, a domain at the edge of its grace period can still fail its batch.
+// The bounty notes go to `sender`, which consumes them like any P2ID note.
+pub async fn gc_expired(mut ctx: Context, naming_id: AccountId, sender: AccountId, max_batches: usize) -> Result<Vec<TransactionId>> {
+    ctx.client.sync_state().await?;
+    let naming_record = ctx.client.get_account(naming_id).await?
+        .ok_or_else(|| RegistryError::AccountNotTracked(naming_id.to_hex()))?;
+    let domains = sweepable_domains(naming_record.account(), unix_now())?;
+    if domains.is_empty() {
+        println!("No domains past their grace period");
+        return Ok(Vec::new());
+    }
+    let bounty = get_gc_bounty(naming_record.account())?;
+
+    let mut tx_ids = Vec::new();
+    for batch in domains.chunks(GC_BATCH_SIZE).take(max_batches) {
+        let note = create_naming_gc_note(sender, naming_id, batch, ctx.compile).await?;
+        tx_ids.push(submit_naming_note(&mut ctx, naming_id, sender, note).await?);
+        match bounty {
+            Some(bounty) => println!("Swept {} domain(s), bounty {} per domain of {}", batch.len(), bounty.amount(), ctx.config.account_label(bounty.faucet_id())),
+            None => println!("Swept {} domain(s)", batch.len()),
+        }
+    }
+    Ok(tx_ids)
+}
+
+// Owner defaults to the registry owner stored on the naming account. None when the confirmation is declined.
+// Works for plain and hashed registrations, the note carries whichever key the name is registered under.
+pub async fn revoke(mut ctx: Context, naming_id: AccountId, owner_id: Option<AccountId>, name: &str, refund_to: Option<AccountId>, skip_confirmation: bool) -> Result<Option<TransactionId>> {
+    let naming_record = ctx.client.get_account(naming_id).await?
+        .ok_or_else(|| RegistryError::AccountNotTracked(naming_id.to_hex()))?;
+    let owner_id = match owner_id {
+        Some(owner_id) => owner_id,
+        None => get_owner_id(naming_record.account())?,
+    };
+
+    let mut registration = None;
+    for lookup in [Lookup::Plain, Lookup::Hashed] {
+        let key = lookup.key(name)?;
+        if let Some(domain_owner) = read_map_item(naming_record.account(), DOMAIN_TO_OWNER_SLOT, key)? {
+            registration = Some((key, domain_owner));
+            break;
+        }
+    }
+    let (domain, domain_owner) = registration.ok_or_else(|| RegistryError::NameNotFound(name.to_string()))?;
+
+    println!("Revoking {} owned by {}", name, ctx.config.account_label(account_id_from_felts(domain_owner[1], domain_owner[0])?));
+    if let Some(refund_to) = refund_to {
+        let record = get_payment_record(naming_record.account(), name)?
+            .ok_or_else(|| RegistryError::NoPaymentRecord(name.to_string()))?;
+        println!("Refunding {} (base units) of {} to {}", record.amount, ctx.config.account_label(record.payment_token), ctx.config.account_label(refund_to));
+    }
+    if !skip_confirmation && !confirm("Continue?")? {
+        println!("Aborted");
+        return Ok(None);
+    }
+
+    let note = create_naming_admin_revoke_note(owner_id, naming_id, domain, refund_to, ctx.compile).await?;
+    let tx_id = submit_naming_note(&mut ctx, naming_id, owner_id, note).await?;
+    println!("✅ Revoked {}", name);
+    Ok(Some(tx_id))
+}
+
+// Storage map sizes next to the on chain registration count
+pub async fn stats(ctx: Context, naming_id: AccountId) -> Result<RegistryStats> {
+    let mut registry = synced_registry(ctx.client, naming_id, ctx.compile).await?;
+
+    let stats = registry_stats(&registry.naming_account().await?)?;
+    println!("Registered names:    {}", stats.registered_names);
+    println!("Resolving names:     {}", stats.resolving_names);
+    println!("Primary names:       {}", stats.reverse_entries);
+    println!("Total registrations: {}", stats.total_registrations);
+    Ok(stats)
+}
+
+// One page of names with their owners, ordered by name
+pub async fn list_names(ctx: Context, naming_id: AccountId, offset: usize, limit: usize) -> Result<Vec<(String, AccountId)>> {
+    let mut registry = synced_registry(ctx.client, naming_id, ctx.compile).await?;
+
+    let names = registry.list_names().await?;
+    let total = names.len();
+    let page: Vec<_> = names.into_iter().skip(offset).take(limit).collect();
+    for (name, owner) in &page {
+        println!("{} -> {}", name, ctx.config.account_label(*owner));
+    }
+    println!("Showing {} of {} names", page.len(), total);
+    Ok(page)
+}
+
+#[cfg(feature = "testing")]
+pub async fn replay(path: &Path, compile: CompileOptions) -> Result<()> {
+    let transcript = Transcript::load(path)?;
+    println!("Replaying {} transcript entries...", transcript.entries.len());
+
+    crate::transcript::replay_transcript(&transcript, compile).await?;
+    println!("✅ Replayed state matches the recorded post state");
     Ok(())
 }
 
+// Read only check of a deployed registry, fails if it doesn't report what this build expects
+pub async fn health(ctx: Context, naming_id: AccountId) -> Result<HealthReport> {
+    let mut registry = synced_registry(ctx.client, naming_id, ctx.compile).await?;
+
+    let report = run_health_check(&mut registry.client, naming_id, registry.compile).await?;
+    println!("Health check version: {}", report.version);
+    println!("Storage slots: {}", report.slot_count);
+    for feature in NamingFeature::ALL {
+        println!("{:?}: {}", feature, if report.has(feature) { "yes" } else { "no" });
+    }
+    if !report.is_expected() {
+        return Err(RegistryError::UnexpectedHealthReport { version: report.version, features: report.features, slot_count: report.slot_count }.into());
+    }
+    println!("✅ Registry matches this build");
+    Ok(report)
+}
+
+// Read only audit of the domain maps and revenue counters. With a fix plan directory the repair notes are
+// written there, one serialized note per file, for their senders to submit. The registry owner sends the
+// permissionless ones unless another sender is given.
+pub async fn audit(ctx: Context, naming_id: AccountId, sender: Option<AccountId>, fix_plan: Option<&Path>) -> Result<AuditReport> {
+    let mut registry = synced_registry(ctx.client, naming_id, ctx.compile).await?;
+
+    let naming = registry.naming_account().await?;
+    let report = audit_registry(&naming)?;
+    if report.is_clean() {
+        println!("✅ No invariant violations");
+        return Ok(report);
+    }
+    for violation in &report.violations {
+        println!("❌ {}", violation);
+    }
+
+    if let Some(dir) = fix_plan {
+        let sender = match sender {
+            Some(sender) => sender,
+            None => get_owner_id(&naming)?,
+        };
+        let plan = plan_repairs(&naming, &report, sender, registry.compile).await?;
+        fs::create_dir_all(dir)?;
+        for (index, (violation, note)) in plan.repairs.iter().enumerate() {
+            let path = dir.join(format!("repair-{index}.bin"));
+            fs::write(&path, note.to_bytes())?;
+            println!("{}: submit as {} to repair: {}", path.display(), ctx.config.account_label(note.metadata().sender()), violation);
+        }
+        for violation in &plan.manual {
+            println!("No note repairs: {}", violation);
+        }
+    }
+    Ok(report)
+}
+
+// Owners of the name over time, replayed from the naming notes the local store saw consumed
+pub async fn history(ctx: Context, naming_id: AccountId, name: &str) -> Result<Vec<OwnershipPeriod>> {
+    let mut client = ctx.client;
+    client.sync_state().await?;
+
+    let log = ownership_log(&mut client, naming_id).await?;
+    let periods = ownership_timeline(&log, name)?;
+    if periods.is_empty() {
+        println!("No ownership changes of {} in the local store", name);
+    }
+    for period in &periods {
+        let owner = match period.owner {
+            Some(owner) => ctx.config.account_label(owner),
+            None => "released".to_string(),
+        };
+        match period.until_block {
+            Some(until) => println!("blocks {}..{}: {}", period.from_block, until, owner),
+            None => println!("blocks {}..: {}", period.from_block, owner),
+        }
+    }
+    Ok(periods)
+}
+
+// Writes the on-chain prices of the token as JSON, to stdout without an output path
+pub async fn export_price_table(ctx: Context, naming_id: AccountId, payment_token: AccountId, output: Option<&Path>) -> Result<PriceTable> {
+    let mut registry = synced_registry(ctx.client, naming_id, ctx.compile).await?;
+
+    let table = registry.export_prices(payment_token).await?;
+    match output {
+        Some(path) => {
+            save_price_table(path, &table)?;
+            println!("✅ Exported {} prices to {}", table.prices.len(), path.display());
+        }
+        None => println!("{}", serde_json::to_string_pretty(&table).map_err(|err| ConfigError::InvalidPriceConfig(err.to_string()))?),
+    }
+    Ok(table)
+}
+
+// Only entries differing from the chain are submitted, None when nothing differs
+pub async fn import_price_table(ctx: Context, naming_id: AccountId, owner_id: Option<AccountId>, input: &Path) -> Result<Option<TransactionId>> {
+    let table = load_price_table(input)?;
+
+    let mut registry = synced_registry(ctx.client, naming_id, ctx.compile).await?;
+
+    let owner_id = match owner_id {
+        Some(owner_id) => owner_id,
+        None => registry.owner().await?,
+    };
+    let (changes, tx_id) = registry.import_prices(owner_id, &table).await?;
+    let Some(tx_id) = tx_id else {
+        println!("✅ Prices already match {}", input.display());
+        return Ok(None);
+    };
+    for (letter_count, price) in &changes {
+        println!("{} letter(s): {}", letter_count, price);
+    }
+    print_tx_link(&ctx.config, tx_id);
+
+    let mut scope = DeferredSync::new(&mut registry.client);
+    scope.defer(tx_id);
+    scope.finish().await?;
+
+    consume_notes(&mut registry.client, naming_id, None).await?;
+
+    Ok(Some(tx_id))
+}
+
+// Owner wallet derived from the mnemonic, imported from chain when it is deployed already
+pub async fn import_account(mut ctx: Context, mnemonic: &str, index: u32) -> Result<AccountId> {
+    let (wallet, _) = create_account_from_mnemonic(&mut ctx.client, &ctx.keystore, mnemonic, index).await?;
+    println!("✅ Recovered {} with its key", ctx.config.account_label(wallet.id()));
+    Ok(wallet.id())
+}
+
+// Values given as flags are used as is, missing values are prompted unless non interactive
+pub async fn init_config(path: &Path, network: Option<Network>, rpc_url: Option<String>, keystore_path: Option<PathBuf>, naming_id: Option<String>, non_interactive: bool) -> Result<CliConfig> {
+    let network = match network {
+        Some(network) => network,
+        None if non_interactive => Network::Testnet,
+        None => prompt("Network (testnet, devnet, mainnet, localhost or an RPC URL)", Some("testnet"))?.parse::<Network>()?,
+    };
+    let rpc_url = match rpc_url {
+        Some(url) => Some(url),
+        None if non_interactive => None,
+        None => Some(prompt("RPC URL", network.endpoint().map(|endpoint| endpoint.to_string()).as_deref())?).filter(|url| !url.is_empty()),
+    };
+    let keystore_path = match keystore_path {
+        Some(path) => path,
+        None if non_interactive => PathBuf::from(DEFAULT_KEYSTORE_PATH),
+        None => PathBuf::from(prompt("Keystore path", Some(DEFAULT_KEYSTORE_PATH))?),
+    };
+    let naming_id = match naming_id {
+        Some(id) => Some(id),
+        None if non_interactive => None,
+        None => Some(prompt("Naming account ID (empty to skip)", Some(""))?).filter(|id| !id.is_empty()),
+    };
+
+    let config = build_config(network, rpc_url, keystore_path, naming_id)?;
+
+    println!("Checking endpoint {}...", config.rpc_url);
+    config.validate_online().await?;
+
+    config.save(path)?;
+    println!("✅ Config written to {}", path.display());
+    Ok(config)
+}
+
+fn prompt(question: &str, default: Option<&str>) -> Result<String> {
+    match default {
+        Some(default) if !default.is_empty() => print!("{} [{}]: ", question, default),
+        _ => print!("{}: ", question),
+    }
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    let answer = answer.trim();
+    if answer.is_empty() {
+        return Ok(default.unwrap_or_default().to_string());
+    }
+    Ok(answer.to_string())
+}
+
+fn confirm(question: &str) -> Result<bool> {
+    print!("{} [y/N] ", question);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
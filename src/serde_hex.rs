@@ -0,0 +1,140 @@
+use miden_client::account::AccountId;
+use miden_crypto::{Felt, StarkField, Word};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::DeserializeOwned};
+
+// Felts as 0x + 16 hex digits of the canonical value, words as 0x + the 4 felts in word order.
+// Values >= the field modulus are rejected instead of being reduced like Felt::new does.
+pub fn felt_to_hex(felt: Felt) -> String {
+    format!("0x{:016x}", felt.as_int())
+}
+
+pub fn felt_from_hex(hex: &str) -> Result<Felt, String> {
+    let digits = hex.strip_prefix("0x").ok_or_else(|| format!("'{hex}' is missing the 0x prefix"))?;
+    parse_felt_digits(digits)
+}
+
+pub fn word_to_hex(word: Word) -> String {
+    let felts: String = word.to_vec().iter().map(|felt| format!("{:016x}", felt.as_int())).collect();
+    format!("0x{felts}")
+}
+
+pub fn word_from_hex(hex: &str) -> Result<Word, String> {
+    let digits = hex.strip_prefix("0x").ok_or_else(|| format!("'{hex}' is missing the 0x prefix"))?;
+    if digits.len() != 64 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("'{hex}' is not 64 hex digits"));
+    }
+    let mut felts = [Felt::new(0); 4];
+    for (i, felt) in felts.iter_mut().enumerate() {
+        *felt = parse_felt_digits(&digits[i * 16..(i + 1) * 16])?;
+    }
+    Ok(Word::new(felts))
+}
+
+// Byte strings as 0x + 2 hex digits per byte
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("0x{digits}")
+}
+
+pub fn bytes_from_hex(hex: &str) -> Result<Vec<u8>, String> {
+    let digits = hex.strip_prefix("0x").ok_or_else(|| format!("'{hex}' is missing the 0x prefix"))?;
+    if digits.len() % 2 != 0 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("'{hex}' is not an even number of hex digits"));
+    }
+    (0..digits.len()).step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|err| format!("'{hex}' is not hex: {err}")))
+        .collect()
+}
+
+fn parse_felt_digits(digits: &str) -> Result<Felt, String> {
+    // from_str_radix would also take a leading +
+    if digits.is_empty() || digits.len() > 16 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("'{digits}' is not a felt"));
+    }
+    let value = u64::from_str_radix(digits, 16).map_err(|err| format!("'{digits}' is not a felt: {err}"))?;
+    if value >= Felt::MODULUS {
+        return Err(format!("{value} is not below the field modulus"));
+    }
+    Ok(Felt::new(value))
+}
+
+// serde adapters, use as #[serde(with = "crate::serde_hex::word")]
+pub mod felt {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(felt: &Felt, serializer: S) -> Result<S::Ok, S::Error> {
+        felt_to_hex(*felt).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Felt, D::Error> {
+        felt_from_hex(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+pub mod word {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(word: &Word, serializer: S) -> Result<S::Ok, S::Error> {
+        word_to_hex(*word).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Word, D::Error> {
+        word_from_hex(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+// Account ids use their own hex form (AccountId::to_hex), the same one the CLI accepts
+pub mod account_id {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(account_id: &AccountId, serializer: S) -> Result<S::Ok, S::Error> {
+        account_id.to_hex().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<AccountId, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        AccountId::from_hex(&hex).map_err(|err| serde::de::Error::custom(format!("'{hex}' is not an account id: {err}")))
+    }
+}
+
+pub mod option_account_id {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(account_id: &Option<AccountId>, serializer: S) -> Result<S::Ok, S::Error> {
+        account_id.map(|account_id| account_id.to_hex()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<AccountId>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|hex| AccountId::from_hex(&hex).map_err(|err| serde::de::Error::custom(format!("'{hex}' is not an account id: {err}"))))
+            .transpose()
+    }
+}
+
+// Miden types in their binary encoding (proofs, headers), as hex of Serializable::to_bytes
+pub mod serializable {
+    use super::*;
+    use miden_client::utils::{Deserializable, Serializable};
+
+    pub fn serialize<T: Serializable, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        bytes_to_hex(&value.to_bytes()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: Deserializable, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        let bytes = bytes_from_hex(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)?;
+        T::read_from_bytes(&bytes).map_err(|err| serde::de::Error::custom(format!("invalid encoding: {err}")))
+    }
+}
+
+// JSON helpers for the public state and report types
+pub trait JsonCodec: Serialize + DeserializeOwned {
+    fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> JsonCodec for T {}
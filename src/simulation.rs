@@ -0,0 +1,37 @@
+use miden_client::{account::AccountId, asset::FungibleAsset, transaction::ExecutedTransaction};
+use miden_testing::MockChain;
+use thiserror::Error;
+
+use crate::{compile::CompileOptions, domain::{try_encode_domain, validate_domain}, notes::{RegisterNoteInputs, create_naming_register_name_note}};
+
+#[derive(Debug, Error)]
+#[error("register simulation failed: {error}")]
+pub struct SimulationFailure {
+    // VM / executor error as reported by the transaction executor
+    pub error: String,
+    // Decoded inputs of the register note, None if the note could not be built
+    pub note_inputs: Option<RegisterNoteInputs>,
+}
+
+// Builds the register note and executes it against the naming account without submitting it.
+// Chain is not modified, so the returned transaction can be inspected (account delta, output notes). The note
+// is compiled in debug mode for source locations in the reported error.
+pub async fn simulate_register(chain: &MockChain, naming_id: AccountId, registrant: AccountId, name: &str, asset: FungibleAsset, years: u32) -> Result<ExecutedTransaction, SimulationFailure> {
+    let domain = validate_domain(name).and_then(|()| try_encode_domain(name))
+        .map_err(|err| SimulationFailure { error: err.to_string(), note_inputs: None })?;
+    let note = create_naming_register_name_note(registrant, naming_id, domain, asset, years, CompileOptions::DEBUG)
+        .await
+        .map_err(|err| SimulationFailure { error: err.to_string(), note_inputs: None })?;
+
+    let note_inputs = RegisterNoteInputs::from_note_inputs(note.recipient().inputs()).ok();
+
+    let tx_ctx = chain
+        .build_tx_context(naming_id, &[], &[note])
+        .and_then(|builder| builder.build())
+        .map_err(|err| SimulationFailure { error: err.to_string(), note_inputs: note_inputs.clone() })?;
+
+    tx_ctx
+        .execute()
+        .await
+        .map_err(|err| SimulationFailure { error: err.to_string(), note_inputs })
+}
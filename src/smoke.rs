@@ -0,0 +1,164 @@
+use miden_client::{account::AccountId, transaction::TransactionId};
+use serde::Serialize;
+use std::{fs, path::Path, time::Instant};
+use tokio::time::Duration;
+
+use crate::{registry::RegistryClient, transaction::{consume_notes, poll_until, wait_for_tx}};
+
+// Steps of the release smoke test, run in this order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmokeStep {
+    // Resolution of a name that already exists on the network
+    ResolveKnown,
+    Register,
+    Activate,
+    // name -> account and account -> name
+    Lookup,
+    Transfer,
+    Release,
+}
+
+impl SmokeStep {
+    pub const ALL: [SmokeStep; 6] = [
+        SmokeStep::ResolveKnown,
+        SmokeStep::Register,
+        SmokeStep::Activate,
+        SmokeStep::Lookup,
+        SmokeStep::Transfer,
+        SmokeStep::Release,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Passed,
+    Failed,
+    // An earlier step failed
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StepResult {
+    pub step: SmokeStep,
+    pub status: StepStatus,
+    pub elapsed_ms: f64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SmokeReport {
+    pub naming_id: String,
+    // Throwaway name registered, transferred and released by the run
+    pub name: String,
+    pub steps: Vec<StepResult>,
+}
+
+impl SmokeReport {
+    pub fn passed(&self) -> bool {
+        self.steps.iter().all(|result| result.status == StepStatus::Passed)
+    }
+
+    fn failed(&self) -> bool {
+        self.steps.iter().any(|result| result.status == StepStatus::Failed)
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+// `primary` pays for and registers the name, `secondary` receives it and releases it. Both must be wallets
+// in the keystore. Activation replaces the name `primary` reverse resolves to, so use dedicated accounts.
+#[derive(Debug, Clone)]
+pub struct SmokeConfig {
+    pub primary: AccountId,
+    pub secondary: AccountId,
+    pub payment_token: AccountId,
+    pub known_name: Option<String>,
+}
+
+// Lookups and note consumption are retried, the naming account only sees a note after it is committed
+const RETRY_TIMEOUT: Duration = Duration::from_secs(60);
+const RETRY_DELAY: Duration = Duration::from_secs(4);
+
+// Runs every step in order, a failed step skips the rest
+pub async fn run_smoke_test(registry: &mut RegistryClient, config: &SmokeConfig, name: &str) -> SmokeReport {
+    let mut report = SmokeReport { naming_id: registry.naming_id.to_hex(), name: name.to_string(), steps: Vec::new() };
+
+    for step in SmokeStep::ALL {
+        if report.failed() {
+            report.steps.push(StepResult { step, status: StepStatus::Skipped, elapsed_ms: 0.0, error: None });
+            continue;
+        }
+        let start = Instant::now();
+        let result = run_step(registry, config, name, step).await;
+        let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+        report.steps.push(match result {
+            Ok(()) => StepResult { step, status: StepStatus::Passed, elapsed_ms, error: None },
+            Err(err) => StepResult { step, status: StepStatus::Failed, elapsed_ms, error: Some(format!("{err:#}")) },
+        });
+    }
+    report
+}
+
+async fn run_step(registry: &mut RegistryClient, config: &SmokeConfig, name: &str, step: SmokeStep) -> anyhow::Result<()> {
+    match step {
+        SmokeStep::ResolveKnown => {
+            let Some(known_name) = config.known_name.as_deref() else {
+                return Ok(());
+            };
+            registry.sync().await?;
+            anyhow::ensure!(registry.resolve(known_name).await?.is_some(), "{known_name} does not resolve");
+        }
+        SmokeStep::Register => {
+            let tx_id = registry.register_names(config.primary, &[name], config.payment_token, 1).await?;
+            settle(registry, tx_id).await?;
+            await_domain_owner(registry, name, Some(config.primary)).await?;
+        }
+        SmokeStep::Activate => {
+            let tx_id = registry.activate(config.primary, name).await?;
+            settle(registry, tx_id).await?;
+        }
+        SmokeStep::Lookup => {
+            poll_until(&format!("{name} to resolve to {}", config.primary.to_hex()), RETRY_TIMEOUT, RETRY_DELAY, async || {
+                registry.sync().await?;
+                Ok((registry.resolve(name).await? == Some(config.primary)).then_some(()))
+            }).await?;
+            let reverse = registry.reverse_resolve(config.primary).await?;
+            anyhow::ensure!(reverse.as_deref() == Some(name), "{} reverse resolves to {reverse:?}", config.primary.to_hex());
+        }
+        SmokeStep::Transfer => {
+            let tx_id = registry.transfer_domain_to_address(config.primary, name, &config.secondary.to_hex()).await?;
+            settle(registry, tx_id).await?;
+            await_domain_owner(registry, name, Some(config.secondary)).await?;
+            registry.verify_transfer(name, config.primary, config.secondary).await?;
+        }
+        SmokeStep::Release => {
+            let tx_id = registry.deregister(config.secondary, name).await?;
+            settle(registry, tx_id).await?;
+            await_domain_owner(registry, name, None).await?;
+        }
+    }
+    Ok(())
+}
+
+// Waits for the submitted transaction, then lets the naming account consume its note
+async fn settle(registry: &mut RegistryClient, tx_id: TransactionId) -> anyhow::Result<()> {
+    wait_for_tx(&mut registry.client, tx_id).await?;
+    poll_until(&format!("the naming account to receive the note of transaction {}", tx_id.to_hex()), RETRY_TIMEOUT, RETRY_DELAY, async || {
+        registry.sync().await?;
+        Ok((consume_notes(&mut registry.client, registry.naming_id, None).await? > 0).then_some(()))
+    }).await?;
+    Ok(())
+}
+
+async fn await_domain_owner(registry: &mut RegistryClient, name: &str, expected: Option<AccountId>) -> anyhow::Result<()> {
+    poll_until(&format!("{name} to be owned by {:?}", expected.map(|id| id.to_hex())), RETRY_TIMEOUT, RETRY_DELAY, async || {
+        registry.sync().await?;
+        Ok((registry.domain_owner(name).await? == expected).then_some(()))
+    }).await?;
+    Ok(())
+}
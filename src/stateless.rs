@@ -0,0 +1,101 @@
+use miden_client::{ClientError, account::AccountId, rpc::NodeRpcClient};
+use miden_crypto::{Word, merkle::SmtProof};
+use miden_objects::account::{AccountHeader, AccountStorageHeader, StorageMap, StorageSlotType};
+use serde::{Deserialize, Serialize};
+use std::{collections::{BTreeMap, BTreeSet}, future::Future, sync::Arc};
+
+use crate::{errors::{RegistryError, Result}, queries::{Lookup, word_to_account_id}, storage::DOMAIN_TO_ACCOUNT_ID_SLOT, transaction::foreign_account_for};
+
+// Lookups without a local store: one map entry of a public account is fetched from the node together with
+// the proofs tying it to a block header, so light tools don't have to import and sync the registry
+
+// Map entry of a public account as returned by the node, with everything needed to check it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageMapWitness {
+    // Block the account state was read at
+    pub block_num: u32,
+    // Account root of that block's header
+    #[serde(with = "crate::serde_hex::word")]
+    pub account_root: Word,
+    // Opening of the account in the account tree
+    #[serde(with = "crate::serde_hex::serializable")]
+    pub account_proof: SmtProof,
+    #[serde(with = "crate::serde_hex::serializable")]
+    pub account_header: AccountHeader,
+    #[serde(with = "crate::serde_hex::serializable")]
+    pub storage_header: AccountStorageHeader,
+    // Opening of the key in the map slot
+    #[serde(with = "crate::serde_hex::serializable")]
+    pub map_proof: SmtProof,
+}
+
+// Where witnesses come from, the node RPC or a mock in tests
+pub trait AccountStateSource {
+    fn storage_map_witness(&mut self, account_id: AccountId, slot: u8, key: Word) -> impl Future<Output = Result<StorageMapWitness>>;
+}
+
+impl AccountStateSource for Arc<dyn NodeRpcClient> {
+    async fn storage_map_witness(&mut self, account_id: AccountId, slot: u8, key: Word) -> Result<StorageMapWitness> {
+        let invalid = |reason| RegistryError::InvalidStorageProof { account_id: account_id.to_hex(), reason };
+        let request = BTreeSet::from([foreign_account_for(account_id, &[(slot, key)])?]);
+        let (block_num, proofs) = self.get_account_proofs(&request, BTreeMap::new()).await.map_err(ClientError::from)?;
+        let (witness, headers) = proofs.into_iter().next().ok_or_else(|| invalid("node returned no account proof"))?.into_parts();
+        let headers = headers.ok_or_else(|| invalid("account is not public"))?;
+        let map_proof = headers.storage_slots.get(&slot).and_then(|proofs| proofs.first()).cloned()
+            .ok_or_else(|| invalid("node returned no map proof"))?;
+        let (block_header, _) = self.get_block_header_by_number(Some(block_num), false).await.map_err(ClientError::from)?;
+
+        Ok(StorageMapWitness {
+            block_num: block_num.as_u32(),
+            account_root: block_header.account_root(),
+            account_proof: witness.into_proof(),
+            account_header: headers.account_header,
+            storage_header: headers.storage_header,
+            map_proof,
+        })
+    }
+}
+
+// Checks the chain block account root -> account header -> storage header -> map root -> entry and returns
+// the value stored under `key`, the empty word when the map doesn't have it
+pub fn verify_storage_map_witness(account_id: AccountId, witness: &StorageMapWitness, slot: u8, key: Word) -> std::result::Result<Word, RegistryError> {
+    let invalid = |reason| RegistryError::InvalidStorageProof { account_id: account_id.to_hex(), reason };
+
+    if witness.account_header.id() != account_id {
+        return Err(invalid("proof is for another account"));
+    }
+    let account_commitment = witness.account_header.commitment();
+    if witness.account_proof.compute_root() != witness.account_root
+        || !witness.account_proof.leaf().entries().iter().any(|(_, value)| *value == account_commitment) {
+        return Err(invalid("account header is not in the block's account tree"));
+    }
+    if witness.storage_header.compute_commitment() != witness.account_header.storage_commitment() {
+        return Err(invalid("storage header does not match the account header"));
+    }
+    let map_root = match witness.storage_header.slot(slot as usize) {
+        Ok((StorageSlotType::Map, root)) => *root,
+        Ok(_) => return Err(RegistryError::StorageSlotNotMap { slot }),
+        Err(_) => return Err(RegistryError::StorageSlotMissing { slot }),
+    };
+    if witness.map_proof.compute_root() != map_root {
+        return Err(invalid("map proof does not match the slot root"));
+    }
+    witness.map_proof.get(&StorageMap::hash_key(key)).ok_or_else(|| invalid("map proof is for another key"))
+}
+
+// Same result as queries::get_account_for_name on a synced store: plain key first, then the hashed one
+pub async fn resolve_via_rpc(source: &mut impl AccountStateSource, naming_id: AccountId, name: &str) -> Result<Option<AccountId>> {
+    Ok(prove_resolution(source, naming_id, name).await?.map(|(_, account_id, _)| account_id))
+}
+
+// Resolution together with the key it was found under and the checked witness of the entry
+pub async fn prove_resolution(source: &mut impl AccountStateSource, naming_id: AccountId, name: &str) -> Result<Option<(Lookup, AccountId, StorageMapWitness)>> {
+    for lookup in [Lookup::Plain, Lookup::Hashed] {
+        let key = lookup.key(name)?;
+        let witness = source.storage_map_witness(naming_id, DOMAIN_TO_ACCOUNT_ID_SLOT, key).await?;
+        if let Some(account_id) = word_to_account_id(verify_storage_map_witness(naming_id, &witness, DOMAIN_TO_ACCOUNT_ID_SLOT, key)?)? {
+            return Ok(Some((lookup, account_id, witness)));
+        }
+    }
+    Ok(None)
+}
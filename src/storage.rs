@@ -1,31 +1,123 @@
 use miden_client::account::{StorageMap, StorageSlot};
-use miden_crypto::{Felt, Word};
-
-fn empty_storage_value() -> StorageSlot {
-    StorageSlot::Value(Word::new([
-        Felt::new(0),
-        Felt::new(0),
-        Felt::new(0),
-        Felt::new(0),
-    ]))
+use miden_crypto::Word;
+
+// Naming contract storage slots, must match naming.masm
+pub const INIT_FLAG_SLOT: u8 = 0;
+pub const OWNER_SLOT: u8 = 1;
+pub const PRICES_SLOT: u8 = 2;
+pub const ACCOUNT_ID_TO_DOMAIN_SLOT: u8 = 3;
+pub const DOMAIN_TO_ACCOUNT_ID_SLOT: u8 = 4;
+pub const DOMAIN_TO_OWNER_SLOT: u8 = 5;
+pub const REF_RATE_SLOT: u8 = 6;
+pub const REF_TOTAL_REVENUE_SLOT: u8 = 7;
+pub const REF_CLAIMED_REVENUE_SLOT: u8 = 8;
+pub const DOMAIN_COUNT_SLOT: u8 = 9;
+pub const TOTAL_REVENUE_SLOT: u8 = 10;
+pub const CLAIMED_REVENUE_SLOT: u8 = 11;
+pub const DOMAIN_EXPIRY_DATES_SLOT: u8 = 12;
+pub const ONE_YEAR_TIMESTAMP_SLOT: u8 = 13;
+pub const RESERVED_NAMES_SLOT: u8 = 14;
+pub const OWNER_PUBKEY_SLOT: u8 = 15;
+pub const COMMITMENTS_SLOT: u8 = 16;
+pub const COMMIT_REVEAL_DELAY_SLOT: u8 = 17;
+pub const FRONTEND_FEE_MAX_BPS_SLOT: u8 = 18;
+pub const FRONTEND_FEES_SLOT: u8 = 19;
+pub const DONATIONS_SLOT: u8 = 20;
+pub const BASE_FEE_SLOT: u8 = 21;
+pub const PRICING_MODE_SLOT: u8 = 22;
+pub const OWNER_DOMAIN_COUNT_SLOT: u8 = 23;
+pub const MAX_DOMAINS_PER_ACCOUNT_SLOT: u8 = 24;
+pub const REVENUE_TOKENS_SLOT: u8 = 25;
+pub const REVENUE_TOKEN_COUNT_SLOT: u8 = 26;
+pub const CLASS_MULTIPLIERS_SLOT: u8 = 27;
+pub const SPONSOR_APPROVALS_SLOT: u8 = 28;
+pub const DOMAIN_SPONSOR_SLOT: u8 = 29;
+pub const MULTISIG_OWNERS_SLOT: u8 = 30;
+pub const MULTISIG_OWNER_LIST_SLOT: u8 = 31;
+pub const MULTISIG_OWNER_COUNT_SLOT: u8 = 32;
+pub const MULTISIG_THRESHOLD_SLOT: u8 = 33;
+pub const ACTION_APPROVALS_SLOT: u8 = 34;
+pub const GC_GRACE_PERIOD_SLOT: u8 = 35;
+pub const GC_BOUNTY_SLOT: u8 = 36;
+pub const PAYMENT_RECORDS_SLOT: u8 = 37;
+pub const REGISTRATION_AUTHORIZATIONS_SLOT: u8 = 38;
+
+// Wallet storage, the RpoFalcon512 auth component comes first and keeps the public key commitment here
+pub const WALLET_PUBKEY_SLOT: u8 = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotKind {
+    Value,
+    Map,
+}
+
+// `name` is the slot constant in naming.masm
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotSpec {
+    pub index: u8,
+    pub name: &'static str,
+    pub kind: SlotKind,
+    pub description: &'static str,
+}
+
+pub const NAMING_STORAGE_LAYOUT: [SlotSpec; 39] = [
+    SlotSpec { index: INIT_FLAG_SLOT, name: "INIT_FLAG_SLOT", kind: SlotKind::Value, description: "init flag" },
+    SlotSpec { index: OWNER_SLOT, name: "OWNER_SLOT", kind: SlotKind::Value, description: "registry owner" },
+    SlotSpec { index: PRICES_SLOT, name: "PRICES_SLOT", kind: SlotKind::Map, description: "[token, letter_count] -> price" },
+    SlotSpec { index: ACCOUNT_ID_TO_DOMAIN_SLOT, name: "ACCOUNT_ID_TO_DOMAIN_SLOT", kind: SlotKind::Map, description: "account to domain" },
+    SlotSpec { index: DOMAIN_TO_ACCOUNT_ID_SLOT, name: "DOMAIN_TO_ACCOUNT_ID_SLOT", kind: SlotKind::Map, description: "domain to account" },
+    SlotSpec { index: DOMAIN_TO_OWNER_SLOT, name: "DOMAIN_TO_OWNER_SLOT", kind: SlotKind::Map, description: "domain to owner" },
+    SlotSpec { index: REF_RATE_SLOT, name: "REF_RATE_SLOT", kind: SlotKind::Map, description: "referrer rates" },
+    SlotSpec { index: REF_TOTAL_REVENUE_SLOT, name: "REF_TOTAL_REVENUE_SLOT", kind: SlotKind::Map, description: "referrer total revenue" },
+    SlotSpec { index: REF_CLAIMED_REVENUE_SLOT, name: "REF_CLAIMED_REVENUE_SLOT", kind: SlotKind::Map, description: "referrer claimed revenue" },
+    SlotSpec { index: DOMAIN_COUNT_SLOT, name: "DOMAIN_COUNT_SLOT", kind: SlotKind::Value, description: "domain count" },
+    SlotSpec { index: TOTAL_REVENUE_SLOT, name: "TOTAL_REVENUE_SLOT", kind: SlotKind::Map, description: "protocol total revenue" },
+    SlotSpec { index: CLAIMED_REVENUE_SLOT, name: "CLAIMED_REVENUE_SLOT", kind: SlotKind::Map, description: "protocol claimed revenue" },
+    SlotSpec { index: DOMAIN_EXPIRY_DATES_SLOT, name: "DOMAIN_EXPIRY_DATES", kind: SlotKind::Map, description: "domain expiry dates" },
+    SlotSpec { index: ONE_YEAR_TIMESTAMP_SLOT, name: "ONE_YEAR_TIMESTAMP_SLOT", kind: SlotKind::Value, description: "seconds in one year" },
+    SlotSpec { index: RESERVED_NAMES_SLOT, name: "RESERVED_NAMES_SLOT", kind: SlotKind::Map, description: "reserved names" },
+    SlotSpec { index: OWNER_PUBKEY_SLOT, name: "OWNER_PUBKEY_SLOT", kind: SlotKind::Value, description: "owner public key, signature mode when set" },
+    SlotSpec { index: COMMITMENTS_SLOT, name: "COMMITMENTS_SLOT", kind: SlotKind::Map, description: "registration commitment -> commit block + 1" },
+    SlotSpec { index: COMMIT_REVEAL_DELAY_SLOT, name: "COMMIT_REVEAL_DELAY_SLOT", kind: SlotKind::Value, description: "commit-reveal delay in blocks" },
+    SlotSpec { index: FRONTEND_FEE_MAX_BPS_SLOT, name: "FRONTEND_FEE_MAX_BPS_SLOT", kind: SlotKind::Value, description: "frontend fee max in basis points" },
+    SlotSpec { index: FRONTEND_FEES_SLOT, name: "FRONTEND_FEES_SLOT", kind: SlotKind::Map, description: "[recipient, token] -> claimable frontend fees" },
+    SlotSpec { index: DONATIONS_SLOT, name: "DONATIONS_SLOT", kind: SlotKind::Map, description: "token -> total donations" },
+    SlotSpec { index: BASE_FEE_SLOT, name: "BASE_FEE_SLOT", kind: SlotKind::Map, description: "token -> flat fee added to every registration" },
+    SlotSpec { index: PRICING_MODE_SLOT, name: "PRICING_MODE_SLOT", kind: SlotKind::Value, description: "pricing mode, 0 discount curve, 1 linear" },
+    SlotSpec { index: OWNER_DOMAIN_COUNT_SLOT, name: "OWNER_DOMAIN_COUNT_SLOT", kind: SlotKind::Map, description: "owner -> domains owned" },
+    SlotSpec { index: MAX_DOMAINS_PER_ACCOUNT_SLOT, name: "MAX_DOMAINS_PER_ACCOUNT_SLOT", kind: SlotKind::Value, description: "max domains per owner, zero is no cap" },
+    SlotSpec { index: REVENUE_TOKENS_SLOT, name: "REVENUE_TOKENS_SLOT", kind: SlotKind::Map, description: "index -> token with protocol revenue" },
+    SlotSpec { index: REVENUE_TOKEN_COUNT_SLOT, name: "REVENUE_TOKEN_COUNT_SLOT", kind: SlotKind::Value, description: "number of revenue tokens" },
+    SlotSpec { index: CLASS_MULTIPLIERS_SLOT, name: "CLASS_MULTIPLIERS_SLOT", kind: SlotKind::Map, description: "name class -> yearly price multiplier in bps" },
+    SlotSpec { index: SPONSOR_APPROVALS_SLOT, name: "SPONSOR_APPROVALS_SLOT", kind: SlotKind::Map, description: "beneficiary -> approved sponsor" },
+    SlotSpec { index: DOMAIN_SPONSOR_SLOT, name: "DOMAIN_SPONSOR_SLOT", kind: SlotKind::Map, description: "domain -> sponsor that paid its registration" },
+    SlotSpec { index: MULTISIG_OWNERS_SLOT, name: "MULTISIG_OWNERS_SLOT", kind: SlotKind::Map, description: "multisig owner -> flag" },
+    SlotSpec { index: MULTISIG_OWNER_LIST_SLOT, name: "MULTISIG_OWNER_LIST_SLOT", kind: SlotKind::Map, description: "index -> multisig owner" },
+    SlotSpec { index: MULTISIG_OWNER_COUNT_SLOT, name: "MULTISIG_OWNER_COUNT_SLOT", kind: SlotKind::Value, description: "number of multisig owners" },
+    SlotSpec { index: MULTISIG_THRESHOLD_SLOT, name: "MULTISIG_THRESHOLD_SLOT", kind: SlotKind::Value, description: "approvals needed by owner notes, zero is single owner" },
+    SlotSpec { index: ACTION_APPROVALS_SLOT, name: "ACTION_APPROVALS_SLOT", kind: SlotKind::Map, description: "hash(owner, action) -> approval flag" },
+    SlotSpec { index: GC_GRACE_PERIOD_SLOT, name: "GC_GRACE_PERIOD_SLOT", kind: SlotKind::Value, description: "seconds after expiry before a sweep may clear a domain" },
+    SlotSpec { index: GC_BOUNTY_SLOT, name: "GC_BOUNTY_SLOT", kind: SlotKind::Value, description: "asset paid per swept domain" },
+    SlotSpec { index: PAYMENT_RECORDS_SLOT, name: "PAYMENT_RECORDS_SLOT", kind: SlotKind::Map, description: "domain -> [amount, token, years] charged at registration" },
+    SlotSpec { index: REGISTRATION_AUTHORIZATIONS_SLOT, name: "REGISTRATION_AUTHORIZATIONS_SLOT", kind: SlotKind::Map, description: "hash(beneficiary, name_hash) -> authorization flag" },
+];
+
+impl SlotKind {
+    pub fn empty_slot(&self) -> StorageSlot {
+        match self {
+            SlotKind::Value => StorageSlot::Value(Word::default()),
+            SlotKind::Map => StorageSlot::Map(StorageMap::new()),
+        }
+    }
+
+    pub fn of(slot: &StorageSlot) -> Self {
+        match slot {
+            StorageSlot::Value(_) => SlotKind::Value,
+            StorageSlot::Map(_) => SlotKind::Map,
+        }
+    }
 }
 
 pub fn naming_storage() -> Vec<StorageSlot> {
-    let storage_slots: Vec<StorageSlot> = vec![
-        empty_storage_value(), // Init flag
-        empty_storage_value(), // owner
-        StorageSlot::Map(StorageMap::new()), // payment token -> price contract
-        StorageSlot::Map(StorageMap::new()), // account to domain
-        StorageSlot::Map(StorageMap::new()), // domain to account
-        StorageSlot::Map(StorageMap::new()), // domain to owner
-        StorageSlot::Map(StorageMap::new()), // calculate price root
-        StorageSlot::Map(StorageMap::new()),
-        StorageSlot::Map(StorageMap::new()),
-        empty_storage_value(),
-        StorageSlot::Map(StorageMap::new()),
-        StorageSlot::Map(StorageMap::new()),
-        StorageSlot::Map(StorageMap::new()),
-        empty_storage_value(), // ONE YEAR TIMESTAMP
-        ];
-    return storage_slots;
-}
\ No newline at end of file
+    NAMING_STORAGE_LAYOUT.iter().map(|spec| spec.kind.empty_slot()).collect()
+}
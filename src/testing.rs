@@ -0,0 +1,165 @@
+use std::{fs, path::Path};
+
+use miden_client::{account::{Account, AccountBuilder, AccountId, AccountStorageMode, StorageMap, StorageSlot}, asset::FungibleAsset, auth::NoAuth, note::{Note, NoteAssets, NoteId, NoteInputs}, transaction::{ExecutedTransaction, OutputNote}};
+use miden_crypto::{Felt, Word};
+use miden_objects::{account::AccountComponent, transaction::AccountInputs};
+use miden_testing::{Auth, MockChain, MockChainBuilder};
+
+use crate::{accounts::validate_token_symbol, compile::CompileOptions, notes::{create_naming_register_name_note, create_note_for_naming_with_serial_num}, storage::naming_storage};
+
+// MockChain fixtures for tests of the registry, here and in downstream crates. Like the rest of the
+// crate they read masm/ relative to the working directory.
+
+pub use miden_client::testing::account_id::{ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2};
+
+// Yearly prices of lengths 1-5 written by the set_all_prices note, longer names cost the 5 letter price
+pub const TEST_PRICES: [u64; 5] = [123123, 45645, 789, 555, 123];
+
+// Naming account compiled in debug mode, not tracked by any chain yet
+pub fn create_test_naming_account() -> Account {
+    create_test_naming_account_with_entries(&[]).unwrap()
+}
+
+// Same account with raw (slot, key, value) map entries in its initial storage, for states the contract
+// should never reach (audit tests)
+pub fn create_test_naming_account_with_entries(entries: &[(u8, Word, Word)]) -> anyhow::Result<Account> {
+    let mut storage = naming_storage();
+    if let Some((slot, _, _)) = entries.iter().find(|(slot, _, _)| *slot as usize >= storage.len()) {
+        anyhow::bail!("storage slot {} does not exist", slot);
+    }
+    for (index, slot) in storage.iter_mut().enumerate() {
+        let slot_entries: Vec<(Word, Word)> = entries.iter()
+            .filter(|(entry_slot, _, _)| *entry_slot as usize == index)
+            .map(|(_, key, value)| (*key, *value))
+            .collect();
+        if !slot_entries.is_empty() {
+            anyhow::ensure!(matches!(slot, StorageSlot::Map(_)), "storage slot {} is not a map", index);
+            *slot = StorageSlot::Map(StorageMap::with_entries(slot_entries)?);
+        }
+    }
+
+    let code = fs::read_to_string(Path::new("./masm/accounts/naming.masm"))?;
+    let component = AccountComponent::compile(
+        code,
+        CompileOptions::DEBUG.assembler(),
+        storage
+    )?.with_supports_all_types();
+
+    Ok(AccountBuilder::new(rand::random())
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .storage_mode(AccountStorageMode::Public)
+        .build_existing()?)
+}
+
+// Basic fungible faucet in the genesis block, for tests paying with a token other than the mock faucets
+pub fn create_test_faucet(builder: &mut MockChainBuilder, symbol: &str, max_supply: u64) -> anyhow::Result<Account> {
+    validate_token_symbol(symbol)?;
+    builder.add_existing_basic_faucet(Auth::BasicAuth, symbol, max_supply, None)
+}
+
+// Accounts and setup notes added to the builder by init_naming. The setup notes are in the genesis block
+// but not consumed yet, pass their ids to execute_notes_and_build_chain.
+pub struct NamingFixture {
+    pub builder: MockChainBuilder,
+    pub owner: Account,
+    pub registrar_1: Account,
+    pub registrar_2: Account,
+    pub registrar_3: Account,
+    pub naming: Account,
+    // 100000 of ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1, held by registrar_1
+    pub fungible_asset: FungibleAsset,
+    pub one_year: u32,
+    pub initialize_note: Note,
+    // Sets TEST_PRICES in fungible_asset
+    pub set_prices_note: Note,
+}
+
+pub async fn init_naming() -> anyhow::Result<NamingFixture> {
+    let mut builder = MockChain::builder();
+    let faucet_id = ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1.try_into()?;
+    let fungible_asset_1 = FungibleAsset::new(faucet_id, 100000)?;
+    let fungible_asset_2 = FungibleAsset::new(faucet_id, 50000)?;
+    let fungible_asset_3 = FungibleAsset::new(faucet_id, 20000)?;
+
+    let owner = builder.add_existing_wallet(Auth::BasicAuth)?;
+    let registrar_1 = builder.add_existing_wallet_with_assets(Auth::BasicAuth, vec![fungible_asset_1.into()])?;
+    let registrar_2 = builder.add_existing_wallet_with_assets(Auth::BasicAuth, vec![fungible_asset_2.into()])?;
+    let registrar_3 = builder.add_existing_wallet_with_assets(Auth::BasicAuth, vec![fungible_asset_3.into()])?;
+    let naming = create_test_naming_account();
+    builder.add_account(naming.clone())?;
+    let one_year: u32 = 500;
+
+    let initialize_inputs = NoteInputs::new(vec![
+        owner.id().suffix(),
+        owner.id().prefix().as_felt(),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(one_year.into()),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+    ])?;
+    let initialize_note = create_note_for_naming_with_serial_num("initialize_naming".to_string(), initialize_inputs, owner.id(), naming.id(), NoteAssets::new(vec![])?, Word::default(), CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut builder, initialize_note.clone())?;
+
+    let set_prices_inputs = NoteInputs::new(vec![fungible_asset_1.faucet_id().suffix(), fungible_asset_1.faucet_id().prefix().as_felt()])?;
+    let set_prices_note = create_note_for_naming_with_serial_num("set_all_prices".to_string(), set_prices_inputs, owner.id(), naming.id(), NoteAssets::new(vec![])?, Word::default(), CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut builder, set_prices_note.clone())?;
+
+    Ok(NamingFixture { builder, owner, registrar_1, registrar_2, registrar_3, naming, fungible_asset: fungible_asset_1, one_year, initialize_note, set_prices_note })
+}
+
+// Foreign account inputs (account, code and the witness against the latest block) for FPI calls into
+// `account_id`, pass them to build_tx_context(..).foreign_accounts(..). Only public accounts can be read.
+pub fn foreign_inputs_for(chain: &MockChain, account_id: AccountId) -> anyhow::Result<AccountInputs> {
+    anyhow::ensure!(account_id.is_public(), "account {} is not public, FPI can't read it", account_id);
+    chain.get_foreign_account_inputs(account_id)
+}
+
+pub fn add_note_to_builder(builder: &mut MockChainBuilder, note: Note) -> anyhow::Result<()> {
+    builder.add_output_note(OutputNote::Full(note));
+    Ok(())
+}
+
+pub async fn execute_notes_and_build_chain(builder: MockChainBuilder, note_ids: &[NoteId], target: &mut Account) -> anyhow::Result<MockChain> {
+    let mut chain = builder.build()?;
+
+    for note_id in note_ids {
+        execute_note(&mut chain, *note_id, target).await?;
+    }
+    Ok(chain)
+}
+
+// Target is updated in place, so the caller's account always matches the committed state.
+// Transaction contexts are built from the chain by id, never from a (possibly stale) Account clone.
+pub async fn execute_note(chain: &mut MockChain, note_id: NoteId, target: &mut Account) -> anyhow::Result<()> {
+    let tx_ctx = chain.build_tx_context(target.id(), &[note_id], &[])?.build()?;
+
+    let executed_tx = tx_ctx.execute().await?;
+    commit_executed(chain, &executed_tx, target)
+}
+
+// Registration from an account that is not a wallet (e.g. a multisig), given its prebuilt inputs from
+// foreign_inputs_for. The registrant doesn't run a transaction, its register note is consumed by the naming
+// account as an unauthenticated note, so no wallet interface or signature is needed.
+pub async fn register_from_custom_account(chain: &mut MockChain, naming: &mut Account, registrant: &AccountInputs, domain: Word, payment: FungibleAsset, years: u32) -> anyhow::Result<Note> {
+    let registrant_id = registrant.id();
+    anyhow::ensure!(chain.committed_account(registrant_id).is_ok(), "registrant {} is not on the chain", registrant_id);
+
+    let note = create_naming_register_name_note(registrant_id, naming.id(), domain, payment, years, CompileOptions::DEBUG).await?;
+    let tx_ctx = chain.build_tx_context(naming.id(), &[], &[note.clone()])?.build()?;
+    let executed_tx = tx_ctx.execute().await?;
+    commit_executed(chain, &executed_tx, naming)?;
+    Ok(note)
+}
+
+fn commit_executed(chain: &mut MockChain, executed_tx: &ExecutedTransaction, target: &mut Account) -> anyhow::Result<()> {
+    target.apply_delta(executed_tx.account_delta())?;
+    chain.add_pending_executed_transaction(executed_tx)?;
+    chain.prove_next_block()?;
+
+    let committed = chain.committed_account(target.id())?;
+    anyhow::ensure!(committed.commitment() == target.commitment(), "local account {} diverged from the committed state", target.id());
+    Ok(())
+}
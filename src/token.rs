@@ -0,0 +1,162 @@
+use miden_client::{Client, account::{Account, AccountId}, keystore::FilesystemKeyStore};
+use miden_lib::account::faucets::BasicFungibleFaucet;
+use rand::rngs::StdRng;
+use serde::Serialize;
+use std::fmt;
+
+use crate::errors::{RegistryError, Result};
+
+// Largest power of ten that fits in u64
+pub const MAX_DECIMALS: u8 = 19;
+
+// Amount in token base units together with the faucet decimals, so it can be shown as e.g. 1.5 REG
+// instead of 150000000
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TokenAmount {
+    base_units: u64,
+    decimals: u8,
+}
+
+impl TokenAmount {
+    pub fn from_base_units(base_units: u64, decimals: u8) -> Self {
+        Self { base_units, decimals }
+    }
+
+    // Parses "1", "1.5" or ".5". Extra fraction digits are only accepted when they are zeros, anything
+    // else would be silently rounded away.
+    pub fn from_display(amount: &str, decimals: u8) -> std::result::Result<Self, RegistryError> {
+        let invalid = |reason| RegistryError::InvalidAmount { amount: amount.to_string(), reason };
+        if decimals > MAX_DECIMALS {
+            return Err(invalid("faucet has more decimals than fit in u64"));
+        }
+
+        let (whole, fraction) = amount.trim().split_once('.').unwrap_or((amount.trim(), ""));
+        let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+            return Err(invalid("expected a decimal number like 1.5"));
+        }
+
+        let significant = fraction.trim_end_matches('0');
+        if significant.len() > decimals as usize {
+            return Err(invalid("more fraction digits than the token has decimals"));
+        }
+
+        let unit = 10_u64.pow(decimals.into());
+        let whole = if whole.is_empty() { 0 } else { whole.parse::<u64>().map_err(|_| invalid("amount does not fit in u64"))? };
+        let fraction = format!("{significant:0<width$}", width = decimals as usize);
+        let fraction = if fraction.is_empty() { 0 } else { fraction.parse::<u64>().map_err(|_| invalid("amount does not fit in u64"))? };
+
+        let base_units = whole.checked_mul(unit)
+            .and_then(|units| units.checked_add(fraction))
+            .ok_or_else(|| invalid("amount does not fit in u64"))?;
+        Ok(Self { base_units, decimals })
+    }
+
+    pub fn base_units(&self) -> u64 {
+        self.base_units
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    // Shortest decimal form, trailing zeros of the fraction are dropped
+    pub fn to_display(&self) -> String {
+        if self.decimals == 0 {
+            return self.base_units.to_string();
+        }
+        let unit = 10_u64.pow(self.decimals.into());
+        let fraction = format!("{:0width$}", self.base_units % unit, width = self.decimals as usize);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            (self.base_units / unit).to_string()
+        } else {
+            format!("{}.{}", self.base_units / unit, fraction)
+        }
+    }
+}
+
+impl fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_display())
+    }
+}
+
+// Token metadata of a basic fungible faucet, fixed when the faucet is created
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FaucetMetadata {
+    pub symbol: String,
+    pub decimals: u8,
+    pub max_supply: u64,
+}
+
+impl FaucetMetadata {
+    pub fn amount(&self, base_units: u64) -> TokenAmount {
+        TokenAmount::from_base_units(base_units, self.decimals)
+    }
+
+    // e.g. "1.5 REG"
+    pub fn format_amount(&self, base_units: u64) -> String {
+        format!("{} {}", self.amount(base_units), self.symbol)
+    }
+}
+
+// Reads the metadata slot of the miden-lib basic fungible faucet component
+pub fn faucet_metadata(faucet: &Account) -> std::result::Result<FaucetMetadata, RegistryError> {
+    let not_a_faucet = || RegistryError::NotAFungibleFaucet(faucet.id().to_hex());
+    let basic = BasicFungibleFaucet::try_from(faucet.clone()).map_err(|_| not_a_faucet())?;
+    let symbol = basic.symbol().to_string().map_err(|_| not_a_faucet())?;
+    Ok(FaucetMetadata { symbol, decimals: basic.decimals(), max_supply: basic.max_supply().as_int() })
+}
+
+// Imports the faucet first when the client doesn't track it
+pub async fn get_faucet_metadata(client: &mut Client<FilesystemKeyStore<StdRng>>, faucet_id: AccountId) -> Result<FaucetMetadata> {
+    if client.get_account(faucet_id).await?.is_none() {
+        client.import_account_by_id(faucet_id).await?;
+    }
+    let record = client.get_account(faucet_id).await?
+        .ok_or_else(|| RegistryError::AccountNotTracked(faucet_id.to_hex()))?;
+    Ok(faucet_metadata(record.account())?)
+}
+
+// Symbol next to the faucet ID, just the ID for tokens without metadata
+pub fn token_label(metadata: Option<&FaucetMetadata>, faucet_id: AccountId) -> String {
+    match metadata {
+        Some(metadata) => format!("{} ({})", metadata.symbol, faucet_id.to_hex()),
+        None => faucet_id.to_hex(),
+    }
+}
+
+// Base units for tokens without metadata
+pub fn format_token_amount(metadata: Option<&FaucetMetadata>, base_units: u64) -> String {
+    match metadata {
+        Some(metadata) => metadata.format_amount(base_units),
+        None => base_units.to_string(),
+    }
+}
+
+// Amount as given on the command line, raw base units skip the faucet decimals lookup so they also work
+// for tokens that aren't basic fungible faucets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliAmount {
+    Raw(u64),
+    Display(String),
+}
+
+impl CliAmount {
+    pub fn parse(amount: &str, raw: bool) -> std::result::Result<Self, RegistryError> {
+        if !raw {
+            return Ok(CliAmount::Display(amount.to_string()));
+        }
+        amount.trim().parse::<u64>()
+            .map(CliAmount::Raw)
+            .map_err(|_| RegistryError::InvalidAmount { amount: amount.to_string(), reason: "expected base units" })
+    }
+
+    pub fn base_units(&self, decimals: u8) -> std::result::Result<u64, RegistryError> {
+        match self {
+            CliAmount::Raw(base_units) => Ok(*base_units),
+            CliAmount::Display(amount) => Ok(TokenAmount::from_display(amount, decimals)?.base_units()),
+        }
+    }
+}
@@ -1,53 +1,234 @@
 use miden_assembly::Library;
-use miden_client::{Client, ClientError, ScriptBuilder, keystore::FilesystemKeyStore, store::TransactionFilter, transaction::{TransactionId, TransactionScript, TransactionStatus}};
+use miden_client::{Client, ScriptBuilder, account::AccountId, keystore::FilesystemKeyStore, rpc::domain::account::AccountStorageRequirements, store::TransactionFilter, transaction::{ForeignAccount, TransactionId, TransactionRequestBuilder, TransactionScript, TransactionStatus}};
+use miden_crypto::Word;
+use std::{collections::BTreeMap, future::Future};
 use rand::rngs::StdRng;
-use tokio::time::{sleep, Duration};
+use tokio::time::{Duration, Instant, sleep};
+
+use crate::{compile::CompileOptions, errors::{MidenIdError, RegistryError, Result}, transcript::TranscriptRecorder};
+
+// Long enough for a few blocks plus a slow sync
+pub const DEFAULT_TX_TIMEOUT: Duration = Duration::from_secs(300);
+const TX_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 pub async fn wait_for_tx(
     client: &mut Client<FilesystemKeyStore<StdRng>>,
     tx_id: TransactionId,
-) -> Result<(), ClientError> {
+) -> Result<()> {
+    poll_tx_status(client, tx_id, DEFAULT_TX_TIMEOUT, TX_POLL_INTERVAL).await
+}
+
+// Where wait_for_tx reads transaction statuses, the client store in practice
+pub trait TransactionStatusSource {
+    // None is a transaction the source doesn't know yet
+    fn transaction_status(&mut self, tx_id: TransactionId) -> impl Future<Output = Result<Option<TransactionStatus>>>;
+}
+
+impl TransactionStatusSource for Client<FilesystemKeyStore<StdRng>> {
+    async fn transaction_status(&mut self, tx_id: TransactionId) -> Result<Option<TransactionStatus>> {
+        SyncSource::sync(self).await?;
+        self.stored_transaction_status(tx_id).await
+    }
+}
+
+// A store whose sync is separate from reading what it holds, so several transactions can be checked
+// after one sync
+pub trait SyncSource {
+    // Returns the synced block height
+    fn sync(&mut self) -> impl Future<Output = Result<u32>>;
+    // Status as of the last sync, None for a transaction the store doesn't know
+    fn stored_transaction_status(&mut self, tx_id: TransactionId) -> impl Future<Output = Result<Option<TransactionStatus>>>;
+}
+
+impl SyncSource for Client<FilesystemKeyStore<StdRng>> {
+    async fn sync(&mut self) -> Result<u32> {
+        Ok(self.sync_state().await?.block_num.as_u32())
+    }
+
+    async fn stored_transaction_status(&mut self, tx_id: TransactionId) -> Result<Option<TransactionStatus>> {
+        let txs = self.get_transactions(TransactionFilter::Ids(vec![tx_id])).await?;
+        Ok(txs.into_iter().next().map(|tx| tx.status))
+    }
+}
+
+// True once committed, TransactionRejected once discarded
+fn is_committed(tx_id: TransactionId, status: Option<TransactionStatus>) -> Result<bool> {
+    match status {
+        Some(TransactionStatus::Committed { .. }) => {
+            tracing::info!(tx_id = %tx_id.to_hex(), "transaction committed");
+            Ok(true)
+        }
+        Some(TransactionStatus::Discarded(cause)) => {
+            tracing::warn!(tx_id = %tx_id.to_hex(), ?cause, "transaction discarded");
+            Err(RegistryError::TransactionRejected { tx_id: tx_id.to_hex(), reason: format!("{cause:?}") }.into())
+        }
+        _ => Ok(false),
+    }
+}
+
+// Fails with TransactionRejected as soon as the transaction is discarded, and with TransactionTimeout
+// when it is still pending after `timeout`. Unknown transactions count as pending.
+pub async fn poll_tx_status(source: &mut impl TransactionStatusSource, tx_id: TransactionId, timeout: Duration, interval: Duration) -> Result<()> {
+    let what = format!("transaction {}", tx_id.to_hex());
+    poll_until(&what, timeout, interval, async || {
+        Ok(is_committed(tx_id, source.transaction_status(tx_id).await?)?.then_some(()))
+    }).await
+        .map_err(|err| as_transaction_timeout(err, tx_id, timeout))
+}
+
+// Transaction waits keep failing with TransactionTimeout, the code callers already match on
+fn as_transaction_timeout(err: MidenIdError, tx_id: TransactionId, timeout: Duration) -> MidenIdError {
+    match err {
+        MidenIdError::Registry(RegistryError::PollTimeout { .. }) => RegistryError::TransactionTimeout { tx_id: tx_id.to_hex(), waited_secs: timeout.as_secs() }.into(),
+        err => err,
+    }
+}
+
+// Checks `condition` every `interval` until it returns a value. Fails with PollTimeout naming `what` once
+// `timeout` would pass before the next check, so no wait for a dropped transaction or note runs forever.
+pub async fn poll_until<T>(what: &str, timeout: Duration, interval: Duration, mut condition: impl AsyncFnMut() -> Result<Option<T>>) -> Result<T> {
+    let deadline = Instant::now() + timeout;
     loop {
-        client.sync_state().await?;
-
-        // Check transaction status
-        let txs = client
-            .get_transactions(TransactionFilter::Ids(vec![tx_id]))
-            .await?;
-
-        let tx_committed = if !txs.is_empty() {
-            matches!(txs[0].status, TransactionStatus::Committed { .. })
-        } else {
-            false
-        };
-
-        if tx_committed {
-            println!("✅ transaction {} committed", tx_id.to_hex());
-            break;
+        if let Some(value) = condition().await? {
+            return Ok(value);
         }
 
-        println!(
-            "Transaction {} not yet committed. Waiting...",
-            tx_id.to_hex()
-        );
-        sleep(Duration::from_secs(2)).await;
+        if Instant::now() + interval > deadline {
+            return Err(RegistryError::PollTimeout { what: what.to_string(), waited_ms: timeout.as_millis() as u64 }.into());
+        }
+        tracing::info!(what, "not there yet, waiting");
+        sleep(interval).await;
     }
-    Ok(())
+}
+
+// Scope for several transactions that don't need each other's commitment: they are deferred instead of
+// waited for one by one, and finish syncs once per poll round until all of them are committed. Dropping
+// the scope without finish syncs nothing.
+pub struct DeferredSync<'a, S> {
+    source: &'a mut S,
+    pending: Vec<TransactionId>,
+    timeout: Duration,
+    interval: Duration,
+}
+
+impl<'a, S: SyncSource> DeferredSync<'a, S> {
+    pub fn new(source: &'a mut S) -> Self {
+        Self { source, pending: Vec::new(), timeout: DEFAULT_TX_TIMEOUT, interval: TX_POLL_INTERVAL }
+    }
+
+    pub fn with_polling(mut self, timeout: Duration, interval: Duration) -> Self {
+        self.timeout = timeout;
+        self.interval = interval;
+        self
+    }
+
+    // The client to submit through, don't sync it inside the scope
+    pub fn source(&mut self) -> &mut S {
+        self.source
+    }
+
+    pub fn defer(&mut self, tx_id: TransactionId) {
+        self.pending.push(tx_id);
+    }
+
+    pub fn pending(&self) -> &[TransactionId] {
+        &self.pending
+    }
+
+    // Syncs at least once, also without deferred transactions. Fails like poll_tx_status on the first
+    // discarded transaction or when some are still pending after the timeout. Returns the synced height.
+    pub async fn finish(self) -> Result<u32> {
+        let source = self.source;
+        let mut pending = self.pending;
+        let result = poll_until("deferred transactions", self.timeout, self.interval, async || {
+            let height = source.sync().await?;
+            let mut still_pending = Vec::new();
+            for &tx_id in &pending {
+                if !is_committed(tx_id, source.stored_transaction_status(tx_id).await?)? {
+                    still_pending.push(tx_id);
+                }
+            }
+            pending = still_pending;
+            Ok(pending.is_empty().then_some(height))
+        }).await;
+
+        match pending.first() {
+            Some(&first_pending) => result.map_err(|err| as_transaction_timeout(err, first_pending, self.timeout)),
+            None => result,
+        }
+    }
+}
+
+// Runs `ops` in a DeferredSync scope and finishes it, nothing is synced when `ops` fails
+pub async fn with_deferred_sync<S: SyncSource, T>(source: &mut S, ops: impl AsyncFnOnce(&mut DeferredSync<'_, S>) -> Result<T>) -> Result<T> {
+    let mut scope = DeferredSync::new(source);
+    let value = ops(&mut scope).await?;
+    scope.finish().await?;
+    Ok(value)
 }
 
 pub fn create_tx_script(
     script_code: String,
     library: Option<Library>,
-) -> anyhow::Result<TransactionScript> {
+    options: CompileOptions,
+) -> Result<TransactionScript> {
     if let Some(lib) = library {
-        return Ok(ScriptBuilder::new(true)
-            .with_dynamically_linked_library(&lib)
-            .unwrap()
-            .compile_tx_script(script_code)
-            .unwrap());
+        return Ok(options.script_builder()
+            .with_dynamically_linked_library(&lib)?
+            .compile_tx_script(script_code)?);
     };
 
-    Ok(ScriptBuilder::new(true)
-        .compile_tx_script(script_code)
-        .unwrap())
-}
\ No newline at end of file
+    Ok(options.script_builder()
+        .compile_tx_script(script_code)?)
+}
+
+// Foreign account for FPI calls in transactions run through the client, on real networks the client fetches
+// the account and storage map proofs over RPC when the transaction executes. `map_reads` are the (slot, key)
+// entries the foreign procedure reads, a missing key fails the transaction.
+pub fn foreign_account_for(account_id: AccountId, map_reads: &[(u8, Word)]) -> Result<ForeignAccount> {
+    let mut keys_by_slot: BTreeMap<u8, Vec<Word>> = BTreeMap::new();
+    for (slot, key) in map_reads {
+        keys_by_slot.entry(*slot).or_default().push(*key);
+    }
+    let requirements = AccountStorageRequirements::new(keys_by_slot.iter().map(|(slot, keys)| (*slot, keys.iter())));
+
+    Ok(ForeignAccount::public(account_id, requirements)?)
+}
+
+// Consumes all consumable notes of the account with a nop script (required for NoAuth accounts)
+// Returns the number of consumed notes
+pub async fn consume_notes(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    transcript: Option<&mut TranscriptRecorder>,
+) -> Result<usize> {
+    let consumable_notes = client.get_consumable_notes(Some(account_id)).await?;
+
+    if consumable_notes.is_empty() {
+        println!("Warning: No consumable notes found");
+        return Ok(0);
+    }
+    println!("Found {} consumable note(s)", consumable_notes.len());
+
+    let note_ids: Vec<_> = consumable_notes.iter().map(|(record, _)| (record.id(), None)).collect();
+
+    let nop_script_code = std::fs::read_to_string(std::path::Path::new("./masm/scripts/nop.masm"))?;
+    let transaction_script = ScriptBuilder::new(false)
+        .compile_tx_script(nop_script_code)?;
+
+    let consume_request = TransactionRequestBuilder::new()
+        .authenticated_input_notes(note_ids.clone())
+        .custom_script(transaction_script)
+        .build()?;
+
+    if let Some(transcript) = transcript {
+        transcript.record_transaction(account_id, &consume_request);
+    }
+    let consume_tx_id = client.submit_new_transaction(account_id, consume_request).await?;
+    println!("Consuming notes via transaction: {:?}", consume_tx_id);
+
+    wait_for_tx(client, consume_tx_id).await?;
+    println!("✅ Notes consumed successfully!");
+
+    Ok(note_ids.len())
+}
@@ -0,0 +1,245 @@
+use miden_client::{
+    account::{Account, AccountId}, asset::Asset, note::Note, transaction::TransactionRequest, utils::Serializable
+};
+#[cfg(feature = "testing")]
+use miden_client::{
+    account::{AccountBuilder, AccountStorageMode}, asset::FungibleAsset, auth::NoAuth, note::{NoteAssets, NoteInputs}, transaction::OutputNote
+};
+use miden_crypto::Felt;
+#[cfg(feature = "testing")]
+use miden_crypto::Word;
+#[cfg(feature = "testing")]
+use miden_testing::MockChain;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::{Path, PathBuf}};
+use thiserror::Error;
+
+use crate::errors::Result;
+#[cfg(feature = "testing")]
+use crate::{accounts::naming_account_component, compile::CompileOptions, notes::create_note_for_naming_with_serial_num};
+
+#[derive(Debug, Error)]
+pub enum TranscriptError {
+    #[error("note {index} uses non fungible assets, only fungible assets can be recorded")]
+    UnsupportedAsset { index: usize },
+    #[error("note {index} ({script}) script root {actual} does not match recorded {expected}")]
+    ScriptMismatch { index: usize, script: String, expected: String, actual: String },
+    #[error("note {index} ({script}) rebuilds to id {actual}, recorded {expected}")]
+    NoteIdMismatch { index: usize, script: String, expected: String, actual: String },
+    #[error("transaction {index} consumes or creates note {note_id} which is not in the transcript")]
+    UnknownNote { index: usize, note_id: String },
+    #[error("transcript has no recorded post state")]
+    MissingPostState,
+    #[error("naming storage root {actual} does not match recorded {expected}")]
+    StateRootMismatch { expected: String, actual: String },
+    #[error("replay of note {index} failed: {error}")]
+    Replay { index: usize, error: String },
+    #[error("invalid transcript json: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetRecord {
+    pub faucet_id: String,
+    pub amount: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteRecord {
+    // Name of the script under masm/notes
+    pub script: String,
+    pub code_hash: String,
+    pub note_id: String,
+    pub serial_num: [u64; 4],
+    pub inputs: Vec<u64>,
+    pub assets: Vec<AssetRecord>,
+    pub sender: String,
+    pub tag: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionRecord {
+    pub account_id: String,
+    // Notes created or consumed by the request
+    pub note_ids: Vec<String>,
+    // Hex encoded serialized TransactionRequest
+    pub request: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TranscriptEntry {
+    Note(NoteRecord),
+    Transaction(TransactionRecord),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostState {
+    pub naming_id: String,
+    pub naming_storage_root: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transcript {
+    pub entries: Vec<TranscriptEntry>,
+    pub post_state: Option<PostState>,
+}
+
+impl Transcript {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content).map_err(TranscriptError::from)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self).map_err(TranscriptError::from)?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    pub fn notes(&self) -> impl Iterator<Item = &NoteRecord> {
+        self.entries.iter().filter_map(|entry| match entry {
+            TranscriptEntry::Note(note) => Some(note),
+            TranscriptEntry::Transaction(_) => None,
+        })
+    }
+}
+
+// Collects every note and transaction request submitted by a script, in order
+pub struct TranscriptRecorder {
+    path: PathBuf,
+    transcript: Transcript,
+}
+
+impl TranscriptRecorder {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, transcript: Transcript::default() }
+    }
+
+    pub fn record_note(&mut self, script: &str, note: &Note) -> Result<()> {
+        let index = self.transcript.entries.len();
+        let assets = note.assets().iter()
+            .map(|asset| match asset {
+                Asset::Fungible(fungible) => Ok(AssetRecord { faucet_id: fungible.faucet_id().to_hex(), amount: fungible.amount() }),
+                Asset::NonFungible(_) => Err(TranscriptError::UnsupportedAsset { index }),
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        self.transcript.entries.push(TranscriptEntry::Note(NoteRecord {
+            script: script.to_string(),
+            code_hash: note.script().root().to_hex(),
+            note_id: note.id().to_hex(),
+            serial_num: <[Felt; 4]>::from(note.serial_num()).map(|felt| felt.as_int()),
+            inputs: note.inputs().values().iter().map(|felt| felt.as_int()).collect(),
+            assets,
+            sender: note.metadata().sender().to_hex(),
+            tag: note.metadata().tag().as_u32(),
+        }));
+        Ok(())
+    }
+
+    pub fn record_transaction(&mut self, account_id: AccountId, request: &TransactionRequest) {
+        let note_ids = request.expected_output_own_notes().iter()
+            .map(|note| note.id())
+            .chain(request.get_input_note_ids())
+            .map(|id| id.to_hex())
+            .collect();
+
+        self.transcript.entries.push(TranscriptEntry::Transaction(TransactionRecord {
+            account_id: account_id.to_hex(),
+            note_ids,
+            request: to_hex(&request.to_bytes()),
+        }));
+    }
+
+    // Stores the naming post state and writes the transcript to disk
+    pub fn finish(mut self, naming: &Account) -> Result<Transcript> {
+        self.transcript.post_state = Some(PostState {
+            naming_id: naming.id().to_hex(),
+            naming_storage_root: naming.storage().commitment().to_hex(),
+        });
+        self.transcript.save(&self.path)?;
+        Ok(self.transcript)
+    }
+}
+
+// Rebuilds every recorded note from the current masm sources, executes them in order against a fresh
+// naming account on a MockChain and checks the resulting storage root against the recorded post state.
+#[cfg(feature = "testing")]
+pub async fn replay_transcript(transcript: &Transcript, options: CompileOptions) -> Result<()> {
+    let post_state = transcript.post_state.as_ref().ok_or(TranscriptError::MissingPostState)?;
+
+    let mut builder = MockChain::builder();
+    let mut naming = AccountBuilder::new([0_u8; 32])
+        .with_auth_component(NoAuth)
+        .with_component(naming_account_component(options)?)
+        .storage_mode(AccountStorageMode::Public)
+        .build_existing()?;
+    builder.add_account(naming.clone()).map_err(|err| TranscriptError::Replay { index: 0, error: err.to_string() })?;
+
+    let mut notes = Vec::new();
+    for (index, entry) in transcript.entries.iter().enumerate() {
+        match entry {
+            TranscriptEntry::Note(record) => {
+                let note = rebuild_note(index, record, naming.id(), options).await?;
+                builder.add_output_note(OutputNote::Full(note.clone()));
+                notes.push((index, note));
+            }
+            TranscriptEntry::Transaction(record) => {
+                // Notes must be recorded before the transaction that submits them
+                for note_id in &record.note_ids {
+                    if !notes.iter().any(|(_, note)| &note.id().to_hex() == note_id) {
+                        return Err(TranscriptError::UnknownNote { index, note_id: note_id.clone() }.into());
+                    }
+                }
+            }
+        }
+    }
+
+    let mut chain = builder.build().map_err(|err| TranscriptError::Replay { index: 0, error: err.to_string() })?;
+    for (index, note) in notes {
+        let replay_error = |err: &dyn std::fmt::Display| TranscriptError::Replay { index, error: err.to_string() };
+
+        let tx_ctx = chain.build_tx_context(naming.id(), &[note.id()], &[])
+            .and_then(|builder| builder.build())
+            .map_err(|err| replay_error(&err))?;
+        let executed_tx = tx_ctx.execute().await.map_err(|err| replay_error(&err))?;
+
+        naming.apply_delta(executed_tx.account_delta())?;
+        chain.add_pending_executed_transaction(&executed_tx).map_err(|err| replay_error(&err))?;
+        chain.prove_next_block().map_err(|err| replay_error(&err))?;
+    }
+
+    let actual = naming.storage().commitment().to_hex();
+    if actual != post_state.naming_storage_root {
+        return Err(TranscriptError::StateRootMismatch { expected: post_state.naming_storage_root.clone(), actual }.into());
+    }
+    Ok(())
+}
+
+#[cfg(feature = "testing")]
+async fn rebuild_note(index: usize, record: &NoteRecord, naming_id: AccountId, options: CompileOptions) -> Result<Note> {
+    let inputs = NoteInputs::new(record.inputs.iter().map(|value| Felt::new(*value)).collect())?;
+    let assets = record.assets.iter()
+        .map(|asset| Ok(FungibleAsset::new(AccountId::from_hex(&asset.faucet_id)?, asset.amount)?.into()))
+        .collect::<Result<Vec<Asset>>>()?;
+    let sender = AccountId::from_hex(&record.sender)?;
+    let serial_num = Word::new(record.serial_num.map(Felt::new));
+
+    let note = create_note_for_naming_with_serial_num(record.script.clone(), inputs, sender, naming_id, NoteAssets::new(assets)?, serial_num, options).await?;
+
+    let code_hash = note.script().root().to_hex();
+    if code_hash != record.code_hash {
+        return Err(TranscriptError::ScriptMismatch { index, script: record.script.clone(), expected: record.code_hash.clone(), actual: code_hash }.into());
+    }
+    // Note id commits to script, inputs and assets, so any tampered input shows up here
+    let note_id = note.id().to_hex();
+    if note_id != record.note_id {
+        return Err(TranscriptError::NoteIdMismatch { index, script: record.script.clone(), expected: record.note_id.clone(), actual: note_id }.into());
+    }
+    Ok(note)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,163 @@
+use miden_client::{account::{Account, AccountId, StorageSlot}, asset::{Asset, FungibleAsset}};
+use miden_crypto::{Felt, Word};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{errors::{RegistryError, Result}, queries::{account_id_to_word, read_map_item, word_to_account_id}, storage::{CLAIMED_REVENUE_SLOT, DONATIONS_SLOT, FRONTEND_FEES_SLOT, REF_CLAIMED_REVENUE_SLOT, REF_TOTAL_REVENUE_SLOT, REVENUE_TOKENS_SLOT, REVENUE_TOKEN_COUNT_SLOT, TOTAL_REVENUE_SLOT}};
+
+// Vault balance of one token next to the counters that should account for it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultRecord {
+    pub vault_balance: u64,
+    pub total_revenue: u64,
+    pub claimed_revenue: u64,
+    pub donations: u64,
+    // Referral earnings not claimed yet
+    pub referrer_owed: u64,
+    // Frontend fees not claimed yet
+    pub frontend_owed: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultReconciliation {
+    // Balance explained by the counters
+    pub accounted: u64,
+    // Balance above the counters, e.g. assets sent directly instead of through a donate note
+    pub unaccounted: u64,
+    // Counters above the balance, the vault can not pay out everything it owes
+    pub shortfall: u64,
+}
+
+impl VaultReconciliation {
+    pub fn is_clean(&self) -> bool {
+        self.unaccounted == 0 && self.shortfall == 0
+    }
+}
+
+pub fn reconcile_vault(record: &VaultRecord) -> VaultReconciliation {
+    let accounted = record.total_revenue.saturating_sub(record.claimed_revenue)
+        .saturating_add(record.donations)
+        .saturating_add(record.referrer_owed)
+        .saturating_add(record.frontend_owed);
+
+    VaultReconciliation {
+        accounted,
+        unaccounted: record.vault_balance.saturating_sub(accounted),
+        shortfall: accounted.saturating_sub(record.vault_balance),
+    }
+}
+
+// Tokens that have received protocol revenue, in the order of their first credit. Listed once revenue is
+// credited, claiming it all doesn't remove the token.
+pub fn get_revenue_tokens(naming: &Account) -> Result<Vec<AccountId>> {
+    let count = naming.storage().get_item(REVENUE_TOKEN_COUNT_SLOT)?[0].as_int();
+    let mut tokens = Vec::with_capacity(count as usize);
+    for index in 0..count {
+        let key = Word::new([Felt::new(index), Felt::new(0), Felt::new(0), Felt::new(0)]);
+        if let Some(word) = read_map_item(naming, REVENUE_TOKENS_SLOT, key)?
+            && let Some(token) = word_to_account_id(word)?
+        {
+            tokens.push(token);
+        }
+    }
+    Ok(tokens)
+}
+
+// Referral earnings are not keyed by token, they are counted against every token
+pub fn vault_record(naming: &Account, payment_token: AccountId) -> Result<VaultRecord> {
+    let token_key = account_id_to_word(payment_token);
+    let read_amount = |slot: u8, key: Word| -> Result<u64> {
+        Ok(read_map_item(naming, slot, key)?.map(|value| value[0].as_int()).unwrap_or(0))
+    };
+
+    let referrer_owed = sum_map(naming, REF_TOTAL_REVENUE_SLOT, |_| true)?
+        .saturating_sub(sum_map(naming, REF_CLAIMED_REVENUE_SLOT, |_| true)?);
+    // Frontend fee keys are [recipient_suffix, recipient_prefix, token_suffix, token_prefix]
+    let frontend_owed = sum_map(naming, FRONTEND_FEES_SLOT, |key| key[2] == token_key[0] && key[3] == token_key[1])?;
+
+    Ok(VaultRecord {
+        vault_balance: naming.vault().get_balance(payment_token)?,
+        total_revenue: read_amount(TOTAL_REVENUE_SLOT, token_key)?,
+        claimed_revenue: read_amount(CLAIMED_REVENUE_SLOT, token_key)?,
+        donations: read_amount(DONATIONS_SLOT, token_key)?,
+        referrer_owed,
+        frontend_owed,
+    })
+}
+
+fn sum_map(naming: &Account, slot: u8, filter: impl Fn(&Word) -> bool) -> Result<u64> {
+    match naming.storage().slots().get(slot as usize) {
+        Some(StorageSlot::Map(map)) => Ok(map.entries()
+            .filter(|(key, _)| filter(key))
+            .fold(0_u64, |sum, (_, value)| sum.saturating_add(value[0].as_int()))),
+        Some(_) => Err(RegistryError::StorageSlotNotMap { slot }.into()),
+        None => Err(RegistryError::StorageSlotMissing { slot }.into()),
+    }
+}
+
+// Fungible balance of one token before and after an operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetDelta {
+    #[serde(with = "crate::serde_hex::account_id")]
+    pub faucet_id: AccountId,
+    pub before: u64,
+    pub after: u64,
+}
+
+impl AssetDelta {
+    // Negative when the account gained tokens
+    pub fn net_cost(&self) -> i64 {
+        self.before as i64 - self.after as i64
+    }
+}
+
+// Tokens whose balance changed between the two states of one account, ordered by faucet id.
+// Non fungible assets are not compared.
+pub fn vault_delta(before: &Account, after: &Account) -> Vec<AssetDelta> {
+    let mut balances: BTreeMap<AccountId, (u64, u64)> = BTreeMap::new();
+    for asset in before.vault().assets() {
+        if let Asset::Fungible(asset) = asset {
+            balances.entry(asset.faucet_id()).or_default().0 = asset.amount();
+        }
+    }
+    for asset in after.vault().assets() {
+        if let Asset::Fungible(asset) = asset {
+            balances.entry(asset.faucet_id()).or_default().1 = asset.amount();
+        }
+    }
+    balances.into_iter()
+        .filter(|(_, (before, after))| before != after)
+        .map(|(faucet_id, (before, after))| AssetDelta { faucet_id, before, after })
+        .collect()
+}
+
+// What an operation cost its sender in one token: the assets its notes carried, what came back and the
+// balance change that remains
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendSummary {
+    #[serde(with = "crate::serde_hex::account_id")]
+    pub faucet_id: AccountId,
+    pub sent: u64,
+    // Derived as sent minus net cost, not observed: whatever the note carried beyond the balance change
+    pub change: u64,
+    pub net_cost: i64,
+}
+
+// One entry per token that was sent or whose balance changed
+pub fn spend_summary(deltas: &[AssetDelta], sent: &[FungibleAsset]) -> Vec<SpendSummary> {
+    let mut summaries: BTreeMap<AccountId, SpendSummary> = BTreeMap::new();
+    for asset in sent {
+        let summary = summaries.entry(asset.faucet_id())
+            .or_insert(SpendSummary { faucet_id: asset.faucet_id(), sent: 0, change: 0, net_cost: 0 });
+        summary.sent = summary.sent.saturating_add(asset.amount());
+    }
+    for delta in deltas {
+        summaries.entry(delta.faucet_id)
+            .or_insert(SpendSummary { faucet_id: delta.faucet_id, sent: 0, change: 0, net_cost: 0 })
+            .net_cost = delta.net_cost();
+    }
+    summaries.into_values()
+        .filter(|summary| summary.sent != 0 || summary.net_cost != 0)
+        .map(|summary| SpendSummary { change: (summary.sent as i128 - summary.net_cost as i128).max(0) as u64, ..summary })
+        .collect()
+}
@@ -0,0 +1,103 @@
+mod test_utils;
+
+use std::sync::Arc;
+
+use miden_client::{account::{AccountBuilder, AccountStorageMode, AccountType}, auth::NoAuth, testing::mock::MockRpcApi, transaction::{OutputNote, TransactionRequestBuilder}};
+use miden_crypto::{Felt, Word};
+use midenname_contracts::{accounts::{add_contract_account, create_basic_wallet, derive_owner_wallet, generate_mnemonic, naming_account_component}, compile::CompileOptions, errors::{MidenIdError, RegistryError}, notes::create_naming_commit_note, testing::init_naming};
+
+use crate::test_utils::{create_mock_client, open_mock_client};
+
+#[tokio::test]
+async fn test_add_new_account_with_seed() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let mut client = create_mock_client(ctx.builder.build()?).await?;
+
+    let account = AccountBuilder::new(rand::random())
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_component(naming_account_component(CompileOptions::DEBUG)?)
+        .with_auth_component(NoAuth)
+        .build()?;
+
+    add_contract_account(&mut client, &account, None).await?;
+    assert!(client.get_account(account.id()).await?.is_some());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_add_new_account_without_seed_fails() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let mut client = create_mock_client(ctx.builder.build()?).await?;
+
+    // Existing account state that was never deployed, there is no seed to deploy it with
+    let account = AccountBuilder::new(rand::random())
+        .storage_mode(AccountStorageMode::Public)
+        .with_component(naming_account_component(CompileOptions::DEBUG)?)
+        .with_auth_component(NoAuth)
+        .build_existing()?;
+
+    let err = add_contract_account(&mut client, &account, None).await.expect_err("Expected add without seed to fail");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::MissingAccountSeed(_))), "Unexpected error: {err}");
+    assert!(client.get_account(account.id()).await?.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_add_existing_account_imports_it() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let naming = ctx.naming.clone();
+    let mut client = create_mock_client(ctx.builder.build()?).await?;
+
+    // Registry deployed by someone else, no seed available
+    add_contract_account(&mut client, &naming, None).await?;
+
+    let record = client.get_account(naming.id()).await?.expect("Expected naming account to be imported");
+    assert_eq!(record.account().id(), naming.id());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reopened_client_signs_with_persisted_keystore() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let rpc = Arc::new(MockRpcApi::new(ctx.builder.build()?));
+    let dir = std::env::temp_dir().join(format!("midenname-test-{}", rand::random::<u64>()));
+
+    let (mut client, keystore) = open_mock_client(rpc.clone(), &dir).await?;
+    let (wallet, _) = create_basic_wallet(&mut client, &keystore).await?;
+    drop((client, keystore));
+
+    // New run on the same directory, the wallet key is only on disk now
+    let (mut client, _) = open_mock_client(rpc, &dir).await?;
+    client.sync_state().await?;
+    let note = create_naming_commit_note(wallet.id(), ctx.naming.id(), Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]), CompileOptions::DEBUG).await?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note)])
+        .build()?;
+    client.submit_new_transaction(wallet.id(), request).await?;
+
+    // First transaction of the wallet deploys it, which needs its signature
+    let record = client.get_account(wallet.id()).await?.expect("Expected wallet to be tracked");
+    assert_eq!(record.account().nonce().as_int(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_mnemonic_derivation_is_deterministic() -> anyhow::Result<()> {
+    let mnemonic = generate_mnemonic();
+    assert_eq!(mnemonic.split_whitespace().count(), 24);
+
+    let (wallet, key) = derive_owner_wallet(&mnemonic, 0)?;
+    let (recovered, recovered_key) = derive_owner_wallet(&mnemonic, 0)?;
+    assert_eq!(recovered.id(), wallet.id());
+    assert_eq!(Word::from(recovered_key.public_key().to_commitment()), Word::from(key.public_key().to_commitment()));
+
+    // Surrounding whitespace doesn't matter, other indexes and mnemonics are other wallets
+    assert_eq!(derive_owner_wallet(&format!("  {mnemonic}\n"), 0)?.0.id(), wallet.id());
+    assert_ne!(derive_owner_wallet(&mnemonic, 1)?.0.id(), wallet.id());
+    assert_ne!(derive_owner_wallet(&generate_mnemonic(), 0)?.0.id(), wallet.id());
+
+    let err = derive_owner_wallet("not a mnemonic", 0).expect_err("Expected invalid mnemonic to fail");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::InvalidMnemonic(_))), "Unexpected error: {err}");
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use miden_client::account::{Account, AccountId};
+use miden_crypto::{Felt, Word};
+use miden_testing::{Auth, MockChain, MockChainBuilder};
+use midenname_contracts::{audit::{Violation, audit_registry, plan_repairs}, compile::CompileOptions, domain::encode_domain, queries::account_id_to_word, storage::{ACCOUNT_ID_TO_DOMAIN_SLOT, CLAIMED_REVENUE_SLOT, DOMAIN_TO_ACCOUNT_ID_SLOT, DOMAIN_TO_OWNER_SLOT, TOTAL_REVENUE_SLOT}, testing::{ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2, add_note_to_builder, create_test_naming_account, create_test_naming_account_with_entries, execute_notes_and_build_chain}};
+
+fn amount(value: u64) -> Word {
+    Word::new([Felt::new(value), Felt::new(0), Felt::new(0), Felt::new(0)])
+}
+
+// Wallets standing in for registrants, with a naming account holding one violation of each class:
+// "alice" resolves to a non owner, "bob" resolves without an owner, "carol" and "dave" are orphaned
+// reverse entries (only carol is still owned by its account), and the revenue counters of both test tokens are off
+struct Inconsistent {
+    naming: Account,
+    accounts: [AccountId; 4],
+    tokens: [AccountId; 2],
+}
+
+fn inconsistent_registry(builder: &mut MockChainBuilder) -> anyhow::Result<Inconsistent> {
+    let mut wallet = || builder.add_existing_wallet(Auth::BasicAuth).map(|wallet| wallet.id());
+    let accounts = [wallet()?, wallet()?, wallet()?, wallet()?];
+    let [a, b, c, d] = accounts;
+    let tokens = [AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1)?, AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2)?];
+    let domain = |name: &str| encode_domain(name.to_string());
+
+    let naming = create_test_naming_account_with_entries(&[
+        (DOMAIN_TO_ACCOUNT_ID_SLOT, domain("alice"), account_id_to_word(a)),
+        (DOMAIN_TO_OWNER_SLOT, domain("alice"), account_id_to_word(b)),
+        (DOMAIN_TO_ACCOUNT_ID_SLOT, domain("bob"), account_id_to_word(a)),
+        (ACCOUNT_ID_TO_DOMAIN_SLOT, account_id_to_word(c), domain("carol")),
+        (DOMAIN_TO_OWNER_SLOT, domain("carol"), account_id_to_word(c)),
+        (ACCOUNT_ID_TO_DOMAIN_SLOT, account_id_to_word(d), domain("dave")),
+        (TOTAL_REVENUE_SLOT, account_id_to_word(tokens[0]), amount(100)),
+        (CLAIMED_REVENUE_SLOT, account_id_to_word(tokens[0]), amount(150)),
+        (TOTAL_REVENUE_SLOT, account_id_to_word(tokens[1]), amount(500)),
+    ])?;
+    builder.add_account(naming.clone())?;
+    Ok(Inconsistent { naming, accounts, tokens })
+}
+
+#[test]
+fn test_consistent_registry_audits_clean() -> anyhow::Result<()> {
+    assert!(audit_registry(&create_test_naming_account())?.is_clean());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_audit_detects_each_violation_class() -> anyhow::Result<()> {
+    let registry = inconsistent_registry(&mut MockChain::builder())?;
+    let [a, b, c, d] = registry.accounts;
+    let report = audit_registry(&registry.naming)?;
+
+    let name_of = |violation: &Violation| match violation {
+        Violation::ForwardOwnerMismatch { domain, .. } | Violation::OrphanedReverse { domain, .. } => domain.name.clone(),
+        _ => None,
+    };
+    for (name, expected) in [
+        ("alice", Violation::ForwardOwnerMismatch { domain: encode_domain("alice".to_string()).into(), account: a, owner: Some(b) }),
+        ("bob", Violation::ForwardOwnerMismatch { domain: encode_domain("bob".to_string()).into(), account: a, owner: None }),
+        ("carol", Violation::OrphanedReverse { account: c, domain: encode_domain("carol".to_string()).into(), resolves_to: None }),
+        ("dave", Violation::OrphanedReverse { account: d, domain: encode_domain("dave".to_string()).into(), resolves_to: None }),
+    ] {
+        assert!(report.violations.contains(&expected), "{name} not reported: {:?}", report.violations);
+        assert_eq!(name_of(&expected).as_deref(), Some(name));
+    }
+    assert!(report.violations.contains(&Violation::ClaimedExceedsRevenue { token: registry.tokens[0], total_revenue: 100, claimed_revenue: 150 }));
+    assert!(report.violations.contains(&Violation::VaultShortfall { token: registry.tokens[1], vault_balance: 0, shortfall: 500 }));
+    // The entries were written directly, so the domain count never saw alice and carol registered
+    assert!(report.violations.contains(&Violation::RegisteredExceedsCount { registered_names: 2, total_registrations: 0 }));
+    assert_eq!(report.violations.len(), 7);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_repair_plan_fixes_the_domain_maps() -> anyhow::Result<()> {
+    let mut builder = MockChain::builder();
+    let mut registry = inconsistent_registry(&mut builder)?;
+    let [_, b, c, _] = registry.accounts;
+    let report = audit_registry(&registry.naming)?;
+
+    let plan = plan_repairs(&registry.naming, &report, b, CompileOptions::DEBUG).await?;
+    let senders: Vec<AccountId> = plan.repairs.iter().map(|(_, note)| note.metadata().sender()).collect();
+    // alice's owner transfers it to itself, bob is cleared and carol is activated again by its owner
+    assert_eq!(senders, vec![b, b, c]);
+    for (_, note) in &plan.repairs {
+        add_note_to_builder(&mut builder, note.clone())?;
+    }
+
+    let note_ids: Vec<_> = plan.repairs.iter().map(|(_, note)| note.id()).collect();
+    execute_notes_and_build_chain(builder, &note_ids, &mut registry.naming).await?;
+
+    // dave owns nothing to activate and the counters need an owner decision
+    assert_eq!(audit_registry(&registry.naming)?.violations, plan.manual);
+    assert_eq!(plan.manual.len(), 4);
+    Ok(())
+}
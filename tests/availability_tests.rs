@@ -0,0 +1,75 @@
+use miden_client::asset::FungibleAsset;
+use midenname_contracts::{availability::{check_availability, suggest_alternatives}, compile::CompileOptions, domain::encode_domain, errors::{MidenIdError, RegistryError}, notes::{create_naming_register_name_note_from_name, create_naming_set_reserved_name_note}, testing::{add_note_to_builder, execute_notes_and_build_chain, init_naming}};
+
+#[tokio::test]
+async fn test_availability_states() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    let register_note = create_naming_register_name_note_from_name(ctx.registrar_1.id(), ctx.naming.id(), "test", FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let reserve_note = create_naming_set_reserved_name_note(ctx.owner.id(), ctx.naming.id(), encode_domain("admin".to_string()), true, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, reserve_note.clone())?;
+
+    let chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), reserve_note.id()], &mut ctx.naming).await?;
+    let now = chain.latest_block_header().timestamp() as u64;
+
+    // Available
+    let free = check_availability(&ctx.naming, "free", token, now)?;
+    assert!(free.is_available());
+    assert_eq!(free.taken_by, None);
+    assert_eq!(free.price, 555);
+
+    // Taken
+    let taken = check_availability(&ctx.naming, "test", token, now)?;
+    assert!(!taken.is_available());
+    assert_eq!(taken.taken_by, Some(ctx.registrar_1.id()));
+
+    // Reserved
+    let reserved = check_availability(&ctx.naming, "admin", token, now)?;
+    assert!(!reserved.is_available());
+    assert!(reserved.reserved);
+    assert_eq!(reserved.taken_by, None);
+
+    // Expired registrations are available again
+    let expired = check_availability(&ctx.naming, "test", token, now + ctx.one_year as u64 + 1)?;
+    assert!(expired.is_available());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_suggest_alternatives_skips_taken() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    let mut note_ids = vec![ctx.initialize_note.id(), ctx.set_prices_note.id()];
+    for name in ["alice", "alice1"] {
+        let register_note = create_naming_register_name_note_from_name(ctx.registrar_1.id(), ctx.naming.id(), name, FungibleAsset::new(token, 123)?, 1, CompileOptions::DEBUG).await?;
+        add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+        note_ids.push(register_note.id());
+    }
+
+    let chain = execute_notes_and_build_chain(ctx.builder, &note_ids, &mut ctx.naming).await?;
+    let now = chain.latest_block_header().timestamp() as u64;
+
+    let suggestions = suggest_alternatives(&ctx.naming, "alice", token, now, 3)?;
+    assert_eq!(suggestions, vec!["alice2", "alice3", "alice4"]);
+
+    // Only look-alike substitutions fit in 20 characters
+    let suggestions = suggest_alternatives(&ctx.naming, "abcdefghijklmnopqrst", token, now, 2)?;
+    assert_eq!(suggestions, vec!["abcd3fghijklmnopqrst", "abcdefgh1jklmnopqrst"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_address_like_names_can_not_be_registered() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    for name in ["0x7bfb0f38b0fafa103f", "mtst1qy35qfqdn8f2vxy"] {
+        let err = create_naming_register_name_note_from_name(ctx.registrar_1.id(), ctx.naming.id(), name, FungibleAsset::new(token, 123)?, 1, CompileOptions::DEBUG).await.expect_err("Expected address-like name to fail");
+        assert!(matches!(err, MidenIdError::Registry(RegistryError::LooksLikeAddress(_))), "Unexpected error: {err}");
+        assert!(check_availability(&ctx.naming, name, token, 0).is_err());
+    }
+    Ok(())
+}
@@ -0,0 +1,122 @@
+mod test_utils;
+
+use miden_client::{Client, account::{Account, AccountId}, asset::FungibleAsset, keystore::FilesystemKeyStore, note::Note, transaction::TransactionRequestBuilder};
+use miden_crypto::Word;
+use miden_testing::{MockChain, MockChainBuilder};
+use midenname_contracts::{accounts::add_contract_account, compile::CompileOptions, domain::encode_domain, notes::create_naming_register_name_note, queries::account_id_to_word, storage::{DOMAIN_TO_OWNER_SLOT, TOTAL_REVENUE_SLOT}, testing::{NamingFixture, init_naming}};
+use rand::rngs::StdRng;
+
+use crate::test_utils::create_mock_client;
+
+// Same scenario has to hold on the MockChain executor and on a client submitting real transactions.
+// Notes are consumed as unauthenticated input notes on both, so they don't have to be in the genesis block.
+trait Backend: Sized {
+    async fn deploy(builder: MockChainBuilder, naming: &Account) -> anyhow::Result<Self>;
+    async fn consume(&mut self, note: &Note) -> anyhow::Result<()>;
+    async fn naming(&mut self) -> anyhow::Result<Account>;
+
+    async fn init(&mut self, ctx: &NamingFixture) -> anyhow::Result<()> {
+        self.consume(&ctx.initialize_note).await?;
+        self.consume(&ctx.set_prices_note).await
+    }
+
+    async fn register(&mut self, ctx: &NamingFixture, registrant: AccountId, name: &str, amount: u64) -> anyhow::Result<()> {
+        let payment = FungibleAsset::new(ctx.fungible_asset.faucet_id(), amount)?;
+        let note = create_naming_register_name_note(registrant, ctx.naming.id(), encode_domain(name.to_string()), payment, 1, CompileOptions::DEBUG).await?;
+        self.consume(&note).await
+    }
+
+    async fn map_item(&mut self, slot: u8, key: Word) -> anyhow::Result<Word> {
+        Ok(self.naming().await?.storage().get_map_item(slot, key)?)
+    }
+}
+
+struct MockChainBackend {
+    chain: MockChain,
+    naming: Account,
+}
+
+impl Backend for MockChainBackend {
+    async fn deploy(builder: MockChainBuilder, naming: &Account) -> anyhow::Result<Self> {
+        Ok(Self { chain: builder.build()?, naming: naming.clone() })
+    }
+
+    async fn consume(&mut self, note: &Note) -> anyhow::Result<()> {
+        let tx_ctx = self.chain.build_tx_context(self.naming.id(), &[], &[note.clone()])?.build()?;
+        let executed_tx = tx_ctx.execute().await?;
+
+        self.naming.apply_delta(executed_tx.account_delta())?;
+        self.chain.add_pending_executed_transaction(&executed_tx)?;
+        self.chain.prove_next_block()?;
+        Ok(())
+    }
+
+    async fn naming(&mut self) -> anyhow::Result<Account> {
+        Ok(self.naming.clone())
+    }
+}
+
+struct ClientBackend {
+    client: Client<FilesystemKeyStore<StdRng>>,
+    naming_id: AccountId,
+}
+
+impl Backend for ClientBackend {
+    async fn deploy(builder: MockChainBuilder, naming: &Account) -> anyhow::Result<Self> {
+        let mut client = create_mock_client(builder.build()?).await?;
+        add_contract_account(&mut client, naming, None).await?;
+        client.sync_state().await?;
+        Ok(Self { client, naming_id: naming.id() })
+    }
+
+    async fn consume(&mut self, note: &Note) -> anyhow::Result<()> {
+        let request = TransactionRequestBuilder::new()
+            .unauthenticated_input_notes([(note.clone(), None)])
+            .build()?;
+        self.client.submit_new_transaction(self.naming_id, request).await?;
+        self.client.sync_state().await?;
+        Ok(())
+    }
+
+    async fn naming(&mut self) -> anyhow::Result<Account> {
+        let record = self.client.get_account(self.naming_id).await?
+            .ok_or_else(|| anyhow::anyhow!("naming account is not tracked"))?;
+        Ok(record.account().clone())
+    }
+}
+
+async fn registration_scenario<B: Backend>() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let builder = std::mem::replace(&mut ctx.builder, MockChain::builder());
+    let mut backend = B::deploy(builder, &ctx.naming).await?;
+    backend.init(&ctx).await?;
+
+    // Underpayment must fail the same way on both backends
+    let result = backend.register(&ctx, ctx.registrar_1.id(), "test", 554).await;
+    assert!(result.is_err(), "Expected register with less than the price to fail");
+
+    backend.register(&ctx, ctx.registrar_1.id(), "test", 555).await?;
+
+    let owner = backend.map_item(DOMAIN_TO_OWNER_SLOT, encode_domain("test".to_string())).await?;
+    assert_eq!(owner[0], ctx.registrar_1.id().suffix());
+    assert_eq!(owner[1], ctx.registrar_1.id().prefix().as_felt());
+
+    let revenue = backend.map_item(TOTAL_REVENUE_SLOT, account_id_to_word(token)).await?;
+    assert_eq!(revenue[0].as_int(), 555);
+
+    // Taken by registrar_1
+    let result = backend.register(&ctx, ctx.registrar_2.id(), "test", 555).await;
+    assert!(result.is_err(), "Expected register of a taken domain to fail");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_registration_scenario_on_mock_chain() -> anyhow::Result<()> {
+    registration_scenario::<MockChainBackend>().await
+}
+
+#[tokio::test]
+async fn test_registration_scenario_on_client() -> anyhow::Result<()> {
+    registration_scenario::<ClientBackend>().await
+}
@@ -0,0 +1,35 @@
+use std::fs;
+
+use miden_client::utils::Serializable;
+use miden_crypto::hash::rpo::Rpo256;
+use midenname_contracts::{accounts::naming_account_component, compile::CompileOptions, notes::{compile_naming_note_script, create_library}};
+
+// Debug info changes the compiled artifacts but not the roots, so notes and accounts compiled in either mode
+// work together
+#[test]
+fn test_debug_and_release_artifacts_differ() -> anyhow::Result<()> {
+    let code = fs::read_to_string("./masm/accounts/naming.masm")?;
+    let debug = create_library(code.clone(), "miden_name::naming", CompileOptions::DEBUG)?;
+    let release = create_library(code, "miden_name::naming", CompileOptions::RELEASE)?;
+    assert_ne!(Rpo256::hash(&debug.to_bytes()), Rpo256::hash(&release.to_bytes()));
+    assert_eq!(debug.digest(), release.digest());
+
+    let debug_component = naming_account_component(CompileOptions::DEBUG)?;
+    let release_component = naming_account_component(CompileOptions::RELEASE)?;
+    assert_ne!(Rpo256::hash(&debug_component.library().to_bytes()), Rpo256::hash(&release_component.library().to_bytes()));
+    assert_eq!(debug_component.library().digest(), release_component.library().digest());
+
+    let debug_note = compile_naming_note_script("activate_domain", CompileOptions::DEBUG)?;
+    let release_note = compile_naming_note_script("activate_domain", CompileOptions::RELEASE)?;
+    assert_ne!(Rpo256::hash(&debug_note.to_bytes()), Rpo256::hash(&release_note.to_bytes()));
+    assert_eq!(debug_note.root(), release_note.root());
+    Ok(())
+}
+
+#[test]
+fn test_deploy_compiles_in_release_mode_unless_asked() {
+    // What deploy and every other command run with when --debug-masm is not given
+    assert!(!CompileOptions::cli(false).debug);
+    assert_eq!(CompileOptions::cli(false), CompileOptions::RELEASE);
+    assert_eq!(CompileOptions::cli(true), CompileOptions::DEBUG);
+}
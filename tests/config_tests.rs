@@ -0,0 +1,315 @@
+mod test_utils;
+
+use std::{collections::BTreeMap, fs, path::{Path, PathBuf}, sync::Arc, time::{Duration, Instant}};
+
+use miden_client::{account::AccountId, keystore::FilesystemKeyStore, rpc::Endpoint, testing::mock::MockRpcApi, transaction::TransactionId};
+use miden_crypto::Word;
+use miden_objects::address::NetworkId;
+use miden_testing::MockChain;
+use midenname_contracts::{client::{CleanPaths, DEFAULT_RPC_TIMEOUT_MS, clean_environment, clean_environment_after, initiate_client_with_timeout, parse_rpc_timeout}, compile::CompileOptions, config::{CliConfig, ConfigError, DEFAULT_STORE_PATH, Network, TESTNET_PAYMENT_TOKEN, build_config}, errors::{MidenIdError, RegistryError}, pricing::testnet_prices};
+use rand::rngs::StdRng;
+
+use crate::test_utils::open_mock_client;
+
+#[test]
+fn test_build_config_defaults_rpc_url() {
+    let config = build_config(Network::Testnet, None, PathBuf::from("./keystore"), Some("0x177e66aab4a3704014a2db204f6d49".to_string())).unwrap();
+
+    assert_eq!(config.rpc_url, Endpoint::testnet().to_string());
+    assert_eq!(config.keystore_path, PathBuf::from("./keystore"));
+    assert!(config.naming_account_id().unwrap().is_some());
+}
+
+#[test]
+fn test_config_roundtrip() {
+    let dir = std::env::temp_dir().join(format!("midenname-config-{}", rand::random::<u64>()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("midenname.toml");
+
+    let config = build_config(Network::Devnet, None, PathBuf::from("/tmp/keystore"), None).unwrap();
+    config.save(&path).unwrap();
+    assert_eq!(CliConfig::load(&path).unwrap(), config);
+
+    // Custom networks are stored as their endpoint and read back as the same endpoint
+    let custom = build_config("https://rpc.example.com:8443".parse().unwrap(), None, PathBuf::from("/tmp/keystore"), None).unwrap();
+    custom.save(&path).unwrap();
+    assert_eq!(CliConfig::load(&path).unwrap(), custom);
+    assert_eq!(custom.rpc_url, "https://rpc.example.com:8443");
+}
+
+#[test]
+fn test_config_validation_failures() {
+    assert_eq!(
+        build_config(Network::Testnet, Some("not a url".to_string()), PathBuf::from("./keystore"), None),
+        Err(ConfigError::InvalidRpcUrl("not a url".to_string()))
+    );
+    assert_eq!(
+        build_config(Network::Testnet, None, PathBuf::new(), None),
+        Err(ConfigError::EmptyKeystorePath)
+    );
+    assert_eq!(
+        build_config(Network::Testnet, None, PathBuf::from("./keystore"), Some("0xzz".to_string())),
+        Err(ConfigError::InvalidAccountId("0xzz".to_string()))
+    );
+    assert_eq!(
+        build_config(Network::Mainnet, None, PathBuf::from("./keystore"), None),
+        Err(ConfigError::RpcUrlRequired("mainnet".to_string()))
+    );
+    assert_eq!(build_config(Network::Mainnet, Some("https://rpc.example.com".to_string()), PathBuf::from("./keystore"), None).unwrap().network, Network::Mainnet);
+}
+
+#[test]
+fn test_load_missing_config() {
+    let result = CliConfig::load(&PathBuf::from("./does-not-exist.toml"));
+    assert!(matches!(result, Err(ConfigError::Io(_))));
+}
+
+#[test]
+fn test_missing_config_falls_back_to_testnet_defaults() {
+    let config = CliConfig::load_or_default(&PathBuf::from("./does-not-exist.toml")).unwrap();
+
+    assert_eq!(config, CliConfig::default());
+    assert_eq!(config.rpc_url, Endpoint::testnet().to_string());
+    assert_eq!(config.store_path, PathBuf::from(DEFAULT_STORE_PATH));
+    assert_eq!(config.payment_token_id().unwrap(), AccountId::from_hex(TESTNET_PAYMENT_TOKEN).unwrap());
+    assert_eq!(config.deploy_prices().unwrap(), testnet_prices());
+    assert_eq!(CleanPaths::from(&config), CleanPaths::default());
+}
+
+#[test]
+fn test_config_without_newer_fields_loads_defaults() {
+    let dir = std::env::temp_dir().join(format!("midenname-config-{}", rand::random::<u64>()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("midenname.toml");
+    fs::write(&path, "network = \"devnet\"\nrpc_url = \"https://rpc.devnet.miden.io\"\nkeystore_path = \"/tmp/keystore\"\n").unwrap();
+
+    let config = CliConfig::load_or_default(&path).unwrap();
+
+    assert_eq!(config.store_path, PathBuf::from(DEFAULT_STORE_PATH));
+    assert_eq!(config.payment_token, None);
+    assert_eq!(config.explorer_tx_url(TransactionId::new(Word::default(), Word::default(), Word::default(), Word::default())), None);
+}
+
+#[test]
+fn test_config_payment_token_and_prices() {
+    let dir = std::env::temp_dir().join(format!("midenname-config-{}", rand::random::<u64>()));
+    fs::create_dir_all(&dir).unwrap();
+    let prices = dir.join("prices.toml");
+    fs::write(&prices, "[[tiers]]\nlength = 3\nprice = 700\n").unwrap();
+
+    let config = CliConfig { payment_token: Some("0x177e66aab4a3704014a2db204f6d49".to_string()), prices: Some(prices), ..CliConfig::default() };
+    assert_eq!(config.payment_token_id().unwrap().to_hex(), "0x177e66aab4a3704014a2db204f6d49");
+    assert_eq!(config.deploy_prices().unwrap(), BTreeMap::from([(3, 700)]));
+
+    let invalid = CliConfig { payment_token: Some("0xzz".to_string()), ..CliConfig::default() };
+    assert_eq!(invalid.validate(), Err(ConfigError::InvalidAccountId("0xzz".to_string())));
+}
+
+#[test]
+fn test_parse_network() {
+    assert_eq!("testnet".parse::<Network>().unwrap(), Network::Testnet);
+    assert_eq!(" Devnet ".parse::<Network>().unwrap(), Network::Devnet);
+    assert_eq!("MAINNET".parse::<Network>().unwrap(), Network::Mainnet);
+    assert_eq!("localhost".parse::<Network>().unwrap(), Network::Localhost);
+    assert_eq!("mainnet".parse::<Network>().unwrap().to_string(), "mainnet");
+    assert_eq!("regtest".parse::<Network>(), Err(ConfigError::UnsupportedNetwork("regtest".to_string())));
+
+    let custom: Network = "http://127.0.0.1:57291".parse().unwrap();
+    assert_eq!(custom, Network::Custom(Endpoint::try_from("http://127.0.0.1:57291").unwrap()));
+    assert_eq!(custom.endpoint().unwrap().to_string(), "http://127.0.0.1:57291");
+    assert_eq!(custom.to_string().parse::<Network>().unwrap(), custom);
+    assert_eq!(Network::Devnet.endpoint(), Some(Endpoint::devnet()));
+    assert_eq!(Network::Mainnet.endpoint(), None);
+}
+
+#[test]
+fn test_network_bech32_prefix() {
+    let account_id = AccountId::from_hex(TESTNET_PAYMENT_TOKEN).unwrap();
+    for (network, prefix, network_id) in [
+        (Network::Mainnet, "mm", NetworkId::Mainnet),
+        (Network::Testnet, "mtst", NetworkId::Testnet),
+        (Network::Devnet, "mdev", NetworkId::Devnet),
+        (Network::Localhost, "mtst", NetworkId::Testnet),
+    ] {
+        assert_eq!(network.bech32_prefix(), prefix);
+        assert_eq!(network.to_bech32(account_id), account_id.to_bech32(network_id));
+        assert!(network.to_bech32(account_id).starts_with(&format!("{prefix}1")));
+    }
+
+    let config = CliConfig { network: Network::Devnet, ..CliConfig::default() };
+    assert_eq!(config.address(account_id), account_id.to_bech32(NetworkId::Devnet));
+}
+
+#[test]
+fn test_parse_rpc_timeout() {
+    assert_eq!(parse_rpc_timeout(None), Ok(DEFAULT_RPC_TIMEOUT_MS));
+    assert_eq!(parse_rpc_timeout(Some("2500")), Ok(2500));
+    assert_eq!(parse_rpc_timeout(Some("0")), Err(ConfigError::InvalidRpcTimeout("0".to_string())));
+    assert_eq!(parse_rpc_timeout(Some("30s")), Err(ConfigError::InvalidRpcTimeout("30s".to_string())));
+}
+
+#[tokio::test]
+async fn test_client_with_tiny_timeout_fails_fast() -> anyhow::Result<()> {
+    let dir = std::env::temp_dir().join(format!("midenname-timeout-{}", rand::random::<u64>()));
+    std::fs::create_dir_all(&dir)?;
+    let keystore = Arc::new(FilesystemKeyStore::<StdRng>::new(dir.join("keystore"))?);
+    // Non routable address, connecting hangs until the timeout
+    let endpoint = Endpoint::try_from("http://10.255.255.1:57291").map_err(|err| anyhow::anyhow!("{err}"))?;
+
+    let start = Instant::now();
+    let result = initiate_client_with_timeout(keystore, &endpoint, dir.join("store.sqlite3"), 200, CompileOptions::DEBUG).await;
+
+    assert!(result.is_err(), "Expected the initial sync to fail");
+    assert!(start.elapsed() < Duration::from_secs(5), "client took {:?} to fail", start.elapsed());
+    Ok(())
+}
+
+fn clean_paths(dir: &Path) -> CleanPaths {
+    CleanPaths { store: dir.join("store.sqlite3"), keystore: dir.join("keystore") }
+}
+
+fn clean_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("midenname-clean-{}", rand::random::<u64>()));
+    fs::create_dir_all(&dir).unwrap();
+    // Must survive every clean
+    fs::write(dir.join("sentinel"), "keep").unwrap();
+    dir
+}
+
+#[test]
+fn test_clean_environment_removes_store_siblings_and_keystore() {
+    let dir = clean_dir();
+    let paths = clean_paths(&dir);
+    for name in ["store.sqlite3", "store.sqlite3-wal", "store.sqlite3-shm"] {
+        fs::write(dir.join(name), "db").unwrap();
+    }
+    fs::create_dir_all(&paths.keystore).unwrap();
+    fs::write(paths.keystore.join("key"), "secret").unwrap();
+
+    let report = clean_environment(&paths);
+
+    assert!(report.is_clean(), "Unexpected failures: {:?}", report.failed);
+    assert_eq!(report.removed, vec![paths.store.clone(), dir.join("store.sqlite3-wal"), dir.join("store.sqlite3-shm"), paths.keystore.clone()]);
+    assert!(report.missing.is_empty());
+    assert!(!paths.store.exists() && !paths.keystore.exists());
+    assert!(dir.join("sentinel").exists());
+}
+
+#[test]
+fn test_clean_environment_directory_store_and_missing_paths() {
+    let dir = clean_dir();
+    let paths = clean_paths(&dir);
+    fs::create_dir_all(&paths.store).unwrap();
+    fs::write(paths.store.join("data"), "db").unwrap();
+
+    let report = clean_environment(&paths);
+
+    assert_eq!(report.removed, vec![paths.store.clone()]);
+    assert_eq!(report.missing, vec![dir.join("store.sqlite3-wal"), dir.join("store.sqlite3-shm"), paths.keystore.clone()]);
+    assert!(dir.join("sentinel").exists());
+
+    // Nothing left, a second clean only reports missing paths
+    let report = clean_environment(&paths);
+    assert!(report.removed.is_empty() && report.is_clean());
+    assert_eq!(report.missing.len(), 4);
+}
+
+// Like a live SQLite connection: on Windows nobody else may delete the file while the handle is open
+#[cfg(windows)]
+fn hold_file(path: &Path) -> fs::File {
+    use std::os::windows::fs::OpenOptionsExt;
+    fs::OpenOptions::new().read(true).share_mode(0).open(path).unwrap()
+}
+
+#[cfg(not(windows))]
+fn hold_file(path: &Path) -> fs::File {
+    fs::File::open(path).unwrap()
+}
+
+#[test]
+fn test_clean_environment_with_held_file_handle() {
+    let dir = clean_dir();
+    let paths = clean_paths(&dir);
+    fs::write(&paths.store, "db").unwrap();
+    let handle = hold_file(&paths.store);
+
+    let report = clean_environment(&paths);
+
+    // Windows refuses to delete the open file even after the retries, unix unlinks it
+    if cfg!(windows) {
+        assert_eq!(report.failed.iter().map(|(path, _)| path.clone()).collect::<Vec<_>>(), vec![paths.store.clone()]);
+        assert!(paths.store.exists());
+    } else {
+        assert!(report.is_clean(), "Unexpected failures: {:?}", report.failed);
+        assert!(!paths.store.exists());
+    }
+    drop(handle);
+    assert!(clean_environment(&paths).is_clean());
+}
+
+#[tokio::test]
+async fn test_clean_environment_after_dropping_client() -> anyhow::Result<()> {
+    let dir = clean_dir();
+    let paths = clean_paths(&dir);
+    let (client, _) = open_mock_client(Arc::new(MockRpcApi::new(MockChain::builder().build()?)), &dir).await?;
+
+    let report = clean_environment_after(client, &paths);
+
+    assert!(report.is_clean(), "Unexpected failures: {:?}", report.failed);
+    assert!(report.removed.contains(&paths.store) && report.removed.contains(&paths.keystore));
+    assert!(dir.join("sentinel").exists());
+    Ok(())
+}
+
+fn aliased_config() -> CliConfig {
+    let naming = AccountId::from_hex("0x177e66aab4a3704014a2db204f6d49").unwrap();
+    let contracts = BTreeMap::from([
+        ("testnet-naming".to_string(), naming.to_hex()),
+        ("token".to_string(), AccountId::from_hex(TESTNET_PAYMENT_TOKEN).unwrap().to_bech32(NetworkId::Testnet)),
+    ]);
+    CliConfig { contracts, ..CliConfig::default() }
+}
+
+#[test]
+fn test_account_id_aliases() {
+    let config = aliased_config();
+    config.validate().unwrap();
+    let naming = AccountId::from_hex("0x177e66aab4a3704014a2db204f6d49").unwrap();
+    let token = AccountId::from_hex(TESTNET_PAYMENT_TOKEN).unwrap();
+
+    // Alias hit, including aliases of bech32 addresses
+    assert_eq!(config.account_id("testnet-naming").unwrap(), naming);
+    assert_eq!(config.account_id(" token ").unwrap(), token);
+    // Miss falls through to the address formats
+    assert_eq!(config.account_id(&naming.to_bech32(NetworkId::Testnet)).unwrap(), naming);
+    assert_eq!(config.account_id(TESTNET_PAYMENT_TOKEN).unwrap(), token);
+    assert!(matches!(config.account_id("devnet-naming"), Err(MidenIdError::Registry(RegistryError::InvalidAddress(_)))));
+
+    assert_eq!(config.account_label(naming), format!("testnet-naming ({})", naming.to_hex()));
+    assert_eq!(config.account_label(token), format!("token ({})", token.to_hex()));
+    assert_eq!(CliConfig::default().account_label(naming), naming.to_hex());
+}
+
+#[test]
+fn test_aliases_that_are_account_ids_are_rejected() {
+    let naming = AccountId::from_hex("0x177e66aab4a3704014a2db204f6d49").unwrap();
+    let mut config = aliased_config();
+    // Would shadow what the hex ID means
+    config.contracts.insert(naming.to_hex(), TESTNET_PAYMENT_TOKEN.to_string());
+    assert_eq!(config.validate(), Err(ConfigError::InvalidAlias(naming.to_hex())));
+
+    let mut config = aliased_config();
+    config.contracts.insert("broken".to_string(), "0xzz".to_string());
+    assert_eq!(config.validate(), Err(ConfigError::InvalidAccountId("0xzz".to_string())));
+
+    // Aliases survive the TOML round trip under [contracts]
+    let dir = std::env::temp_dir().join(format!("midenname-config-{}", rand::random::<u64>()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("midenname.toml");
+    let config = CliConfig { naming_id: Some("testnet-naming".to_string()), ..aliased_config() };
+    config.save(&path).unwrap();
+    assert!(fs::read_to_string(&path).unwrap().contains("[contracts]"));
+    let loaded = CliConfig::load(&path).unwrap();
+    assert_eq!(loaded, config);
+    assert_eq!(loaded.naming_account_id().unwrap(), Some(naming));
+}
@@ -0,0 +1,27 @@
+use miden_client::asset::FungibleAsset;
+use midenname_contracts::{compile::CompileOptions, domain::encode_domain, dump::{DecodedWord, SlotContents, dump_storage}, notes::create_naming_register_name_note, storage::{DOMAIN_TO_OWNER_SLOT, INIT_FLAG_SLOT, NAMING_STORAGE_LAYOUT, ONE_YEAR_TIMESTAMP_SLOT, OWNER_SLOT, PRICES_SLOT}, testing::{add_note_to_builder, execute_notes_and_build_chain, init_naming}};
+
+#[tokio::test]
+async fn test_dump_storage_labels_and_decodes_slots() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain("test".to_string());
+
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?, 1, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id()], &mut ctx.naming).await?;
+
+    let dump = dump_storage(&ctx.naming);
+    assert_eq!(dump.len(), NAMING_STORAGE_LAYOUT.len());
+    assert_eq!(dump[&OWNER_SLOT].name, "OWNER_SLOT");
+
+    assert_eq!(dump[&INIT_FLAG_SLOT].contents, SlotContents::Value(DecodedWord::Flag(true)));
+    assert_eq!(dump[&OWNER_SLOT].contents, SlotContents::Value(DecodedWord::Account(ctx.owner.id())));
+    assert_eq!(dump[&ONE_YEAR_TIMESTAMP_SLOT].contents, SlotContents::Value(DecodedWord::Amount(ctx.one_year.into())));
+    assert_eq!(dump[&DOMAIN_TO_OWNER_SLOT].contents, SlotContents::Map(vec![(DecodedWord::Domain("test".to_string()), DecodedWord::Account(ctx.registrar_1.id()))]));
+
+    // Price keys have no decoder, prices do
+    let SlotContents::Map(prices) = &dump[&PRICES_SLOT].contents else { panic!("prices slot is not a map") };
+    assert_eq!(prices.len(), 5);
+    assert!(prices.iter().all(|(key, value)| matches!(key, DecodedWord::Raw(_)) && matches!(value, DecodedWord::Amount(_))));
+    Ok(())
+}
@@ -1,5 +1,7 @@
-use miden_crypto::{Felt, Word};
-use midenname_contracts::domain::{encode_domain, decode_domain};
+use miden_crypto::{Felt, StarkField, Word};
+use midenname_contracts::{domain::{MAX_CHAR_CODE, MAX_DOMAIN_LENGTH, checked_felt, decode_char, encode_char, encode_domain, decode_domain, generate_unique_names, looks_like_address, try_decode_domain, try_encode_domain, validate_domain}, errors::RegistryError};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::collections::{HashMap, HashSet};
 
 #[test]
 fn encode_letter() {
@@ -57,4 +59,128 @@ fn decode_multiple_felts() {
     let decoded_domain = decode_domain(encoded_word);
 
     assert_eq!(decoded_domain, "aliceandbobandjoe");
-}
\ No newline at end of file
+}
+#[test]
+fn try_encode_rejects_invalid_domains() {
+    assert_eq!(try_encode_domain(""), Err(RegistryError::EmptyDomain));
+    assert_eq!(try_encode_domain("alice_bob"), Err(RegistryError::InvalidCharacter { character: '_', position: 5 }));
+    assert_eq!(try_encode_domain("abcdefghijklmnopqrstu"), Err(RegistryError::DomainTooLong { length: 21 }));
+    assert_eq!(try_encode_domain("alice"), Ok(encode_domain("alice".to_string())));
+}
+
+#[test]
+fn validate_rejects_address_like_names() {
+    // 20 characters, the longest a name can be
+    for name in ["0x7bfb0f38b0fafa103f", "0xabcdef", "mtst1qy35qfqdn8f2vxy", "mm1arp0azyk9jugtgq", "mdev1qq"] {
+        assert_eq!(validate_domain(name), Err(RegistryError::LooksLikeAddress(name.to_string())), "{name}");
+    }
+    // Characters outside the hex digits or the bech32 charset can't be part of an address
+    for name in ["0xalice", "mtst1bob", "mtst", "mm1", "alice0x12", "mtstalice"] {
+        assert!(!looks_like_address(name), "{name}");
+        assert_eq!(validate_domain(name), Ok(()));
+    }
+    // Already registered look-alikes can still be encoded for lookups and management
+    assert!(try_encode_domain("0xabcdef").is_ok());
+}
+
+const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+fn random_name(rng: &mut StdRng) -> String {
+    let length = rng.random_range(1..=MAX_DOMAIN_LENGTH);
+    (0..length).map(|_| ALPHABET[rng.random_range(0..ALPHABET.len())] as char).collect()
+}
+
+#[test]
+fn encoding_distinguishes_trailing_a_and_length() {
+    // 'a' is 1, so a trailing 'a' only changes the low bits of the next byte and the length felt
+    let pairs = [("a", "aa"), ("ab", "aba"), ("abcdefg", "abcdefga"), ("abcdefghijklmn", "abcdefghijklmna"), ("ba", "ab")];
+    for (left, right) in pairs {
+        assert_ne!(encode_domain(left.to_string()), encode_domain(right.to_string()), "{left} and {right} collide");
+    }
+}
+
+#[test]
+fn encoding_is_injective_up_to_three_chars() {
+    let chars: Vec<String> = ALPHABET.iter().map(|&c| (c as char).to_string()).collect();
+    let mut names = chars.clone();
+    for length in 2..=3 {
+        let shorter: Vec<String> = names.iter().filter(|name| name.len() == length - 1).cloned().collect();
+        names.extend(shorter.iter().flat_map(|name| chars.iter().map(move |c| format!("{name}{c}"))));
+    }
+
+    let mut seen: HashMap<Vec<u64>, String> = HashMap::new();
+    for name in names {
+        let encoded = encode_domain(name.clone());
+        assert_eq!(try_decode_domain(encoded).as_deref(), Some(name.as_str()));
+        let key = encoded.to_vec().iter().map(|felt| felt.as_int()).collect();
+        if let Some(other) = seen.insert(key, name.clone()) {
+            panic!("{name} and {other} have the same encoding");
+        }
+    }
+}
+
+#[test]
+fn encoding_is_injective_over_random_pairs() {
+    let mut rng = StdRng::seed_from_u64(0x6e616d65);
+    for _ in 0..10_000 {
+        let (left, right) = (random_name(&mut rng), random_name(&mut rng));
+        let (left_word, right_word) = (encode_domain(left.clone()), encode_domain(right.clone()));
+
+        assert_eq!(left == right, left_word == right_word, "{left} and {right}");
+        assert_eq!(try_decode_domain(left_word), Some(left));
+    }
+}
+
+#[test]
+fn try_decode_rejects_non_canonical_words() {
+    let ab = encode_domain("ab".to_string());
+    // 'b' moved up one byte, leaving a gap
+    let gapped = Word::new([ab[0], ab[1], Felt::new(0x020001), ab[3]]);
+    // 8th character packed into the first felt instead of the second
+    let overfull = Word::new([Felt::new(0), Felt::new(0), Felt::new(0x0807060504030201), Felt::new(8)]);
+    let bad_char = Word::new([Felt::new(0), Felt::new(0), Felt::new(0x25), Felt::new(1)]);
+
+    for word in [gapped, overfull, bad_char, Word::default()] {
+        assert_eq!(try_decode_domain(word), None, "{word:?} should not decode");
+    }
+}
+
+#[test]
+fn generated_names_are_unique_valid_and_deterministic() {
+    let names = generate_unique_names(2_000, 42);
+    assert_eq!(names.len(), 2_000);
+    assert_eq!(names.iter().collect::<HashSet<_>>().len(), names.len());
+    for name in &names {
+        assert!(validate_domain(name).is_ok(), "{name} is not a valid domain");
+    }
+
+    assert_eq!(generate_unique_names(2_000, 42), names);
+    assert_ne!(generate_unique_names(2_000, 43), names);
+}
+
+#[test]
+fn largest_packed_name_round_trips_exactly() {
+    // '9' has the highest character code, a full length all-'9' name packs the largest felts
+    assert_eq!(encode_char('9'), Some(MAX_CHAR_CODE));
+    let name = "9".repeat(MAX_DOMAIN_LENGTH);
+
+    let encoded = try_encode_domain(&name).unwrap();
+    assert_eq!(encoded[0], Felt::new(0x242424242424));
+    assert_eq!(encoded[1], Felt::new(0x24242424242424));
+    assert_eq!(encoded[2], Felt::new(0x24242424242424));
+    assert_eq!(encoded[3], Felt::new(MAX_DOMAIN_LENGTH as u64));
+    assert!(encoded.iter().all(|felt| felt.as_int() < Felt::MODULUS));
+
+    assert_eq!(try_decode_domain(encoded), Some(name.clone()));
+    assert_eq!(decode_domain(encoded), name);
+}
+
+#[test]
+fn character_table_and_checked_felts() {
+    assert_eq!(decode_char(MAX_CHAR_CODE), Some('9'));
+    assert_eq!(decode_char(MAX_CHAR_CODE + 1), None);
+
+    assert_eq!(checked_felt(Felt::MODULUS - 1), Ok(Felt::new(Felt::MODULUS - 1)));
+    assert_eq!(checked_felt(Felt::MODULUS), Err(RegistryError::FeltOverflow { value: Felt::MODULUS }));
+    assert_eq!(checked_felt(u64::MAX), Err(RegistryError::FeltOverflow { value: u64::MAX }));
+}
@@ -0,0 +1,106 @@
+use std::{collections::{BTreeSet, HashSet}, fs};
+
+use midenname_contracts::errors::{CONTRACT_ERROR_CODES, MidenIdError, RegistryError, contract_error_code};
+
+// Golden list, frontends translate on these codes. A failing assert here means a code changed:
+// add a new variant with a new code instead of renaming an existing one.
+fn registry_error_codes() -> Vec<(RegistryError, &'static str)> {
+    vec![
+        (RegistryError::ZeroPrice, "ZERO_PRICE"),
+        (RegistryError::PriceExceedsFieldModulus { price: 0 }, "PRICE_TOO_HIGH"),
+        (RegistryError::PriceNotU32 { price: 0 }, "PRICE_NOT_U32"),
+        (RegistryError::PriceOverflow { yearly_price: 0, years: 0 }, "PRICE_OVERFLOW"),
+        (RegistryError::InvalidLetterCount { letter_count: 0 }, "INVALID_LETTER_COUNT"),
+        (RegistryError::StorageSlotMissing { slot: 0 }, "STORAGE_SLOT_MISSING"),
+        (RegistryError::StorageSlotNotMap { slot: 0 }, "STORAGE_SLOT_NOT_MAP"),
+        (RegistryError::EmptyDomain, "EMPTY_NAME"),
+        (RegistryError::DomainTooLong { length: 0 }, "NAME_TOO_LONG"),
+        (RegistryError::InvalidCharacter { character: '_', position: 0 }, "INVALID_CHAR"),
+        (RegistryError::InvalidNoteInputs { expected: 0, actual: 0 }, "INVALID_NOTE_INPUTS"),
+        (RegistryError::TooManyNoteInputs { count: 0, max: 0 }, "TOO_MANY_NOTE_INPUTS"),
+        (RegistryError::InvalidNoteField { template: "", field: "", reason: "" }, "INVALID_NOTE_FIELD"),
+        (RegistryError::UnknownNoteTemplate(String::new()), "UNKNOWN_NOTE_TEMPLATE"),
+        (RegistryError::OwnerNotSet, "REGISTRY_NOT_INITIALIZED"),
+        (RegistryError::AccountNotTracked(String::new()), "ACCOUNT_NOT_TRACKED"),
+        (RegistryError::NameNotFound(String::new()), "NAME_NOT_FOUND"),
+        (RegistryError::InvalidAddress(String::new()), "INVALID_ADDRESS"),
+        (RegistryError::InvalidRegistrationYears { years: 0 }, "INVALID_REGISTRATION_YEARS"),
+        (RegistryError::PaymentAssetCount { expected: 0, actual: 0 }, "INVALID_PAYMENT_ASSETS"),
+        (RegistryError::WrongPaymentAsset { expected: String::new(), actual: String::new() }, "WRONG_PAYMENT_ASSET"),
+        (RegistryError::KeyNotFound(String::new()), "KEY_NOT_FOUND"),
+        (RegistryError::MissingAccountSeed(String::new()), "MISSING_ACCOUNT_SEED"),
+        (RegistryError::InvalidMnemonic(String::new()), "INVALID_MNEMONIC"),
+        (RegistryError::InvalidTokenSymbol { symbol: String::new(), reason: "" }, "INVALID_TOKEN_SYMBOL"),
+        (RegistryError::DuplicateInBatch { name: String::new() }, "DUPLICATE_IN_BATCH"),
+        (RegistryError::InvalidSweepBatch { count: 0, max: 0 }, "INVALID_SWEEP_BATCH"),
+        (RegistryError::UnexpectedHealthReport { version: 0, features: 0, slot_count: 0 }, "UNEXPECTED_HEALTH_REPORT"),
+        (RegistryError::StaleState { local_height: 0 }, "STALE_STATE"),
+        (RegistryError::ClassMultiplierTooHigh { bps: 0 }, "CLASS_MULTIPLIER_TOO_HIGH"),
+        (RegistryError::ClassPriceOverflow { price: 0, bps: 0 }, "CLASS_PRICE_OVERFLOW"),
+        (RegistryError::SenderMismatch { sender: String::new() }, "SENDER_MISMATCH"),
+        (RegistryError::InvalidProposal(String::new()), "INVALID_PROPOSAL"),
+        (RegistryError::TransactionRejected { tx_id: String::new(), reason: String::new() }, "TRANSACTION_REJECTED"),
+        (RegistryError::TransactionTimeout { tx_id: String::new(), waited_secs: 0 }, "TRANSACTION_TIMEOUT"),
+        (RegistryError::PollTimeout { what: String::new(), waited_ms: 0 }, "POLL_TIMEOUT"),
+        (RegistryError::InvalidAmount { amount: String::new(), reason: "" }, "INVALID_AMOUNT"),
+        (RegistryError::NotAFungibleFaucet(String::new()), "NOT_A_FUNGIBLE_FAUCET"),
+        (RegistryError::LooksLikeAddress(String::new()), "LOOKS_LIKE_ADDRESS"),
+        (RegistryError::InvalidStorageProof { account_id: String::new(), reason: "" }, "INVALID_STORAGE_PROOF"),
+        (RegistryError::InconsistentDomainState { name: String::new(), reason: "" }, "INCONSISTENT_DOMAIN_STATE"),
+        (RegistryError::InvalidCertificate { name: String::new(), reason: "" }, "INVALID_CERTIFICATE"),
+        (RegistryError::NoteParamsMismatch { template: String::new(), reason: "" }, "NOTE_PARAMS_MISMATCH"),
+        (RegistryError::FeltOverflow { value: 0 }, "FELT_OVERFLOW"),
+        (RegistryError::NoPaymentRecord(String::new()), "NO_PAYMENT_RECORD"),
+        (RegistryError::RegistrationNotAuthorized { name: String::new(), beneficiary: String::new() }, "REGISTRATION_NOT_AUTHORIZED"),
+    ]
+}
+
+#[test]
+fn test_registry_error_codes_are_stable_and_unique() {
+    let golden = registry_error_codes();
+    let unique: HashSet<&str> = golden.iter().map(|(_, code)| *code).collect();
+    assert_eq!(unique.len(), golden.len(), "two registry errors share a code");
+
+    for (err, code) in golden {
+        assert_eq!(err.code(), code, "code of {err:?} changed");
+        assert_eq!(MidenIdError::from(err).code(), code);
+    }
+}
+
+#[test]
+fn test_contract_error_messages_are_unique() {
+    let messages: HashSet<&str> = CONTRACT_ERROR_CODES.iter().map(|(message, _)| *message).collect();
+    assert_eq!(messages.len(), CONTRACT_ERROR_CODES.len());
+
+    // A message contained in another one would shadow it, unless both map to the same code
+    for (message, code) in CONTRACT_ERROR_CODES {
+        assert_eq!(contract_error_code(message), Some(*code), "'{message}' is shadowed by an earlier entry");
+    }
+}
+
+#[test]
+fn test_every_masm_assert_message_has_a_code() {
+    let mut sources = vec![fs::read_to_string("./masm/accounts/naming.masm").unwrap()];
+    for entry in fs::read_dir("./masm/notes").unwrap() {
+        sources.push(fs::read_to_string(entry.unwrap().path()).unwrap());
+    }
+
+    let messages: BTreeSet<String> = sources.iter()
+        .flat_map(|source| source.lines())
+        .filter_map(|line| line.trim().strip_prefix("const.ERR_"))
+        .filter_map(|line| line.split_once("=\"").map(|(_, rest)| rest.trim_end_matches('"').to_string()))
+        .collect();
+    assert!(!messages.is_empty());
+
+    for message in messages {
+        assert!(contract_error_code(&message).is_some(), "MASM error '{message}' has no code");
+    }
+}
+
+#[test]
+fn test_executor_error_maps_to_contract_code() {
+    let error = "transaction program execution failed: assertion failed at clock cycle 1234 with error message: Domain is already taken";
+    assert_eq!(contract_error_code(error), Some("NAME_TAKEN"));
+    assert_eq!(contract_error_code("assertion failed with error message: Paid amount less than price"), Some("INSUFFICIENT_PAYMENT"));
+    assert_eq!(contract_error_code("connection refused"), None);
+}
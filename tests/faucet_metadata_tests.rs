@@ -0,0 +1,38 @@
+use miden_client::{account::{AccountBuilder, AccountStorageMode, AccountType}, auth::NoAuth};
+use miden_crypto::Felt;
+use miden_lib::account::faucets::BasicFungibleFaucet;
+use miden_objects::asset::TokenSymbol;
+use miden_testing::MockChain;
+use midenname_contracts::{errors::RegistryError, testing::{create_test_naming_account, init_naming}, token::{FaucetMetadata, faucet_metadata, format_token_amount, token_label}};
+
+#[test]
+fn test_faucet_metadata_of_mock_chain_faucet() -> anyhow::Result<()> {
+    let faucet = AccountBuilder::new([7; 32])
+        .account_type(AccountType::FungibleFaucet)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(BasicFungibleFaucet::new(TokenSymbol::new("REG")?, 6, Felt::new(21_000_000_000_000))?)
+        .build_existing()?;
+    let mut builder = MockChain::builder();
+    builder.add_account(faucet.clone())?;
+    let chain = builder.build()?;
+
+    let metadata = faucet_metadata(&chain.committed_account(faucet.id())?)?;
+    assert_eq!(metadata, FaucetMetadata { symbol: "REG".to_string(), decimals: 6, max_supply: 21_000_000_000_000 });
+    assert_eq!(metadata.format_amount(1_500_000), "1.5 REG");
+    assert_eq!(token_label(Some(&metadata), faucet.id()), format!("REG ({})", faucet.id().to_hex()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_non_faucet_accounts_fall_back_to_raw_output() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let naming = create_test_naming_account();
+
+    for account in [&naming, &ctx.registrar_1] {
+        assert_eq!(faucet_metadata(account), Err(RegistryError::NotAFungibleFaucet(account.id().to_hex())));
+    }
+    assert_eq!(format_token_amount(None, 1_500_000), "1500000");
+    assert_eq!(token_label(None, naming.id()), naming.id().to_hex());
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use std::fs;
+
+use miden_client::asset::FungibleAsset;
+use midenname_contracts::{compile::CompileOptions, domain::encode_domain, notes::{create_naming_register_name_note_from_name, create_naming_transfer_domain_note}, errors::RegistryError, testing::{add_note_to_builder, create_test_faucet, execute_note, execute_notes_and_build_chain, init_naming}};
+
+// A context built from an Account value runs against whatever state that clone had.
+// Tests go through execute_note or build_tx_context with an account id instead.
+#[test]
+fn test_no_transaction_context_is_built_from_an_account_clone() {
+    for entry in fs::read_dir("./tests").unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "rs") || path.ends_with("fixture_tests.rs") {
+            continue;
+        }
+        let source = fs::read_to_string(&path).unwrap();
+        assert!(!source.contains("TransactionContextBuilder::new("), "{} builds a transaction context from an account value", path.display());
+        for line in source.lines().filter(|line| line.contains("build_tx_context(")) {
+            let target = line.split("build_tx_context(").nth(1).unwrap().split(',').next().unwrap();
+            assert!(target.ends_with(".id()"), "{}: context target `{target}` is not an account id", path.display());
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_execute_note_keeps_account_in_sync_across_registration_and_transfer() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    let register_note = create_naming_register_name_note_from_name(ctx.registrar_1.id(), ctx.naming.id(), "test", FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let transfer_note = create_naming_transfer_domain_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("test".to_string()), ctx.registrar_2.id(), CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, transfer_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id()], &mut ctx.naming).await?;
+    let nonce_after_register = ctx.naming.nonce().as_int();
+    execute_note(&mut chain, transfer_note.id(), &mut ctx.naming).await?;
+
+    assert!(ctx.naming.nonce().as_int() > nonce_after_register);
+    assert_eq!(chain.committed_account(ctx.naming.id())?.commitment(), ctx.naming.commitment());
+    Ok(())
+}
+
+#[test]
+fn test_create_test_faucet_rejects_invalid_symbols() -> anyhow::Result<()> {
+    let mut builder = miden_testing::MockChain::builder();
+
+    let err = create_test_faucet(&mut builder, "ABCDEFGHIJKLMNOPQRST", 1_000_000).expect_err("Expected a 20 character symbol to fail");
+    assert_eq!(err.downcast_ref::<RegistryError>(), Some(&RegistryError::InvalidTokenSymbol { symbol: "ABCDEFGHIJKLMNOPQRST".to_string(), reason: "at most 6 characters allowed" }));
+    for symbol in ["", "usd", "MIDÉN", "US-D"] {
+        let err = create_test_faucet(&mut builder, symbol, 1_000_000).expect_err("Expected invalid symbol to fail");
+        assert!(matches!(err.downcast_ref::<RegistryError>(), Some(RegistryError::InvalidTokenSymbol { .. })), "Unexpected error for '{symbol}': {err}");
+    }
+
+    let faucet = create_test_faucet(&mut builder, "USDC", 1_000_000)?;
+    assert!(faucet.is_faucet());
+    Ok(())
+}
@@ -0,0 +1,58 @@
+mod test_utils;
+
+use miden_client::{asset::FungibleAsset, note::NoteType};
+use miden_crypto::{Felt, Word};
+use midenname_contracts::{compile::CompileOptions, health::{HEALTH_CHECK_VERSION, naming_features}, roots::naming_procedure_root, storage::NAMING_STORAGE_LAYOUT, testing::{execute_notes_and_build_chain, foreign_inputs_for, init_naming}, transaction::create_tx_script};
+
+use crate::test_utils::create_p2id_note_exact;
+
+// Calls naming::health_check through FPI and checks its output on chain
+fn health_check_fpi_script(naming_id: miden_client::account::AccountId) -> anyhow::Result<String> {
+    let root = naming_procedure_root("health_check")?.expect("health_check is exported");
+    Ok(format!("
+        use.miden::tx
+        use.std::sys
+
+        begin
+            padw padw padw padw
+            # => [pad(16)]
+            push.{root}
+            push.{suffix} push.{prefix}
+            # => [naming_prefix, naming_suffix, HEALTH_CHECK_ROOT, pad(16)]
+            exec.tx::execute_foreign_procedure
+            # => [version, features, slot_count, 0, ...]
+            push.{version} assert_eq.err=\"health check version\"
+            push.{features} assert_eq.err=\"health check features\"
+            push.{slot_count} assert_eq.err=\"health check slot count\"
+            exec.sys::truncate_stack
+        end",
+        suffix = naming_id.suffix(),
+        prefix = naming_id.prefix().as_felt(),
+        version = HEALTH_CHECK_VERSION,
+        features = naming_features(),
+        slot_count = NAMING_STORAGE_LAYOUT.len(),
+    ))
+}
+
+#[tokio::test]
+async fn test_wallet_reads_naming_storage_through_fpi() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+
+    // The wallet also receives a payment, so the transaction isn't empty
+    let payment = create_p2id_note_exact(ctx.registrar_2.id(), ctx.registrar_1.id(), vec![FungibleAsset::new(ctx.fungible_asset.faucet_id(), 10)?.into()], NoteType::Public, Felt::new(0), Word::default())?;
+    let script = create_tx_script(health_check_fpi_script(ctx.naming.id())?, None, CompileOptions::DEBUG)?;
+
+    let tx_ctx = chain.build_tx_context(ctx.registrar_1.id(), &[], &[payment.clone()])?
+        .foreign_accounts(vec![foreign_inputs_for(&chain, ctx.naming.id())?])
+        .tx_script(script.clone())
+        .build()?;
+    tx_ctx.execute().await?;
+
+    // Without the foreign inputs the kernel can't load the naming account
+    let tx_ctx = chain.build_tx_context(ctx.registrar_1.id(), &[], &[payment])?
+        .tx_script(script)
+        .build()?;
+    assert!(tx_ctx.execute().await.is_err(), "Expected FPI without foreign inputs to fail");
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use miden_client::{asset::FungibleAsset, note::{NoteAssets, NoteInputs}};
+use midenname_contracts::{compile::CompileOptions, domain::{encode_domain, hash_domain, try_decode_domain}, errors::contract_error_code, notes::{create_naming_extend_hashed_domain_note, create_naming_register_hashed_note, create_naming_register_name_note, create_note_for_naming}, queries::{Lookup, account_id_to_word, get_account_for_name, get_account_for_name_with, get_domain_owner, get_name_for_account, iter_registered_domains, list_registered_names}, storage::DOMAIN_EXPIRY_DATES_SLOT, testing::{add_note_to_builder, execute_note, execute_notes_and_build_chain, init_naming}};
+
+#[tokio::test]
+async fn test_hashed_registration_resolves_by_name() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let registrant = ctx.registrar_1.id();
+    let token = ctx.fungible_asset.faucet_id();
+    let domain = encode_domain("alice".to_string());
+    let name_hash = hash_domain(domain);
+
+    let register_note = create_naming_register_hashed_note(registrant, ctx.naming.id(), domain, FungibleAsset::new(token, 123)?, 1, CompileOptions::DEBUG).await?;
+    // Later operations name the domain by its hash
+    let activate_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(name_hash.to_vec())?, registrant, ctx.naming.id(), NoteAssets::new(vec![])?, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, activate_note.clone())?;
+
+    execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), activate_note.id()], &mut ctx.naming).await?;
+
+    assert_eq!(get_account_for_name(&ctx.naming, "alice")?, Some(registrant));
+    assert_eq!(get_account_for_name_with(&ctx.naming, "alice", Lookup::Hashed)?, Some(registrant));
+    assert_eq!(get_account_for_name_with(&ctx.naming, "alice", Lookup::Plain)?, None);
+    assert_eq!(get_domain_owner(&ctx.naming, "alice")?, Some(registrant));
+
+    // Only the hash is stored, it doesn't decode to a name
+    let keys: Vec<_> = iter_registered_domains(&ctx.naming).map(|(key, _)| key).collect();
+    assert_eq!(keys, vec![name_hash]);
+    assert_eq!(try_decode_domain(name_hash), None);
+    assert!(list_registered_names(&ctx.naming).is_empty());
+    assert_eq!(get_name_for_account(&ctx.naming, registrant)?, None);
+
+    let total_revenue = ctx.naming.storage().get_map_item(10, account_id_to_word(token))?;
+    assert_eq!(total_revenue[0].as_int(), 123);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_name_cannot_be_registered_plain_and_hashed() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let domain = encode_domain("alice".to_string());
+
+    let hashed_note = create_naming_register_hashed_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 123)?, 1, CompileOptions::DEBUG).await?;
+    let plain_note = create_naming_register_name_note(ctx.registrar_2.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 123)?, 1, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, hashed_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, plain_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), hashed_note.id()], &mut ctx.naming).await?;
+    let err = execute_note(&mut chain, plain_note.id(), &mut ctx.naming).await.expect_err("name is registered hashed");
+    assert_eq!(contract_error_code(&format!("{err:?}")), Some("NAME_TAKEN"), "Unexpected error: {err:?}");
+    assert_eq!(get_domain_owner(&ctx.naming, "alice")?, Some(ctx.registrar_1.id()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hashed_registration_renews_and_extends() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let registrant = ctx.registrar_1.id();
+    let token = ctx.fungible_asset.faucet_id();
+    let domain = encode_domain("alice".to_string());
+    let name_hash = hash_domain(domain);
+
+    let register_note = create_naming_register_hashed_note(registrant, ctx.naming.id(), domain, FungibleAsset::new(token, 123)?, 1, CompileOptions::DEBUG).await?;
+    // Sent again by the owner with the plain name it renews
+    let renew_note = create_naming_register_hashed_note(registrant, ctx.naming.id(), domain, FungibleAsset::new(token, 123)?, 1, CompileOptions::DEBUG).await?;
+    let extend_note = create_naming_extend_hashed_domain_note(registrant, ctx.naming.id(), domain, FungibleAsset::new(token, 123)?, 1, CompileOptions::DEBUG).await?;
+    let other_extend_note = create_naming_extend_hashed_domain_note(ctx.registrar_2.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 123)?, 1, CompileOptions::DEBUG).await?;
+    for note in [&register_note, &renew_note, &extend_note, &other_extend_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id()], &mut ctx.naming).await?;
+    let registered_expiry = ctx.naming.storage().get_map_item(DOMAIN_EXPIRY_DATES_SLOT, name_hash)?[0].as_int();
+
+    execute_note(&mut chain, renew_note.id(), &mut ctx.naming).await?;
+    let renewed_expiry = ctx.naming.storage().get_map_item(DOMAIN_EXPIRY_DATES_SLOT, name_hash)?[0].as_int();
+    assert_eq!(renewed_expiry, registered_expiry + ctx.one_year as u64);
+    assert_eq!(get_domain_owner(&ctx.naming, "alice")?, Some(registrant));
+
+    execute_note(&mut chain, extend_note.id(), &mut ctx.naming).await?;
+    let extended_expiry = ctx.naming.storage().get_map_item(DOMAIN_EXPIRY_DATES_SLOT, name_hash)?[0].as_int();
+    assert_eq!(extended_expiry, renewed_expiry + ctx.one_year as u64);
+
+    let err = execute_note(&mut chain, other_extend_note.id(), &mut ctx.naming).await.expect_err("only the owner extends");
+    assert_eq!(contract_error_code(&format!("{err:?}")), Some("NOT_DOMAIN_OWNER"), "Unexpected error: {err:?}");
+
+    // Nothing was stored under the plain name
+    assert_eq!(ctx.naming.storage().get_map_item(DOMAIN_EXPIRY_DATES_SLOT, domain)?, Default::default());
+    Ok(())
+}
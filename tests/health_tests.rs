@@ -0,0 +1,67 @@
+mod test_utils;
+
+use std::{fs, path::Path};
+
+use miden_client::{account::{AccountBuilder, AccountStorageMode}, auth::NoAuth};
+use miden_lib::transaction::TransactionKernel;
+use miden_objects::account::AccountComponent;
+use midenname_contracts::{accounts::add_contract_account, compile::CompileOptions, health::{HEALTH_CHECK_VERSION, NamingFeature, naming_features, run_health_check}, storage::{NAMING_STORAGE_LAYOUT, naming_storage}, testing::init_naming};
+
+use crate::test_utils::create_mock_client;
+
+#[test]
+fn test_masm_feature_bitmap_matches_features() {
+    let code = fs::read_to_string(Path::new("./masm/accounts/naming.masm")).unwrap();
+    let constant = |name: &str| -> u64 {
+        let line = code.lines().find(|line| line.starts_with(&format!("const.{name}="))).unwrap_or_else(|| panic!("{name} missing from naming.masm"));
+        line.split('=').nth(1).unwrap().split_whitespace().next().unwrap().parse().unwrap()
+    };
+
+    assert_eq!(constant("HEALTH_FEATURES"), naming_features());
+    assert_eq!(constant("HEALTH_CHECK_VERSION"), HEALTH_CHECK_VERSION);
+    assert_eq!(constant("STORAGE_SLOT_COUNT"), NAMING_STORAGE_LAYOUT.len() as u64);
+}
+
+#[tokio::test]
+async fn test_health_check_reports_compiled_features() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let naming = ctx.naming.clone();
+    let mut client = create_mock_client(ctx.builder.build()?).await?;
+    add_contract_account(&mut client, &naming, None).await?;
+    client.sync_state().await?;
+
+    let report = run_health_check(&mut client, naming.id(), CompileOptions::DEBUG).await?;
+
+    assert!(report.is_expected(), "Unexpected report: {report:?}");
+    assert_eq!(report.features, naming_features());
+    for feature in NamingFeature::ALL {
+        assert!(report.has(feature), "{feature:?} missing");
+    }
+    // Read only, the account is unchanged
+    let record = client.get_account(naming.id()).await?.unwrap();
+    assert_eq!(record.account().commitment(), naming.commitment());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_health_check_fails_on_missing_slots() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let code = fs::read_to_string(Path::new("./masm/accounts/naming.masm"))?;
+    let mut slots = naming_storage();
+    slots.truncate(20);
+    let component = AccountComponent::compile(code, TransactionKernel::assembler().with_debug_mode(true), slots)?.with_supports_all_types();
+    let truncated = AccountBuilder::new(rand::random())
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .storage_mode(AccountStorageMode::Public)
+        .build_existing()?;
+    ctx.builder.add_account(truncated.clone())?;
+
+    let mut client = create_mock_client(ctx.builder.build()?).await?;
+    add_contract_account(&mut client, &truncated, None).await?;
+    client.sync_state().await?;
+
+    let result = run_health_check(&mut client, truncated.id(), CompileOptions::DEBUG).await;
+    assert!(result.is_err(), "Expected health check of an account without the last slots to fail");
+    Ok(())
+}
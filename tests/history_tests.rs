@@ -0,0 +1,135 @@
+use miden_client::{asset::FungibleAsset, note::{NoteAssets, NoteInputs}};
+use miden_crypto::Felt;
+use midenname_contracts::{compile::CompileOptions, domain::{encode_domain, hash_domain}, history::{OwnershipChange, OwnershipLog, OwnershipPeriod, owner_at_block, ownership_timeline}, notes::{create_naming_admin_revoke_note, create_naming_deregister_note, create_naming_gc_note, create_naming_register_authorized_note, create_naming_register_hashed_note, create_naming_register_name_note, create_naming_transfer_domain_note, create_note_for_naming}, testing::{add_note_to_builder, execute_note, init_naming}};
+
+#[tokio::test]
+async fn test_ownership_history_follows_transfers_and_releases() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain("test".to_string());
+    let token = ctx.fungible_asset.faucet_id();
+    let [first, second, third] = [ctx.registrar_1.id(), ctx.registrar_2.id(), ctx.registrar_3.id()];
+
+    let notes = [
+        ctx.initialize_note.clone(),
+        ctx.set_prices_note.clone(),
+        create_naming_register_name_note(first, ctx.naming.id(), domain, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?,
+        create_naming_transfer_domain_note(first, ctx.naming.id(), domain, second, CompileOptions::DEBUG).await?,
+        create_naming_deregister_note(second, ctx.naming.id(), domain, CompileOptions::DEBUG).await?,
+        create_naming_register_name_note(third, ctx.naming.id(), domain, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?,
+    ];
+    for note in &notes[2..] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    // Each note is consumed in its own block
+    let mut chain = ctx.builder.build()?;
+    let mut consumed = Vec::new();
+    for note in &notes {
+        execute_note(&mut chain, note.id(), &mut ctx.naming).await?;
+        consumed.push((chain.latest_block_header().block_num().as_u32(), note.clone()));
+    }
+    let blocks: Vec<u32> = consumed.iter().map(|(block_num, _)| *block_num).collect();
+
+    // Setup notes don't change ownership
+    let log = OwnershipLog::from_notes(consumed.into_iter().rev())?;
+    let changes: Vec<OwnershipChange> = log.events.iter().map(|event| event.change).collect();
+    assert_eq!(changes, vec![OwnershipChange::Registered(first), OwnershipChange::Transferred(second), OwnershipChange::Released, OwnershipChange::Registered(third)]);
+
+    assert_eq!(ownership_timeline(&log, "test")?, vec![
+        OwnershipPeriod { owner: Some(first), from_block: blocks[2], until_block: Some(blocks[3]) },
+        OwnershipPeriod { owner: Some(second), from_block: blocks[3], until_block: Some(blocks[4]) },
+        OwnershipPeriod { owner: None, from_block: blocks[4], until_block: Some(blocks[5]) },
+        OwnershipPeriod { owner: Some(third), from_block: blocks[5], until_block: None },
+    ]);
+    assert!(ownership_timeline(&log, "other")?.is_empty());
+
+    assert_eq!(owner_at_block(&log, "test", blocks[1])?, None);
+    assert_eq!(owner_at_block(&log, "test", blocks[2])?, Some(first));
+    assert_eq!(owner_at_block(&log, "test", blocks[3])?, Some(second));
+    assert_eq!(owner_at_block(&log, "test", blocks[4])?, None);
+    assert_eq!(owner_at_block(&log, "test", blocks[5] + 100)?, Some(third));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ownership_history_follows_hashed_registrations() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let domain = encode_domain("test".to_string());
+    let token = ctx.fungible_asset.faucet_id();
+    let [first, second] = [ctx.registrar_1.id(), ctx.registrar_2.id()];
+
+    let register_note = create_naming_register_hashed_note(first, ctx.naming.id(), domain, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    // Later notes name the domain by its hash
+    let transfer_note = create_naming_transfer_domain_note(first, ctx.naming.id(), hash_domain(domain), second, CompileOptions::DEBUG).await?;
+    // The contract takes any non zero hashed flag
+    let inputs = NoteInputs::new(vec![token.suffix(), token.prefix().as_felt(), Felt::new(0), Felt::new(0), domain[0], domain[1], domain[2], domain[3], Felt::new(1), Felt::new(7), Felt::new(0), Felt::new(0)])?;
+    let flagged_note = create_note_for_naming("register_name".to_string(), inputs, second, ctx.naming.id(), NoteAssets::new(vec![FungibleAsset::new(token, 555)?.into()])?, CompileOptions::DEBUG).await?;
+
+    let log = OwnershipLog::from_notes([(10, register_note), (11, transfer_note), (12, flagged_note)])?;
+    let keys: Vec<_> = log.events.iter().map(|event| event.domain).collect();
+    assert_eq!(keys, vec![hash_domain(domain); 3]);
+
+    assert_eq!(ownership_timeline(&log, "test")?, vec![
+        OwnershipPeriod { owner: Some(first), from_block: 10, until_block: Some(11) },
+        OwnershipPeriod { owner: Some(second), from_block: 11, until_block: None },
+    ]);
+    assert_eq!(owner_at_block(&log, "test", 10)?, Some(first));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ownership_history_releases_swept_names() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let domains = [encode_domain("test".to_string()), encode_domain("demo".to_string())];
+    let token = ctx.fungible_asset.faucet_id();
+    let first = ctx.registrar_1.id();
+
+    let register_1 = create_naming_register_name_note(first, ctx.naming.id(), domains[0], FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let register_2 = create_naming_register_name_note(first, ctx.naming.id(), domains[1], FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let gc_note = create_naming_gc_note(ctx.registrar_3.id(), ctx.naming.id(), &domains, CompileOptions::DEBUG).await?;
+
+    // One release per name of the batch, the empty entries are not events
+    let log = OwnershipLog::from_notes([(10, register_1), (11, register_2), (20, gc_note)])?;
+    let changes: Vec<_> = log.events.iter().map(|event| (event.block_num, event.domain, event.change)).collect();
+    assert_eq!(changes, vec![
+        (10, domains[0], OwnershipChange::Registered(first)),
+        (11, domains[1], OwnershipChange::Registered(first)),
+        (20, domains[0], OwnershipChange::Released),
+        (20, domains[1], OwnershipChange::Released),
+    ]);
+    assert_eq!(owner_at_block(&log, "test", 19)?, Some(first));
+    assert_eq!(owner_at_block(&log, "demo", 20)?, None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ownership_history_releases_revoked_names() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let domain = encode_domain("test".to_string());
+    let token = ctx.fungible_asset.faucet_id();
+    let first = ctx.registrar_1.id();
+
+    let register_note = create_naming_register_name_note(first, ctx.naming.id(), domain, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let revoke_note = create_naming_admin_revoke_note(ctx.owner.id(), ctx.naming.id(), domain, Some(first), CompileOptions::DEBUG).await?;
+
+    let log = OwnershipLog::from_notes([(10, register_note), (12, revoke_note)])?;
+    assert_eq!(ownership_timeline(&log, "test")?, vec![
+        OwnershipPeriod { owner: Some(first), from_block: 10, until_block: Some(12) },
+        OwnershipPeriod { owner: None, from_block: 12, until_block: None },
+    ]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ownership_history_credits_authorized_beneficiary() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let domain = encode_domain("test".to_string());
+    let token = ctx.fungible_asset.faucet_id();
+    let [relayer, beneficiary] = [ctx.registrar_1.id(), ctx.registrar_2.id()];
+
+    let register_note = create_naming_register_authorized_note(relayer, ctx.naming.id(), beneficiary, domain, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+
+    let log = OwnershipLog::from_notes([(10, register_note)])?;
+    assert_eq!(owner_at_block(&log, "test", 10)?, Some(beneficiary));
+    Ok(())
+}
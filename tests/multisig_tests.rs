@@ -0,0 +1,139 @@
+use miden_client::{account::Account, note::{Note, NoteId}};
+use miden_crypto::{Felt, Word};
+use miden_testing::MockChain;
+use midenname_contracts::{compile::CompileOptions, errors::contract_error_code, notes::{SetPriceInput, action_hash, create_naming_add_multisig_owner_note, create_naming_approve_action_note, create_naming_set_multisig_threshold_note, create_naming_set_price_note}, queries::{get_multisig_owners, get_multisig_threshold, has_approved_action}, storage::PRICES_SLOT, testing::{NamingFixture, add_note_to_builder, execute_note, execute_notes_and_build_chain, init_naming}};
+
+// Registrars 1 to 3 become multisig owners with a 2 of 3 threshold
+async fn multisig_setup_notes(ctx: &NamingFixture) -> anyhow::Result<Vec<Note>> {
+    let mut notes = Vec::new();
+    for account in [&ctx.registrar_1, &ctx.registrar_2, &ctx.registrar_3] {
+        notes.push(create_naming_add_multisig_owner_note(ctx.owner.id(), ctx.naming.id(), account.id(), CompileOptions::DEBUG).await?);
+    }
+    notes.push(create_naming_set_multisig_threshold_note(ctx.owner.id(), ctx.naming.id(), 2, CompileOptions::DEBUG).await?);
+    Ok(notes)
+}
+
+// Consumes all notes in one transaction, in the given order
+async fn execute_batch(chain: &mut MockChain, note_ids: &[NoteId], target: &mut Account) -> anyhow::Result<()> {
+    let executed_tx = chain.build_tx_context(target.id(), note_ids, &[])?.build()?.execute().await?;
+    target.apply_delta(&executed_tx.account_delta())?;
+    chain.add_pending_executed_transaction(&executed_tx)?;
+    chain.prove_next_block()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_owner_action_runs_with_two_of_three_approvals() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let setup = multisig_setup_notes(&ctx).await?;
+
+    let set_price_note = create_naming_set_price_note(ctx.registrar_1.id(), ctx.naming.id(), token, SetPriceInput { letter_count: 3, price: 777 }, false, CompileOptions::DEBUG).await?;
+    let action = action_hash(&set_price_note);
+    let approve_1 = create_naming_approve_action_note(ctx.registrar_1.id(), ctx.naming.id(), action, CompileOptions::DEBUG).await?;
+    let approve_2 = create_naming_approve_action_note(ctx.registrar_2.id(), ctx.naming.id(), action, CompileOptions::DEBUG).await?;
+    for note in setup.iter().chain([&set_price_note, &approve_1, &approve_2]) {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut note_ids = vec![ctx.initialize_note.id(), ctx.set_prices_note.id()];
+    note_ids.extend(setup.iter().map(|note| note.id()));
+    note_ids.extend([approve_1.id(), approve_2.id()]);
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &note_ids, &mut ctx.naming).await?;
+
+    assert_eq!(get_multisig_owners(&ctx.naming)?, vec![ctx.registrar_1.id(), ctx.registrar_2.id(), ctx.registrar_3.id()]);
+    assert_eq!(get_multisig_threshold(&ctx.naming)?, 2);
+    assert!(has_approved_action(&ctx.naming, action, ctx.registrar_2.id())?);
+
+    execute_note(&mut chain, set_price_note.id(), &mut ctx.naming).await?;
+    let price_key = Word::new([token.suffix(), token.prefix().as_felt(), Felt::new(3), Felt::new(0)]);
+    assert_eq!(ctx.naming.storage().get_map_item(PRICES_SLOT, price_key)?[0].as_int(), 777);
+
+    // Approvals are used up by the execution
+    assert!(!has_approved_action(&ctx.naming, action, ctx.registrar_1.id())?);
+    assert!(!has_approved_action(&ctx.naming, action, ctx.registrar_2.id())?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_owner_action_rejected_with_one_of_three_approvals() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let setup = multisig_setup_notes(&ctx).await?;
+
+    let set_price_note = create_naming_set_price_note(ctx.registrar_1.id(), ctx.naming.id(), token, SetPriceInput { letter_count: 3, price: 777 }, false, CompileOptions::DEBUG).await?;
+    let approve_1 = create_naming_approve_action_note(ctx.registrar_1.id(), ctx.naming.id(), action_hash(&set_price_note), CompileOptions::DEBUG).await?;
+    // The registry owner alone is no longer enough
+    let owner_note = create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), token, SetPriceInput { letter_count: 3, price: 777 }, false, CompileOptions::DEBUG).await?;
+    for note in setup.iter().chain([&set_price_note, &approve_1, &owner_note]) {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut note_ids = vec![ctx.initialize_note.id(), ctx.set_prices_note.id()];
+    note_ids.extend(setup.iter().map(|note| note.id()));
+    note_ids.push(approve_1.id());
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &note_ids, &mut ctx.naming).await?;
+    let price_key = Word::new([token.suffix(), token.prefix().as_felt(), Felt::new(3), Felt::new(0)]);
+    let price_before = ctx.naming.storage().get_map_item(PRICES_SLOT, price_key)?;
+
+    let err = execute_note(&mut chain, set_price_note.id(), &mut ctx.naming).await.expect_err("only one approval");
+    assert_eq!(contract_error_code(&format!("{err:?}")), Some("ACTION_NOT_APPROVED"), "Unexpected error: {err:?}");
+
+    let err = execute_note(&mut chain, owner_note.id(), &mut ctx.naming).await.expect_err("registry owner is not a multisig owner");
+    assert_eq!(contract_error_code(&format!("{err:?}")), Some("NOT_MULTISIG_OWNER"), "Unexpected error: {err:?}");
+
+    assert_eq!(ctx.naming.storage().get_map_item(PRICES_SLOT, price_key)?, price_before);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_approved_action_runs_after_other_notes_in_batch() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let setup = multisig_setup_notes(&ctx).await?;
+
+    let set_price_note = create_naming_set_price_note(ctx.registrar_1.id(), ctx.naming.id(), token, SetPriceInput { letter_count: 3, price: 777 }, false, CompileOptions::DEBUG).await?;
+    let action = action_hash(&set_price_note);
+    let approve_1 = create_naming_approve_action_note(ctx.registrar_1.id(), ctx.naming.id(), action, CompileOptions::DEBUG).await?;
+    let approve_2 = create_naming_approve_action_note(ctx.registrar_2.id(), ctx.naming.id(), action, CompileOptions::DEBUG).await?;
+    for note in setup.iter().chain([&set_price_note, &approve_1, &approve_2]) {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut note_ids = vec![ctx.initialize_note.id(), ctx.set_prices_note.id()];
+    note_ids.extend(setup.iter().map(|note| note.id()));
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &note_ids, &mut ctx.naming).await?;
+
+    // The action is not the first note, its own recipient and sender must be checked
+    execute_batch(&mut chain, &[approve_1.id(), approve_2.id(), set_price_note.id()], &mut ctx.naming).await?;
+    let price_key = Word::new([token.suffix(), token.prefix().as_felt(), Felt::new(3), Felt::new(0)]);
+    assert_eq!(ctx.naming.storage().get_map_item(PRICES_SLOT, price_key)?[0].as_int(), 777);
+    assert!(!has_approved_action(&ctx.naming, action, ctx.registrar_1.id())?);
+    assert!(!has_approved_action(&ctx.naming, action, ctx.registrar_2.id())?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_outsider_approval_bundled_after_owner_approval_rejected() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let setup = multisig_setup_notes(&ctx).await?;
+
+    let set_price_note = create_naming_set_price_note(ctx.registrar_1.id(), ctx.naming.id(), token, SetPriceInput { letter_count: 3, price: 777 }, false, CompileOptions::DEBUG).await?;
+    let action = action_hash(&set_price_note);
+    let approve_1 = create_naming_approve_action_note(ctx.registrar_1.id(), ctx.naming.id(), action, CompileOptions::DEBUG).await?;
+    // The registry owner is not a multisig owner, its approval must not be credited to registrar_1
+    let outsider_approve = create_naming_approve_action_note(ctx.owner.id(), ctx.naming.id(), action, CompileOptions::DEBUG).await?;
+    for note in setup.iter().chain([&approve_1, &outsider_approve]) {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut note_ids = vec![ctx.initialize_note.id(), ctx.set_prices_note.id()];
+    note_ids.extend(setup.iter().map(|note| note.id()));
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &note_ids, &mut ctx.naming).await?;
+
+    let err = execute_batch(&mut chain, &[approve_1.id(), outsider_approve.id()], &mut ctx.naming).await.expect_err("outsider approval");
+    assert_eq!(contract_error_code(&format!("{err:?}")), Some("NOT_MULTISIG_OWNER"), "Unexpected error: {err:?}");
+    assert!(!has_approved_action(&ctx.naming, action, ctx.registrar_1.id())?);
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use miden_client::asset::FungibleAsset;
+use miden_crypto::{Felt, Word};
+use midenname_contracts::{compile::CompileOptions, domain::encode_domain, errors::contract_error_code, notes::{COMMITMENT_REVEAL_WINDOW, create_naming_commit_note, create_naming_register_name_note, create_naming_reveal_note, create_naming_set_commit_reveal_delay_note, registration_commitment}, queries::account_id_from_felts, testing::{add_note_to_builder, execute_note, execute_notes_and_build_chain, init_naming}};
+
+#[tokio::test]
+async fn test_commit_reveal_register() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain("alice".to_string());
+    let salt = Word::new([Felt::new(11), Felt::new(22), Felt::new(33), Felt::new(44)]);
+    let payment = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 123)?;
+    let commitment = registration_commitment(domain, salt, ctx.registrar_1.id());
+
+    let delay_note = create_naming_set_commit_reveal_delay_note(ctx.owner.id(), ctx.naming.id(), 2, CompileOptions::DEBUG).await?;
+    let commit_note = create_naming_commit_note(ctx.registrar_1.id(), ctx.naming.id(), commitment, CompileOptions::DEBUG).await?;
+    let reveal_note = create_naming_reveal_note(ctx.registrar_1.id(), ctx.naming.id(), domain, salt, payment, 1, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, delay_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, commit_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, reveal_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), delay_note.id(), commit_note.id()], &mut ctx.naming).await?;
+
+    let committed_block = ctx.naming.storage().get_map_item(16, commitment)?;
+    assert_ne!(committed_block, Word::default());
+
+    // Delay not passed yet
+    let result = execute_note(&mut chain, reveal_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected early reveal to fail");
+
+    chain.prove_next_block()?;
+    execute_note(&mut chain, reveal_note.id(), &mut ctx.naming).await?;
+
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain)?;
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.registrar_1.id());
+    // Commitment is consumed by the reveal
+    assert_eq!(ctx.naming.storage().get_map_item(16, commitment)?, Word::default());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_front_runner_blocked_by_commitment() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain("alice".to_string());
+    let salt = Word::new([Felt::new(11), Felt::new(22), Felt::new(33), Felt::new(44)]);
+    let commitment = registration_commitment(domain, salt, ctx.registrar_1.id());
+
+    let delay_note = create_naming_set_commit_reveal_delay_note(ctx.owner.id(), ctx.naming.id(), 1, CompileOptions::DEBUG).await?;
+    let commit_note = create_naming_commit_note(ctx.registrar_1.id(), ctx.naming.id(), commitment, CompileOptions::DEBUG).await?;
+    // Front-runner saw the public reveal note and tries to take the name first
+    let direct_register_note = create_naming_register_name_note(ctx.registrar_2.id(), ctx.naming.id(), domain, FungibleAsset::new(ctx.fungible_asset.faucet_id(), 123)?, 1, CompileOptions::DEBUG).await?;
+    let copied_reveal_note = create_naming_reveal_note(ctx.registrar_2.id(), ctx.naming.id(), domain, salt, FungibleAsset::new(ctx.fungible_asset.faucet_id(), 123)?, 1, CompileOptions::DEBUG).await?;
+    let reveal_note = create_naming_reveal_note(ctx.registrar_1.id(), ctx.naming.id(), domain, salt, FungibleAsset::new(ctx.fungible_asset.faucet_id(), 123)?, 1, CompileOptions::DEBUG).await?;
+    for note in [&delay_note, &commit_note, &direct_register_note, &copied_reveal_note, &reveal_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), delay_note.id(), commit_note.id()], &mut ctx.naming).await?;
+    chain.prove_next_block()?;
+
+    let result = execute_note(&mut chain, direct_register_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected direct register to fail while commit-reveal is enabled");
+
+    // Commitment is bound to the original registrant
+    let result = execute_note(&mut chain, copied_reveal_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected reveal by another sender to fail");
+
+    execute_note(&mut chain, reveal_note.id(), &mut ctx.naming).await?;
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain)?;
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.registrar_1.id());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_expired_commitment_can_not_be_revealed_and_can_be_made_again() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain("alice".to_string());
+    let salt = Word::new([Felt::new(11), Felt::new(22), Felt::new(33), Felt::new(44)]);
+    let payment = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 123)?;
+    let commitment = registration_commitment(domain, salt, ctx.registrar_1.id());
+
+    let delay_note = create_naming_set_commit_reveal_delay_note(ctx.owner.id(), ctx.naming.id(), 2, CompileOptions::DEBUG).await?;
+    let commit_note = create_naming_commit_note(ctx.registrar_1.id(), ctx.naming.id(), commitment, CompileOptions::DEBUG).await?;
+    let recommit_note = create_naming_commit_note(ctx.registrar_1.id(), ctx.naming.id(), commitment, CompileOptions::DEBUG).await?;
+    let reveal_note = create_naming_reveal_note(ctx.registrar_1.id(), ctx.naming.id(), domain, salt, payment, 1, CompileOptions::DEBUG).await?;
+    for note in [&delay_note, &commit_note, &recommit_note, &reveal_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), delay_note.id(), commit_note.id()], &mut ctx.naming).await?;
+    // Stored as the commit block + 1, never zero
+    let stored_block = ctx.naming.storage().get_map_item(16, commitment)?[0].as_int() as u32;
+    assert!(stored_block >= 1);
+    let last_reveal_block = stored_block - 1 + 2 + COMMITMENT_REVEAL_WINDOW;
+
+    // A live commitment can't be made again
+    let err = execute_note(&mut chain, recommit_note.id(), &mut ctx.naming).await.expect_err("Expected a live commitment to be kept");
+    assert_eq!(contract_error_code(&format!("{err:?}")), Some("COMMITMENT_EXISTS"), "Unexpected error: {err:?}");
+
+    chain.prove_until_block(last_reveal_block + 1)?;
+    let err = execute_note(&mut chain, reveal_note.id(), &mut ctx.naming).await.expect_err("Expected an expired commitment to be rejected");
+    assert_eq!(contract_error_code(&format!("{err:?}")), Some("COMMITMENT_EXPIRED"), "Unexpected error: {err:?}");
+
+    // Committing again restarts the delay
+    execute_note(&mut chain, recommit_note.id(), &mut ctx.naming).await?;
+    let result = execute_note(&mut chain, reveal_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected the renewed commitment to wait for the delay");
+    chain.prove_next_block()?;
+    execute_note(&mut chain, reveal_note.id(), &mut ctx.naming).await?;
+    assert_eq!(ctx.naming.storage().get_map_item(16, commitment)?, Word::default());
+    Ok(())
+}
@@ -0,0 +1,63 @@
+mod test_utils;
+
+use miden_client::{account::{Account, AccountBuilder, AccountComponent, AccountStorageMode, auth}, asset::{Asset, FungibleAsset}, note::{NoteAssets, NoteInputs}, transaction::TransactionKernel};
+use miden_testing::MockChainBuilder;
+use midenname_contracts::{compile::CompileOptions, domain::encode_domain, notes::{create_naming_register_name_note, create_naming_transfer_domain_note}, queries::{get_account_for_name, get_domain_owner}, testing::{add_note_to_builder, execute_notes_and_build_chain, foreign_inputs_for, init_naming, register_from_custom_account}};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use crate::test_utils::create_note_for_naming;
+
+// Stand-in for a smart contract account (e.g. a multisig): no BasicWallet interface and no signature,
+// only its own procedure and some assets
+fn add_custom_account(builder: &mut MockChainBuilder, assets: Vec<Asset>) -> anyhow::Result<Account> {
+    let component = AccountComponent::compile("export.noop\n    push.0 drop\nend", TransactionKernel::assembler(), vec![])?
+        .with_supports_all_types();
+
+    let account = AccountBuilder::new(ChaCha20Rng::from_os_rng().random())
+        .with_auth_component(auth::NoAuth)
+        .with_component(component)
+        .with_assets(assets)
+        .storage_mode(AccountStorageMode::Public)
+        .build_existing()?;
+    builder.add_account(account.clone())?;
+    Ok(account)
+}
+
+// The registry only reads the note sender, so the registrant's account type doesn't matter
+#[tokio::test]
+async fn test_register_activate_and_transfer_from_custom_account() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let domain = encode_domain("vault".to_string());
+    let registrant = add_custom_account(&mut ctx.builder, vec![FungibleAsset::new(token, 1000)?.into()])?;
+
+    let register_note = create_naming_register_name_note(registrant.id(), ctx.naming.id(), encode_domain("test".to_string()), FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let vault_register_note = create_naming_register_name_note(registrant.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 123)?, 1, CompileOptions::DEBUG).await?;
+    let activate_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain.to_vec())?, registrant.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    let transfer_note = create_naming_transfer_domain_note(registrant.id(), ctx.naming.id(), encode_domain("test".to_string()), ctx.registrar_2.id(), CompileOptions::DEBUG).await?;
+    for note in [&register_note, &vault_register_note, &activate_note, &transfer_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), vault_register_note.id(), activate_note.id(), transfer_note.id()], &mut ctx.naming).await?;
+
+    assert_eq!(get_domain_owner(&ctx.naming, "vault")?, Some(registrant.id()));
+    assert_eq!(get_account_for_name(&ctx.naming, "vault")?, Some(registrant.id()));
+    assert_eq!(get_domain_owner(&ctx.naming, "test")?, Some(ctx.registrar_2.id()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_from_custom_account_inputs() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let registrant = add_custom_account(&mut ctx.builder, vec![FungibleAsset::new(token, 1000)?.into()])?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    let registrant_inputs = foreign_inputs_for(&chain, registrant.id())?;
+    register_from_custom_account(&mut chain, &mut ctx.naming, &registrant_inputs, encode_domain("vault".to_string()), FungibleAsset::new(token, 123)?, 1).await?;
+
+    assert_eq!(get_domain_owner(&ctx.naming, "vault")?, Some(registrant.id()));
+    Ok(())
+}
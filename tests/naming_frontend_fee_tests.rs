@@ -0,0 +1,72 @@
+use miden_client::asset::FungibleAsset;
+use midenname_contracts::{compile::CompileOptions, domain::encode_domain, notes::{FrontendFee, create_naming_claim_frontend_fees_note, create_naming_register_name_note_with_frontend_fee, create_naming_set_frontend_fee_max_note}, queries::{account_id_from_felts, account_id_to_word, get_frontend_fee_balance}, testing::{add_note_to_builder, execute_note, execute_notes_and_build_chain, init_naming}};
+
+#[tokio::test]
+async fn test_register_with_frontend_fee_splits_revenue() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain("test".to_string());
+    let token = ctx.fungible_asset.faucet_id();
+    let frontend_fee = FrontendFee { recipient: ctx.registrar_3.id(), bps: 250 };
+
+    let max_note = create_naming_set_frontend_fee_max_note(ctx.owner.id(), ctx.naming.id(), 500, CompileOptions::DEBUG).await?;
+    let register_note = create_naming_register_name_note_with_frontend_fee(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 555)?, 1, frontend_fee, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, max_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+
+    execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), max_note.id(), register_note.id()], &mut ctx.naming).await?;
+
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain)?;
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.registrar_1.id());
+
+    // 2.5% of 555 rounded down
+    assert_eq!(get_frontend_fee_balance(&ctx.naming, ctx.registrar_3.id(), token)?, 13);
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(token))?;
+    assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), 542);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_frontend_fee_above_max_fails() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain("test".to_string());
+    let token = ctx.fungible_asset.faucet_id();
+
+    let max_note = create_naming_set_frontend_fee_max_note(ctx.owner.id(), ctx.naming.id(), 100, CompileOptions::DEBUG).await?;
+    let register_note = create_naming_register_name_note_with_frontend_fee(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 555)?, 1, FrontendFee { recipient: ctx.registrar_3.id(), bps: 101 }, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, max_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), max_note.id()], &mut ctx.naming).await?;
+
+    let result = execute_note(&mut chain, register_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected frontend fee above max to fail");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_claim_frontend_fees() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain("test".to_string());
+    let token = ctx.fungible_asset.faucet_id();
+    let frontend_fee = FrontendFee { recipient: ctx.registrar_3.id(), bps: 500 };
+
+    let max_note = create_naming_set_frontend_fee_max_note(ctx.owner.id(), ctx.naming.id(), 500, CompileOptions::DEBUG).await?;
+    let register_note = create_naming_register_name_note_with_frontend_fee(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 555)?, 1, frontend_fee, CompileOptions::DEBUG).await?;
+    let claim_note = create_naming_claim_frontend_fees_note(ctx.registrar_3.id(), ctx.naming.id(), token, CompileOptions::DEBUG).await?;
+    // Nothing accrued for registrar_2
+    let empty_claim_note = create_naming_claim_frontend_fees_note(ctx.registrar_2.id(), ctx.naming.id(), token, CompileOptions::DEBUG).await?;
+    for note in [&max_note, &register_note, &claim_note, &empty_claim_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), max_note.id(), register_note.id()], &mut ctx.naming).await?;
+    assert_eq!(get_frontend_fee_balance(&ctx.naming, ctx.registrar_3.id(), token)?, 27);
+
+    execute_note(&mut chain, claim_note.id(), &mut ctx.naming).await?;
+    assert_eq!(get_frontend_fee_balance(&ctx.naming, ctx.registrar_3.id(), token)?, 0);
+    assert_eq!(ctx.naming.vault().get_balance(token)?, 555 - 27);
+
+    let result = execute_note(&mut chain, empty_claim_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected claim without balance to fail");
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use miden_client::asset::FungibleAsset;
+use miden_crypto::Word;
+use miden_testing::MockChain;
+use midenname_contracts::{compile::CompileOptions, domain::encode_domain, errors::{MidenIdError, RegistryError}, notes::{GC_BATCH_SIZE, create_naming_gc_note, create_naming_register_name_note, create_naming_set_gc_bounty_note, create_naming_set_gc_grace_period_note}, queries::{account_id_to_word, get_gc_bounty, get_gc_grace_period, sweepable_domains}, storage::{CLAIMED_REVENUE_SLOT, DOMAIN_EXPIRY_DATES_SLOT, DOMAIN_TO_OWNER_SLOT}, testing::{add_note_to_builder, execute_note, execute_notes_and_build_chain, init_naming}};
+
+fn now(chain: &MockChain) -> u64 {
+    chain.latest_block_header().timestamp() as u64
+}
+
+fn advance_to(chain: &mut MockChain, time: u64) -> anyhow::Result<()> {
+    while now(chain) < time {
+        chain.prove_next_block()?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_gc_sweeps_domains_past_grace_period() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let domains = [encode_domain("test".to_string()), encode_domain("demo".to_string())];
+
+    let grace_note = create_naming_set_gc_grace_period_note(ctx.owner.id(), ctx.naming.id(), 100, CompileOptions::DEBUG).await?;
+    let register_1 = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domains[0], FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let register_2 = create_naming_register_name_note(ctx.registrar_2.id(), ctx.naming.id(), domains[1], FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    // Anyone can sweep
+    let gc_note = create_naming_gc_note(ctx.registrar_3.id(), ctx.naming.id(), &domains, CompileOptions::DEBUG).await?;
+    for note in [&grace_note, &register_1, &register_2, &gc_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), grace_note.id(), register_1.id(), register_2.id()], &mut ctx.naming).await?;
+    assert_eq!(get_gc_grace_period(&ctx.naming)?, 100);
+    assert!(sweepable_domains(&ctx.naming, now(&chain))?.is_empty());
+
+    let expiry = ctx.naming.storage().get_map_item(DOMAIN_EXPIRY_DATES_SLOT, domains[1])?[0].as_int();
+    advance_to(&mut chain, expiry + 100)?;
+    assert_eq!(sweepable_domains(&ctx.naming, now(&chain))?.len(), 2);
+
+    execute_note(&mut chain, gc_note.id(), &mut ctx.naming).await?;
+    for domain in domains {
+        assert_eq!(ctx.naming.storage().get_map_item(DOMAIN_TO_OWNER_SLOT, domain)?, Word::default());
+        assert_eq!(ctx.naming.storage().get_map_item(DOMAIN_EXPIRY_DATES_SLOT, domain)?, Word::default());
+    }
+    assert!(sweepable_domains(&ctx.naming, now(&chain))?.is_empty());
+    // No bounty set, nothing left the vault
+    assert_eq!(ctx.naming.vault().get_balance(token)?, 1110);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_gc_live_domain_fails() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let domain = encode_domain("test".to_string());
+
+    let grace_note = create_naming_set_gc_grace_period_note(ctx.owner.id(), ctx.naming.id(), 1000, CompileOptions::DEBUG).await?;
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let gc_note = create_naming_gc_note(ctx.registrar_2.id(), ctx.naming.id(), &[domain], CompileOptions::DEBUG).await?;
+    // A single unregistered domain fails the whole batch
+    let mixed_gc_note = create_naming_gc_note(ctx.registrar_2.id(), ctx.naming.id(), &[domain, encode_domain("free".to_string())], CompileOptions::DEBUG).await?;
+    for note in [&grace_note, &register_note, &gc_note, &mixed_gc_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), grace_note.id(), register_note.id()], &mut ctx.naming).await?;
+    let result = execute_note(&mut chain, gc_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected sweeping a live domain to fail");
+
+    // Expired but still in its grace period
+    let expiry = ctx.naming.storage().get_map_item(DOMAIN_EXPIRY_DATES_SLOT, domain)?[0].as_int();
+    advance_to(&mut chain, expiry + 1)?;
+    assert!(now(&chain) < expiry + 1000);
+    let result = execute_note(&mut chain, gc_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected sweeping a domain in its grace period to fail");
+
+    advance_to(&mut chain, expiry + 1000)?;
+    let result = execute_note(&mut chain, mixed_gc_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected sweeping an unregistered domain to fail");
+    assert_ne!(ctx.naming.storage().get_map_item(DOMAIN_TO_OWNER_SLOT, domain)?, Word::default());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_gc_pays_bounty_from_protocol_revenue() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let domains = [encode_domain("test".to_string()), encode_domain("demo".to_string())];
+
+    let bounty_note = create_naming_set_gc_bounty_note(ctx.owner.id(), ctx.naming.id(), Some(FungibleAsset::new(token, 100)?), CompileOptions::DEBUG).await?;
+    let register_1 = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domains[0], FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let register_2 = create_naming_register_name_note(ctx.registrar_2.id(), ctx.naming.id(), domains[1], FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let gc_note = create_naming_gc_note(ctx.registrar_3.id(), ctx.naming.id(), &domains, CompileOptions::DEBUG).await?;
+    for note in [&bounty_note, &register_1, &register_2, &gc_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), bounty_note.id(), register_1.id(), register_2.id()], &mut ctx.naming).await?;
+    assert_eq!(get_gc_bounty(&ctx.naming)?, Some(FungibleAsset::new(token, 100)?));
+
+    let expiry = ctx.naming.storage().get_map_item(DOMAIN_EXPIRY_DATES_SLOT, domains[1])?[0].as_int();
+    advance_to(&mut chain, expiry)?;
+    execute_note(&mut chain, gc_note.id(), &mut ctx.naming).await?;
+
+    // 100 per swept domain, counted as claimed protocol revenue
+    assert_eq!(ctx.naming.vault().get_balance(token)?, 1110 - 200);
+    let claimed_revenue_slot = ctx.naming.storage().get_map_item(CLAIMED_REVENUE_SLOT, account_id_to_word(token))?;
+    assert_eq!(claimed_revenue_slot[0].as_int(), 200);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_gc_bounty_capped_at_protocol_revenue() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let domains = [encode_domain("test".to_string()), encode_domain("demo".to_string())];
+
+    let bounty_note = create_naming_set_gc_bounty_note(ctx.owner.id(), ctx.naming.id(), Some(FungibleAsset::new(token, 1000)?), CompileOptions::DEBUG).await?;
+    let register_1 = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domains[0], FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let register_2 = create_naming_register_name_note(ctx.registrar_2.id(), ctx.naming.id(), domains[1], FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let gc_note = create_naming_gc_note(ctx.registrar_3.id(), ctx.naming.id(), &domains, CompileOptions::DEBUG).await?;
+    for note in [&bounty_note, &register_1, &register_2, &gc_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), bounty_note.id(), register_1.id(), register_2.id()], &mut ctx.naming).await?;
+    let expiry = ctx.naming.storage().get_map_item(DOMAIN_EXPIRY_DATES_SLOT, domains[1])?[0].as_int();
+    advance_to(&mut chain, expiry)?;
+    execute_note(&mut chain, gc_note.id(), &mut ctx.naming).await?;
+
+    // 2000 owed but only 1110 unclaimed, the sweep still goes through and pays what is left
+    assert_eq!(ctx.naming.storage().get_map_item(DOMAIN_TO_OWNER_SLOT, domains[0])?, Word::default());
+    assert_eq!(ctx.naming.vault().get_balance(token)?, 0);
+    let claimed_revenue_slot = ctx.naming.storage().get_map_item(CLAIMED_REVENUE_SLOT, account_id_to_word(token))?;
+    assert_eq!(claimed_revenue_slot[0].as_int(), 1110);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_gc_note_batch_validation() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let domain = encode_domain("test".to_string());
+
+    let err = create_naming_gc_note(ctx.registrar_1.id(), ctx.naming.id(), &[], CompileOptions::DEBUG).await.expect_err("Expected an empty sweep to fail");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::InvalidSweepBatch { count: 0, .. })), "Unexpected error: {err}");
+
+    let too_many: Vec<Word> = (0..=GC_BATCH_SIZE).map(|i| encode_domain(format!("name{i}"))).collect();
+    let err = create_naming_gc_note(ctx.registrar_1.id(), ctx.naming.id(), &too_many, CompileOptions::DEBUG).await.expect_err("Expected an oversized sweep to fail");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::InvalidSweepBatch { count: 17, max: 16 })), "Unexpected error: {err}");
+
+    let err = create_naming_gc_note(ctx.registrar_1.id(), ctx.naming.id(), &[domain, domain], CompileOptions::DEBUG).await.expect_err("Expected a duplicate domain to fail");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::DuplicateInBatch { .. })), "Unexpected error: {err}");
+    Ok(())
+}
@@ -0,0 +1,176 @@
+mod test_utils;
+
+use miden_client::{account::Account, auth::AuthSecretKey, note::NoteId};
+use miden_crypto::{Felt, Word};
+use miden_testing::MockChain;
+use midenname_contracts::{accounts::{create_account_from_mnemonic, create_basic_wallet, generate_mnemonic, sign_as}, compile::CompileOptions, errors::contract_error_code, notes::{SetPriceInput, SignedOwnerNote, create_naming_initialize_note, create_naming_set_owner_pubkey_note, create_naming_set_price_note, create_naming_transfer_owner_note, create_naming_transfer_owner_note_signed, sign_owner_note}, queries::account_id_to_word, storage::{ONE_YEAR_TIMESTAMP_SLOT, OWNER_SLOT, PRICES_SLOT}, testing::{add_note_to_builder, execute_note, execute_notes_and_build_chain, init_naming}};
+
+use crate::test_utils::create_mock_client_with_keystore;
+
+async fn execute_signed_note(chain: &mut MockChain, note_id: NoteId, signed: &SignedOwnerNote, target: &mut Account) -> anyhow::Result<()> {
+    let tx_ctx = chain.build_tx_context(target.id(), &[note_id], &[])?
+        .extend_advice_map(vec![signed.advice_map_entry()])
+        .build()?;
+
+    let executed_tx = tx_ctx.execute().await?;
+
+    target.apply_delta(&executed_tx.account_delta())?;
+    chain.add_pending_executed_transaction(&executed_tx)?;
+    chain.prove_next_block()?;
+
+    Ok(())
+}
+
+fn owner_word(account: &Account) -> Word {
+    account_id_to_word(account.id())
+}
+
+#[tokio::test]
+async fn test_forged_owner_note_without_signature_rejected() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let owner_key = AuthSecretKey::new_rpo_falcon512();
+
+    let set_pubkey_note = create_naming_set_owner_pubkey_note(ctx.owner.id(), ctx.naming.id(), owner_key.public_key().to_commitment().into(), CompileOptions::DEBUG).await?;
+    // Claims to be sent by the owner but carries no signature
+    let forged_note = create_naming_transfer_owner_note(ctx.owner.id(), ctx.naming.id(), ctx.registrar_1.id(), CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, set_pubkey_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, forged_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), set_pubkey_note.id()], &mut ctx.naming).await?;
+    let result = execute_note(&mut chain, forged_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected unsigned owner note to be rejected");
+    assert_eq!(ctx.naming.storage().get_item(1)?, owner_word(&ctx.owner));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unsigned_owner_note_bundled_after_signed_note_rejected() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let owner_key = AuthSecretKey::new_rpo_falcon512();
+
+    let set_pubkey_note = create_naming_set_owner_pubkey_note(ctx.owner.id(), ctx.naming.id(), owner_key.public_key().to_commitment().into(), CompileOptions::DEBUG).await?;
+    let signed_price = sign_owner_note(create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), token, SetPriceInput { letter_count: 6, price: 666 }, false, CompileOptions::DEBUG).await?, &owner_key);
+    // Rides on the signature of the first note in the same transaction
+    let forged_note = create_naming_transfer_owner_note(ctx.owner.id(), ctx.naming.id(), ctx.registrar_1.id(), CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, set_pubkey_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, signed_price.note.clone())?;
+    add_note_to_builder(&mut ctx.builder, forged_note.clone())?;
+
+    let chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), set_pubkey_note.id()], &mut ctx.naming).await?;
+    let tx_ctx = chain.build_tx_context(ctx.naming.id(), &[signed_price.note.id(), forged_note.id()], &[])?
+        .extend_advice_map(vec![signed_price.advice_map_entry()])
+        .build()?;
+
+    let result = tx_ctx.execute().await;
+    assert!(result.is_err(), "Expected unsigned owner note bundled with a signed one to be rejected");
+    assert_eq!(ctx.naming.storage().get_item(OWNER_SLOT)?, owner_word(&ctx.owner));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_second_initialize_rejected() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    // Anyone can send an init note, a second one must not reset the owner
+    let reinit_note = create_naming_initialize_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.registrar_1.id(), ctx.one_year * 2, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, reinit_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    let price_key = Word::new([token.suffix(), token.prefix().as_felt(), Felt::new(3), Felt::new(0)]);
+    let price_before = ctx.naming.storage().get_map_item(PRICES_SLOT, price_key)?;
+
+    let err = execute_note(&mut chain, reinit_note.id(), &mut ctx.naming).await.expect_err("Expected second initialize to fail");
+    assert_eq!(contract_error_code(&format!("{err:?}")), Some("ALREADY_INITIALIZED"), "Unexpected error: {err:?}");
+
+    assert_eq!(ctx.naming.storage().get_item(OWNER_SLOT)?, owner_word(&ctx.owner));
+    assert_eq!(ctx.naming.storage().get_map_item(PRICES_SLOT, price_key)?, price_before);
+    assert_eq!(ctx.naming.storage().get_item(ONE_YEAR_TIMESTAMP_SLOT)?[0].as_int(), ctx.one_year as u64);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_signed_transfer_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let owner_key = AuthSecretKey::new_rpo_falcon512();
+    let other_key = AuthSecretKey::new_rpo_falcon512();
+
+    let set_pubkey_note = create_naming_set_owner_pubkey_note(ctx.owner.id(), ctx.naming.id(), owner_key.public_key().to_commitment().into(), CompileOptions::DEBUG).await?;
+    let wrong_signed = create_naming_transfer_owner_note_signed(ctx.owner.id(), ctx.naming.id(), ctx.registrar_2.id(), &other_key, CompileOptions::DEBUG).await?;
+    let signed = create_naming_transfer_owner_note_signed(ctx.owner.id(), ctx.naming.id(), ctx.registrar_1.id(), &owner_key, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, set_pubkey_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, wrong_signed.note.clone())?;
+    add_note_to_builder(&mut ctx.builder, signed.note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), set_pubkey_note.id()], &mut ctx.naming).await?;
+
+    let result = execute_signed_note(&mut chain, wrong_signed.note.id(), &wrong_signed, &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected note signed by another key to be rejected");
+
+    execute_signed_note(&mut chain, signed.note.id(), &signed, &mut ctx.naming).await?;
+    assert_eq!(ctx.naming.storage().get_item(1)?, owner_word(&ctx.registrar_1));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_new_owner_wallet_signs_price_update() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    // Wallet created through the client, its key is only reachable through the keystore
+    let (mut client, keystore) = create_mock_client_with_keystore(MockChain::builder().build()?).await?;
+    let (wallet, wallet_key) = create_basic_wallet(&mut client, &keystore).await?;
+
+    let transfer_note = create_naming_transfer_owner_note(ctx.owner.id(), ctx.naming.id(), wallet.id(), CompileOptions::DEBUG).await?;
+    let set_pubkey_note = create_naming_set_owner_pubkey_note(wallet.id(), ctx.naming.id(), wallet_key.public_key().to_commitment().into(), CompileOptions::DEBUG).await?;
+    let price_input = SetPriceInput { letter_count: 6, price: 666 };
+    let unsigned_price_note = create_naming_set_price_note(wallet.id(), ctx.naming.id(), token, price_input, false, CompileOptions::DEBUG).await?;
+    let signed_price = sign_as(&mut client, &keystore, wallet.id(), create_naming_set_price_note(wallet.id(), ctx.naming.id(), token, price_input, false, CompileOptions::DEBUG).await?).await?;
+    for note in [&transfer_note, &set_pubkey_note, &unsigned_price_note, &signed_price.note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), transfer_note.id(), set_pubkey_note.id()], &mut ctx.naming).await?;
+    assert_eq!(ctx.naming.storage().get_item(1)?, owner_word(&wallet));
+
+    let result = execute_note(&mut chain, unsigned_price_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected unsigned price update to be rejected");
+
+    execute_signed_note(&mut chain, signed_price.note.id(), &signed_price, &mut ctx.naming).await?;
+    let price = ctx.naming.storage().get_map_item(2, Word::new([token.suffix(), token.prefix().as_felt(), Felt::new(6), Felt::new(0)]))?;
+    assert_eq!(price[0].as_int(), 666);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_owner_recovered_from_mnemonic_signs_price_update() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let mnemonic = generate_mnemonic();
+
+    // Owner created on one machine, the key only survives as the mnemonic
+    let (mut client, keystore) = create_mock_client_with_keystore(MockChain::builder().build()?).await?;
+    let (wallet, wallet_key) = create_account_from_mnemonic(&mut client, &keystore, &mnemonic, 0).await?;
+    drop((client, keystore));
+
+    // Recovered into a fresh store and keystore
+    let (mut client, keystore) = create_mock_client_with_keystore(MockChain::builder().build()?).await?;
+    let (recovered, _) = create_account_from_mnemonic(&mut client, &keystore, &mnemonic, 0).await?;
+    assert_eq!(recovered.id(), wallet.id());
+
+    let transfer_note = create_naming_transfer_owner_note(ctx.owner.id(), ctx.naming.id(), wallet.id(), CompileOptions::DEBUG).await?;
+    let set_pubkey_note = create_naming_set_owner_pubkey_note(wallet.id(), ctx.naming.id(), wallet_key.public_key().to_commitment().into(), CompileOptions::DEBUG).await?;
+    let price_input = SetPriceInput { letter_count: 6, price: 777 };
+    let signed_price = sign_as(&mut client, &keystore, recovered.id(), create_naming_set_price_note(wallet.id(), ctx.naming.id(), token, price_input, false, CompileOptions::DEBUG).await?).await?;
+    for note in [&transfer_note, &set_pubkey_note, &signed_price.note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), transfer_note.id(), set_pubkey_note.id()], &mut ctx.naming).await?;
+    execute_signed_note(&mut chain, signed_price.note.id(), &signed_price, &mut ctx.naming).await?;
+    let price = ctx.naming.storage().get_map_item(PRICES_SLOT, Word::new([token.suffix(), token.prefix().as_felt(), Felt::new(6), Felt::new(0)]))?;
+    assert_eq!(price[0].as_int(), 777);
+    Ok(())
+}
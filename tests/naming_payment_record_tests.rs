@@ -0,0 +1,52 @@
+use miden_client::asset::FungibleAsset;
+use midenname_contracts::{compile::CompileOptions, domain::encode_domain, notes::{create_naming_register_hashed_note, create_naming_register_name_note, create_naming_renew_via_register, create_naming_transfer_domain_note}, pricing::quote_registration, queries::{PaymentRecord, get_domain_owner, get_payment_record}, testing::{add_note_to_builder, execute_note, execute_notes_and_build_chain, init_naming}};
+
+#[tokio::test]
+async fn test_registration_records_price_token_and_years() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    let register_1 = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("test".to_string()), FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    // Overpaying records the price, not the paid amount
+    let register_2 = create_naming_register_name_note(ctx.registrar_2.id(), ctx.naming.id(), encode_domain("demo".to_string()), FungibleAsset::new(token, 5000)?, 3, CompileOptions::DEBUG).await?;
+    let register_3 = create_naming_register_hashed_note(ctx.registrar_3.id(), ctx.naming.id(), encode_domain("secret".to_string()), FungibleAsset::new(token, 5000)?, 5, CompileOptions::DEBUG).await?;
+    for note in [&register_1, &register_2, &register_3] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_1.id(), register_2.id(), register_3.id()], &mut ctx.naming).await?;
+    for (name, years) in [("test", 1), ("demo", 3), ("secret", 5)] {
+        let expected = PaymentRecord { amount: quote_registration(&ctx.naming, name, token, years)?, payment_token: token, years: years.into() };
+        assert_eq!(get_payment_record(&ctx.naming, name)?, Some(expected), "{name}");
+    }
+    assert_eq!(get_payment_record(&ctx.naming, "test")?.unwrap().amount, 555);
+    assert_eq!(get_payment_record(&ctx.naming, "free")?, None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_payment_record_survives_transfer_and_renewal() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let domain = encode_domain("test".to_string());
+
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let transfer_note = create_naming_transfer_domain_note(ctx.registrar_1.id(), ctx.naming.id(), domain, ctx.registrar_2.id(), CompileOptions::DEBUG).await?;
+    let renew_note = create_naming_renew_via_register(ctx.registrar_2.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 5000)?, 3, CompileOptions::DEBUG).await?;
+    for note in [&register_note, &transfer_note, &renew_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id()], &mut ctx.naming).await?;
+    let registered = get_payment_record(&ctx.naming, "test")?;
+    assert_eq!(registered, Some(PaymentRecord { amount: 555, payment_token: token, years: 1 }));
+
+    execute_note(&mut chain, transfer_note.id(), &mut ctx.naming).await?;
+    assert_eq!(get_domain_owner(&ctx.naming, "test")?, Some(ctx.registrar_2.id()));
+    assert_eq!(get_payment_record(&ctx.naming, "test")?, registered);
+
+    // The record is the registration, renewals leave it alone
+    execute_note(&mut chain, renew_note.id(), &mut ctx.naming).await?;
+    assert_eq!(get_payment_record(&ctx.naming, "test")?, registered);
+    Ok(())
+}
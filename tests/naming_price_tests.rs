@@ -0,0 +1,609 @@
+mod test_utils;
+
+use miden_client::{account::AccountId, asset::FungibleAsset, note::{NoteAssets, NoteInputs}};
+use std::{collections::BTreeMap, fs, path::Path};
+use miden_crypto::{Felt, StarkField, Word};
+use midenname_contracts::{compile::CompileOptions, config::{ConfigError, PriceTier, load_price_config, load_price_table, price_table, save_price_table}, domain::encode_domain, errors::{MidenIdError, RegistryError, contract_error_code}, notes::{SetPriceInput, create_naming_free_register_name_note, create_naming_register_name_note, create_naming_set_base_fee_note, create_naming_set_class_multiplier_note, create_naming_set_price_note, create_naming_set_price_notes, create_naming_set_pricing_mode_note}, pricing::{NameClass, PriceTable, PricingMode, apply_class_multiplier, calculate_registration_cost, calculate_registration_cost_for_mode, export_prices, get_base_fee, get_class_multiplier, get_pricing_mode, price_change_requires_confirmation, price_changes, quote_registration, testnet_prices}, queries::{account_id_from_felts, account_id_to_word}, testing::{ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2, NamingFixture, TEST_PRICES, add_note_to_builder, execute_note, execute_notes_and_build_chain, init_naming}};
+
+use crate::test_utils::create_note_for_naming;
+
+#[tokio::test]
+async fn test_set_price_note_rejects_zero_price() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+
+    let result = create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), SetPriceInput { letter_count: 3, price: 0 }, false, CompileOptions::DEBUG).await;
+    let err = result.expect_err("Expected zero price to be rejected");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::ZeroPrice)));
+
+    // Explicitly allowed free registrations
+    create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), SetPriceInput { letter_count: 3, price: 0 }, true, CompileOptions::DEBUG).await?;
+    Ok(())
+}
+
+// A price for a mistyped token ID would never match a payment, registrations in the real token keep failing
+#[tokio::test]
+async fn test_set_price_notes_reject_non_faucet_tokens() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let not_a_faucet = ctx.registrar_1.id();
+
+    let err = create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), not_a_faucet, SetPriceInput { letter_count: 3, price: 100 }, false, CompileOptions::DEBUG).await.expect_err("Expected wallet ID to be rejected");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::NotAFungibleFaucet(ref id)) if *id == not_a_faucet.to_hex()));
+
+    let err = create_naming_set_price_notes(ctx.owner.id(), ctx.naming.id(), ctx.naming.id(), &testnet_prices(), CompileOptions::DEBUG).await.expect_err("Expected naming ID to be rejected");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::NotAFungibleFaucet(_))));
+
+    // Price tables are checked before import
+    let dir = std::env::temp_dir().join(format!("midenname-price-table-{}", rand::random::<u64>()));
+    fs::create_dir_all(&dir)?;
+    let path = dir.join("prices.json");
+    save_price_table(&path, &PriceTable { payment_token: not_a_faucet, prices: testnet_prices() })?;
+    let err = load_price_table(&path).expect_err("Expected wallet ID to be rejected");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::NotAFungibleFaucet(_))));
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_price_note_rejects_modulus_overflow() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+
+    let result = create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), SetPriceInput { letter_count: 3, price: Felt::MODULUS }, false, CompileOptions::DEBUG).await;
+    let err = result.expect_err("Expected price above modulus to be rejected");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::PriceExceedsFieldModulus { price }) if price == Felt::MODULUS));
+
+    // Below the modulus but above what the contract's u32 price arithmetic accepts
+    let result = create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), SetPriceInput { letter_count: 3, price: Felt::MODULUS - 1 }, false, CompileOptions::DEBUG).await;
+    assert!(matches!(result, Err(MidenIdError::Registry(RegistryError::PriceNotU32 { .. }))), "Unexpected result: {result:?}");
+
+    create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), SetPriceInput { letter_count: 3, price: u32::MAX.into() }, false, CompileOptions::DEBUG).await?;
+    Ok(())
+}
+
+#[test]
+fn test_large_price_change_requires_confirmation() {
+    assert!(!price_change_requires_confirmation(100, 1000));
+    assert!(price_change_requires_confirmation(100, 1001));
+    assert!(!price_change_requires_confirmation(100, 10));
+    assert!(price_change_requires_confirmation(100, 9));
+    assert!(price_change_requires_confirmation(100, 0));
+    // Unset price never asks for confirmation
+    assert!(!price_change_requires_confirmation(0, 1_000_000));
+}
+
+#[tokio::test]
+async fn test_set_price() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let set_price_note = create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), SetPriceInput { letter_count: 7, price: 999 }, false, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, set_price_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    execute_note(&mut chain, set_price_note.id(), &mut ctx.naming).await?;
+
+    let price_slot = ctx.naming.storage().get_map_item(2, Word::new([
+        ctx.fungible_asset.faucet_id().suffix(),
+        ctx.fungible_asset.faucet_id().prefix().as_felt(),
+        Felt::new(7),
+        Felt::new(0),
+    ]))?;
+    assert_eq!(price_slot.get(0).unwrap().as_int(), 999);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_price_not_u32() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let set_price_note = create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), SetPriceInput { letter_count: 7, price: u32::MAX as u64 + 1 }, false, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, set_price_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    let result = execute_note(&mut chain, set_price_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected revert but succeeded.");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_price_by_not_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let set_price_note = create_naming_set_price_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), SetPriceInput { letter_count: 7, price: 999 }, false, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, set_price_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    let result = execute_note(&mut chain, set_price_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected revert but succeeded.");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_price_above_field_modulus() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+
+    // Felt silently reduces values above the modulus, u64::MAX would be stored as a tiny price
+    assert_ne!(Felt::new(u64::MAX).as_int(), u64::MAX);
+    assert_eq!(Felt::new(Felt::MODULUS + 5).as_int(), 5);
+
+    let result = create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), SetPriceInput { letter_count: 3, price: u64::MAX }, false, CompileOptions::DEBUG).await;
+    let err = result.expect_err("Expected price above modulus to be rejected");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::PriceExceedsFieldModulus { price: u64::MAX })));
+
+    let result = create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), SetPriceInput { letter_count: 3, price: Felt::MODULUS + 5 }, false, CompileOptions::DEBUG).await;
+    assert!(result.is_err(), "Expected price wrapping to 5 to be rejected");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_free_register_against_paid_price_fails() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let set_price_note = create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), SetPriceInput { letter_count: 4, price: 100 }, false, CompileOptions::DEBUG).await?;
+    let register_note = create_naming_free_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), encode_domain("test".to_string()), 1, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, set_price_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), set_price_note.id()], &mut ctx.naming).await?;
+    let result = execute_note(&mut chain, register_note.id(), &mut ctx.naming).await;
+
+    let err = result.expect_err("Expected register without assets to fail");
+    assert!(format!("{err:?}").contains("Payment required"), "Unexpected failure: {err:?}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_free_register_with_zero_price() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let set_price_note = create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), SetPriceInput { letter_count: 4, price: 0 }, true, CompileOptions::DEBUG).await?;
+    let domain = encode_domain("test".to_string());
+    let register_note = create_naming_free_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), domain, 1, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, set_price_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+
+    execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), set_price_note.id(), register_note.id()], &mut ctx.naming).await?;
+
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain)?;
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.registrar_1.id());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_price_note_rejects_invalid_letter_count() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+
+    for letter_count in [0, 21] {
+        let result = create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), SetPriceInput { letter_count, price: 100 }, false, CompileOptions::DEBUG).await;
+        let err = result.expect_err("Expected invalid letter count to be rejected");
+        assert!(matches!(err, MidenIdError::Registry(RegistryError::InvalidLetterCount { letter_count: count }) if count == letter_count));
+    }
+    Ok(())
+}
+
+// Raw set_price note, bypasses the Rust side validation
+async fn raw_set_price_note(ctx: &NamingFixture, inputs: [u64; 6]) -> anyhow::Result<miden_client::note::Note> {
+    let token = ctx.fungible_asset.faucet_id();
+    let mut values = vec![token.suffix(), token.prefix().as_felt()];
+    values.extend(inputs.map(Felt::new));
+
+    create_note_for_naming("set_price".to_string(), NoteInputs::new(values)?, ctx.owner.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await
+}
+
+#[tokio::test]
+async fn test_set_price_invalid_inputs_rejected_on_chain() -> anyhow::Result<()> {
+    let cases = [
+        // Price and letter count swapped: letter count 123, price 1
+        ([123, 0, 1, 0, 0, 0], "Letter count must be between 1 and 21"),
+        ([0, 0, 100, 0, 0, 0], "Letter count must be between 1 and 21"),
+        ([22, 0, 100, 0, 0, 0], "Letter count must be between 1 and 21"),
+        // Garbage in key and price padding
+        ([3, 7, 100, 0, 0, 0], "Set price padding must be zero"),
+        ([3, 0, 100, 0, 0, 9], "Set price padding must be zero"),
+    ];
+
+    for (inputs, expected) in cases {
+        let mut ctx = init_naming().await?;
+        let note = raw_set_price_note(&ctx, inputs).await?;
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+
+        let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+        let result = execute_note(&mut chain, note.id(), &mut ctx.naming).await;
+
+        let err = result.expect_err("Expected invalid set_price inputs to be rejected");
+        assert!(format!("{err:?}").contains(expected), "Inputs {inputs:?}: unexpected failure {err:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_calculate_registration_cost_discounts() {
+    assert_eq!(calculate_registration_cost(0, 555, 1), Ok(555));
+    assert_eq!(calculate_registration_cost(0, 555, 2), Ok(1110));
+    // 30% off, 555 * 0.3 = 166.5 is floored
+    assert_eq!(calculate_registration_cost(0, 555, 3), Ok((555 - 166) * 3));
+    assert_eq!(calculate_registration_cost(0, 555, 5), Ok((555 - 277) * 5));
+    assert_eq!(calculate_registration_cost(0, 555, 0), Err(RegistryError::InvalidRegistrationYears { years: 0 }));
+    assert_eq!(calculate_registration_cost(0, 555, 11), Err(RegistryError::InvalidRegistrationYears { years: 11 }));
+}
+
+#[tokio::test]
+async fn test_quote_matches_charged_amount() -> anyhow::Result<()> {
+    let names = [("test", 1), ("abc", 3), ("hello", 5)];
+
+    // Quotes are read from an initialized registry
+    let mut priced = init_naming().await?;
+    let token = priced.fungible_asset.faucet_id();
+    execute_notes_and_build_chain(priced.builder, &[priced.initialize_note.id(), priced.set_prices_note.id()], &mut priced.naming).await?;
+    let quotes = names.iter()
+        .map(|(name, years)| quote_registration(&priced.naming, name, token, *years))
+        .collect::<Result<Vec<u64>, _>>()?;
+
+    let mut ctx = init_naming().await?;
+    let mut short_notes = Vec::new();
+    let mut notes = Vec::new();
+    for ((name, years), quote) in names.iter().zip(&quotes) {
+        let domain = encode_domain(name.to_string());
+        short_notes.push(create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, quote - 1)?, *years, CompileOptions::DEBUG).await?);
+        notes.push(create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, *quote)?, *years, CompileOptions::DEBUG).await?);
+    }
+    for note in short_notes.iter().chain(&notes) {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    let mut expected_revenue = 0;
+    for ((short_note, note), quote) in short_notes.iter().zip(&notes).zip(&quotes) {
+        let result = execute_note(&mut chain, short_note.id(), &mut ctx.naming).await;
+        assert!(result.is_err(), "Expected register below the quote to fail");
+        execute_note(&mut chain, note.id(), &mut ctx.naming).await?;
+
+        expected_revenue += quote;
+        let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(token))?;
+        assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), expected_revenue);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_calculate_registration_cost_with_base_fee() {
+    for (base_fee, yearly_price, years) in [(0, 555, 1), (100, 555, 1), (100, 555, 2), (40, 789, 3), (7, 123, 5), (250, 0, 4)] {
+        let expected = base_fee + calculate_registration_cost(0, yearly_price, years).unwrap();
+        assert_eq!(calculate_registration_cost(base_fee, yearly_price, years), Ok(expected));
+    }
+    // Base fee is charged once, not per year
+    assert_eq!(calculate_registration_cost(100, 555, 2), Ok(100 + 555 * 2));
+}
+
+#[test]
+fn test_calculate_registration_cost_overflow() {
+    let max = u64::from(u32::MAX);
+    assert_eq!(calculate_registration_cost(0, max, 1), Ok(max));
+    assert_eq!(calculate_registration_cost(1, max, 1), Err(RegistryError::PriceOverflow { yearly_price: max, years: 1 }));
+    assert_eq!(calculate_registration_cost(0, max / 2 + 1, 2), Err(RegistryError::PriceOverflow { yearly_price: max / 2 + 1, years: 2 }));
+    // The contract multiplies the yearly price by the discount bps in u32 before dividing
+    assert_eq!(calculate_registration_cost(0, max / 3000 + 1, 3), Err(RegistryError::PriceOverflow { yearly_price: max / 3000 + 1, years: 3 }));
+    assert_eq!(calculate_registration_cost(u64::MAX, u64::MAX, 10), Err(RegistryError::PriceOverflow { yearly_price: u64::MAX, years: 10 }));
+}
+
+#[tokio::test]
+async fn test_register_charges_base_fee() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let domain = encode_domain("test".to_string());
+
+    let base_fee_note = create_naming_set_base_fee_note(ctx.owner.id(), ctx.naming.id(), token, 100, CompileOptions::DEBUG).await?;
+    // Without the base fee the price would be enough
+    let short_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 555 * 2)?, 2, CompileOptions::DEBUG).await?;
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 100 + 555 * 2)?, 2, CompileOptions::DEBUG).await?;
+    for note in [&base_fee_note, &short_note, &register_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), base_fee_note.id()], &mut ctx.naming).await?;
+    assert_eq!(get_base_fee(&ctx.naming, token)?, 100);
+    assert_eq!(get_base_fee(&ctx.naming, AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2)?)?, 0);
+    assert_eq!(quote_registration(&ctx.naming, "test", token, 2)?, 100 + 555 * 2);
+
+    let result = execute_note(&mut chain, short_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected register without the base fee to fail");
+    execute_note(&mut chain, register_note.id(), &mut ctx.naming).await?;
+
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(token))?;
+    assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), 100 + 555 * 2);
+    Ok(())
+}
+
+#[test]
+fn test_name_class_of_domain() {
+    for (name, class) in [("test", NameClass::Alpha), ("1234", NameClass::Numeric), ("0", NameClass::Numeric), ("a1", NameClass::Mixed), ("abcdefghijklmnopqrs9", NameClass::Mixed), ("90000000000000000000", NameClass::Numeric)] {
+        assert_eq!(NameClass::of_domain(encode_domain(name.to_string())), class, "{name}");
+    }
+    assert_eq!("numeric".parse::<NameClass>(), Ok(NameClass::Numeric));
+    assert!("digits".parse::<NameClass>().is_err());
+}
+
+#[test]
+fn test_apply_class_multiplier() {
+    assert_eq!(apply_class_multiplier(555, 0), Ok(555));
+    assert_eq!(apply_class_multiplier(555, 10000), Ok(555));
+    assert_eq!(apply_class_multiplier(555, 20000), Ok(555 * 2));
+    assert_eq!(apply_class_multiplier(555, 15000), Ok(832));
+    assert_eq!(apply_class_multiplier(123123, 5000), Ok(61561));
+    assert_eq!(apply_class_multiplier(u64::from(u32::MAX), 10000), Ok(u64::from(u32::MAX) / 10000 * 10000 + 7295));
+
+    // The contract keeps the scaled price in u32 and rejects a price that does not start there
+    for (price, bps) in [(1_000_000_000, 50000), (u64::from(u32::MAX) + 1, 10000)] {
+        assert_eq!(apply_class_multiplier(price, bps), Err(RegistryError::ClassPriceOverflow { price, bps }));
+    }
+}
+
+#[tokio::test]
+async fn test_class_multiplier_overflow_rejected_on_chain() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    let price_note = create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), token, SetPriceInput { letter_count: 4, price: 1_000_000_000 }, false, CompileOptions::DEBUG).await?;
+    let multiplier_note = create_naming_set_class_multiplier_note(ctx.owner.id(), ctx.naming.id(), NameClass::Numeric, 50000, CompileOptions::DEBUG).await?;
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("1234".to_string()), FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    for note in [&price_note, &multiplier_note, &register_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), price_note.id(), multiplier_note.id()], &mut ctx.naming).await?;
+    let err = quote_registration(&ctx.naming, "1234", token, 1).expect_err("Expected the quote to overflow like the contract");
+    assert_eq!(err.code(), "CLASS_PRICE_OVERFLOW");
+
+    let err = execute_note(&mut chain, register_note.id(), &mut ctx.naming).await.expect_err("Expected the scaled price to overflow");
+    assert_eq!(contract_error_code(&format!("{err:?}")), Some("CLASS_PRICE_OVERFLOW"), "Unexpected error: {err:?}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_numeric_name_costs_class_multiplier() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let numeric = encode_domain("1234".to_string());
+    let alpha = encode_domain("test".to_string());
+
+    let multiplier_note = create_naming_set_class_multiplier_note(ctx.owner.id(), ctx.naming.id(), NameClass::Numeric, 20000, CompileOptions::DEBUG).await?;
+    let short_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), numeric, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let numeric_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), numeric, FungibleAsset::new(token, 555 * 2)?, 1, CompileOptions::DEBUG).await?;
+    let alpha_note = create_naming_register_name_note(ctx.registrar_2.id(), ctx.naming.id(), alpha, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    for note in [&multiplier_note, &short_note, &numeric_note, &alpha_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), multiplier_note.id()], &mut ctx.naming).await?;
+    assert_eq!(get_class_multiplier(&ctx.naming, NameClass::Numeric)?, 20000);
+    assert_eq!(get_class_multiplier(&ctx.naming, NameClass::Alpha)?, 0);
+    assert_eq!(quote_registration(&ctx.naming, "1234", token, 1)?, 555 * 2);
+    assert_eq!(quote_registration(&ctx.naming, "test", token, 1)?, 555);
+
+    let result = execute_note(&mut chain, short_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected the 4 letter price to be short for a numeric name");
+    execute_note(&mut chain, numeric_note.id(), &mut ctx.naming).await?;
+    execute_note(&mut chain, alpha_note.id(), &mut ctx.naming).await?;
+
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(token))?;
+    assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), 555 * 2 + 555);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_class_multiplier_rejects_high_multiplier() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+
+    let result = create_naming_set_class_multiplier_note(ctx.owner.id(), ctx.naming.id(), NameClass::Mixed, 100001, CompileOptions::DEBUG).await;
+    assert!(matches!(result, Err(MidenIdError::Registry(RegistryError::ClassMultiplierTooHigh { bps: 100001 }))), "Unexpected result: {result:?}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_price_notes_from_empty_table() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+
+    let notes = create_naming_set_price_notes(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), &BTreeMap::new(), CompileOptions::DEBUG).await?;
+    assert!(notes.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_price_notes_from_sparse_table() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let prices = BTreeMap::from([(3, 300), (7, 700)]);
+
+    let notes = create_naming_set_price_notes(ctx.owner.id(), ctx.naming.id(), token, &prices, CompileOptions::DEBUG).await?;
+    assert_eq!(notes.len(), 2);
+    for note in &notes {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut note_ids = vec![ctx.initialize_note.id()];
+    note_ids.extend(notes.iter().map(|note| note.id()));
+    execute_notes_and_build_chain(ctx.builder, &note_ids, &mut ctx.naming).await?;
+
+    for (letter_count, price) in [(3, 300), (7, 700), (5, 0)] {
+        let price_slot = ctx.naming.storage().get_map_item(2, Word::new([token.suffix(), token.prefix().as_felt(), Felt::new(letter_count), Felt::new(0)]))?;
+        assert_eq!(price_slot.get(0).unwrap().as_int(), price, "price of {letter_count} letters");
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_price_notes_reject_out_of_range_length() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    for letter_count in [0, 21] {
+        let prices = BTreeMap::from([(3, 300), (letter_count, 100)]);
+        let err = create_naming_set_price_notes(ctx.owner.id(), ctx.naming.id(), token, &prices, CompileOptions::DEBUG).await.expect_err("Expected out of range length to fail");
+        assert!(matches!(err, MidenIdError::Registry(RegistryError::InvalidLetterCount { letter_count: invalid }) if invalid == letter_count), "Unexpected error: {err}");
+    }
+
+    let err = create_naming_set_price_notes(ctx.owner.id(), ctx.naming.id(), token, &BTreeMap::from([(4, 0)]), CompileOptions::DEBUG).await.expect_err("Expected zero price to fail");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::ZeroPrice)), "Unexpected error: {err}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_prices_from_sample_config() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    let prices = load_price_config(Path::new("./prices.example.toml"))?;
+    assert_eq!(prices, testnet_prices());
+
+    let notes = create_naming_set_price_notes(ctx.owner.id(), ctx.naming.id(), token, &prices, CompileOptions::DEBUG).await?;
+    for note in &notes {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+    let mut note_ids = vec![ctx.initialize_note.id()];
+    note_ids.extend(notes.iter().map(|note| note.id()));
+    execute_notes_and_build_chain(ctx.builder, &note_ids, &mut ctx.naming).await?;
+
+    for letter_count in 1..=20u8 {
+        let price_slot = ctx.naming.storage().get_map_item(2, Word::new([token.suffix(), token.prefix().as_felt(), Felt::new(letter_count.into()), Felt::new(0)]))?;
+        assert_eq!(price_slot.get(0).unwrap().as_int(), prices.get(&letter_count).copied().unwrap_or(0), "price of {letter_count} letters");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_price_config_json_and_invalid_tiers() -> anyhow::Result<()> {
+    let dir = std::env::temp_dir().join(format!("midenname-prices-{}", rand::random::<u64>()));
+    fs::create_dir_all(&dir)?;
+    let path = dir.join("prices.json");
+    fs::write(&path, r#"[{"length": 3, "price": 300}, {"length": 7, "price": 700}]"#)?;
+    assert_eq!(load_price_config(&path)?, BTreeMap::from([(3, 300), (7, 700)]));
+
+    fs::write(&path, r#"[{"length": 3}]"#)?;
+    assert!(matches!(load_price_config(&path), Err(MidenIdError::Config(ConfigError::InvalidPriceConfig(_)))));
+
+    let duplicate = [PriceTier { length: 3, price: 300 }, PriceTier { length: 3, price: 400 }];
+    assert!(matches!(price_table(&duplicate), Err(MidenIdError::Config(ConfigError::InvalidPriceConfig(_)))));
+    assert!(matches!(price_table(&[]), Err(MidenIdError::Config(ConfigError::InvalidPriceConfig(_)))));
+    assert!(matches!(price_table(&[PriceTier { length: 21, price: 1 }]), Err(MidenIdError::Registry(RegistryError::InvalidLetterCount { letter_count: 21 }))));
+    assert!(matches!(price_table(&[PriceTier { length: 2, price: 0 }]), Err(MidenIdError::Registry(RegistryError::ZeroPrice))));
+    assert!(matches!(price_table(&[PriceTier { length: 2, price: 1 << 32 }]), Err(MidenIdError::Registry(RegistryError::PriceNotU32 { price: 0x1_0000_0000 }))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_import_prices_submits_only_changed_entries() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+
+    let exported = export_prices(&ctx.naming, token)?;
+    assert_eq!(exported.payment_token, token);
+    for (index, price) in TEST_PRICES.iter().enumerate() {
+        assert_eq!(exported.prices.get(&(index as u8 + 1)), Some(price));
+    }
+
+    let mut edited = exported.clone();
+    edited.prices.insert(2, 40_000);
+    edited.prices.insert(9, 900);
+    let dir = std::env::temp_dir().join(format!("midenname-price-table-{}", rand::random::<u64>()));
+    fs::create_dir_all(&dir)?;
+    let path = dir.join("prices.json");
+    save_price_table(&path, &edited)?;
+    let table = load_price_table(&path)?;
+    assert_eq!(table, edited);
+
+    let changes = price_changes(&export_prices(&ctx.naming, token)?.prices, &table.prices);
+    assert_eq!(changes, BTreeMap::from([(2, 40_000), (9, 900)]));
+    let notes = create_naming_set_price_notes(ctx.owner.id(), ctx.naming.id(), token, &changes, CompileOptions::DEBUG).await?;
+    assert_eq!(notes.len(), 2);
+
+    let tx_ctx = chain.build_tx_context(ctx.naming.id(), &[], &notes)?.build()?;
+    let executed_tx = tx_ctx.execute().await?;
+    ctx.naming.apply_delta(executed_tx.account_delta())?;
+    chain.add_pending_executed_transaction(&executed_tx)?;
+    chain.prove_next_block()?;
+
+    assert_eq!(export_prices(&ctx.naming, token)?, table);
+    assert!(price_changes(&export_prices(&ctx.naming, token)?.prices, &table.prices).is_empty());
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_calculate_registration_cost_for_mode() {
+    for years in 1..=10 {
+        assert_eq!(calculate_registration_cost_for_mode(PricingMode::Linear, 0, 555, years), Ok(555 * years as u64));
+        assert_eq!(calculate_registration_cost_for_mode(PricingMode::DiscountCurve, 0, 555, years), calculate_registration_cost(0, 555, years));
+    }
+    assert_eq!(calculate_registration_cost_for_mode(PricingMode::Linear, 100, 555, 5), Ok(100 + 555 * 5));
+    assert_eq!(calculate_registration_cost_for_mode(PricingMode::Linear, 0, 555, 11), Err(RegistryError::InvalidRegistrationYears { years: 11 }));
+}
+
+// A 5 year "test" registration is charged the discounted price only under the discount curve
+#[tokio::test]
+async fn test_register_charges_per_pricing_mode() -> anyhow::Result<()> {
+    let curve_price = (555 - 277) * 5;
+    let linear_price = 555 * 5;
+
+    for (mode, charged) in [(PricingMode::DiscountCurve, curve_price), (PricingMode::Linear, linear_price)] {
+        let mut ctx = init_naming().await?;
+        let token = ctx.fungible_asset.faucet_id();
+        let domain = encode_domain("test".to_string());
+
+        let mode_note = create_naming_set_pricing_mode_note(ctx.owner.id(), ctx.naming.id(), mode, CompileOptions::DEBUG).await?;
+        let short_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, charged - 1)?, 5, CompileOptions::DEBUG).await?;
+        let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, charged)?, 5, CompileOptions::DEBUG).await?;
+        for note in [&mode_note, &short_note, &register_note] {
+            add_note_to_builder(&mut ctx.builder, note.clone())?;
+        }
+
+        let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), mode_note.id()], &mut ctx.naming).await?;
+        assert_eq!(get_pricing_mode(&ctx.naming)?, mode);
+        assert_eq!(quote_registration(&ctx.naming, "test", token, 5)?, charged);
+
+        let result = execute_note(&mut chain, short_note.id(), &mut ctx.naming).await;
+        assert!(result.is_err(), "Expected register below the {mode:?} price to fail");
+        execute_note(&mut chain, register_note.id(), &mut ctx.naming).await?;
+
+        let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(token))?;
+        assert_eq!(total_revenue_slot[0].as_int(), charged);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_pricing_mode_rejects_unknown_mode_and_non_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let unknown_note = create_note_for_naming("set_pricing_mode".to_string(), NoteInputs::new(vec![Felt::new(2), Felt::new(0), Felt::new(0), Felt::new(0)])?, ctx.owner.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    let non_owner_note = create_naming_set_pricing_mode_note(ctx.registrar_1.id(), ctx.naming.id(), PricingMode::Linear, CompileOptions::DEBUG).await?;
+    for note in [&unknown_note, &non_owner_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    for note in [&unknown_note, &non_owner_note] {
+        let result = execute_note(&mut chain, note.id(), &mut ctx.naming).await;
+        assert!(result.is_err(), "Expected set_pricing_mode to fail");
+    }
+    assert_eq!(get_pricing_mode(&ctx.naming)?, PricingMode::DiscountCurve);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_base_fee_above_u32_rejected_on_chain() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    assert!(matches!(create_naming_set_base_fee_note(ctx.owner.id(), ctx.naming.id(), token, 1 << 32, CompileOptions::DEBUG).await, Err(MidenIdError::Registry(RegistryError::PriceNotU32 { .. }))));
+    // Built by hand, as a client that skips the checks would
+    let inputs = NoteInputs::new(vec![Felt::new(1 << 32), Felt::new(0), Felt::new(0), Felt::new(0), token.suffix(), token.prefix().as_felt(), Felt::new(0), Felt::new(0)])?;
+    let base_fee_note = create_note_for_naming("set_base_fee".to_string(), inputs, ctx.owner.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    add_note_to_builder(&mut ctx.builder, base_fee_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    let err = execute_note(&mut chain, base_fee_note.id(), &mut ctx.naming).await.expect_err("base fee above u32");
+    assert_eq!(contract_error_code(&format!("{err:?}")), Some("PRICE_TOO_HIGH"), "Unexpected error: {err:?}");
+    assert_eq!(get_base_fee(&ctx.naming, token)?, 0);
+    Ok(())
+}
@@ -5,12 +5,11 @@ use std::any::Any;
 use miden_client::{asset::FungibleAsset, note::{NoteAssets, NoteExecutionHint, NoteInputs, NoteTag, NoteType}, transaction::OutputNote};
 use miden_crypto::{Felt, Word, rand::RpoRandomCoin};
 use miden_lib::note::create_p2id_note;
-use midenname_contracts::domain::{encode_domain, encode_domain_as_felts, unsafe_encode_domain};
+use midenname_contracts::{domain::{encode_domain, encode_domain_as_felts, unsafe_encode_domain}, testing::{add_note_to_builder, execute_note, execute_notes_and_build_chain, init_naming}};
 use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
-use test_utils::init_naming;
 
-use crate::test_utils::{add_note_to_builder, create_note_for_naming, create_p2id_note_exact, execute_note, execute_notes_and_build_chain};
+use crate::test_utils::{create_note_for_naming, create_p2id_note_exact};
 
 #[tokio::test]
 #[ignore = "not implemented"]
@@ -21,7 +20,7 @@ async fn test_claim_protocol_revenue() -> anyhow::Result<()> {
     // Register domain to increase protocol revenue
     let domain = encode_domain_as_felts("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -53,8 +52,8 @@ async fn test_claim_protocol_revenue() -> anyhow::Result<()> {
         NoteType::Public.into(),
         Felt::new(27),
         NoteTag::from_account_id(ctx.naming.id()).into(),
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
-        Felt::new(ctx.fungible_asset.faucet_id().prefix().as_u64()),
+        ctx.fungible_asset.faucet_id().suffix(),
+        ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
     ].to_vec())?;
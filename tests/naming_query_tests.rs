@@ -0,0 +1,160 @@
+use std::{fs, path::Path};
+
+use miden_client::{account::{Account, AccountBuilder, AccountStorageMode, StorageSlot}, asset::FungibleAsset};
+use miden_crypto::Word;
+use miden_lib::{account::auth, transaction::TransactionKernel};
+use miden_objects::account::AccountComponent;
+use midenname_contracts::{audit::audit_registry, compile::CompileOptions, domain::encode_domain, errors::{MidenIdError, RegistryError}, notes::{create_naming_deregister_note, create_naming_register_name_note}, queries::{RegistryStats, account_id_from_felts, account_id_to_felts, account_id_to_word, get_account_for_name, get_domain_owner, get_name_for_account, get_owner_id, get_total_registrations, iter_registered_domains, list_registered_names, map_len, registry_stats, word_to_account_id}, storage::{DOMAIN_TO_OWNER_SLOT, INIT_FLAG_SLOT, naming_storage}, testing::{add_note_to_builder, create_test_naming_account, execute_note, execute_notes_and_build_chain, init_naming}};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+// Older layout where mapping slots were value slots
+fn create_legacy_naming_account() -> Account {
+    let mut storage_slots = naming_storage();
+    storage_slots[3] = StorageSlot::Value(Word::default());
+    storage_slots[4] = StorageSlot::Value(Word::default());
+    storage_slots.truncate(5);
+
+    let code = fs::read_to_string(Path::new("./masm/accounts/naming.masm")).unwrap();
+    let component = AccountComponent::compile(
+        code,
+        TransactionKernel::assembler().with_debug_mode(true),
+        storage_slots
+    ).unwrap().with_supports_all_types();
+
+    AccountBuilder::new(ChaCha20Rng::from_os_rng().random())
+        .with_auth_component(auth::NoAuth)
+        .with_component(component)
+        .storage_mode(AccountStorageMode::Public)
+        .build_existing().unwrap()
+}
+
+#[tokio::test]
+async fn test_queries_on_uninitialized_naming() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let naming = create_test_naming_account();
+
+    assert_eq!(get_account_for_name(&naming, "test")?, None);
+    assert_eq!(get_domain_owner(&naming, "test")?, None);
+    assert_eq!(get_name_for_account(&naming, ctx.registrar_1.id())?, None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_queries_absent_key() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("test".to_string()), cost, 1, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id()], &mut ctx.naming).await?;
+
+    assert_eq!(get_domain_owner(&ctx.naming, "test")?, Some(ctx.registrar_1.id()));
+    assert_eq!(get_domain_owner(&ctx.naming, "other")?, None);
+    assert_eq!(get_account_for_name(&ctx.naming, "other")?, None);
+    assert_eq!(get_name_for_account(&ctx.naming, ctx.registrar_2.id())?, None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_queries_on_legacy_naming() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let naming = create_legacy_naming_account();
+
+    let err = get_account_for_name(&naming, "test").expect_err("Expected slot type error");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::StorageSlotNotMap { slot: 4 })));
+
+    let err = get_name_for_account(&naming, ctx.registrar_1.id()).expect_err("Expected slot type error");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::StorageSlotNotMap { slot: 3 })));
+
+    let err = get_domain_owner(&naming, "test").expect_err("Expected missing slot error");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::StorageSlotMissing { slot: 5 })));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_owner_id() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let naming = create_test_naming_account();
+
+    let err = get_owner_id(&naming).expect_err("Expected uninitialized naming to have no owner");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::OwnerNotSet)));
+
+    execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id()], &mut ctx.naming).await?;
+    assert_eq!(get_owner_id(&ctx.naming)?, ctx.owner.id());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_registered_names() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let registrations = [
+        ("alice", ctx.registrar_1.id(), 123),
+        ("bob", ctx.registrar_2.id(), 789),
+        ("carol", ctx.registrar_1.id(), 123),
+        ("dave", ctx.registrar_3.id(), 555),
+        ("erin", ctx.registrar_2.id(), 555),
+    ];
+
+    let mut note_ids = vec![ctx.initialize_note.id(), ctx.set_prices_note.id()];
+    for (name, registrant, price) in registrations {
+        let note = create_naming_register_name_note(registrant, ctx.naming.id(), encode_domain(name.to_string()), FungibleAsset::new(token, price)?, 1, CompileOptions::DEBUG).await?;
+        note_ids.push(note.id());
+        add_note_to_builder(&mut ctx.builder, note)?;
+    }
+    execute_notes_and_build_chain(ctx.builder, &note_ids, &mut ctx.naming).await?;
+
+    assert_eq!(iter_registered_domains(&ctx.naming).count(), 5);
+    let expected: Vec<(String, _)> = registrations.iter().map(|(name, owner, _)| (name.to_string(), *owner)).collect();
+    assert_eq!(list_registered_names(&ctx.naming), expected);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_registry_stats_count_registered_names() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let registrations = [("alice", 123), ("bob", 789), ("carol", 123), ("dave", 555)];
+
+    let mut note_ids = vec![ctx.initialize_note.id(), ctx.set_prices_note.id()];
+    for (name, price) in registrations {
+        let note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain(name.to_string()), FungibleAsset::new(token, price)?, 1, CompileOptions::DEBUG).await?;
+        note_ids.push(note.id());
+        add_note_to_builder(&mut ctx.builder, note)?;
+    }
+    let deregister_note = create_naming_deregister_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("bob".to_string()), CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, deregister_note.clone())?;
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &note_ids, &mut ctx.naming).await?;
+
+    assert_eq!(map_len(&ctx.naming, DOMAIN_TO_OWNER_SLOT)?, registrations.len());
+    assert_eq!(get_total_registrations(&ctx.naming)?, registrations.len() as u64);
+    assert_eq!(registry_stats(&ctx.naming)?, RegistryStats { registered_names: 4, resolving_names: 0, reverse_entries: 0, total_registrations: 4 });
+
+    // A released name leaves the map but stays counted
+    execute_note(&mut chain, deregister_note.id(), &mut ctx.naming).await?;
+    assert_eq!(map_len(&ctx.naming, DOMAIN_TO_OWNER_SLOT)?, 3);
+    assert_eq!(get_total_registrations(&ctx.naming)?, 4);
+    assert!(audit_registry(&ctx.naming)?.is_clean());
+
+    let result = map_len(&ctx.naming, INIT_FLAG_SLOT);
+    assert!(matches!(result, Err(MidenIdError::Registry(RegistryError::StorageSlotNotMap { slot: 0 }))), "Unexpected result: {result:?}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_account_id_felts_round_trip() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+
+    for account_id in [ctx.owner.id(), ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id()] {
+        let (prefix, suffix) = account_id_to_felts(account_id);
+        assert_eq!(prefix, account_id.prefix().as_felt());
+        assert_eq!(suffix, account_id.suffix());
+        assert_eq!(account_id_from_felts(prefix, suffix)?, account_id);
+        assert_eq!(word_to_account_id(account_id_to_word(account_id))?, Some(account_id));
+
+        // Swapped halves are not an id
+        assert!(account_id_from_felts(suffix, prefix).is_err(), "Expected swapped felts of {account_id} to be rejected");
+    }
+    Ok(())
+}
@@ -2,10 +2,10 @@ mod test_utils;
 
 use miden_client::{asset::FungibleAsset, note::{NoteAssets, NoteInputs}};
 use miden_crypto::{Felt, Word};
-use midenname_contracts::domain::{encode_domain, encode_domain_as_felts, unsafe_encode_domain};
-use test_utils::init_naming;
+use midenname_contracts::{domain::{encode_domain, encode_domain_as_felts, unsafe_encode_domain}, notes::with_serial_num, queries::{account_id_from_felts, account_id_to_word}, testing::{add_note_to_builder, execute_note, execute_notes_and_build_chain, init_naming}};
+
+use crate::test_utils::{create_note_for_naming, get_test_prices};
 
-use crate::test_utils::{add_note_to_builder, create_note_for_naming, execute_note, execute_notes_and_build_chain, get_test_prices, create_note_for_naming_with_custom_serial_num};
 #[tokio::test]
 async fn test_naming_register_under_referrer() -> anyhow::Result<()> {
     let mut ctx = init_naming().await?;
@@ -15,8 +15,8 @@ async fn test_naming_register_under_referrer() -> anyhow::Result<()> {
         Felt::new(0),
         Felt::new(0),
         Felt::new(0),
-        Felt::new(ctx.registrar_2.id().suffix().as_int()),
-        Felt::new(ctx.registrar_2.id().prefix().as_u64()),
+        ctx.registrar_2.id().suffix(),
+        ctx.registrar_2.id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
     ].to_vec())?;
@@ -27,11 +27,11 @@ async fn test_naming_register_under_referrer() -> anyhow::Result<()> {
     let domain = encode_domain_as_felts("test".to_string());
     let domain_word = encode_domain("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.registrar_2.id().suffix().as_int()),
-        Felt::new(ctx.registrar_2.id().prefix().as_u64()),
+        ctx.registrar_2.id().suffix(),
+        ctx.registrar_2.id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -55,11 +55,10 @@ async fn test_naming_register_under_referrer() -> anyhow::Result<()> {
     let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain_word)?;
     let domain_expiry_slot = ctx.naming.storage().get_map_item(12, domain_word)?;
     let domain_to_id = ctx.naming.storage().get_map_item(4, domain_word)?;
-    let id_to_domain = ctx.naming.storage().get_map_item(3, Word::new([Felt::new(ctx.registrar_1.id().suffix().as_int()), Felt::new(ctx.registrar_1.id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let id_to_domain = ctx.naming.storage().get_map_item(3, account_id_to_word(ctx.registrar_1.id()))?;
 
 
-    assert_eq!(domain_owner_slot.get(0).unwrap().as_int(), ctx.registrar_1.id().suffix().as_int());
-    assert_eq!(domain_owner_slot.get(1).unwrap().as_int(), ctx.registrar_1.id().prefix().as_u64());
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.registrar_1.id());
 
     assert!(domain_expiry_slot.get(0).unwrap().as_int() >= (1700000000 + ctx.one_year).into());
 
@@ -70,12 +69,12 @@ async fn test_naming_register_under_referrer() -> anyhow::Result<()> {
     
     // Protocol values
 
-    let total_revenue_slot = ctx.naming.storage().get_map_item(10, Word::new([Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()), Felt::new(ctx.fungible_asset.faucet_id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(ctx.fungible_asset.faucet_id()))?;
     assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), 444);
 
     // Referrer values
 
-    let referrer_slot = ctx.naming.storage().get_map_item(7, Word::new([Felt::new(ctx.registrar_2.id().suffix().as_int()), Felt::new(ctx.registrar_2.id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let referrer_slot = ctx.naming.storage().get_map_item(7, account_id_to_word(ctx.registrar_2.id()))?;
     assert_eq!(referrer_slot.get(0).unwrap().as_int(), 111);
     Ok(())
 }
@@ -89,8 +88,8 @@ async fn test_naming_referrer_revenue_accumulation() -> anyhow::Result<()> {
         Felt::new(0),
         Felt::new(0),
         Felt::new(0),
-        Felt::new(ctx.registrar_2.id().suffix().as_int()),
-        Felt::new(ctx.registrar_2.id().prefix().as_u64()),
+        ctx.registrar_2.id().suffix(),
+        ctx.registrar_2.id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
     ].to_vec())?;
@@ -101,11 +100,11 @@ async fn test_naming_referrer_revenue_accumulation() -> anyhow::Result<()> {
     let domain = encode_domain_as_felts("test".to_string());
     let domain_word = encode_domain("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.registrar_2.id().suffix().as_int()),
-        Felt::new(ctx.registrar_2.id().prefix().as_u64()),
+        ctx.registrar_2.id().suffix(),
+        ctx.registrar_2.id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -127,11 +126,11 @@ async fn test_naming_referrer_revenue_accumulation() -> anyhow::Result<()> {
     let domain_2 = encode_domain_as_felts("test2".to_string());
     let domain_word_2 = encode_domain("test2".to_string());
     let register_note_inputs_2 = NoteInputs::new([
-        Felt::new(ctx.registrar_2.id().suffix().as_int()),
-        Felt::new(ctx.registrar_2.id().prefix().as_u64()),
+        ctx.registrar_2.id().suffix(),
+        ctx.registrar_2.id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -147,19 +146,19 @@ async fn test_naming_referrer_revenue_accumulation() -> anyhow::Result<()> {
     
     let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 123)?;
     let register_asset = NoteAssets::new(vec![cost.into()])?;
-    let register_note_2 = create_note_for_naming_with_custom_serial_num("register_with_referrer".to_string(), register_note_inputs_2, ctx.registrar_1.id(), ctx.naming.id(), register_asset, Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(1)])).await?;
+    let register_note_2 = with_serial_num(create_note_for_naming("register_with_referrer".to_string(), register_note_inputs_2, ctx.registrar_1.id(), ctx.naming.id(), register_asset).await?, Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(1)]));
     add_note_to_builder(&mut ctx.builder, register_note_2.clone())?;
 
     let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), set_ref_rate_note.id(), register_note.id(), register_note_2.id()], &mut ctx.naming).await?;
     
     // Protocol values
 
-    let total_revenue_slot = ctx.naming.storage().get_map_item(10, Word::new([Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()), Felt::new(ctx.fungible_asset.faucet_id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(ctx.fungible_asset.faucet_id()))?;
     assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), 543);
 
     // Referrer values
 
-    let referrer_slot = ctx.naming.storage().get_map_item(7, Word::new([Felt::new(ctx.registrar_2.id().suffix().as_int()), Felt::new(ctx.registrar_2.id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let referrer_slot = ctx.naming.storage().get_map_item(7, account_id_to_word(ctx.registrar_2.id()))?;
     assert_eq!(referrer_slot.get(0).unwrap().as_int(), 135);
     Ok(())
 }
\ No newline at end of file
@@ -1,11 +1,21 @@
 mod test_utils;
 
-use miden_client::{asset::FungibleAsset, note::{NoteAssets, NoteInputs}};
+use miden_client::{account::AccountId, asset::{FungibleAsset, NonFungibleAsset}, note::{NoteAssets, NoteInputs}, testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2};
 use miden_crypto::{Felt, Word};
-use midenname_contracts::domain::{encode_domain, encode_domain_as_felts, unsafe_encode_domain};
-use test_utils::init_naming;
+use midenname_contracts::{compile::CompileOptions, domain::{MAX_DOMAIN_LENGTH, encode_domain, encode_domain_as_felts, try_encode_domain, unsafe_encode_domain}, errors::{MidenIdError, RegistryError, contract_error_code}, notes::{RegisterNoteInputs, create_naming_deregister_note, create_naming_register_name_note, create_naming_register_name_note_from_name, create_naming_register_name_notes, create_naming_register_name_note_with_assets, create_naming_renew_via_register, create_naming_set_max_domains_note, create_naming_transfer_domain_note, with_serial_num}, queries::{account_id_from_felts, account_id_to_word, get_domain_count, get_max_domains_per_account}, testing::{add_note_to_builder, execute_note, execute_notes_and_build_chain, init_naming}};
+use miden_testing::MockChain;
 
-use crate::test_utils::{add_note_to_builder, create_note_for_naming, execute_note, execute_notes_and_build_chain, get_test_prices, create_note_for_naming_with_custom_serial_num};
+use crate::test_utils::{create_note_for_naming, get_test_prices};
+
+// (total revenue, domain count, vault balance) of the payment token, read from the chain instead of the
+// locally tracked account so a failed transaction can't hide partial changes
+fn committed_revenue_state(chain: &MockChain, naming_id: AccountId, token: AccountId) -> anyhow::Result<(u64, u64, u64)> {
+    let naming = chain.committed_account(naming_id)?;
+
+    let revenue = naming.storage().get_map_item(10, account_id_to_word(token))?;
+    let count = naming.storage().get_item(9)?;
+    Ok((revenue[0].as_int(), count[0].as_int(), naming.vault().get_balance(token)?))
+}
 
 #[tokio::test]
 async fn test_naming_initialize() -> anyhow::Result<()> {
@@ -21,8 +31,7 @@ async fn test_naming_initialize() -> anyhow::Result<()> {
     let one_year_slot = ctx.naming.storage().get_item(13)?;
 
     assert_eq!(init_slot.get(0).unwrap().as_int(), 1);
-    assert_eq!(owner_slot.get(1).unwrap().as_int(), ctx.owner.id().prefix().as_u64());
-    assert_eq!(owner_slot.get(0).unwrap().as_int(), ctx.owner.id().suffix().as_int());
+    assert_eq!(account_id_from_felts(owner_slot[1], owner_slot[0])?, ctx.owner.id());
     assert_eq!(one_year_slot.get(0).unwrap().as_int(), 500);
 
     // Assert prices
@@ -31,7 +40,7 @@ async fn test_naming_initialize() -> anyhow::Result<()> {
         let price_slot = ctx.naming.storage()
             .get_map_item(2, 
                 Word::new([
-                        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+                        ctx.fungible_asset.faucet_id().suffix(),
                         ctx.fungible_asset.faucet_id().prefix().as_felt(),
                         Felt::new(i as u64),
                         Felt::new(0)
@@ -50,7 +59,7 @@ async fn test_naming_register_activate() -> anyhow::Result<()> {
     let domain = encode_domain_as_felts("test".to_string());
     let domain_word = encode_domain("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -78,11 +87,10 @@ async fn test_naming_register_activate() -> anyhow::Result<()> {
     let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain_word)?;
     let domain_expiry_slot = ctx.naming.storage().get_map_item(12, domain_word)?;
     let domain_to_id = ctx.naming.storage().get_map_item(4, domain_word)?;
-    let id_to_domain = ctx.naming.storage().get_map_item(3, Word::new([Felt::new(ctx.registrar_1.id().suffix().as_int()), Felt::new(ctx.registrar_1.id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let id_to_domain = ctx.naming.storage().get_map_item(3, account_id_to_word(ctx.registrar_1.id()))?;
 
 
-    assert_eq!(domain_owner_slot.get(0).unwrap().as_int(), ctx.registrar_1.id().suffix().as_int());
-    assert_eq!(domain_owner_slot.get(1).unwrap().as_int(), ctx.registrar_1.id().prefix().as_u64());
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.registrar_1.id());
 
     assert!(domain_expiry_slot.get(0).unwrap().as_int() >= (1700000000 + ctx.one_year).into());
 
@@ -93,7 +101,7 @@ async fn test_naming_register_activate() -> anyhow::Result<()> {
     
     // Protocol values
 
-    let total_revenue_slot = ctx.naming.storage().get_map_item(10, Word::new([Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()), Felt::new(ctx.fungible_asset.faucet_id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(ctx.fungible_asset.faucet_id()))?;
     assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), 555);
 
     let total_domain_count = ctx.naming.storage().get_item(9)?;
@@ -105,10 +113,9 @@ async fn test_naming_register_activate() -> anyhow::Result<()> {
     execute_note(&mut chain, activate_note.id(), &mut ctx.naming).await?; // Use always updated account as target
 
     let domain_to_id = ctx.naming.storage().get_map_item(4, domain_word)?;
-    let id_to_domain = ctx.naming.storage().get_map_item(3, Word::new([Felt::new(ctx.registrar_1.id().suffix().as_int()), Felt::new(ctx.registrar_1.id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let id_to_domain = ctx.naming.storage().get_map_item(3, account_id_to_word(ctx.registrar_1.id()))?;
 
-    assert_eq!(domain_to_id.get(0).unwrap().as_int(), ctx.registrar_1.id().suffix().as_int()); // Now domain mapping must be matched
-    assert_eq!(domain_to_id.get(1).unwrap().as_int(), ctx.registrar_1.id().prefix().as_u64());
+    assert_eq!(account_id_from_felts(domain_to_id[1], domain_to_id[0])?, ctx.registrar_1.id()); // Now domain mapping must be matched
     assert_eq!(id_to_domain, domain_word);
     Ok(())
 }
@@ -120,7 +127,7 @@ async fn test_naming_register_activate_by_not_owner() -> anyhow::Result<()> {
     let domain = encode_domain_as_felts("test".to_string());
     let domain_word = encode_domain("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -148,11 +155,10 @@ async fn test_naming_register_activate_by_not_owner() -> anyhow::Result<()> {
     let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain_word)?;
     let domain_expiry_slot = ctx.naming.storage().get_map_item(12, domain_word)?;
     let domain_to_id = ctx.naming.storage().get_map_item(4, domain_word)?;
-    let id_to_domain = ctx.naming.storage().get_map_item(3, Word::new([Felt::new(ctx.registrar_1.id().suffix().as_int()), Felt::new(ctx.registrar_1.id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let id_to_domain = ctx.naming.storage().get_map_item(3, account_id_to_word(ctx.registrar_1.id()))?;
 
 
-    assert_eq!(domain_owner_slot.get(0).unwrap().as_int(), ctx.registrar_1.id().suffix().as_int());
-    assert_eq!(domain_owner_slot.get(1).unwrap().as_int(), ctx.registrar_1.id().prefix().as_u64());
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.registrar_1.id());
 
     assert!(domain_expiry_slot.get(0).unwrap().as_int() >= (1700000000 + ctx.one_year).into());
 
@@ -163,7 +169,7 @@ async fn test_naming_register_activate_by_not_owner() -> anyhow::Result<()> {
     
     // Protocol values
 
-    let total_revenue_slot = ctx.naming.storage().get_map_item(10, Word::new([Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()), Felt::new(ctx.fungible_asset.faucet_id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(ctx.fungible_asset.faucet_id()))?;
     assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), 555);
 
     let total_domain_count = ctx.naming.storage().get_item(9)?;
@@ -185,7 +191,7 @@ async fn test_naming_register_already_exist_domain() -> anyhow::Result<()> {
 
     let domain = encode_domain_as_felts("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -213,12 +219,15 @@ async fn test_naming_register_already_exist_domain() -> anyhow::Result<()> {
     let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
     
     execute_note(&mut chain, register_note.id(), &mut ctx.naming).await?;
+    let before = committed_revenue_state(&chain, ctx.naming.id(), ctx.fungible_asset.faucet_id())?;
+    assert_eq!(before.0, 555);
 
     // Try to register again with different owner
     
     let result = execute_note(&mut chain, register_note_2.id(), &mut ctx.naming).await;
 
     assert!(result.is_err(), "Expected domain register fails. But it succeeded");
+    assert_eq!(committed_revenue_state(&chain, ctx.naming.id(), ctx.fungible_asset.faucet_id())?, before, "Failed registration changed revenue state");
     Ok(())
 }
 
@@ -228,7 +237,7 @@ async fn test_naming_register_already_exist_domain_from_same_owner() -> anyhow::
 
     let domain = encode_domain_as_felts("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -272,7 +281,7 @@ async fn test_naming_register_two_domains_activate_after() -> anyhow::Result<()>
 
     // Notes
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -296,7 +305,7 @@ async fn test_naming_register_two_domains_activate_after() -> anyhow::Result<()>
     let second_domain = encode_domain_as_felts("test2".to_string());
     let second_domain_word = encode_domain("test2".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -326,11 +335,10 @@ async fn test_naming_register_two_domains_activate_after() -> anyhow::Result<()>
     let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain_word)?;
     let domain_expiry_slot = ctx.naming.storage().get_map_item(12, domain_word)?;
     let domain_to_id = ctx.naming.storage().get_map_item(4, domain_word)?;
-    let id_to_domain = ctx.naming.storage().get_map_item(3, Word::new([Felt::new(ctx.registrar_1.id().suffix().as_int()), Felt::new(ctx.registrar_1.id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let id_to_domain = ctx.naming.storage().get_map_item(3, account_id_to_word(ctx.registrar_1.id()))?;
 
 
-    assert_eq!(domain_owner_slot.get(0).unwrap().as_int(), ctx.registrar_1.id().suffix().as_int());
-    assert_eq!(domain_owner_slot.get(1).unwrap().as_int(), ctx.registrar_1.id().prefix().as_u64());
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.registrar_1.id());
 
     assert!(domain_expiry_slot.get(0).unwrap().as_int() >= (1700000000 + ctx.one_year).into());
 
@@ -341,7 +349,7 @@ async fn test_naming_register_two_domains_activate_after() -> anyhow::Result<()>
     
     // Protocol values
 
-    let total_revenue_slot = ctx.naming.storage().get_map_item(10, Word::new([Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()), Felt::new(ctx.fungible_asset.faucet_id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(ctx.fungible_asset.faucet_id()))?;
     assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), 555);
 
     let total_domain_count = ctx.naming.storage().get_item(9)?;
@@ -352,10 +360,9 @@ async fn test_naming_register_two_domains_activate_after() -> anyhow::Result<()>
     execute_note(&mut chain, activate_note_1.id(), &mut ctx.naming).await?; // Use always updated account as target
 
     let domain_to_id = ctx.naming.storage().get_map_item(4, domain_word)?;
-    let id_to_domain = ctx.naming.storage().get_map_item(3, Word::new([Felt::new(ctx.registrar_1.id().suffix().as_int()), Felt::new(ctx.registrar_1.id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let id_to_domain = ctx.naming.storage().get_map_item(3, account_id_to_word(ctx.registrar_1.id()))?;
 
-    assert_eq!(domain_to_id.get(0).unwrap().as_int(), ctx.registrar_1.id().suffix().as_int()); // Now domain mapping must be matched
-    assert_eq!(domain_to_id.get(1).unwrap().as_int(), ctx.registrar_1.id().prefix().as_u64());
+    assert_eq!(account_id_from_felts(domain_to_id[1], domain_to_id[0])?, ctx.registrar_1.id()); // Now domain mapping must be matched
     assert_eq!(id_to_domain, domain_word);
 
     // Register new domain
@@ -366,8 +373,7 @@ async fn test_naming_register_two_domains_activate_after() -> anyhow::Result<()>
     let second_domain_owner_slot = ctx.naming.storage().get_map_item(5, second_domain_word)?;
     let second_domain_expiry_slot = ctx.naming.storage().get_map_item(12, second_domain_word)?;
 
-    assert_eq!(second_domain_owner_slot.get(0).unwrap().as_int(), ctx.registrar_1.id().suffix().as_int());
-    assert_eq!(second_domain_owner_slot.get(1).unwrap().as_int(), ctx.registrar_1.id().prefix().as_u64());
+    assert_eq!(account_id_from_felts(second_domain_owner_slot[1], second_domain_owner_slot[0])?, ctx.registrar_1.id());
 
     assert!(second_domain_expiry_slot.get(0).unwrap().as_int() >= (1700000000 + ctx.one_year).into());
 
@@ -377,21 +383,19 @@ async fn test_naming_register_two_domains_activate_after() -> anyhow::Result<()>
     execute_note(&mut chain, activate_note_2.id(), &mut ctx.naming).await?; // Use always updated account as target
 
     let domain_to_id = ctx.naming.storage().get_map_item(4, second_domain_word)?;
-    let id_to_domain = ctx.naming.storage().get_map_item(3, Word::new([Felt::new(ctx.registrar_1.id().suffix().as_int()), Felt::new(ctx.registrar_1.id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let id_to_domain = ctx.naming.storage().get_map_item(3, account_id_to_word(ctx.registrar_1.id()))?;
 
-    assert_eq!(domain_to_id.get(0).unwrap().as_int(), ctx.registrar_1.id().suffix().as_int()); // Now domain mapping must be matched
-    assert_eq!(domain_to_id.get(1).unwrap().as_int(), ctx.registrar_1.id().prefix().as_u64());
+    assert_eq!(account_id_from_felts(domain_to_id[1], domain_to_id[0])?, ctx.registrar_1.id()); // Now domain mapping must be matched
     assert_eq!(id_to_domain, second_domain_word);
 
     // Check first domain mapping
 
     let first_domain_to_id = ctx.naming.storage().get_map_item(4, domain_word)?;
-    assert_eq!(first_domain_to_id.get(0).unwrap().as_int(), ctx.registrar_1.id().suffix().as_int()); // First domain must remain mapping to old address
-    assert_eq!(first_domain_to_id.get(1).unwrap().as_int(), ctx.registrar_1.id().prefix().as_u64());
+    assert_eq!(account_id_from_felts(first_domain_to_id[1], first_domain_to_id[0])?, ctx.registrar_1.id()); // First domain must remain mapping to old address
 
     // Ensure protocol values
 
-    let total_revenue_slot = ctx.naming.storage().get_map_item(10, Word::new([Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()), Felt::new(ctx.fungible_asset.faucet_id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(ctx.fungible_asset.faucet_id()))?;
     assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), 555 + 123);
 
     let total_domain_count = ctx.naming.storage().get_item(9)?;
@@ -405,7 +409,7 @@ async fn test_naming_register_less_amount() -> anyhow::Result<()> {
 
     let domain = encode_domain_as_felts("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -424,9 +428,11 @@ async fn test_naming_register_less_amount() -> anyhow::Result<()> {
     let note = create_note_for_naming("register_name".to_string(), register_note_inputs, ctx.registrar_1.id(), ctx.naming.id(), register_asset).await?;
     add_note_to_builder(&mut ctx.builder, note.clone())?;
     let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    let before = committed_revenue_state(&chain, ctx.naming.id(), ctx.fungible_asset.faucet_id())?;
     let result = execute_note(&mut chain, note.id(), &mut ctx.naming).await;
 
     assert!(result.is_err(), "Expected revert but succeeded.");
+    assert_eq!(committed_revenue_state(&chain, ctx.naming.id(), ctx.fungible_asset.faucet_id())?, before, "Failed registration changed revenue state");
     Ok(())
 }
 
@@ -436,7 +442,7 @@ async fn test_naming_register_higher_amount() -> anyhow::Result<()> {
 
     let domain = encode_domain_as_felts("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -457,7 +463,7 @@ async fn test_naming_register_higher_amount() -> anyhow::Result<()> {
     let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
     execute_note(&mut chain, note.id(), &mut ctx.naming).await?;
 
-    let total_revenue_slot = ctx.naming.storage().get_map_item(10, Word::new([Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()), Felt::new(ctx.fungible_asset.faucet_id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(ctx.fungible_asset.faucet_id()))?;
     assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), 555); // Protocol only saves actual cost as revenue
 
     let total_domain_count = ctx.naming.storage().get_item(9)?;
@@ -472,7 +478,7 @@ async fn test_naming_register_wrong_letter_length() -> anyhow::Result<()> {
 
     let domain = encode_domain_as_felts("testtesttesttest".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -503,7 +509,7 @@ async fn test_naming_register_too_much_letters() -> anyhow::Result<()> {
 
     let domain = unsafe_encode_domain("testtesttesttest123123123123".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -528,13 +534,37 @@ async fn test_naming_register_too_much_letters() -> anyhow::Result<()> {
     Ok(())
 }
 
+// encode_domain validates the name, unsafe_encode_domain only packs it so tests can hand the contract
+// words the client would refuse to build. The contract keeps its own bound (MAX_NAME_LENGTH = 21 in
+// naming.masm, one above the client's MAX_DOMAIN_LENGTH) since notes can be built without this crate.
+#[tokio::test]
+async fn test_encoder_and_contract_length_limits() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    let over_client_limit = "a".repeat(MAX_DOMAIN_LENGTH + 1);
+    assert_eq!(try_encode_domain(&over_client_limit), Err(RegistryError::DomainTooLong { length: MAX_DOMAIN_LENGTH + 1 }));
+    assert!(std::panic::catch_unwind(|| encode_domain(over_client_limit.clone())).is_err(), "encode_domain should panic on a 21 char name");
+    assert_eq!(unsafe_encode_domain(over_client_limit)[3], Felt::new(MAX_DOMAIN_LENGTH as u64 + 1));
+
+    let over_contract_limit = unsafe_encode_domain("a".repeat(MAX_DOMAIN_LENGTH + 2));
+    assert_eq!(over_contract_limit[3], Felt::new(22));
+    let note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), over_contract_limit, FungibleAsset::new(token, 123)?, 1, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    let result = execute_note(&mut chain, note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected the contract to reject a 22 char domain");
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_naming_register_empty_domain() -> anyhow::Result<()> {
     let mut ctx = init_naming().await?;
 
     let domain = unsafe_encode_domain("".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -566,7 +596,7 @@ async fn test_expired_domain_clear() -> anyhow::Result<()> {
     let domain = encode_domain_as_felts("test".to_string());
     let domain_word = encode_domain("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -602,7 +632,7 @@ async fn test_expired_domain_clear() -> anyhow::Result<()> {
     let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain_word)?;
     let domain_expiry_slot = ctx.naming.storage().get_map_item(12, domain_word)?;
     let domain_to_id = ctx.naming.storage().get_map_item(4, domain_word)?;
-    let id_to_domain = ctx.naming.storage().get_map_item(3, Word::new([Felt::new(ctx.registrar_1.id().suffix().as_int()), Felt::new(ctx.registrar_1.id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let id_to_domain = ctx.naming.storage().get_map_item(3, account_id_to_word(ctx.registrar_1.id()))?;
 
 
     assert_eq!(domain_owner_slot.get(0).unwrap().as_int(),0);
@@ -623,7 +653,7 @@ async fn test_expired_domain_clear_not_expired() -> anyhow::Result<()> {
 
     let domain = encode_domain_as_felts("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -665,7 +695,7 @@ async fn test_expired_domain_rebuy() -> anyhow::Result<()> {
     let domain = encode_domain_as_felts("test".to_string());
     let domain_word = encode_domain("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -683,7 +713,7 @@ async fn test_expired_domain_rebuy() -> anyhow::Result<()> {
     let register_asset = NoteAssets::new(vec![cost.into()])?;
     let register_note = create_note_for_naming("register_name".to_string(), register_note_inputs.clone(), ctx.registrar_1.id(), ctx.naming.id(), register_asset.clone()).await?;
     add_note_to_builder(&mut ctx.builder, register_note.clone())?;
-    let activate_note_1 = create_note_for_naming_with_custom_serial_num("activate_domain".to_string(), NoteInputs::new(domain_word.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?, Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)])).await?;
+    let activate_note_1 = with_serial_num(create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain_word.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?, Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]));
     add_note_to_builder(&mut ctx.builder, activate_note_1.clone())?; 
 
     let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 556)?; // Pay more to not create same nullifier
@@ -691,7 +721,7 @@ async fn test_expired_domain_rebuy() -> anyhow::Result<()> {
     let register_note_2 = create_note_for_naming("register_name".to_string(), register_note_inputs.clone(), ctx.registrar_2.id(), ctx.naming.id(), register_asset.clone()).await?;
     add_note_to_builder(&mut ctx.builder, register_note_2.clone())?;
 
-    let activate_note_2 = create_note_for_naming_with_custom_serial_num("activate_domain".to_string(), NoteInputs::new(domain_word.to_vec())?, ctx.registrar_2.id(), ctx.naming.id(), NoteAssets::new(vec![])?, Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(5)])).await?;
+    let activate_note_2 = with_serial_num(create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain_word.to_vec())?, ctx.registrar_2.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?, Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(5)]));
     add_note_to_builder(&mut ctx.builder, activate_note_2.clone())?; 
 
     let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
@@ -703,8 +733,7 @@ async fn test_expired_domain_rebuy() -> anyhow::Result<()> {
     let domain_expiry_slot = ctx.naming.storage().get_map_item(12, domain_word)?;
 
 
-    assert_eq!(domain_owner_slot.get(0).unwrap().as_int(), ctx.registrar_1.id().suffix().as_int());
-    assert_eq!(domain_owner_slot.get(1).unwrap().as_int(), ctx.registrar_1.id().prefix().as_u64());
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.registrar_1.id());
 
     assert!(domain_expiry_slot.get(0).unwrap().as_int() >= (1700000000 + ctx.one_year).into());
 
@@ -715,10 +744,9 @@ async fn test_expired_domain_rebuy() -> anyhow::Result<()> {
     let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain_word)?;
     let domain_expiry_slot = ctx.naming.storage().get_map_item(12, domain_word)?;
     let domain_to_id = ctx.naming.storage().get_map_item(4, domain_word)?;
-    let id_to_domain = ctx.naming.storage().get_map_item(3, Word::new([Felt::new(ctx.registrar_2.id().suffix().as_int()), Felt::new(ctx.registrar_2.id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let id_to_domain = ctx.naming.storage().get_map_item(3, account_id_to_word(ctx.registrar_2.id()))?;
 
-    assert_eq!(domain_owner_slot.get(0).unwrap().as_int(), ctx.registrar_2.id().suffix().as_int());
-    assert_eq!(domain_owner_slot.get(1).unwrap().as_int(), ctx.registrar_2.id().prefix().as_u64());
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.registrar_2.id());
 
     assert!(domain_expiry_slot.get(0).unwrap().as_int() >= (1700000000 + ctx.one_year).into());
     
@@ -730,10 +758,9 @@ async fn test_expired_domain_rebuy() -> anyhow::Result<()> {
     execute_note(&mut chain, activate_note_2.id(), &mut ctx.naming).await?;
 
     let domain_to_id = ctx.naming.storage().get_map_item(4, domain_word)?;
-    let id_to_domain = ctx.naming.storage().get_map_item(3, Word::new([Felt::new(ctx.registrar_2.id().suffix().as_int()), Felt::new(ctx.registrar_2.id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let id_to_domain = ctx.naming.storage().get_map_item(3, account_id_to_word(ctx.registrar_2.id()))?;
     
-    assert_eq!(domain_to_id.get(0).unwrap().as_int(), ctx.registrar_2.id().suffix().as_int()); // Now domain mapping must be matched
-    assert_eq!(domain_to_id.get(1).unwrap().as_int(), ctx.registrar_2.id().prefix().as_u64());
+    assert_eq!(account_id_from_felts(domain_to_id[1], domain_to_id[0])?, ctx.registrar_2.id()); // Now domain mapping must be matched
     assert_eq!(id_to_domain, domain_word);
     Ok(())
 }
@@ -745,7 +772,7 @@ async fn test_extend_domain_by_owner() -> anyhow::Result<()> {
     let domain = encode_domain_as_felts("test".to_string());
     let domain_word = encode_domain("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -788,7 +815,7 @@ async fn test_extend_expired_domain() -> anyhow::Result<()> {
 
     let domain = encode_domain_as_felts("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -829,7 +856,7 @@ async fn test_extend_domain_by_not_owner() -> anyhow::Result<()> {
 
     let domain = encode_domain_as_felts("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -861,6 +888,52 @@ async fn test_extend_domain_by_not_owner() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_renew_via_register_by_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain_word = encode_domain("test".to_string());
+    let token = ctx.fungible_asset.faucet_id();
+
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain_word, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let renew_note = create_naming_renew_via_register(ctx.registrar_1.id(), ctx.naming.id(), domain_word, FungibleAsset::new(token, 555 * 2)?, 2, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, renew_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id()], &mut ctx.naming).await?;
+    let registered_expiry = ctx.naming.storage().get_map_item(12, domain_word)?.get(0).unwrap().as_int();
+
+    execute_note(&mut chain, renew_note.id(), &mut ctx.naming).await?;
+
+    // One year from registration plus two renewed years
+    let renewed_expiry = ctx.naming.storage().get_map_item(12, domain_word)?.get(0).unwrap().as_int();
+    assert_eq!(renewed_expiry, registered_expiry + 2 * ctx.one_year as u64);
+
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain_word)?;
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.registrar_1.id());
+
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(token))?;
+    assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), 555 * 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_renew_via_register_by_not_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain_word = encode_domain("test".to_string());
+    let token = ctx.fungible_asset.faucet_id();
+
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain_word, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let renew_note = create_naming_renew_via_register(ctx.registrar_2.id(), ctx.naming.id(), domain_word, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, renew_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id()], &mut ctx.naming).await?;
+
+    let result = execute_note(&mut chain, renew_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected register of a taken domain by another account to fail");
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_register_with_discount_5yr() -> anyhow::Result<()> {
     let mut ctx = init_naming().await?;
@@ -868,7 +941,7 @@ async fn test_register_with_discount_5yr() -> anyhow::Result<()> {
     let domain = encode_domain_as_felts("test".to_string());
     let domain_word = encode_domain("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -899,7 +972,7 @@ async fn test_register_with_discount_5yr() -> anyhow::Result<()> {
     let expected_expiry = current_time + (ctx.one_year * 5);
     assert_eq!(expiry, expected_expiry as u64);
 
-    let total_revenue_slot = ctx.naming.storage().get_map_item(10, Word::new([Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()), Felt::new(ctx.fungible_asset.faucet_id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(ctx.fungible_asset.faucet_id()))?;
     assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), discounted_cost);
 
     Ok(())
@@ -912,7 +985,7 @@ async fn test_register_with_discount_3yr() -> anyhow::Result<()> {
     let domain = encode_domain_as_felts("test".to_string());
     let domain_word = encode_domain("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -942,7 +1015,7 @@ async fn test_register_with_discount_3yr() -> anyhow::Result<()> {
     let expected_expiry = current_time + (ctx.one_year * 3);
     assert_eq!(expiry, expected_expiry as u64);
 
-    let total_revenue_slot = ctx.naming.storage().get_map_item(10, Word::new([Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()), Felt::new(ctx.fungible_asset.faucet_id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(ctx.fungible_asset.faucet_id()))?;
     assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), discounted_cost);
 
     Ok(())
@@ -955,7 +1028,7 @@ let mut ctx = init_naming().await?;
     let domain = encode_domain_as_felts("test".to_string());
     let domain_word = encode_domain("test".to_string());
     let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().suffix(),
         ctx.fungible_asset.faucet_id().prefix().as_felt(),
         Felt::new(0),
         Felt::new(0),
@@ -986,8 +1059,322 @@ let mut ctx = init_naming().await?;
     let expected_expiry = current_time + (ctx.one_year * 10);
     assert_eq!(expiry, expected_expiry as u64);
 
-    let total_revenue_slot = ctx.naming.storage().get_map_item(10, Word::new([Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()), Felt::new(ctx.fungible_asset.faucet_id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(ctx.fungible_asset.faucet_id()))?;
     assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), discounted_cost);
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_register_note_from_name_matches_hand_built_word() -> anyhow::Result<()> {
+    let cost = 123;
+    let mut revenues = Vec::new();
+    let mut input_commitments = Vec::new();
+
+    for from_name in [false, true] {
+        let mut ctx = init_naming().await?;
+        let payment = FungibleAsset::new(ctx.fungible_asset.faucet_id(), cost)?;
+
+        let register_note = if from_name {
+            create_naming_register_name_note_from_name(ctx.registrar_1.id(), ctx.naming.id(), "alice", payment, 1, CompileOptions::DEBUG).await?
+        } else {
+            // alice, length felt set by hand
+            let domain_word = Word::new([Felt::new(0), Felt::new(0), Felt::new(0x503090c01), Felt::new(5)]);
+            create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain_word, payment, 1, CompileOptions::DEBUG).await?
+        };
+        add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+
+        execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id()], &mut ctx.naming).await?;
+
+        let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(ctx.fungible_asset.faucet_id()))?;
+        revenues.push(total_revenue_slot.get(0).unwrap().as_int());
+        input_commitments.push(register_note.recipient().inputs().commitment());
+    }
+
+    assert_eq!(revenues, vec![cost, cost]);
+    assert_eq!(input_commitments[0], input_commitments[1]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_identical_register_notes_have_distinct_ids() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let domain_word = encode_domain("test".to_string());
+    let payment = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+
+    let first = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain_word, payment, 1, CompileOptions::DEBUG).await?;
+    let second = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain_word, payment, 1, CompileOptions::DEBUG).await?;
+
+    assert_eq!(first.recipient().inputs().commitment(), second.recipient().inputs().commitment());
+    assert_ne!(first.id(), second.id());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_note_rejects_invalid_years() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let payment = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+
+    for years in [0, 11] {
+        let err = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("test".to_string()), payment, years, CompileOptions::DEBUG).await
+            .expect_err("Expected invalid registration term to be rejected");
+        assert!(matches!(err, MidenIdError::Registry(RegistryError::InvalidRegistrationYears { years: invalid }) if invalid == years), "Unexpected error: {err}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_register_note_inputs_reject_years_above_u32() -> anyhow::Result<()> {
+    let mut values = RegisterNoteInputs { payment_token: AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2)?, domain: encode_domain("test".to_string()), years: 1 }
+        .to_note_inputs()?
+        .values()
+        .to_vec();
+    values[8] = Felt::new(u64::from(u32::MAX) + 1);
+
+    let err = RegisterNoteInputs::from_note_inputs(&NoteInputs::new(values)?).expect_err("Expected years above u32 to be rejected");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::InvalidNoteField { field: "years", .. })), "Unexpected error: {err}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_invalid_years_rejected_on_chain() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let domain = encode_domain("test".to_string());
+
+    // Built by hand, the note constructors refuse these terms
+    let mut notes = Vec::new();
+    for years in [0_u64, 11] {
+        let mut inputs = vec![token.suffix(), token.prefix().as_felt(), Felt::new(0), Felt::new(0)];
+        inputs.extend(domain.to_vec());
+        inputs.extend([Felt::new(years), Felt::new(0), Felt::new(0), Felt::new(0)]);
+        let note = create_note_for_naming("register_name".to_string(), NoteInputs::new(inputs)?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![FungibleAsset::new(token, 555 * 11)?.into()])?).await?;
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+        notes.push(note);
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    for note in notes {
+        let result = execute_note(&mut chain, note.id(), &mut ctx.naming).await;
+        assert!(result.is_err(), "Expected register with an invalid term to fail");
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_non_canonical_domain_rejected() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    // Both have 2 non-zero bytes for length 2, so they pass the character count check
+    let gapped = Word::new([Felt::new(0), Felt::new(0), Felt::new(0x020001), Felt::new(2)]);
+    let misplaced = Word::new([Felt::new(0), Felt::new(0x0201), Felt::new(0), Felt::new(2)]);
+
+    let mut notes = Vec::new();
+    for domain in [gapped, misplaced] {
+        let note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 45645)?, 1, CompileOptions::DEBUG).await?;
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+        notes.push(note);
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    for note in notes {
+        let result = execute_note(&mut chain, note.id(), &mut ctx.naming).await;
+        assert!(result.is_err(), "Expected non-canonical domain word to be rejected");
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_out_of_alphabet_bytes_rejected() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    // Same non-zero byte count as the declared length, only the character codes are wrong
+    let code_200 = Word::new([Felt::new(0), Felt::new(0), Felt::new(0xC80201), Felt::new(3)]);
+    let code_37 = Word::new([Felt::new(0), Felt::new(0), Felt::new(0x250201), Felt::new(3)]);
+    // Fourth byte beyond the declared length of 3
+    let dirty_padding = Word::new([Felt::new(0), Felt::new(0), Felt::new(0x04030201), Felt::new(3)]);
+
+    let mut notes = Vec::new();
+    for domain in [code_200, code_37, dirty_padding] {
+        let note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 789)?, 1, CompileOptions::DEBUG).await?;
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+        notes.push(note);
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    for note in notes {
+        let result = execute_note(&mut chain, note.id(), &mut ctx.naming).await;
+        assert!(result.is_err(), "Expected domain word with invalid bytes to be rejected");
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_note_rejects_malformed_payment_assets() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let other_token = AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2)?;
+    let domain = encode_domain("test".to_string());
+
+    let two_assets = NoteAssets::new(vec![FungibleAsset::new(token, 555)?.into(), FungibleAsset::new(other_token, 10)?.into()])?;
+    let err = create_naming_register_name_note_with_assets(ctx.registrar_1.id(), ctx.naming.id(), token, domain, 1, two_assets, CompileOptions::DEBUG).await
+        .expect_err("Expected two assets to be rejected");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::PaymentAssetCount { expected: 1, actual: 2 })), "Unexpected error: {err}");
+
+    let wrong_faucet = NoteAssets::new(vec![FungibleAsset::new(other_token, 555)?.into()])?;
+    let err = create_naming_register_name_note_with_assets(ctx.registrar_1.id(), ctx.naming.id(), token, domain, 1, wrong_faucet, CompileOptions::DEBUG).await
+        .expect_err("Expected payment in another token to be rejected");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::WrongPaymentAsset { .. })), "Unexpected error: {err}");
+
+    let err = create_naming_register_name_note_with_assets(ctx.registrar_1.id(), ctx.naming.id(), token, domain, 1, NoteAssets::new(vec![])?, CompileOptions::DEBUG).await
+        .expect_err("Expected paid registration without payment to be rejected");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::PaymentAssetCount { expected: 1, actual: 0 })), "Unexpected error: {err}");
+
+    let non_fungible = NoteAssets::new(vec![NonFungibleAsset::mock(&[1, 2, 3])])?;
+    let err = create_naming_register_name_note_with_assets(ctx.registrar_1.id(), ctx.naming.id(), token, domain, 1, non_fungible, CompileOptions::DEBUG).await
+        .expect_err("Expected a non-fungible payment to be rejected");
+    assert_eq!(err.code(), "WRONG_PAYMENT_ASSET");
+    Ok(())
+}
+
+// Notes built without the constructor checks, the contract rejects them before crediting anything
+#[tokio::test]
+async fn test_register_rejects_extra_and_non_fungible_assets() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let other_token = AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2)?;
+    let inputs = RegisterNoteInputs { payment_token: token, domain: encode_domain("test".to_string()), years: 1 }.to_note_inputs()?;
+
+    let cases = [
+        (vec![FungibleAsset::new(token, 555)?.into(), FungibleAsset::new(other_token, 10)?.into()], "INVALID_PAYMENT_ASSETS"),
+        (vec![NonFungibleAsset::mock(&[1, 2, 3])], "WRONG_PAYMENT_ASSET"),
+        (vec![FungibleAsset::new(other_token, 555)?.into()], "WRONG_PAYMENT_ASSET"),
+    ];
+    let mut notes = Vec::new();
+    for (assets, code) in cases {
+        let note = create_note_for_naming("register_name".to_string(), inputs.clone(), ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(assets)?).await?;
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+        notes.push((note, code));
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    let before = committed_revenue_state(&chain, ctx.naming.id(), token)?;
+    for (note, code) in notes {
+        let err = execute_note(&mut chain, note.id(), &mut ctx.naming).await.expect_err("Expected malformed payment to be rejected");
+        assert_eq!(contract_error_code(&format!("{err:?}")), Some(code), "Unexpected error: {err:?}");
+    }
+    assert_eq!(committed_revenue_state(&chain, ctx.naming.id(), token)?, before);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_batch_register_notes_reject_duplicates() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    let err = create_naming_register_name_notes(ctx.registrar_1.id(), ctx.naming.id(), token, &[("test", 555), ("alice", 123), ("test", 555)], 1, CompileOptions::DEBUG).await
+        .expect_err("Expected a repeated name to be rejected");
+    assert!(matches!(&err, MidenIdError::Registry(RegistryError::DuplicateInBatch { name }) if name == "test"), "Unexpected error: {err}");
+
+    let notes = create_naming_register_name_notes(ctx.registrar_1.id(), ctx.naming.id(), token, &[("test", 555), ("alice", 123)], 1, CompileOptions::DEBUG).await?;
+    assert_eq!(notes.len(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_same_transaction_duplicate_register_fails_atomically() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain_word = encode_domain("test".to_string());
+    let token = ctx.fungible_asset.faucet_id();
+
+    let first = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain_word, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let second = create_naming_register_name_note(ctx.registrar_2.id(), ctx.naming.id(), domain_word, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, first.clone())?;
+    add_note_to_builder(&mut ctx.builder, second.clone())?;
+
+    let chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    let before = committed_revenue_state(&chain, ctx.naming.id(), token)?;
+
+    // The second note sees the first one's write and fails as taken, so the whole transaction reverts
+    let result = chain.build_tx_context(ctx.naming.id(), &[first.id(), second.id()], &[])?.build()?.execute().await;
+    assert!(result.is_err(), "Expected the second registration of the same domain to fail");
+    assert_eq!(committed_revenue_state(&chain, ctx.naming.id(), token)?, before);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_same_transaction_duplicate_register_by_same_sender_renews() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain_word = encode_domain("test".to_string());
+    let token = ctx.fungible_asset.faucet_id();
+
+    let first = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain_word, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let second = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain_word, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, first.clone())?;
+    add_note_to_builder(&mut ctx.builder, second.clone())?;
+
+    let chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+
+    // The sender already owns the domain when the second note runs, so it is applied as a one year renewal
+    let current_time = chain.latest_block_header().timestamp() as u64;
+    let executed_tx = chain.build_tx_context(ctx.naming.id(), &[first.id(), second.id()], &[])?.build()?.execute().await?;
+    ctx.naming.apply_delta(executed_tx.account_delta())?;
+
+    let expiry = ctx.naming.storage().get_map_item(12, domain_word)?.get(0).unwrap().as_int();
+    assert_eq!(expiry, current_time + 2 * ctx.one_year as u64);
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, account_id_to_word(token))?;
+    assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), 555 * 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_max_domains_per_account() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let registrant = ctx.registrar_1.id();
+    let payment = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+
+    let set_max_note = create_naming_set_max_domains_note(ctx.owner.id(), ctx.naming.id(), 2, CompileOptions::DEBUG).await?;
+    let mut register_notes = Vec::new();
+    for name in ["aaaa", "bbbb", "cccc", "cccc"] {
+        register_notes.push(create_naming_register_name_note(registrant, ctx.naming.id(), encode_domain(name.to_string()), payment, 1, CompileOptions::DEBUG).await?);
+    }
+    let deregister_note = create_naming_deregister_note(registrant, ctx.naming.id(), encode_domain("aaaa".to_string()), CompileOptions::DEBUG).await?;
+    for note in register_notes.iter().chain([&set_max_note, &deregister_note]) {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), set_max_note.id(), register_notes[0].id(), register_notes[1].id()], &mut ctx.naming).await?;
+    assert_eq!(get_max_domains_per_account(&ctx.naming)?, 2);
+    assert_eq!(get_domain_count(&ctx.naming, registrant)?, 2);
+
+    let err = execute_note(&mut chain, register_notes[2].id(), &mut ctx.naming).await.expect_err("Expected registration above the cap to fail");
+    assert_eq!(contract_error_code(&format!("{err:?}")), Some("QUOTA_EXCEEDED"), "Unexpected error: {err:?}");
+
+    execute_note(&mut chain, deregister_note.id(), &mut ctx.naming).await?;
+    assert_eq!(get_domain_count(&ctx.naming, registrant)?, 1);
+
+    execute_note(&mut chain, register_notes[3].id(), &mut ctx.naming).await?;
+    assert_eq!(get_domain_count(&ctx.naming, registrant)?, 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transfer_moves_domain_count() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let (sender, receiver) = (ctx.registrar_1.id(), ctx.registrar_2.id());
+    let domain = encode_domain("test".to_string());
+    let payment = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+
+    let register_note = create_naming_register_name_note(sender, ctx.naming.id(), domain, payment, 1, CompileOptions::DEBUG).await?;
+    let transfer_note = create_naming_transfer_domain_note(sender, ctx.naming.id(), domain, receiver, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, transfer_note.clone())?;
+
+    execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), transfer_note.id()], &mut ctx.naming).await?;
+    assert_eq!(get_domain_count(&ctx.naming, sender)?, 0);
+    assert_eq!(get_domain_count(&ctx.naming, receiver)?, 1);
+    Ok(())
+}
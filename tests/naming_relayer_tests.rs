@@ -0,0 +1,56 @@
+use miden_client::asset::FungibleAsset;
+use midenname_contracts::{compile::CompileOptions, domain::{encode_domain, hash_domain}, errors::{MidenIdError, RegistryError, contract_error_code}, notes::{create_naming_authorize_registration_note, create_naming_register_authorized_note}, queries::{get_domain_owner, get_domain_sponsor, is_registration_authorized}, testing::{add_note_to_builder, execute_note, execute_notes_and_build_chain, init_naming}};
+
+#[tokio::test]
+async fn test_relayer_registers_authorized_domain_for_beneficiary() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let domain = encode_domain("test".to_string());
+    let beneficiary = ctx.registrar_1.id();
+    let relayer = ctx.registrar_2.id();
+
+    let authorize_note = create_naming_authorize_registration_note(beneficiary, ctx.naming.id(), hash_domain(domain), CompileOptions::DEBUG).await?;
+    let register_note = create_naming_register_authorized_note(relayer, ctx.naming.id(), beneficiary, domain, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    for note in [&authorize_note, &register_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), authorize_note.id()], &mut ctx.naming).await?;
+    assert!(is_registration_authorized(&ctx.naming, beneficiary, "test")?);
+    assert!(!is_registration_authorized(&ctx.naming, relayer, "test")?);
+
+    execute_note(&mut chain, register_note.id(), &mut ctx.naming).await?;
+    assert_eq!(get_domain_owner(&ctx.naming, "test")?, Some(beneficiary));
+    assert_eq!(get_domain_sponsor(&ctx.naming, "test")?, Some(relayer));
+    assert_eq!(ctx.naming.vault().get_balance(token)?, 555);
+    // One authorization pays for one registration
+    assert!(!is_registration_authorized(&ctx.naming, beneficiary, "test")?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unauthorized_relayer_registration_fails() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let domain = encode_domain("test".to_string());
+    let beneficiary = ctx.registrar_1.id();
+    let relayer = ctx.registrar_2.id();
+
+    // Authorizes another domain, and an authorization sent by the relayer doesn't count for the beneficiary
+    let other_domain_note = create_naming_authorize_registration_note(beneficiary, ctx.naming.id(), hash_domain(encode_domain("demo".to_string())), CompileOptions::DEBUG).await?;
+    let relayer_authorize_note = create_naming_authorize_registration_note(relayer, ctx.naming.id(), hash_domain(domain), CompileOptions::DEBUG).await?;
+    let register_note = create_naming_register_authorized_note(relayer, ctx.naming.id(), beneficiary, domain, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    for note in [&other_domain_note, &relayer_authorize_note, &register_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), other_domain_note.id(), relayer_authorize_note.id()], &mut ctx.naming).await?;
+    let err = execute_note(&mut chain, register_note.id(), &mut ctx.naming).await.expect_err("beneficiary did not authorize the domain");
+    assert_eq!(contract_error_code(&format!("{err:?}")), Some("REGISTRATION_NOT_AUTHORIZED"), "Unexpected error: {err:?}");
+    assert_eq!(get_domain_owner(&ctx.naming, "test")?, None);
+    assert_eq!(ctx.naming.vault().get_balance(token)?, 0);
+
+    let err = create_naming_register_authorized_note(beneficiary, ctx.naming.id(), beneficiary, domain, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await.expect_err("Expected relaying for oneself to fail");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::SenderMismatch { .. })), "Unexpected error: {err}");
+    Ok(())
+}
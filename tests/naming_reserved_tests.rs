@@ -0,0 +1,102 @@
+mod test_utils;
+
+use miden_client::{asset::FungibleAsset, note::{NoteAssets, NoteInputs}};
+use miden_crypto::Felt;
+use midenname_contracts::{compile::CompileOptions, domain::{encode_domain, encode_domain_as_felts}, notes::create_naming_set_reserved_name_note, queries::account_id_from_felts, testing::{NamingFixture, add_note_to_builder, execute_note, execute_notes_and_build_chain, init_naming}};
+
+use crate::test_utils::create_note_for_naming;
+
+fn register_inputs(ctx: &NamingFixture, domain: &str) -> anyhow::Result<NoteInputs> {
+    let domain = encode_domain_as_felts(domain.to_string());
+    Ok(NoteInputs::new([
+        ctx.fungible_asset.faucet_id().suffix(),
+        ctx.fungible_asset.faucet_id().prefix().as_felt(),
+        Felt::new(0),
+        Felt::new(0),
+        domain[0],
+        domain[1],
+        domain[2],
+        domain[3],
+        Felt::new(1), // register length
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+    ].to_vec())?)
+}
+
+#[tokio::test]
+async fn test_reserved_name_register_by_user_fails() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain_word = encode_domain("admin".to_string());
+
+    let reserve_note = create_naming_set_reserved_name_note(ctx.owner.id(), ctx.naming.id(), domain_word, true, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, reserve_note.clone())?;
+
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 123)?;
+    let register_note = create_note_for_naming("register_name".to_string(), register_inputs(&ctx, "admin")?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![cost.into()])?).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), reserve_note.id()], &mut ctx.naming).await?;
+
+    let reserved_slot = ctx.naming.storage().get_map_item(14, domain_word)?;
+    assert_eq!(reserved_slot.get(0).unwrap().as_int(), 1);
+
+    let result = execute_note(&mut chain, register_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected reserved domain register by user fails. But it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reserved_name_register_by_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain_word = encode_domain("admin".to_string());
+
+    let reserve_note = create_naming_set_reserved_name_note(ctx.owner.id(), ctx.naming.id(), domain_word, true, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, reserve_note.clone())?;
+
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 123)?;
+    let register_note = create_note_for_naming("register_name".to_string(), register_inputs(&ctx, "admin")?, ctx.owner.id(), ctx.naming.id(), NoteAssets::new(vec![cost.into()])?).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+
+    execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), reserve_note.id(), register_note.id()], &mut ctx.naming).await?;
+
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain_word)?;
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.owner.id());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_released_name_register_by_user() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain_word = encode_domain("admin".to_string());
+
+    let reserve_note = create_naming_set_reserved_name_note(ctx.owner.id(), ctx.naming.id(), domain_word, true, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, reserve_note.clone())?;
+    let release_note = create_naming_set_reserved_name_note(ctx.owner.id(), ctx.naming.id(), domain_word, false, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, release_note.clone())?;
+
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 123)?;
+    let register_note = create_note_for_naming("register_name".to_string(), register_inputs(&ctx, "admin")?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![cost.into()])?).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+
+    execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), reserve_note.id(), release_note.id(), register_note.id()], &mut ctx.naming).await?;
+
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain_word)?;
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.registrar_1.id());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reserve_name_by_not_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain_word = encode_domain("admin".to_string());
+
+    let reserve_note = create_naming_set_reserved_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain_word, true, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, reserve_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    let result = execute_note(&mut chain, reserve_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected revert but succeeded.");
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use miden_client::asset::FungibleAsset;
+use miden_crypto::Word;
+use midenname_contracts::{compile::CompileOptions, domain::encode_domain, notes::{create_naming_admin_revoke_note, create_naming_register_name_note}, queries::{account_id_from_felts, account_id_to_word}, storage::{CLAIMED_REVENUE_SLOT, DOMAIN_EXPIRY_DATES_SLOT, DOMAIN_TO_OWNER_SLOT, PAYMENT_RECORDS_SLOT}, testing::{add_note_to_builder, execute_note, execute_notes_and_build_chain, init_naming}};
+
+#[tokio::test]
+async fn test_admin_revoke_refunds_recorded_payment() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let domain = encode_domain("test".to_string());
+
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let revoke_note = create_naming_admin_revoke_note(ctx.owner.id(), ctx.naming.id(), domain, Some(ctx.registrar_1.id()), CompileOptions::DEBUG).await?;
+    for note in [&register_note, &revoke_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id()], &mut ctx.naming).await?;
+    assert_eq!(ctx.naming.storage().get_map_item(PAYMENT_RECORDS_SLOT, domain)?[0].as_int(), 555);
+
+    execute_note(&mut chain, revoke_note.id(), &mut ctx.naming).await?;
+    assert_eq!(ctx.naming.storage().get_map_item(DOMAIN_TO_OWNER_SLOT, domain)?, Word::default());
+    assert_eq!(ctx.naming.storage().get_map_item(DOMAIN_EXPIRY_DATES_SLOT, domain)?, Word::default());
+    assert_eq!(ctx.naming.storage().get_map_item(PAYMENT_RECORDS_SLOT, domain)?, Word::default());
+    // The refund left the vault and is counted as claimed protocol revenue
+    assert_eq!(ctx.naming.vault().get_balance(token)?, 0);
+    let claimed_revenue_slot = ctx.naming.storage().get_map_item(CLAIMED_REVENUE_SLOT, account_id_to_word(token))?;
+    assert_eq!(claimed_revenue_slot[0].as_int(), 555);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_admin_revoke_without_refund_keeps_vault() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let domain = encode_domain("test".to_string());
+
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let revoke_note = create_naming_admin_revoke_note(ctx.owner.id(), ctx.naming.id(), domain, None, CompileOptions::DEBUG).await?;
+    // Released names can be registered again
+    let register_again_note = create_naming_register_name_note(ctx.registrar_2.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    for note in [&register_note, &revoke_note, &register_again_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id()], &mut ctx.naming).await?;
+    execute_note(&mut chain, revoke_note.id(), &mut ctx.naming).await?;
+    assert_eq!(ctx.naming.storage().get_map_item(DOMAIN_TO_OWNER_SLOT, domain)?, Word::default());
+    assert_eq!(ctx.naming.storage().get_map_item(PAYMENT_RECORDS_SLOT, domain)?, Word::default());
+    assert_eq!(ctx.naming.vault().get_balance(token)?, 555);
+    assert_eq!(ctx.naming.storage().get_map_item(CLAIMED_REVENUE_SLOT, account_id_to_word(token))?, Word::default());
+
+    execute_note(&mut chain, register_again_note.id(), &mut ctx.naming).await?;
+    let domain_owner_slot = ctx.naming.storage().get_map_item(DOMAIN_TO_OWNER_SLOT, domain)?;
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.registrar_2.id());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_admin_revoke_by_non_owner_fails() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let domain = encode_domain("test".to_string());
+
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let revoke_note = create_naming_admin_revoke_note(ctx.registrar_2.id(), ctx.naming.id(), domain, Some(ctx.registrar_2.id()), CompileOptions::DEBUG).await?;
+    for note in [&register_note, &revoke_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id()], &mut ctx.naming).await?;
+    let result = execute_note(&mut chain, revoke_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected a revoke by a non owner to fail");
+
+    let domain_owner_slot = ctx.naming.storage().get_map_item(DOMAIN_TO_OWNER_SLOT, domain)?;
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.registrar_1.id());
+    assert_eq!(ctx.naming.vault().get_balance(token)?, 555);
+    Ok(())
+}
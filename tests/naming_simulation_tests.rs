@@ -0,0 +1,38 @@
+use miden_client::asset::FungibleAsset;
+use midenname_contracts::{domain::encode_domain, queries::account_id_from_felts, simulation::simulate_register, testing::{execute_notes_and_build_chain, init_naming}};
+
+#[tokio::test]
+async fn test_simulate_register() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let executed_tx = simulate_register(&chain, ctx.naming.id(), ctx.registrar_1.id(), "test", cost, 1).await?;
+
+    // Simulation does not touch the chain, delta can be applied manually
+    let mut naming = ctx.naming.clone();
+    naming.apply_delta(executed_tx.account_delta())?;
+
+    let domain_owner_slot = naming.storage().get_map_item(5, encode_domain("test".to_string()))?;
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.registrar_1.id());
+
+    let committed_owner_slot = chain.committed_account(ctx.naming.id())?.storage().get_map_item(5, encode_domain("test".to_string()))?;
+    assert_eq!(committed_owner_slot.get(0).unwrap().as_int(), 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_simulate_register_failure() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 554)?;
+    let failure = simulate_register(&chain, ctx.naming.id(), ctx.registrar_1.id(), "test", cost, 1).await.expect_err("Expected simulation to fail");
+
+    let note_inputs = failure.note_inputs.expect("Note inputs must be decoded");
+    assert_eq!(note_inputs.domain, encode_domain("test".to_string()));
+    assert_eq!(note_inputs.payment_token, ctx.fungible_asset.faucet_id());
+    assert_eq!(note_inputs.years, 1);
+    assert!(!failure.error.is_empty());
+    Ok(())
+}
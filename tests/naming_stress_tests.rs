@@ -0,0 +1,68 @@
+mod test_utils;
+
+use std::collections::BTreeMap;
+
+use miden_client::{account::Account, asset::FungibleAsset, note::{Note, NoteAssets, NoteInputs}};
+use miden_testing::MockChain;
+use midenname_contracts::{compile::CompileOptions, domain::{encode_domain, generate_unique_names}, notes::{create_naming_register_name_note, create_naming_set_price_notes}, pricing::quote_registration, queries::{get_account_for_name, get_domain_owner, get_name_for_account, list_registered_names}, testing::{add_note_to_builder, execute_notes_and_build_chain, init_naming}};
+
+use crate::test_utils::create_note_for_naming;
+
+const STRESS_NAMES: usize = 300;
+
+// Registration notes are built after the prices are set, so they are consumed as unauthenticated input notes
+async fn execute_unauthenticated(chain: &mut MockChain, naming: &mut Account, note: &Note) -> anyhow::Result<()> {
+    let executed_tx = chain.build_tx_context(naming.id(), &[], &[note.clone()])?.build()?.execute().await?;
+
+    naming.apply_delta(executed_tx.account_delta())?;
+    chain.add_pending_executed_transaction(&executed_tx)?;
+    chain.prove_next_block()?;
+    Ok(())
+}
+
+// Slow, run with `cargo test --test naming_stress_tests -- --ignored`
+#[tokio::test]
+#[ignore = "registers hundreds of names"]
+async fn test_register_many_names_keeps_maps_consistent() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let registrars = [ctx.registrar_1.id(), ctx.registrar_2.id(), ctx.registrar_3.id()];
+
+    // init_naming only prices lengths 1-5
+    let long_prices: BTreeMap<u8, u64> = (6..=20).map(|length| (length, 100)).collect();
+    let price_notes = create_naming_set_price_notes(ctx.owner.id(), ctx.naming.id(), token, &long_prices, CompileOptions::DEBUG).await?;
+    for note in &price_notes {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+    let mut setup_ids = vec![ctx.initialize_note.id(), ctx.set_prices_note.id()];
+    setup_ids.extend(price_notes.iter().map(Note::id));
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &setup_ids, &mut ctx.naming).await?;
+
+    let names = generate_unique_names(STRESS_NAMES, 7);
+    for (i, name) in names.iter().enumerate() {
+        let amount = quote_registration(&ctx.naming, name, token, 1)?;
+        let note = create_naming_register_name_note(registrars[i % 3], ctx.naming.id(), encode_domain(name.clone()), FungibleAsset::new(token, amount)?, 1, CompileOptions::DEBUG).await?;
+        execute_unauthenticated(&mut chain, &mut ctx.naming, &note).await?;
+    }
+
+    for (i, name) in names.iter().enumerate() {
+        assert_eq!(get_domain_owner(&ctx.naming, name)?, Some(registrars[i % 3]), "{name} has the wrong owner");
+    }
+    let mut expected: Vec<String> = names.clone();
+    expected.sort();
+    let registered: Vec<String> = list_registered_names(&ctx.naming).into_iter().map(|(name, _)| name).collect();
+    assert_eq!(registered, expected);
+    assert_eq!(ctx.naming.storage().get_item(9)?[0].as_int(), STRESS_NAMES as u64);
+
+    // Each account has one reverse entry, activate the last name of every registrar
+    for (offset, registrar) in registrars.iter().enumerate() {
+        let name = &names[STRESS_NAMES - 3 + offset];
+        let domain = encode_domain(name.clone());
+        let activate_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain.to_vec())?, *registrar, ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+        execute_unauthenticated(&mut chain, &mut ctx.naming, &activate_note).await?;
+
+        assert_eq!(get_account_for_name(&ctx.naming, name)?, Some(*registrar));
+        assert_eq!(get_name_for_account(&ctx.naming, *registrar)?.as_deref(), Some(name.as_str()));
+    }
+    Ok(())
+}
@@ -0,0 +1,148 @@
+mod test_utils;
+
+use miden_client::{asset::FungibleAsset, note::{NoteAssets, NoteInputs}};
+use miden_objects::address::NetworkId;
+use midenname_contracts::{availability::check_availability, compile::CompileOptions, domain::encode_domain, errors::{MidenIdError, RegistryError}, notes::{create_naming_deregister_note, create_naming_register_name_note, create_naming_transfer_domain_note}, queries::{account_id_from_felts, get_account_for_name, get_domain_owner, get_name_for_account, parse_account_id, verify_transfer}, testing::{add_note_to_builder, execute_note, execute_notes_and_build_chain, init_naming}};
+
+use crate::test_utils::create_note_for_naming;
+
+#[tokio::test]
+async fn test_transfer_domain_to_bech32_address() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain("test".to_string());
+    let address = ctx.registrar_2.id().to_bech32(NetworkId::Testnet);
+
+    let new_owner = parse_account_id(&address)?;
+    assert_eq!(new_owner, ctx.registrar_2.id());
+
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?, 1, CompileOptions::DEBUG).await?;
+    let transfer_note = create_naming_transfer_domain_note(ctx.registrar_1.id(), ctx.naming.id(), domain, new_owner, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, transfer_note.clone())?;
+
+    execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), transfer_note.id()], &mut ctx.naming).await?;
+
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain)?;
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.registrar_2.id());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_parse_account_id_formats() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let account_id = ctx.registrar_1.id();
+
+    assert_eq!(parse_account_id(&account_id.to_hex())?, account_id);
+    assert_eq!(parse_account_id(&format!(" {} ", account_id.to_bech32(NetworkId::Testnet)))?, account_id);
+
+    let err = parse_account_id("alice").expect_err("Expected invalid address to fail");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::InvalidAddress(_))), "Unexpected error: {err}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deregister_by_owner_releases_domain() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain("test".to_string());
+    let token = ctx.fungible_asset.faucet_id();
+
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let activate_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    let deregister_note = create_naming_deregister_note(ctx.registrar_1.id(), ctx.naming.id(), domain, CompileOptions::DEBUG).await?;
+    for note in [&register_note, &activate_note, &deregister_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), activate_note.id()], &mut ctx.naming).await?;
+    assert_eq!(get_account_for_name(&ctx.naming, "test")?, Some(ctx.registrar_1.id()));
+    let count = ctx.naming.storage().get_item(9)?;
+
+    execute_note(&mut chain, deregister_note.id(), &mut ctx.naming).await?;
+
+    let now = chain.latest_block_header().timestamp() as u64;
+    assert!(check_availability(&ctx.naming, "test", token, now)?.is_available());
+    assert_eq!(get_account_for_name(&ctx.naming, "test")?, None);
+    assert_eq!(get_name_for_account(&ctx.naming, ctx.registrar_1.id())?, None);
+    assert_eq!(get_domain_owner(&ctx.naming, "test")?, None);
+    assert_eq!(ctx.naming.storage().get_item(9)?, count, "Domain count changed");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deregister_by_not_owner_fails() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain("test".to_string());
+
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?, 1, CompileOptions::DEBUG).await?;
+    let deregister_note = create_naming_deregister_note(ctx.registrar_2.id(), ctx.naming.id(), domain, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, deregister_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id()], &mut ctx.naming).await?;
+
+    let result = execute_note(&mut chain, deregister_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected deregister by another account to fail");
+    assert_eq!(get_domain_owner(&ctx.naming, "test")?, Some(ctx.registrar_1.id()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transfer_moves_reverse_entry_to_new_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let alice = encode_domain("alice".to_string());
+    let bob = encode_domain("bob".to_string());
+
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), alice, FungibleAsset::new(token, 123)?, 1, CompileOptions::DEBUG).await?;
+    let activate_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(alice.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    let transfer_note = create_naming_transfer_domain_note(ctx.registrar_1.id(), ctx.naming.id(), alice, ctx.registrar_2.id(), CompileOptions::DEBUG).await?;
+    // The previous owner registers and activates another name afterwards
+    let register_bob_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), bob, FungibleAsset::new(token, 789)?, 1, CompileOptions::DEBUG).await?;
+    let activate_bob_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(bob.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    for note in [&register_note, &activate_note, &transfer_note, &register_bob_note, &activate_bob_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), activate_note.id(), transfer_note.id()], &mut ctx.naming).await?;
+
+    verify_transfer(&ctx.naming, "alice", ctx.registrar_1.id(), ctx.registrar_2.id())?;
+    assert_eq!(get_name_for_account(&ctx.naming, ctx.registrar_1.id())?, None);
+    assert_eq!(get_name_for_account(&ctx.naming, ctx.registrar_2.id())?, Some("alice".to_string()));
+    assert_eq!(get_account_for_name(&ctx.naming, "alice")?, Some(ctx.registrar_2.id()));
+
+    execute_note(&mut chain, register_bob_note.id(), &mut ctx.naming).await?;
+    execute_note(&mut chain, activate_bob_note.id(), &mut ctx.naming).await?;
+    assert_eq!(get_name_for_account(&ctx.naming, ctx.registrar_1.id())?, Some("bob".to_string()));
+    assert_eq!(get_account_for_name(&ctx.naming, "alice")?, Some(ctx.registrar_2.id()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transfer_keeps_other_default_names() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let [alice, bob, carol] = ["alice", "bob", "carol"].map(|name| encode_domain(name.to_string()));
+
+    let mut notes = Vec::new();
+    // registrar_1 activates alice, then bob which becomes its default name. registrar_2 has carol.
+    for (registrant, domain, price) in [(&ctx.registrar_1, alice, 123), (&ctx.registrar_1, bob, 789), (&ctx.registrar_2, carol, 123)] {
+        notes.push(create_naming_register_name_note(registrant.id(), ctx.naming.id(), domain, FungibleAsset::new(token, price)?, 1, CompileOptions::DEBUG).await?);
+        notes.push(create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain.to_vec())?, registrant.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?);
+    }
+    notes.push(create_naming_transfer_domain_note(ctx.registrar_1.id(), ctx.naming.id(), alice, ctx.registrar_2.id(), CompileOptions::DEBUG).await?);
+    for note in &notes {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut note_ids = vec![ctx.initialize_note.id(), ctx.set_prices_note.id()];
+    note_ids.extend(notes.iter().map(|note| note.id()));
+    execute_notes_and_build_chain(ctx.builder, &note_ids, &mut ctx.naming).await?;
+
+    verify_transfer(&ctx.naming, "alice", ctx.registrar_1.id(), ctx.registrar_2.id())?;
+    assert_eq!(get_name_for_account(&ctx.naming, ctx.registrar_1.id())?, Some("bob".to_string()));
+    assert_eq!(get_name_for_account(&ctx.naming, ctx.registrar_2.id())?, Some("carol".to_string()));
+    // The new owner already has a default name, alice waits for activation
+    assert_eq!(get_account_for_name(&ctx.naming, "alice")?, None);
+    assert_eq!(get_domain_owner(&ctx.naming, "alice")?, Some(ctx.registrar_2.id()));
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use miden_client::asset::FungibleAsset;
+use miden_crypto::{Felt, Word};
+use midenname_contracts::{compile::CompileOptions, domain::encode_domain, errors::{MidenIdError, RegistryError}, notes::{NoteParams, create_naming_activate_domain_note, create_naming_register_name_note, recreate_note, with_serial_num}, queries::account_id_from_felts, serde_hex::JsonCodec, storage::DOMAIN_TO_OWNER_SLOT, testing::{add_note_to_builder, execute_notes_and_build_chain, init_naming}};
+
+#[tokio::test]
+async fn test_recreated_register_note_is_identical_and_executes() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain("test".to_string());
+    let payment = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+
+    let note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, payment, 1, CompileOptions::DEBUG).await?;
+    let params = NoteParams::from_note("register_name", ctx.naming.id(), &note)?;
+    // Lost note, only the stored parameters are left
+    let params = NoteParams::from_json(&params.to_json()?)?;
+    let recreated = recreate_note(&params, CompileOptions::DEBUG).await?;
+    assert_eq!(recreated.id(), note.id());
+    assert_eq!(recreated.nullifier(), note.nullifier());
+
+    add_note_to_builder(&mut ctx.builder, recreated.clone())?;
+    execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), recreated.id()], &mut ctx.naming).await?;
+
+    let domain_owner_slot = ctx.naming.storage().get_map_item(DOMAIN_TO_OWNER_SLOT, domain)?;
+    assert_eq!(account_id_from_felts(domain_owner_slot[1], domain_owner_slot[0])?, ctx.registrar_1.id());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_with_serial_num_makes_constructors_deterministic() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let domain = encode_domain("test".to_string());
+    let serial_num = Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]);
+
+    let first = with_serial_num(create_naming_activate_domain_note(ctx.registrar_1.id(), ctx.naming.id(), domain, CompileOptions::DEBUG).await?, serial_num);
+    let second = with_serial_num(create_naming_activate_domain_note(ctx.registrar_1.id(), ctx.naming.id(), domain, CompileOptions::DEBUG).await?, serial_num);
+    assert_eq!(first.id(), second.id());
+    assert_eq!(first.serial_num(), serial_num);
+
+    // Parameters only describe notes of their template and registry
+    let err = NoteParams::from_note("deregister_domain", ctx.naming.id(), &first).expect_err("Expected a template mismatch");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::NoteParamsMismatch { .. })), "Unexpected error: {err}");
+    let err = NoteParams::from_note("activate_domain", ctx.registrar_2.id(), &first).expect_err("Expected a registry mismatch");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::NoteParamsMismatch { .. })), "Unexpected error: {err}");
+    Ok(())
+}
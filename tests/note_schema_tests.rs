@@ -0,0 +1,112 @@
+use std::{collections::BTreeSet, fs};
+
+use miden_client::{asset::FungibleAsset, note::NoteInputs};
+use miden_crypto::{Felt, Word};
+use miden_objects::MAX_INPUTS_PER_NOTE;
+use midenname_contracts::{compile::CompileOptions, domain::{encode_domain, hash_domain}, errors::{MidenIdError, RegistryError}, notes::{FrontendFee, SetPriceInput, action_hash, create_naming_activate_domain_note, create_naming_add_multisig_owner_note, create_naming_admin_revoke_note, create_naming_approve_action_note, create_naming_approve_sponsor_note, create_naming_authorize_registration_note, create_naming_claim_frontend_fees_note, create_naming_commit_note, create_naming_deregister_note, create_naming_donate_note, create_naming_free_register_name_note, create_naming_gc_note, create_naming_initialize_note, create_naming_register_hashed_note, create_naming_register_name_note, create_naming_register_authorized_note, create_naming_register_name_note_with_frontend_fee, create_naming_register_sponsored_note, create_naming_renew_via_register, create_naming_reveal_note, create_naming_set_base_fee_note, create_naming_set_class_multiplier_note, create_naming_set_commit_reveal_delay_note, create_naming_set_frontend_fee_max_note, create_naming_set_gc_bounty_note, create_naming_set_gc_grace_period_note, create_naming_set_max_domains_note, create_naming_set_multisig_threshold_note, create_naming_set_owner_pubkey_note, create_naming_set_price_note, create_naming_set_pricing_mode_note, create_naming_set_reserved_name_note, create_naming_transfer_domain_note, create_naming_transfer_owner_note, registration_commitment}, pricing::{NameClass, PricingMode}, schema::{NOTE_SCHEMAS, note_schema, schema_report, validate_note}, testing::init_naming};
+
+#[test]
+fn test_every_note_template_has_a_schema() {
+    let templates: BTreeSet<String> = fs::read_dir("./masm/notes").unwrap()
+        .map(|entry| entry.unwrap().path().file_stem().unwrap().to_string_lossy().to_string())
+        .collect();
+    let schemas: BTreeSet<String> = NOTE_SCHEMAS.iter().map(|schema| schema.template.to_string()).collect();
+
+    assert_eq!(schemas.len(), NOTE_SCHEMAS.len(), "duplicate schema");
+    assert_eq!(templates, schemas);
+    for schema in &NOTE_SCHEMAS {
+        assert!(schema.input_count() <= MAX_INPUTS_PER_NOTE, "{} has too many inputs", schema.template);
+    }
+}
+
+#[tokio::test]
+async fn test_note_constructors_conform_to_schemas() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let (owner, naming, registrant) = (ctx.owner.id(), ctx.naming.id(), ctx.registrar_1.id());
+    let token = ctx.fungible_asset.faucet_id();
+    let payment = FungibleAsset::new(token, 555)?;
+    let domain = encode_domain("test".to_string());
+    let salt = Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]);
+
+    let notes = vec![
+        ("initialize_naming", ctx.initialize_note.clone()),
+        ("initialize_naming", create_naming_initialize_note(owner, naming, registrant, ctx.one_year, CompileOptions::DEBUG).await?),
+        ("set_all_prices", ctx.set_prices_note.clone()),
+        ("set_price", create_naming_set_price_note(owner, naming, token, SetPriceInput { letter_count: 3, price: 0 }, true, CompileOptions::DEBUG).await?),
+        ("set_base_fee", create_naming_set_base_fee_note(owner, naming, token, 10, CompileOptions::DEBUG).await?),
+        ("set_pricing_mode", create_naming_set_pricing_mode_note(owner, naming, PricingMode::Linear, CompileOptions::DEBUG).await?),
+        ("set_reserved_name", create_naming_set_reserved_name_note(owner, naming, domain, false, CompileOptions::DEBUG).await?),
+        ("set_class_multiplier", create_naming_set_class_multiplier_note(owner, naming, NameClass::Alpha, 0, CompileOptions::DEBUG).await?),
+        ("set_commit_reveal_delay", create_naming_set_commit_reveal_delay_note(owner, naming, 0, CompileOptions::DEBUG).await?),
+        ("set_frontend_fee_max", create_naming_set_frontend_fee_max_note(owner, naming, 500, CompileOptions::DEBUG).await?),
+        ("set_max_domains_per_account", create_naming_set_max_domains_note(owner, naming, 3, CompileOptions::DEBUG).await?),
+        ("set_gc_grace_period", create_naming_set_gc_grace_period_note(owner, naming, 86400, CompileOptions::DEBUG).await?),
+        ("set_gc_bounty", create_naming_set_gc_bounty_note(owner, naming, Some(payment), CompileOptions::DEBUG).await?),
+        ("set_gc_bounty", create_naming_set_gc_bounty_note(owner, naming, None, CompileOptions::DEBUG).await?),
+        ("admin_revoke", create_naming_admin_revoke_note(owner, naming, domain, Some(registrant), CompileOptions::DEBUG).await?),
+        ("admin_revoke", create_naming_admin_revoke_note(owner, naming, domain, None, CompileOptions::DEBUG).await?),
+        ("set_owner_pubkey", create_naming_set_owner_pubkey_note(owner, naming, Word::default(), CompileOptions::DEBUG).await?),
+        ("transfer_ownership", create_naming_transfer_owner_note(owner, naming, registrant, CompileOptions::DEBUG).await?),
+        ("add_multisig_owner", create_naming_add_multisig_owner_note(owner, naming, registrant, CompileOptions::DEBUG).await?),
+        ("set_multisig_threshold", create_naming_set_multisig_threshold_note(owner, naming, 0, CompileOptions::DEBUG).await?),
+        ("approve_action", create_naming_approve_action_note(registrant, naming, action_hash(&ctx.set_prices_note), CompileOptions::DEBUG).await?),
+        ("register_name", create_naming_register_name_note(registrant, naming, domain, payment, 1, CompileOptions::DEBUG).await?),
+        ("register_name", create_naming_free_register_name_note(registrant, naming, token, domain, 2, CompileOptions::DEBUG).await?),
+        ("register_name", create_naming_renew_via_register(registrant, naming, domain, payment, 3, CompileOptions::DEBUG).await?),
+        ("register_name", create_naming_register_hashed_note(registrant, naming, domain, payment, 1, CompileOptions::DEBUG).await?),
+        ("register_with_frontend_fee", create_naming_register_name_note_with_frontend_fee(registrant, naming, domain, payment, 1, FrontendFee { recipient: ctx.registrar_2.id(), bps: 0 }, CompileOptions::DEBUG).await?),
+        ("register_sponsored", create_naming_register_sponsored_note(owner, naming, registrant, domain, payment, 1, CompileOptions::DEBUG).await?),
+        ("approve_sponsor", create_naming_approve_sponsor_note(registrant, naming, Some(owner), CompileOptions::DEBUG).await?),
+        ("approve_sponsor", create_naming_approve_sponsor_note(registrant, naming, None, CompileOptions::DEBUG).await?),
+        ("authorize_registration", create_naming_authorize_registration_note(registrant, naming, hash_domain(domain), CompileOptions::DEBUG).await?),
+        ("register_authorized", create_naming_register_authorized_note(owner, naming, registrant, domain, payment, 1, CompileOptions::DEBUG).await?),
+        ("commit_registration", create_naming_commit_note(registrant, naming, registration_commitment(domain, salt, registrant), CompileOptions::DEBUG).await?),
+        ("reveal_registration", create_naming_reveal_note(registrant, naming, domain, salt, payment, 1, CompileOptions::DEBUG).await?),
+        ("transfer_domain", create_naming_transfer_domain_note(registrant, naming, domain, ctx.registrar_2.id(), CompileOptions::DEBUG).await?),
+        ("activate_domain", create_naming_activate_domain_note(registrant, naming, domain, CompileOptions::DEBUG).await?),
+        ("deregister_domain", create_naming_deregister_note(registrant, naming, domain, CompileOptions::DEBUG).await?),
+        ("gc_expired_domains", create_naming_gc_note(registrant, naming, &[domain], CompileOptions::DEBUG).await?),
+        ("claim_frontend_fees", create_naming_claim_frontend_fees_note(registrant, naming, token, CompileOptions::DEBUG).await?),
+        ("donate", create_naming_donate_note(registrant, naming, payment, CompileOptions::DEBUG).await?),
+    ];
+
+    for (template, note) in notes {
+        validate_note(&note, template).map_err(|err| anyhow::anyhow!("{template}: {err}"))?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_schema_encode_rejects_values_that_do_not_fit() {
+    let schema = note_schema("transfer_ownership").unwrap();
+
+    let result = schema.encode(&[&[Felt::new(1), Felt::new(2), Felt::new(3)]]);
+    assert!(matches!(result, Err(MidenIdError::Registry(RegistryError::InvalidNoteField { field: "new_owner", .. }))), "Unexpected result: {result:?}");
+
+    let result = schema.encode(&[&[Felt::new(0), Felt::new(0)]]);
+    assert!(matches!(result, Err(MidenIdError::Registry(RegistryError::InvalidNoteField { reason: "required field is empty", .. }))), "Unexpected result: {result:?}");
+
+    let result = schema.encode(&[]);
+    assert!(matches!(result, Err(MidenIdError::Registry(RegistryError::InvalidNoteInputs { expected: 1, actual: 0 }))), "Unexpected result: {result:?}");
+
+    assert_eq!(note_schema("mint").unwrap_err(), RegistryError::UnknownNoteTemplate("mint".to_string()));
+}
+
+#[test]
+fn test_schema_validate_rejects_dirty_padding() {
+    let schema = note_schema("set_base_fee").unwrap();
+    let inputs = NoteInputs::new([10, 0, 1, 0, 1, 2, 0, 0].map(Felt::new).to_vec()).unwrap();
+
+    assert_eq!(schema.validate(&inputs), Err(RegistryError::InvalidNoteField { template: "set_base_fee", field: "padding", reason: "padding must be zero" }));
+}
+
+#[test]
+fn test_schema_report_lists_every_template() -> anyhow::Result<()> {
+    let report: serde_json::Value = serde_json::from_str(&schema_report()?)?;
+    let entries = report.as_array().unwrap();
+
+    assert_eq!(entries.len(), NOTE_SCHEMAS.len());
+    let register = entries.iter().find(|entry| entry["template"] == "register_name").unwrap();
+    assert_eq!(register["fields"][0], serde_json::json!({ "name": "payment_token", "width": 2, "kind": "required" }));
+    Ok(())
+}
@@ -0,0 +1,225 @@
+mod test_utils;
+
+use miden_client::{Client, account::{AccountId, StorageMap}, asset::FungibleAsset, keystore::FilesystemKeyStore, note::{Note, NoteAssets, NoteInputs}, store::TransactionFilter, transaction::{OutputNote, TransactionRequestBuilder}};
+use miden_crypto::Word;
+use miden_testing::MockChain;
+use midenname_contracts::{accounts::{add_contract_account, create_basic_wallet}, compile::CompileOptions, domain::encode_domain, errors::{MidenIdError, RegistryError}, notes::{create_naming_initialize_note, create_naming_register_name_note, create_naming_set_reserved_name_note}, pricing::PriceTable, queries::{account_id_to_word, word_to_account_id}, registry::{QueryPolicy, RegistryClient}, storage::DOMAIN_TO_ACCOUNT_ID_SLOT, testing::{add_note_to_builder, execute_notes_and_build_chain, init_naming}};
+use rand::rngs::StdRng;
+use std::{collections::BTreeMap, time::{Duration, SystemTime}};
+
+use crate::test_utils::{create_mock_client, create_mock_client_with_keystore, create_note_for_naming};
+
+#[tokio::test]
+async fn test_connect_tracks_registry_on_empty_store() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain_word = encode_domain("test".to_string());
+
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain_word, cost, 1, CompileOptions::DEBUG).await?;
+    let activate_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain_word.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, activate_note.clone())?;
+
+    let chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), activate_note.id()], &mut ctx.naming).await?;
+
+    let client = create_mock_client(chain).await?;
+    assert!(client.get_account(ctx.naming.id()).await?.is_none());
+
+    let mut registry = RegistryClient::connect(client, ctx.naming.id()).await?;
+
+    assert_eq!(registry.resolve("test").await?, Some(ctx.registrar_1.id()));
+    assert_eq!(registry.reverse_resolve(ctx.registrar_1.id()).await?, Some("test".to_string()));
+    assert_eq!(registry.domain_owner("test").await?, Some(ctx.registrar_1.id()));
+    assert_eq!(registry.owner().await?, ctx.owner.id());
+    assert_eq!(registry.resolve("nobody").await?, None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_resolve_with_metadata() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain_word = encode_domain("test".to_string());
+
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain_word, cost, 1, CompileOptions::DEBUG).await?;
+    let activate_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain_word.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, activate_note.clone())?;
+
+    let chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), activate_note.id()], &mut ctx.naming).await?;
+    let chain_tip = chain.latest_block_header().block_num().as_u32();
+
+    let before = SystemTime::now();
+    let mut registry = RegistryClient::connect(create_mock_client(chain).await?, ctx.naming.id()).await?;
+    let result = registry.resolve_with_metadata("test").await?;
+
+    assert_eq!(result.account_id, Some(ctx.registrar_1.id()));
+    assert_eq!(result.block_height, chain_tip);
+    assert!(result.fetched_at >= before);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_check_availability() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    let reserve_note = create_naming_set_reserved_name_note(ctx.owner.id(), ctx.naming.id(), encode_domain("admin".to_string()), true, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, reserve_note.clone())?;
+
+    let chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), reserve_note.id()], &mut ctx.naming).await?;
+    let mut registry = RegistryClient::connect(create_mock_client(chain).await?, ctx.naming.id()).await?;
+
+    let available = registry.check_availability("abc", token).await?;
+    assert!(available.is_available());
+    assert_eq!(available.price, 789);
+
+    let reserved = registry.check_availability("admin", token).await?;
+    assert!(reserved.reserved);
+    assert_eq!(registry.suggest_alternatives("admin", token, 2).await?, vec!["admin1", "admin2"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_name_proof_verifies_against_storage_commitment() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain_word = encode_domain("test".to_string());
+
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain_word, cost, 1, CompileOptions::DEBUG).await?;
+    let activate_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain_word.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, activate_note.clone())?;
+
+    let chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), activate_note.id()], &mut ctx.naming).await?;
+    let committed_storage = chain.committed_account(ctx.naming.id())?.storage().commitment();
+
+    let mut registry = RegistryClient::connect(create_mock_client(chain).await?, ctx.naming.id()).await?;
+    let (value, proof) = registry.name_proof("test").await?;
+    assert_eq!(word_to_account_id(value)?, Some(ctx.registrar_1.id()));
+
+    // Map root is the slot value, which the storage commitment of the committed account covers
+    let naming = registry.naming_account().await?;
+    assert_eq!(naming.storage().commitment(), committed_storage);
+    let root = naming.storage().get_item(DOMAIN_TO_ACCOUNT_ID_SLOT)?;
+    assert!(proof.verify_membership(&StorageMap::hash_key(domain_word), &value, &root));
+    assert!(!proof.verify_membership(&StorageMap::hash_key(domain_word), &account_id_to_word(ctx.registrar_2.id()), &root));
+
+    // Unresolved names prove the empty word
+    let nobody = encode_domain("nobody".to_string());
+    let (value, proof) = registry.name_proof("nobody").await?;
+    assert_eq!(value, Word::default());
+    assert!(proof.verify_membership(&StorageMap::hash_key(nobody), &Word::default(), &root));
+    Ok(())
+}
+
+// Chain with "test" registered and activated by the registrant, returns (naming, registrant, chain)
+async fn registered_chain() -> anyhow::Result<(AccountId, AccountId, MockChain)> {
+    let mut ctx = init_naming().await?;
+    let domain_word = encode_domain("test".to_string());
+
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain_word, cost, 1, CompileOptions::DEBUG).await?;
+    let activate_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain_word.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, activate_note.clone())?;
+
+    let chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), activate_note.id()], &mut ctx.naming).await?;
+    Ok((ctx.naming.id(), ctx.registrar_1.id(), chain))
+}
+
+#[tokio::test]
+async fn test_stale_lookup_auto_syncs() -> anyhow::Result<()> {
+    let (naming_id, registrant, chain) = registered_chain().await?;
+    let chain_tip = chain.latest_block_header().block_num().as_u32();
+
+    let mut registry = RegistryClient::connect(create_mock_client(chain).await?, naming_id).await?;
+    assert_eq!(registry.policy, QueryPolicy::default());
+    assert_eq!(registry.last_sync_height(), None);
+
+    assert_eq!(registry.resolve("test").await?, Some(registrant));
+    assert_eq!(registry.last_sync_height(), Some(chain_tip));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stale_lookup_fails_under_require_fresh() -> anyhow::Result<()> {
+    let (naming_id, registrant, chain) = registered_chain().await?;
+
+    let client = create_mock_client(chain).await?;
+    let mut registry = RegistryClient::connect(client, naming_id).await?
+        .with_policy(QueryPolicy::RequireFresh { max_age: Duration::from_secs(60) });
+
+    let err = registry.resolve("test").await.expect_err("Expected a lookup before any sync to fail");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::StaleState { .. })), "Unexpected error: {err}");
+    let err = registry.name_proof("test").await.expect_err("Expected a proof before any sync to fail");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::StaleState { .. })), "Unexpected error: {err}");
+
+    // Fresh state is read without syncing again
+    let height = registry.sync().await?;
+    assert_eq!(registry.resolve("test").await?, Some(registrant));
+    assert_eq!(registry.domain_owner("test").await?, Some(registrant));
+    assert_eq!(registry.last_sync_height(), Some(height));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unchecked_policy_reads_store_as_is() -> anyhow::Result<()> {
+    let (naming_id, registrant, chain) = registered_chain().await?;
+
+    let mut registry = RegistryClient::connect(create_mock_client(chain).await?, naming_id).await?
+        .with_policy(QueryPolicy::Unchecked);
+
+    // connect tracked and synced the registry itself, which the policy doesn't see
+    assert_eq!(registry.resolve("test").await?, Some(registrant));
+    assert_eq!(registry.last_sync_height(), None);
+    Ok(())
+}
+
+// The naming account has no auth, anyone can make it consume notes
+async fn consume_on_naming(client: &mut Client<FilesystemKeyStore<StdRng>>, naming_id: AccountId, notes: Vec<Note>) -> anyhow::Result<()> {
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes(notes.into_iter().map(|note| (note, None)))
+        .build()?;
+    client.submit_new_transaction(naming_id, request).await?;
+    client.sync_state().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_import_prices_through_client_submits_only_changes() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let (mut client, keystore) = create_mock_client_with_keystore(ctx.builder.build()?).await?;
+    add_contract_account(&mut client, &ctx.naming, None).await?;
+
+    // The registry owner is a wallet the client signs for
+    let (owner, _) = create_basic_wallet(&mut client, &keystore).await?;
+    let initialize_note = create_naming_initialize_note(owner.id(), ctx.naming.id(), owner.id(), ctx.one_year, CompileOptions::DEBUG).await?;
+    consume_on_naming(&mut client, ctx.naming.id(), vec![initialize_note]).await?;
+
+    let mut registry = RegistryClient::connect(client, ctx.naming.id()).await?;
+    let table = PriceTable { payment_token: token, prices: BTreeMap::from([(3, 300), (4, 200)]) };
+    let (changes, tx_id) = registry.import_prices(owner.id(), &table).await?;
+    assert_eq!(changes, table.prices);
+    let tx_id = tx_id.expect("Expected a transaction for the changed prices");
+
+    // One set_price note per change, sent by the owner
+    let records = registry.client.get_transactions(TransactionFilter::Ids(vec![tx_id])).await?;
+    let notes: Vec<Note> = records[0].details.output_notes.iter()
+        .filter_map(|note| match note {
+            OutputNote::Full(note) => Some(note.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(notes.len(), 2);
+    assert!(notes.iter().all(|note| note.metadata().sender() == owner.id()));
+    consume_on_naming(&mut registry.client, ctx.naming.id(), notes).await?;
+    assert_eq!(registry.export_prices(token).await?, table);
+
+    // Nothing left to change, nothing is submitted
+    let (changes, tx_id) = registry.import_prices(owner.id(), &table).await?;
+    assert!(changes.is_empty());
+    assert_eq!(tx_id, None);
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use std::{collections::BTreeMap, path::Path};
+
+use midenname_contracts::roots::{ProcedureRoots, ROOTS_LOCK_PATH, RootChange, compute_procedure_roots, diff_procedure_roots, verify_procedure_roots, write_roots_lock};
+
+fn roots(entries: &[(&str, &str)]) -> ProcedureRoots {
+    let procs: BTreeMap<String, String> = entries.iter().map(|(name, root)| (name.to_string(), root.to_string())).collect();
+    BTreeMap::from([("naming".to_string(), procs)])
+}
+
+#[test]
+fn test_diff_procedure_roots() {
+    let locked = roots(&[("register", "0x01"), ("transfer", "0x02"), ("init", "0x03")]);
+    let current = roots(&[("register", "0x01"), ("transfer", "0x22"), ("donate", "0x04")]);
+
+    let changes = diff_procedure_roots(&locked, &current);
+    assert_eq!(changes, vec![
+        RootChange::Removed { contract: "naming".to_string(), procedure: "init".to_string(), root: "0x03".to_string() },
+        RootChange::Changed { contract: "naming".to_string(), procedure: "transfer".to_string(), locked: "0x02".to_string(), current: "0x22".to_string() },
+        RootChange::Added { contract: "naming".to_string(), procedure: "donate".to_string(), root: "0x04".to_string() },
+    ]);
+    assert!(diff_procedure_roots(&current, &current).is_empty());
+}
+
+// Fails on any MASM edit that changes a root until roots.lock is regenerated and reviewed
+#[test]
+fn test_procedure_roots_match_lock() -> anyhow::Result<()> {
+    if std::env::var_os("UPDATE_ROOTS").is_some() {
+        write_roots_lock(Path::new(ROOTS_LOCK_PATH), &compute_procedure_roots()?)?;
+    }
+
+    let changes = verify_procedure_roots()?;
+    let report: Vec<String> = changes.iter().map(ToString::to_string).collect();
+    assert!(changes.is_empty(), "procedure roots differ from roots.lock, run `UPDATE_ROOTS=1 cargo test --test roots_tests` after review:\n{}", report.join("\n"));
+    Ok(())
+}
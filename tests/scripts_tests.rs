@@ -0,0 +1,94 @@
+mod test_utils;
+
+use std::{fs, path::PathBuf, sync::Arc};
+
+use miden_client::{account::AccountId, asset::FungibleAsset, note::{Note, NoteAssets, NoteInputs}, testing::mock::MockRpcApi, utils::Deserializable};
+use midenname_contracts::{compile::CompileOptions, config::{CliConfig, load_price_table}, context::Context, domain::encode_domain, dump::SlotContents, notes::{action_hash, create_naming_register_name_note}, pricing::{PriceTable, export_prices}, scripts::{PriceUpdate, check, dump, export_price_table, health, list_names, propose_set_price, resolve}, storage::DOMAIN_TO_ACCOUNT_ID_SLOT, testing::{add_note_to_builder, execute_notes_and_build_chain, init_naming}, token::CliAmount};
+
+use crate::test_utils::{create_note_for_naming, open_mock_client};
+
+// Naming account with "test" registered and activated by registrar_1, served by a mock node
+struct Registry {
+    rpc: Arc<MockRpcApi>,
+    naming_id: AccountId,
+    registrant: AccountId,
+    token: AccountId,
+    prices: PriceTable,
+}
+
+impl Registry {
+    async fn new() -> anyhow::Result<Self> {
+        let mut ctx = init_naming().await?;
+        let token = ctx.fungible_asset.faucet_id();
+        let domain = encode_domain("test".to_string());
+
+        let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+        let activate_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+        add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+        add_note_to_builder(&mut ctx.builder, activate_note.clone())?;
+
+        let chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), activate_note.id()], &mut ctx.naming).await?;
+        let prices = export_prices(&ctx.naming, token)?;
+        Ok(Self { rpc: Arc::new(MockRpcApi::new(chain)), naming_id: ctx.naming.id(), registrant: ctx.registrar_1.id(), token, prices })
+    }
+
+    // Scripts consume their context, each one gets a fresh store on the same chain
+    async fn context(&self) -> anyhow::Result<Context> {
+        let (client, keystore) = open_mock_client(self.rpc.clone(), &temp_dir()).await?;
+        Ok(Context::new(CliConfig::default(), client, keystore, CompileOptions::DEBUG))
+    }
+}
+
+fn temp_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("midenname-scripts-{}", rand::random::<u64>()))
+}
+
+#[tokio::test]
+async fn test_lookup_scripts_return_registry_state() -> anyhow::Result<()> {
+    let registry = Registry::new().await?;
+
+    let taken = check(registry.context().await?, registry.naming_id, registry.token, "test", 3).await?;
+    assert_eq!(taken.availability.taken_by, Some(registry.registrant));
+    assert!(!taken.alternatives.is_empty() && taken.alternatives.len() <= 3);
+    assert!(!taken.alternatives.contains(&"test".to_string()));
+
+    assert_eq!(resolve(registry.context().await?, registry.naming_id, "test").await?, Some(registry.registrant));
+    assert_eq!(resolve(registry.context().await?, registry.naming_id, "nobody").await?, None);
+
+    assert_eq!(list_names(registry.context().await?, registry.naming_id, 0, 50).await?, vec![("test".to_string(), registry.registrant)]);
+    assert!(list_names(registry.context().await?, registry.naming_id, 1, 50).await?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_inspection_scripts_return_reports() -> anyhow::Result<()> {
+    let registry = Registry::new().await?;
+
+    let slots = dump(registry.context().await?, registry.naming_id).await?;
+    assert!(matches!(&slots[&DOMAIN_TO_ACCOUNT_ID_SLOT].contents, SlotContents::Map(entries) if !entries.is_empty()));
+
+    assert!(health(registry.context().await?, registry.naming_id).await?.is_expected());
+
+    let dir = temp_dir();
+    fs::create_dir_all(&dir)?;
+    let output = dir.join("prices.json");
+    let table = export_price_table(registry.context().await?, registry.naming_id, registry.token, Some(&output)).await?;
+    assert_eq!(table, registry.prices);
+    assert_eq!(load_price_table(&output)?, table);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_propose_set_price_writes_the_note_it_hashes() -> anyhow::Result<()> {
+    let registry = Registry::new().await?;
+    let dir = temp_dir();
+    fs::create_dir_all(&dir)?;
+    let output = dir.join("proposal.bin");
+
+    let action = propose_set_price(registry.context().await?, registry.naming_id, registry.registrant, &PriceUpdate { payment_token: registry.token, letter_count: 3, price: CliAmount::Raw(700), allow_free: false }, &output).await?;
+
+    let note = Note::read_from_bytes(&fs::read(&output)?)?;
+    assert_eq!(action_hash(&note), action);
+    assert_eq!(note.metadata().sender(), registry.registrant);
+    Ok(())
+}
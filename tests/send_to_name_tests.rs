@@ -0,0 +1,51 @@
+use miden_client::{asset::FungibleAsset, note::{NoteAssets, NoteInputs}};
+use midenname_contracts::{compile::CompileOptions, domain::encode_domain, errors::{MidenIdError, RegistryError}, notes::{create_naming_register_name_note, create_note_for_naming, create_p2id_note_to_name}, queries::get_account_for_name, testing::{add_note_to_builder, execute_notes_and_build_chain, init_naming}};
+
+#[tokio::test]
+async fn test_send_to_name() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain("alice".to_string());
+    let token = ctx.fungible_asset.faucet_id();
+
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 123)?, 1, CompileOptions::DEBUG).await?;
+    let activate_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, activate_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), activate_note.id()], &mut ctx.naming).await?;
+
+    // Unregistered names fail before any note is built
+    let err = create_p2id_note_to_name(&ctx.naming, ctx.registrar_2.id(), "bob", vec![FungibleAsset::new(token, 10)?.into()]).expect_err("Expected unknown name to fail");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::NameNotFound(_))), "Unexpected error: {err}");
+
+    let balance_before = ctx.registrar_1.vault().get_balance(token)?;
+    let send_note = create_p2id_note_to_name(&ctx.naming, ctx.registrar_2.id(), "alice", vec![FungibleAsset::new(token, 100)?.into()])?;
+
+    // P2ID note consumed by the resolved wallet as an unauthenticated input note
+    let tx_ctx = chain.build_tx_context(ctx.registrar_1.id(), &[], &[send_note])?.build()?;
+    let executed_tx = tx_ctx.execute().await?;
+    ctx.registrar_1.apply_delta(executed_tx.account_delta())?;
+
+    assert_eq!(ctx.registrar_1.vault().get_balance(token)?, balance_before + 100);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_send_to_address_like_name_is_refused() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    // Registered on chain directly, the contract doesn't apply the address rule
+    let name = "0x7bfb0f38b0fafa103f";
+    let domain = encode_domain(name.to_string());
+    let token = ctx.fungible_asset.faucet_id();
+
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(token, 123)?, 1, CompileOptions::DEBUG).await?;
+    let activate_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, activate_note.clone())?;
+    execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), activate_note.id()], &mut ctx.naming).await?;
+
+    assert_eq!(get_account_for_name(&ctx.naming, name)?, Some(ctx.registrar_1.id()));
+    let err = create_p2id_note_to_name(&ctx.naming, ctx.registrar_2.id(), name, vec![FungibleAsset::new(token, 10)?.into()]).expect_err("Expected address-like name to be refused");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::LooksLikeAddress(_))), "Unexpected error: {err}");
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use miden_client::{account::AccountId, testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1};
+use miden_crypto::{Felt, StarkField, Word};
+use midenname_contracts::{availability::Availability, domain::encode_domain, dump::{DecodedWord, SlotContents}, notes::{FrontendFee, RegisterNoteInputs, SetPriceInput}, pricing::PricingMode, registry::ResolveResult, roots::RootChange, serde_hex::{JsonCodec, felt_from_hex, felt_to_hex, word_from_hex, word_to_hex}, vault::{VaultReconciliation, VaultRecord}};
+
+fn account() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1).unwrap()
+}
+
+fn assert_round_trip<T: JsonCodec + PartialEq + std::fmt::Debug>(value: T) {
+    let json = value.to_json().unwrap();
+    assert_eq!(T::from_json(&json).unwrap(), value, "round trip through {json}");
+}
+
+#[test]
+fn test_word_hex_edge_cases() {
+    let zero = Word::default();
+    assert_eq!(word_to_hex(zero), format!("0x{}", "0".repeat(64)));
+    assert_eq!(word_from_hex(&word_to_hex(zero)), Ok(zero));
+
+    let max = Felt::new(Felt::MODULUS - 1);
+    assert_eq!(felt_to_hex(max), "0xffffffff00000000");
+    assert_eq!(felt_from_hex("0xffffffff00000000"), Ok(max));
+    let max_word = Word::new([max, Felt::new(0), Felt::new(1), max]);
+    assert_eq!(word_from_hex(&word_to_hex(max_word)), Ok(max_word));
+
+    // Felt::new would silently reduce these
+    assert!(felt_from_hex("0xffffffff00000001").is_err());
+    assert!(felt_from_hex("0xffffffffffffffff").is_err());
+    assert!(word_from_hex(&format!("0x{}ffffffff00000001", "0".repeat(48))).is_err());
+
+    assert!(felt_from_hex("12").is_err(), "missing prefix");
+    assert!(felt_from_hex("0x").is_err());
+    assert!(felt_from_hex("0x+1").is_err());
+    assert!(word_from_hex("0x00").is_err(), "short word");
+    assert!(word_from_hex(&format!("0x{}", "é".repeat(32))).is_err());
+}
+
+#[test]
+fn test_word_fields_serialize_as_hex() -> anyhow::Result<()> {
+    let inputs = RegisterNoteInputs { payment_token: account(), domain: encode_domain("alice".to_string()), years: 2 };
+    let json: serde_json::Value = serde_json::from_str(&inputs.to_json()?)?;
+
+    assert_eq!(json["payment_token"], account().to_hex());
+    assert_eq!(json["domain"], word_to_hex(inputs.domain));
+    assert_eq!(json["years"], 2);
+    Ok(())
+}
+
+#[test]
+fn test_public_types_round_trip() {
+    assert_round_trip(RegisterNoteInputs { payment_token: account(), domain: encode_domain("alice".to_string()), years: 2 });
+    assert_round_trip(SetPriceInput { letter_count: 3, price: 555 });
+    assert_round_trip(FrontendFee { recipient: account(), bps: 250 });
+    assert_round_trip(PricingMode::Linear);
+    assert_round_trip(Availability { taken_by: Some(account()), reserved: false, price: 789 });
+    assert_round_trip(Availability { taken_by: None, reserved: true, price: 0 });
+    assert_round_trip(ResolveResult { account_id: Some(account()), block_height: 12, fetched_at: UNIX_EPOCH + Duration::from_millis(1_700_000_000_123) });
+    assert_round_trip(VaultRecord { vault_balance: 10, total_revenue: 8, claimed_revenue: 2, donations: 1, referrer_owed: 3, frontend_owed: 0 });
+    assert_round_trip(VaultReconciliation { accounted: 10, unaccounted: 0, shortfall: 0 });
+    assert_round_trip(RootChange::Changed { contract: "naming".to_string(), procedure: "register".to_string(), locked: "0x01".to_string(), current: "0x02".to_string() });
+    assert_round_trip(SlotContents::Map(vec![
+        (DecodedWord::Domain("alice".to_string()), DecodedWord::Account(account())),
+        (DecodedWord::Raw(Word::new([Felt::new(Felt::MODULUS - 1), Felt::new(0), Felt::new(0), Felt::new(7)])), DecodedWord::Empty),
+    ]));
+    assert_round_trip(SlotContents::Value(DecodedWord::Flag(true)));
+}
+
+#[test]
+fn test_from_json_rejects_invalid_hex() {
+    let json = format!(r#"{{"payment_token":"{}","domain":"0x1234","years":1}}"#, account().to_hex());
+    assert!(RegisterNoteInputs::from_json(&json).is_err());
+
+    let json = r#"{"payment_token":"not an id","domain":"0x0000000000000000000000000000000000000000000000000000000000000000","years":1}"#;
+    assert!(RegisterNoteInputs::from_json(json).is_err());
+}
@@ -0,0 +1,67 @@
+mod test_utils;
+
+use midenname_contracts::{registry::RegistryClient, smoke::{SmokeConfig, SmokeReport, SmokeStep, StepResult, StepStatus, run_smoke_test}, testing::{execute_notes_and_build_chain, init_naming}};
+
+use crate::test_utils::create_mock_client;
+
+// Registry with prices set and nothing registered, the client tracks no wallets
+async fn smoke_registry() -> anyhow::Result<(RegistryClient, SmokeConfig)> {
+    let mut ctx = init_naming().await?;
+    let config = SmokeConfig { primary: ctx.registrar_1.id(), secondary: ctx.registrar_2.id(), payment_token: ctx.fungible_asset.faucet_id(), known_name: None };
+    let chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    let registry = RegistryClient::connect(create_mock_client(chain).await?, ctx.naming.id()).await?;
+    Ok((registry, config))
+}
+
+fn statuses(report: &SmokeReport) -> Vec<(SmokeStep, StepStatus)> {
+    report.steps.iter().map(|result| (result.step, result.status)).collect()
+}
+
+#[test]
+fn test_report_passes_only_when_every_step_passed() {
+    let step = |step, status| StepResult { step, status, elapsed_ms: 1.0, error: None };
+    let mut report = SmokeReport { naming_id: "0x01".to_string(), name: "smoke".to_string(), steps: SmokeStep::ALL.iter().map(|&s| step(s, StepStatus::Passed)).collect() };
+    assert!(report.passed());
+
+    report.steps[5].status = StepStatus::Skipped;
+    assert!(!report.passed());
+}
+
+#[tokio::test]
+async fn test_unresolved_known_name_fails_and_skips_the_rest() -> anyhow::Result<()> {
+    let (mut registry, mut config) = smoke_registry().await?;
+    config.known_name = Some("nobody".to_string());
+
+    let report = run_smoke_test(&mut registry, &config, "smoke").await;
+    let mut expected = vec![(SmokeStep::ResolveKnown, StepStatus::Failed)];
+    expected.extend(SmokeStep::ALL[1..].iter().map(|&step| (step, StepStatus::Skipped)));
+    assert_eq!(statuses(&report), expected);
+    assert!(!report.passed());
+    assert!(report.steps[0].error.as_deref().is_some_and(|error| error.contains("nobody does not resolve")), "Unexpected error: {:?}", report.steps[0].error);
+    assert!(report.steps[1..].iter().all(|result| result.error.is_none() && result.elapsed_ms == 0.0));
+
+    // The JSON report names the run and every step
+    let path = std::env::temp_dir().join(format!("midenname-smoke-{}.json", rand::random::<u64>()));
+    report.write(&path)?;
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+    assert_eq!(json["naming_id"], registry.naming_id.to_hex());
+    assert_eq!(json["name"], "smoke");
+    assert_eq!(json["steps"][0]["step"], "resolve_known");
+    assert_eq!(json["steps"][0]["status"], "failed");
+    assert_eq!(json["steps"][5]["step"], "release");
+    assert_eq!(json["steps"][5]["status"], "skipped");
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_from_untracked_wallet_fails_the_run() -> anyhow::Result<()> {
+    let (mut registry, config) = smoke_registry().await?;
+
+    // Without a known name the first step has nothing to check
+    let report = run_smoke_test(&mut registry, &config, "smoke").await;
+    assert_eq!(&statuses(&report)[..3], &[(SmokeStep::ResolveKnown, StepStatus::Passed), (SmokeStep::Register, StepStatus::Failed), (SmokeStep::Activate, StepStatus::Skipped)]);
+    assert!(report.steps[1].error.is_some());
+    assert!(!report.passed());
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use miden_client::asset::FungibleAsset;
+use midenname_contracts::{compile::CompileOptions, domain::encode_domain, errors::{MidenIdError, RegistryError, contract_error_code}, notes::{create_naming_approve_sponsor_note, create_naming_deregister_note, create_naming_register_sponsored_note}, queries::{get_approved_sponsor, get_domain_count, get_domain_owner, get_domain_sponsor}, testing::{add_note_to_builder, execute_note, execute_notes_and_build_chain, init_naming}};
+
+#[tokio::test]
+async fn test_forged_beneficiary_rejected() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let (relayer, beneficiary, approved) = (ctx.registrar_1.id(), ctx.registrar_2.id(), ctx.registrar_3.id());
+    let payment = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+
+    // The beneficiary approved someone, but not the relayer
+    let approve_note = create_naming_approve_sponsor_note(beneficiary, ctx.naming.id(), Some(approved), CompileOptions::DEBUG).await?;
+    let forged_note = create_naming_register_sponsored_note(relayer, ctx.naming.id(), beneficiary, encode_domain("test".to_string()), payment, 1, CompileOptions::DEBUG).await?;
+    add_note_to_builder(&mut ctx.builder, approve_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, forged_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), approve_note.id()], &mut ctx.naming).await?;
+    let err = execute_note(&mut chain, forged_note.id(), &mut ctx.naming).await.expect_err("relayer is not the approved sponsor");
+    assert_eq!(contract_error_code(&format!("{err:?}")), Some("SENDER_MISMATCH"), "Unexpected error: {err:?}");
+
+    assert_eq!(get_domain_owner(&ctx.naming, "test")?, None);
+    assert_eq!(get_domain_count(&ctx.naming, beneficiary)?, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sponsored_registration_with_approval() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let (sponsor, beneficiary) = (ctx.registrar_1.id(), ctx.registrar_2.id());
+    let payment = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+
+    let approve_note = create_naming_approve_sponsor_note(beneficiary, ctx.naming.id(), Some(sponsor), CompileOptions::DEBUG).await?;
+    let register_note = create_naming_register_sponsored_note(sponsor, ctx.naming.id(), beneficiary, encode_domain("test".to_string()), payment, 1, CompileOptions::DEBUG).await?;
+    let revoke_note = create_naming_approve_sponsor_note(beneficiary, ctx.naming.id(), None, CompileOptions::DEBUG).await?;
+    let revoked_register_note = create_naming_register_sponsored_note(sponsor, ctx.naming.id(), beneficiary, encode_domain("abcd".to_string()), payment, 1, CompileOptions::DEBUG).await?;
+    for note in [&approve_note, &register_note, &revoke_note, &revoked_register_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), approve_note.id(), register_note.id()], &mut ctx.naming).await?;
+    assert_eq!(get_approved_sponsor(&ctx.naming, beneficiary)?, Some(sponsor));
+    assert_eq!(get_domain_owner(&ctx.naming, "test")?, Some(beneficiary));
+    assert_eq!(get_domain_sponsor(&ctx.naming, "test")?, Some(sponsor));
+    assert_eq!(get_domain_count(&ctx.naming, beneficiary)?, 1);
+    assert_eq!(get_domain_count(&ctx.naming, sponsor)?, 0);
+
+    execute_note(&mut chain, revoke_note.id(), &mut ctx.naming).await?;
+    assert_eq!(get_approved_sponsor(&ctx.naming, beneficiary)?, None);
+    let err = execute_note(&mut chain, revoked_register_note.id(), &mut ctx.naming).await.expect_err("approval was revoked");
+    assert_eq!(contract_error_code(&format!("{err:?}")), Some("SENDER_MISMATCH"), "Unexpected error: {err:?}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sponsored_note_rejects_sender_as_beneficiary() -> anyhow::Result<()> {
+    let ctx = init_naming().await?;
+    let registrant = ctx.registrar_1.id();
+    let payment = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+
+    let result = create_naming_register_sponsored_note(registrant, ctx.naming.id(), registrant, encode_domain("test".to_string()), payment, 1, CompileOptions::DEBUG).await;
+    assert!(matches!(result, Err(MidenIdError::Registry(RegistryError::SenderMismatch { .. }))), "Unexpected result: {result:?}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deregister_clears_sponsor() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let (sponsor, beneficiary) = (ctx.registrar_1.id(), ctx.registrar_2.id());
+    let payment = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+
+    let approve_note = create_naming_approve_sponsor_note(beneficiary, ctx.naming.id(), Some(sponsor), CompileOptions::DEBUG).await?;
+    let register_note = create_naming_register_sponsored_note(sponsor, ctx.naming.id(), beneficiary, encode_domain("test".to_string()), payment, 1, CompileOptions::DEBUG).await?;
+    let deregister_note = create_naming_deregister_note(beneficiary, ctx.naming.id(), encode_domain("test".to_string()), CompileOptions::DEBUG).await?;
+    for note in [&approve_note, &register_note, &deregister_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), approve_note.id(), register_note.id()], &mut ctx.naming).await?;
+    assert_eq!(get_domain_sponsor(&ctx.naming, "test")?, Some(sponsor));
+
+    execute_note(&mut chain, deregister_note.id(), &mut ctx.naming).await?;
+    assert_eq!(get_domain_owner(&ctx.naming, "test")?, None);
+    assert_eq!(get_domain_sponsor(&ctx.naming, "test")?, None);
+    Ok(())
+}
@@ -0,0 +1,159 @@
+mod test_utils;
+
+use miden_client::{account::{Account, AccountId}, asset::FungibleAsset, note::{NoteAssets, NoteInputs}};
+use miden_crypto::{Felt, Word, merkle::Smt};
+use miden_objects::account::{AccountHeader, AccountStorageHeader};
+use midenname_contracts::{certificate::{Certificate, issue_certificate, verify_certificate, verify_historical}, compile::CompileOptions, domain::encode_domain, errors::{MidenIdError, RegistryError, Result}, notes::create_naming_register_name_note, queries::{Lookup, account_id_to_word, open_map_item}, stateless::{AccountStateSource, StorageMapWitness, resolve_via_rpc}, testing::{add_note_to_builder, execute_notes_and_build_chain, init_naming}};
+
+use crate::test_utils::create_note_for_naming;
+
+// Stands in for the node RPC: serves the committed naming account under a one account tree
+struct MockNode {
+    account: Account,
+    account_tree: Smt,
+    tamper: fn(&mut StorageMapWitness),
+}
+
+impl MockNode {
+    fn new(account: Account, tamper: fn(&mut StorageMapWitness)) -> anyhow::Result<Self> {
+        let account_tree = Smt::with_entries([(account_id_to_word(account.id()), AccountHeader::from(&account).commitment())])?;
+        Ok(Self { account, account_tree, tamper })
+    }
+}
+
+impl AccountStateSource for MockNode {
+    async fn storage_map_witness(&mut self, account_id: AccountId, slot: u8, key: Word) -> Result<StorageMapWitness> {
+        let storage = self.account.storage();
+        let mut witness = StorageMapWitness {
+            block_num: 1,
+            account_root: self.account_tree.root(),
+            account_proof: self.account_tree.open(&account_id_to_word(account_id)),
+            account_header: (&self.account).into(),
+            storage_header: AccountStorageHeader::new(storage.slots().iter().map(|slot| (slot.slot_type(), slot.value())).collect()),
+            map_proof: open_map_item(&self.account, slot, key)?.1,
+        };
+        (self.tamper)(&mut witness);
+        Ok(witness)
+    }
+}
+
+// Naming account with "test" registered and activated by registrar_1, as committed on the chain
+async fn registered_naming() -> anyhow::Result<(Account, AccountId)> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain("test".to_string());
+
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain, FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?, 1, CompileOptions::DEBUG).await?;
+    let activate_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, activate_note.clone())?;
+
+    let chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), activate_note.id()], &mut ctx.naming).await?;
+    Ok((chain.committed_account(ctx.naming.id())?, ctx.registrar_1.id()))
+}
+
+#[tokio::test]
+async fn test_resolve_via_rpc_without_local_store() -> anyhow::Result<()> {
+    let (naming, registrant) = registered_naming().await?;
+    let naming_id = naming.id();
+    let mut node = MockNode::new(naming, |_| {})?;
+
+    assert_eq!(resolve_via_rpc(&mut node, naming_id, "test").await?, Some(registrant));
+    // Non-membership is proven for both the plain and the hashed key
+    assert_eq!(resolve_via_rpc(&mut node, naming_id, "nobody").await?, None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_resolve_via_rpc_rejects_tampered_proofs() -> anyhow::Result<()> {
+    let (naming, _) = registered_naming().await?;
+    let naming_id = naming.id();
+
+    let tampered: [fn(&mut StorageMapWitness); 3] = [
+        // Root of another block
+        |witness| witness.account_root = Word::default(),
+        // Storage that the account header doesn't commit to
+        |witness| witness.storage_header = AccountStorageHeader::new(Vec::new()),
+        // Opening from another map
+        |witness| witness.map_proof = Smt::new().open(&Word::default()),
+    ];
+    for tamper in tampered {
+        let mut node = MockNode::new(naming.clone(), tamper)?;
+        let err = resolve_via_rpc(&mut node, naming_id, "test").await.expect_err("Expected tampered proof to fail");
+        assert!(matches!(err, MidenIdError::Registry(RegistryError::InvalidStorageProof { .. })), "Unexpected error: {err}");
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_certificate_verifies_against_its_block_root() -> anyhow::Result<()> {
+    let (naming, registrant) = registered_naming().await?;
+    let naming_id = naming.id();
+    let mut node = MockNode::new(naming, |_| {})?;
+    let block_root = node.account_tree.root();
+
+    let certificate = issue_certificate(&mut node, naming_id, "test").await?;
+    assert_eq!((certificate.account_id, certificate.lookup, certificate.block_num()), (registrant, Lookup::Plain, 1));
+
+    // Checked offline from the exported JSON
+    let path = std::env::temp_dir().join(format!("midenname-certificate-{}.json", rand::random::<u64>()));
+    certificate.save(&path)?;
+    let certificate = Certificate::load(&path)?;
+    verify_certificate(&certificate, block_root)?;
+
+    let err = issue_certificate(&mut node, naming_id, "nobody").await.expect_err("Expected unregistered name to fail");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::NameNotFound(_))), "Unexpected error: {err}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_certificate_rejects_other_roots_and_tampering() -> anyhow::Result<()> {
+    let (naming, _) = registered_naming().await?;
+    let naming_id = naming.id();
+    let mut node = MockNode::new(naming.clone(), |_| {})?;
+    let certificate = issue_certificate(&mut node, naming_id, "test").await?;
+
+    // Account root of another block, where the tree also holds some other account
+    let other_root = Smt::with_entries([
+        (account_id_to_word(naming_id), AccountHeader::from(&naming).commitment()),
+        (Word::new([Felt::new(1), Felt::new(2), Felt::new(0), Felt::new(0)]), Word::new([Felt::new(3); 4])),
+    ])?.root();
+    let err = verify_certificate(&certificate, other_root).expect_err("Expected another block's root to fail");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::InvalidCertificate { .. })), "Unexpected error: {err}");
+
+    // Claiming that root doesn't help, the account proof doesn't open to it
+    let mut moved = certificate.clone();
+    moved.witness.account_root = other_root;
+    let err = verify_certificate(&moved, other_root).expect_err("Expected rewritten root to fail");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::InvalidStorageProof { .. })), "Unexpected error: {err}");
+
+    let mut tampered = certificate.clone();
+    tampered.account_id = naming_id;
+    let err = verify_certificate(&tampered, node.account_tree.root()).expect_err("Expected tampered account to fail");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::InvalidCertificate { .. })), "Unexpected error: {err}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_historical_certificates_need_a_trusted_root_per_block() -> anyhow::Result<()> {
+    let (naming, registrant) = registered_naming().await?;
+    let naming_id = naming.id();
+    let mut node = MockNode::new(naming, |_| {})?;
+    let root = node.account_tree.root();
+
+    // The same proofs claimed for a later block, where the account tree happens to be unchanged
+    let earlier = issue_certificate(&mut node, naming_id, "test").await?;
+    let mut later = earlier.clone();
+    later.witness.block_num = 7;
+
+    let trusted = std::collections::BTreeMap::from([(1, root), (7, root)]);
+    assert_eq!(verify_historical(&[later.clone(), earlier.clone()], &trusted)?, vec![(1, registrant), (7, registrant)]);
+
+    let err = verify_historical(&[earlier.clone(), later.clone()], &std::collections::BTreeMap::from([(1, root)])).expect_err("Expected untrusted block to fail");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::InvalidCertificate { .. })), "Unexpected error: {err}");
+
+    let mut renamed = later.clone();
+    renamed.name = "other".to_string();
+    let err = verify_historical(&[earlier, renamed], &trusted).expect_err("Expected mixed names to fail");
+    assert!(matches!(err, MidenIdError::Registry(RegistryError::InvalidCertificate { .. })), "Unexpected error: {err}");
+    Ok(())
+}
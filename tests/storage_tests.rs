@@ -0,0 +1,36 @@
+use std::fs;
+
+use midenname_contracts::{accounts::naming_account_component, compile::CompileOptions, storage::{NAMING_STORAGE_LAYOUT, SlotKind}};
+
+#[test]
+fn test_storage_layout_indices_are_sequential() {
+    for (position, spec) in NAMING_STORAGE_LAYOUT.iter().enumerate() {
+        assert_eq!(spec.index as usize, position, "{} is listed at position {}", spec.name, position);
+    }
+}
+
+#[test]
+fn test_storage_layout_matches_masm_constants() -> anyhow::Result<()> {
+    let code = fs::read_to_string("./masm/accounts/naming.masm")?;
+
+    for spec in NAMING_STORAGE_LAYOUT {
+        let declaration = format!("const.{}={}", spec.name, spec.index);
+        assert!(
+            code.lines().any(|line| line.split_whitespace().next() == Some(declaration.as_str())),
+            "naming.masm does not declare {declaration}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_compiled_component_storage_matches_layout() -> anyhow::Result<()> {
+    let component = naming_account_component(CompileOptions::DEBUG)?;
+    let slots = component.storage_slots();
+
+    assert_eq!(slots.len(), NAMING_STORAGE_LAYOUT.len());
+    for (slot, spec) in slots.iter().zip(NAMING_STORAGE_LAYOUT) {
+        assert_eq!(SlotKind::of(slot), spec.kind, "slot {} ({}) has the wrong kind", spec.index, spec.name);
+    }
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use midenname_contracts::{errors::RegistryError, token::{CliAmount, TokenAmount}};
+
+fn invalid_reason(amount: &str, decimals: u8) -> &'static str {
+    match TokenAmount::from_display(amount, decimals) {
+        Err(RegistryError::InvalidAmount { reason, .. }) => reason,
+        other => panic!("Unexpected result for {amount}: {other:?}"),
+    }
+}
+
+#[test]
+fn test_display_amount_round_trips() {
+    let amount = TokenAmount::from_display("1.5", 8).unwrap();
+    assert_eq!(amount.base_units(), 150_000_000);
+    assert_eq!(amount.to_display(), "1.5");
+
+    assert_eq!(TokenAmount::from_display("42", 8).unwrap().base_units(), 4_200_000_000);
+    assert_eq!(TokenAmount::from_display(".25", 2).unwrap().base_units(), 25);
+    assert_eq!(TokenAmount::from_display("7", 0).unwrap().to_display(), "7");
+    assert_eq!(TokenAmount::from_base_units(100_000_001, 8).to_display(), "1.00000001");
+    assert_eq!(TokenAmount::from_base_units(200_000_000, 8).to_string(), "2");
+}
+
+#[test]
+fn test_trailing_zeros_beyond_decimals_are_accepted() {
+    let amount = TokenAmount::from_display("1.50000000000", 8).unwrap();
+    assert_eq!(amount.base_units(), 150_000_000);
+    assert_eq!(amount.to_display(), "1.5");
+}
+
+#[test]
+fn test_more_fraction_digits_than_decimals_is_rejected() {
+    assert_eq!(invalid_reason("0.123456789", 8), "more fraction digits than the token has decimals");
+    assert_eq!(invalid_reason("1.5", 0), "more fraction digits than the token has decimals");
+}
+
+#[test]
+fn test_malformed_amounts_are_rejected() {
+    for amount in ["", ".", "1,5", "-1", "1.2.3", "abc"] {
+        assert_eq!(invalid_reason(amount, 8), "expected a decimal number like 1.5");
+    }
+}
+
+#[test]
+fn test_overflowing_amounts_are_rejected() {
+    assert_eq!(invalid_reason("184467440737.09551616", 8), "amount does not fit in u64");
+    assert_eq!(invalid_reason("99999999999999999999", 0), "amount does not fit in u64");
+    assert_eq!(invalid_reason("1", 20), "faucet has more decimals than fit in u64");
+    assert_eq!(TokenAmount::from_display("184467440737.09551615", 8).unwrap().base_units(), u64::MAX);
+}
+
+#[test]
+fn test_raw_cli_amount_skips_decimals() {
+    let raw = CliAmount::parse("150", true).unwrap();
+    assert_eq!(raw, CliAmount::Raw(150));
+    assert_eq!(raw.base_units(8).unwrap(), 150);
+
+    assert_eq!(CliAmount::parse("1.5", false).unwrap().base_units(8).unwrap(), 150_000_000);
+    assert!(matches!(CliAmount::parse("1.5", true), Err(RegistryError::InvalidAmount { .. })));
+}
@@ -0,0 +1,78 @@
+use miden_client::{note::{NoteAssets, NoteInputs}, transaction::{OutputNote, TransactionRequestBuilder}};
+use miden_crypto::Felt;
+use midenname_contracts::{compile::CompileOptions, errors::MidenIdError, notes::{SetPriceInput, create_naming_set_price_note, create_note_for_naming}, testing::{add_note_to_builder, execute_notes_and_build_chain, init_naming}, transcript::{Transcript, TranscriptEntry, TranscriptError, TranscriptRecorder, replay_transcript}};
+
+// Records init + set_price against a mock chain, the same way deploy records them on the network
+async fn record_transcript() -> anyhow::Result<Transcript> {
+    let mut ctx = init_naming().await?;
+    let path = std::env::temp_dir().join(format!("midenname-transcript-{}.json", rand::random::<u64>()));
+    let mut recorder = TranscriptRecorder::new(path.clone());
+
+    let init_inputs = NoteInputs::new(vec![
+        ctx.owner.id().suffix(),
+        ctx.owner.id().prefix().as_felt(),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(ctx.one_year.into()),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+    ])?;
+    let init_note = create_note_for_naming("initialize_naming".to_string(), init_inputs, ctx.owner.id(), ctx.naming.id(), NoteAssets::new(vec![])?, CompileOptions::DEBUG).await?;
+    let set_price_note = create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), SetPriceInput { letter_count: 3, price: 100 }, false, CompileOptions::DEBUG).await?;
+
+    for (script, note) in [("initialize_naming", &init_note), ("set_price", &set_price_note)] {
+        recorder.record_note(script, note)?;
+        let request = TransactionRequestBuilder::new()
+            .own_output_notes(vec![OutputNote::Full(note.clone())])
+            .build()?;
+        recorder.record_transaction(ctx.owner.id(), &request);
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    execute_notes_and_build_chain(ctx.builder, &[init_note.id(), set_price_note.id()], &mut ctx.naming).await?;
+    recorder.finish(&ctx.naming)?;
+
+    let transcript = Transcript::load(&path)?;
+    std::fs::remove_file(path)?;
+    Ok(transcript)
+}
+
+#[tokio::test]
+async fn test_replay_recorded_transcript() -> anyhow::Result<()> {
+    let transcript = record_transcript().await?;
+
+    assert_eq!(transcript.entries.len(), 4);
+    assert_eq!(transcript.notes().count(), 2);
+    replay_transcript(&transcript, CompileOptions::DEBUG).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replay_tampered_price_fails() -> anyhow::Result<()> {
+    let mut transcript = record_transcript().await?;
+
+    let set_price = transcript.entries.iter_mut()
+        .find_map(|entry| match entry {
+            TranscriptEntry::Note(note) if note.script == "set_price" => Some(note),
+            _ => None,
+        })
+        .unwrap();
+    // Inputs are [suffix, prefix, letter_count, 0, price, 0, 0, 0]
+    set_price.inputs[4] = 1;
+
+    let err = replay_transcript(&transcript, CompileOptions::DEBUG).await.expect_err("Expected tampered transcript to fail replay");
+    assert!(matches!(err, MidenIdError::Transcript(TranscriptError::NoteIdMismatch { index: 2, .. })), "Unexpected error: {err}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replay_tampered_post_state_fails() -> anyhow::Result<()> {
+    let mut transcript = record_transcript().await?;
+
+    transcript.post_state.as_mut().unwrap().naming_storage_root = "0x00".to_string();
+
+    let err = replay_transcript(&transcript, CompileOptions::DEBUG).await.expect_err("Expected mismatched post state to fail replay");
+    assert!(matches!(err, MidenIdError::Transcript(TranscriptError::StateRootMismatch { .. })), "Unexpected error: {err}");
+    Ok(())
+}
@@ -0,0 +1,136 @@
+mod test_utils;
+
+use miden_client::{account::AccountId, asset::FungibleAsset, note::NoteType, transaction::OutputNote};
+use miden_crypto::{Felt, Word};
+use miden_testing::Auth;
+use midenname_contracts::{compile::CompileOptions, domain::encode_domain, notes::{SetPriceInput, create_naming_donate_note, create_naming_register_name_note, create_naming_set_price_note}, testing::{ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2, add_note_to_builder, execute_note, execute_notes_and_build_chain, init_naming}, vault::{AssetDelta, SpendSummary, VaultRecord, get_revenue_tokens, reconcile_vault, spend_summary, vault_delta, vault_record}};
+
+use crate::test_utils::create_p2id_note_exact;
+
+#[test]
+fn test_reconcile_clean_vault() {
+    let record = VaultRecord { vault_balance: 900, total_revenue: 1000, claimed_revenue: 200, referrer_owed: 60, frontend_owed: 40, ..Default::default() };
+
+    let reconciliation = reconcile_vault(&record);
+    assert_eq!(reconciliation.accounted, 900);
+    assert!(reconciliation.is_clean());
+}
+
+#[test]
+fn test_reconcile_donated_vault() {
+    let record = VaultRecord { vault_balance: 1250, total_revenue: 1000, donations: 250, ..Default::default() };
+
+    let reconciliation = reconcile_vault(&record);
+    assert_eq!(reconciliation.accounted, 1250);
+    assert_eq!(reconciliation.unaccounted, 0);
+    assert!(reconciliation.is_clean());
+}
+
+#[test]
+fn test_reconcile_unaccounted_vault() {
+    let record = VaultRecord { vault_balance: 1300, total_revenue: 1000, donations: 250, ..Default::default() };
+
+    let reconciliation = reconcile_vault(&record);
+    assert_eq!(reconciliation.unaccounted, 50);
+    assert_eq!(reconciliation.shortfall, 0);
+    assert!(!reconciliation.is_clean());
+
+    let short = reconcile_vault(&VaultRecord { vault_balance: 900, total_revenue: 1000, ..Default::default() });
+    assert_eq!(short.shortfall, 100);
+    assert_eq!(short.unaccounted, 0);
+}
+
+#[tokio::test]
+async fn test_donation_and_direct_deposit_on_chain() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("test".to_string()), FungibleAsset::new(token, 555)?, 1, CompileOptions::DEBUG).await?;
+    let donate_note = create_naming_donate_note(ctx.registrar_2.id(), ctx.naming.id(), FungibleAsset::new(token, 100)?, CompileOptions::DEBUG).await?;
+    // Plain P2ID payment goes through receive_asset and is not counted anywhere
+    let direct_note = create_p2id_note_exact(ctx.registrar_3.id(), ctx.naming.id(), vec![FungibleAsset::new(token, 7)?.into()], NoteType::Public, Felt::new(0), Word::default())?;
+    for note in [&register_note, &donate_note, &direct_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), donate_note.id()], &mut ctx.naming).await?;
+
+    let record = vault_record(&ctx.naming, token)?;
+    assert_eq!(record.donations, 100);
+    assert_eq!(record.vault_balance, 655);
+    assert!(reconcile_vault(&record).is_clean());
+
+    execute_note(&mut chain, direct_note.id(), &mut ctx.naming).await?;
+    let reconciliation = reconcile_vault(&vault_record(&ctx.naming, token)?);
+    assert_eq!(reconciliation.unaccounted, 7);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_revenue_tokens_listed_once_in_credit_order() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token_1 = ctx.fungible_asset.faucet_id();
+    let token_2: AccountId = ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2.try_into()?;
+    let registrar = ctx.builder.add_existing_wallet_with_assets(Auth::BasicAuth, vec![FungibleAsset::new(token_2, 1000)?.into()])?;
+
+    let set_price_note = create_naming_set_price_note(ctx.owner.id(), ctx.naming.id(), token_2, SetPriceInput { letter_count: 4, price: 100 }, false, CompileOptions::DEBUG).await?;
+    let notes = [
+        set_price_note,
+        create_naming_register_name_note(registrar.id(), ctx.naming.id(), encode_domain("test".to_string()), FungibleAsset::new(token_2, 100)?, 1, CompileOptions::DEBUG).await?,
+        create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("mine".to_string()), FungibleAsset::new(token_1, 555)?, 1, CompileOptions::DEBUG).await?,
+        create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("also".to_string()), FungibleAsset::new(token_1, 555)?, 1, CompileOptions::DEBUG).await?,
+    ];
+    for note in &notes {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut note_ids = vec![ctx.initialize_note.id(), ctx.set_prices_note.id()];
+    note_ids.extend(notes.iter().map(|note| note.id()));
+    assert!(get_revenue_tokens(&ctx.naming)?.is_empty());
+    execute_notes_and_build_chain(ctx.builder, &note_ids, &mut ctx.naming).await?;
+
+    assert_eq!(get_revenue_tokens(&ctx.naming)?, vec![token_2, token_1]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_vault_delta_of_minted_spent_and_unchanged_balances() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+
+    // registrar_1 sends the register note out of its own vault through a SPAWN note it consumes
+    let payment = FungibleAsset::new(token, 555)?;
+    let register_note = create_naming_register_name_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("test".to_string()), payment, 1, CompileOptions::DEBUG).await?;
+    let spawn_note = ctx.builder.add_spawn_note([&register_note])?;
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+
+    let before = ctx.registrar_1.clone();
+    let executed_tx = chain.build_tx_context(ctx.registrar_1.id(), &[spawn_note.id()], &[])?
+        .extend_expected_output_notes(vec![OutputNote::Full(register_note.clone())])
+        .build()?
+        .execute()
+        .await?;
+    ctx.registrar_1.apply_delta(executed_tx.account_delta())?;
+    chain.add_pending_executed_transaction(&executed_tx)?;
+    chain.prove_next_block()?;
+    let naming_before = ctx.naming.clone();
+    execute_note(&mut chain, register_note.id(), &mut ctx.naming).await?;
+
+    // Spent down by the payment the note carried, nothing came back
+    let spent = vault_delta(&before, &ctx.registrar_1);
+    assert_eq!(spent, vec![AssetDelta { faucet_id: token, before: 100000, after: 100000 - 555 }]);
+    assert_eq!(spent[0].net_cost(), 555);
+    let summary = spend_summary(&spent, &[payment]);
+    assert_eq!(summary, vec![SpendSummary { faucet_id: token, sent: 555, change: 0, net_cost: 555 }]);
+
+    // The registry received it, a gain is a negative cost
+    let received = vault_delta(&naming_before, &ctx.naming);
+    assert_eq!(received, vec![AssetDelta { faucet_id: token, before: 0, after: 555 }]);
+    assert_eq!(received[0].net_cost(), -555);
+
+    // Nothing changed, a note that carried nothing costs nothing
+    assert!(vault_delta(&ctx.registrar_1, &ctx.registrar_1).is_empty());
+    assert!(spend_summary(&[], &[]).is_empty());
+    Ok(())
+}
+
@@ -0,0 +1,169 @@
+use std::collections::VecDeque;
+
+use miden_client::transaction::{DiscardCause, TransactionId, TransactionStatus};
+use miden_crypto::{Felt, Word};
+use miden_objects::block::BlockNumber;
+use midenname_contracts::{errors::{MidenIdError, RegistryError, Result}, transaction::{DeferredSync, SyncSource, TransactionStatusSource, poll_tx_status, poll_until, with_deferred_sync}};
+use tokio::time::Duration;
+
+// Replays the given statuses, then keeps reporting the transaction as unknown
+struct MockStatuses {
+    statuses: VecDeque<TransactionStatus>,
+    polls: usize,
+}
+
+impl MockStatuses {
+    fn new(statuses: Vec<TransactionStatus>) -> Self {
+        Self { statuses: statuses.into(), polls: 0 }
+    }
+}
+
+impl TransactionStatusSource for MockStatuses {
+    async fn transaction_status(&mut self, _tx_id: TransactionId) -> Result<Option<TransactionStatus>> {
+        self.polls += 1;
+        Ok(self.statuses.pop_front())
+    }
+}
+
+fn tx_id() -> TransactionId {
+    TransactionId::new(Word::default(), Word::default(), Word::default(), Word::default())
+}
+
+fn numbered_tx_id(n: u64) -> TransactionId {
+    TransactionId::new(Word::new([Felt::new(n), Felt::new(0), Felt::new(0), Felt::new(0)]), Word::default(), Word::default(), Word::default())
+}
+
+fn committed() -> TransactionStatus {
+    TransactionStatus::Committed { block_number: BlockNumber::from(1), commit_timestamp: 0 }
+}
+
+// Client stand in counting syncs. Submitting a transaction makes it pending until `commit_after` more
+// syncs have happened.
+struct MockSync {
+    syncs: u32,
+    commit_after: u32,
+    // Transaction and the sync count it is committed at
+    submitted: Vec<(TransactionId, u32)>,
+    discarded: Vec<TransactionId>,
+}
+
+impl MockSync {
+    fn new(commit_after: u32) -> Self {
+        Self { syncs: 0, commit_after, submitted: Vec::new(), discarded: Vec::new() }
+    }
+
+    fn submit(&mut self, tx_id: TransactionId) -> TransactionId {
+        self.submitted.push((tx_id, self.syncs + self.commit_after));
+        tx_id
+    }
+}
+
+impl SyncSource for MockSync {
+    async fn sync(&mut self) -> Result<u32> {
+        self.syncs += 1;
+        Ok(self.syncs)
+    }
+
+    async fn stored_transaction_status(&mut self, tx_id: TransactionId) -> Result<Option<TransactionStatus>> {
+        if self.discarded.contains(&tx_id) {
+            return Ok(Some(TransactionStatus::Discarded(DiscardCause::Expired)));
+        }
+        Ok(self.submitted.iter().find(|(id, _)| *id == tx_id).map(|&(_, committed_at)| if self.syncs >= committed_at { committed() } else { TransactionStatus::Pending }))
+    }
+}
+
+#[tokio::test]
+async fn test_rejected_transaction_fails_immediately() {
+    let mut source = MockStatuses::new(vec![TransactionStatus::Pending, TransactionStatus::Discarded(DiscardCause::Expired)]);
+
+    let result = poll_tx_status(&mut source, tx_id(), Duration::from_secs(60), Duration::from_millis(1)).await;
+    assert!(matches!(result, Err(MidenIdError::Registry(RegistryError::TransactionRejected { .. }))), "Unexpected result: {result:?}");
+    assert_eq!(source.polls, 2);
+}
+
+#[tokio::test]
+async fn test_never_committed_transaction_times_out() {
+    let mut source = MockStatuses::new(vec![TransactionStatus::Pending; 3]);
+
+    let result = poll_tx_status(&mut source, tx_id(), Duration::from_millis(50), Duration::from_millis(5)).await;
+    assert!(matches!(result, Err(MidenIdError::Registry(RegistryError::TransactionTimeout { .. }))), "Unexpected result: {result:?}");
+}
+
+#[tokio::test]
+async fn test_deferred_sync_syncs_once_for_three_operations() -> Result<()> {
+    let mut source = MockSync::new(1);
+
+    let submitted = with_deferred_sync(&mut source, async |scope| {
+        let mut submitted = Vec::new();
+        for n in 1..=3 {
+            let tx_id = scope.source().submit(numbered_tx_id(n));
+            scope.defer(tx_id);
+            submitted.push(tx_id);
+        }
+        assert_eq!(scope.pending().len(), 3);
+        Ok(submitted)
+    }).await?;
+
+    assert_eq!(submitted.len(), 3);
+    assert_eq!(source.syncs, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deferred_sync_polls_until_every_transaction_is_committed() -> Result<()> {
+    let mut source = MockSync::new(3);
+    let mut scope = DeferredSync::new(&mut source).with_polling(Duration::from_secs(60), Duration::from_millis(1));
+    for n in 1..=3 {
+        let tx_id = scope.source().submit(numbered_tx_id(n));
+        scope.defer(tx_id);
+    }
+    assert_eq!(scope.finish().await?, 3);
+    assert_eq!(source.syncs, 3);
+
+    // A failed scope never syncs
+    let result = with_deferred_sync(&mut source, async |_| Err::<(), _>(RegistryError::InvalidProposal("proposal.bin".to_string()).into())).await;
+    assert!(result.is_err());
+    assert_eq!(source.syncs, 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deferred_sync_fails_on_a_discarded_transaction() {
+    let mut source = MockSync::new(1);
+    source.discarded.push(numbered_tx_id(2));
+
+    let result = with_deferred_sync(&mut source, async |scope| {
+        for n in 1..=3 {
+            let tx_id = scope.source().submit(numbered_tx_id(n));
+            scope.defer(tx_id);
+        }
+        Ok(())
+    }).await;
+    assert!(matches!(result, Err(MidenIdError::Registry(RegistryError::TransactionRejected { .. }))), "Unexpected result: {result:?}");
+    assert_eq!(source.syncs, 1);
+}
+
+#[tokio::test]
+async fn test_poll_until_times_out_with_what_it_waited_for() {
+    let mut checks = 0;
+    let result = poll_until("a note that never arrives", Duration::from_millis(50), Duration::from_millis(5), async || {
+        checks += 1;
+        Ok(None::<()>)
+    }).await;
+
+    let err = result.expect_err("Expected an impossible condition to time out");
+    assert!(matches!(&err, MidenIdError::Registry(RegistryError::PollTimeout { waited_ms: 50, .. })), "Unexpected error: {err}");
+    assert_eq!(err.to_string(), "timed out after 50ms waiting for a note that never arrives");
+    assert!(checks > 1);
+}
+
+#[tokio::test]
+async fn test_poll_until_returns_the_first_value() -> Result<()> {
+    let mut checks = 0;
+    let value = poll_until("the third check", Duration::from_secs(60), Duration::from_millis(1), async || {
+        checks += 1;
+        Ok((checks == 3).then_some(checks))
+    }).await?;
+    assert_eq!(value, 3);
+    Ok(())
+}